@@ -0,0 +1,106 @@
+//! ANSI colours for the text report. Styling is switched on once at startup with
+//! [`set_enabled`]; until then every helper returns its text unchanged.
+
+use std::fmt;
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// When to colour the text report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Colour only when stdout is a terminal and `NO_COLOR` isn't set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether this choice colours output on the current stdout.
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            other => Err(format!(
+                "unknown color choice '{other}' (expected auto, always or never)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ColorChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        })
+    }
+}
+
+/// Turn styling on or off for the rest of the run.
+pub fn set_enabled(on: bool) {
+    ENABLED.store(on, Ordering::Relaxed);
+}
+
+/// `text` wrapped in the SGR sequence `code` when `on`.
+fn paint(on: bool, code: &str, text: &str) -> String {
+    if on {
+        format!("\x1b[{code}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn bold(text: &str) -> String {
+    paint(ENABLED.load(Ordering::Relaxed), "1", text)
+}
+
+pub fn red(text: &str) -> String {
+    paint(ENABLED.load(Ordering::Relaxed), "31", text)
+}
+
+pub fn green(text: &str) -> String {
+    paint(ENABLED.load(Ordering::Relaxed), "32", text)
+}
+
+pub fn yellow(text: &str) -> String {
+    paint(ENABLED.load(Ordering::Relaxed), "33", text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_choice_from_str() {
+        assert_eq!("always".parse(), Ok(ColorChoice::Always));
+        assert_eq!("NEVER".parse(), Ok(ColorChoice::Never));
+        assert_eq!("auto".parse(), Ok(ColorChoice::Auto));
+        assert!("sometimes".parse::<ColorChoice>().is_err());
+    }
+
+    #[test]
+    fn test_paint_only_when_on() {
+        assert_eq!(paint(true, "31", "x"), "\x1b[31mx\x1b[0m");
+        assert_eq!(paint(false, "31", "x"), "x");
+    }
+}
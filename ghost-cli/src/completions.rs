@@ -0,0 +1,275 @@
+//! Shell completion scripts for `ghost`.
+//!
+//! argh has no completion support of its own, so the flags are read back out of the
+//! `--help` text it generates. The scripts can therefore never fall out of step with
+//! the `Cli` definition.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A shell to generate a completion script for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            other => Err(format!(
+                "unknown shell '{other}' (expected bash, zsh or fish)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        })
+    }
+}
+
+/// One command-line flag, as listed in the help text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flag {
+    /// The long name without its dashes, e.g. `mkdocs-yaml`.
+    pub long: String,
+    pub short: Option<char>,
+    /// Whether the flag is followed by a value (`--help-urls <path>`).
+    pub takes_value: bool,
+    pub description: String,
+}
+
+/// Read the flags out of argh's help text: names and descriptions from the `Options:`
+/// list, and whether each takes a value from the `Usage:` line.
+pub fn flags_from_help(help: &str) -> Vec<Flag> {
+    let usage: Vec<&str> = help
+        .lines()
+        .skip_while(|l| !l.starts_with("Usage:"))
+        .take_while(|l| !l.trim().is_empty())
+        .flat_map(|l| l.split_whitespace())
+        .map(|t| t.trim_matches(['[', ']']))
+        .collect();
+    let takes_value = |long: &str| {
+        usage
+            .windows(2)
+            .any(|w| w[0] == format!("--{long}") && w[1].starts_with('<'))
+    };
+
+    let mut flags: Vec<Flag> = Vec::new();
+    for line in help.lines().skip_while(|l| l.trim() != "Options:").skip(1) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if !trimmed.starts_with('-') {
+            // A wrapped description, or one pushed onto its own line by a long name.
+            if let Some(flag) = flags.last_mut() {
+                if !flag.description.is_empty() {
+                    flag.description.push(' ');
+                }
+                flag.description.push_str(trimmed);
+            }
+            continue;
+        }
+        let (names, description) = trimmed.split_once("  ").unwrap_or((trimmed, ""));
+        let mut long = None;
+        let mut short = None;
+        for name in names.split(',').map(str::trim) {
+            if let Some(l) = name.strip_prefix("--") {
+                long = Some(l.to_string());
+            } else if let Some(s) = name.strip_prefix('-') {
+                short = s.chars().next();
+            }
+        }
+        if let Some(long) = long {
+            flags.push(Flag {
+                takes_value: takes_value(&long),
+                long,
+                short,
+                description: description.trim().to_string(),
+            });
+        }
+    }
+    flags
+}
+
+/// Render a completion script for `bin` in `shell`. Flags that take a value complete
+/// file names, since most of ghost's options are paths.
+pub fn generate(shell: Shell, bin: &str, flags: &[Flag]) -> String {
+    match shell {
+        Shell::Bash => bash(bin, flags),
+        Shell::Zsh => zsh(bin, flags),
+        Shell::Fish => fish(bin, flags),
+    }
+}
+
+fn bash(bin: &str, flags: &[Flag]) -> String {
+    let func = format!("_{}", bin.replace('-', "_"));
+    let words: Vec<String> = flags
+        .iter()
+        .flat_map(|f| {
+            f.short
+                .map(|s| format!("-{s}"))
+                .into_iter()
+                .chain(std::iter::once(format!("--{}", f.long)))
+        })
+        .collect();
+    let valued: Vec<String> = flags
+        .iter()
+        .filter(|f| f.takes_value)
+        .map(|f| format!("--{}", f.long))
+        .collect();
+
+    let mut out = String::new();
+    out.push_str(&format!("{func}() {{\n"));
+    out.push_str("    local cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    out.push_str("    local prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+    if !valued.is_empty() {
+        out.push_str("    case \"$prev\" in\n");
+        out.push_str(&format!("        {})\n", valued.join("|")));
+        out.push_str("            COMPREPLY=( $(compgen -f -- \"$cur\") )\n");
+        out.push_str("            return 0\n");
+        out.push_str("            ;;\n");
+        out.push_str("    esac\n");
+    }
+    out.push_str(&format!(
+        "    COMPREPLY=( $(compgen -W \"{}\" -- \"$cur\") )\n",
+        words.join(" ")
+    ));
+    out.push_str("}\n");
+    out.push_str(&format!("complete -o filenames -F {func} {bin}\n"));
+    out
+}
+
+fn zsh(bin: &str, flags: &[Flag]) -> String {
+    let mut out = format!("#compdef {bin}\n\n_arguments \\\n");
+    let specs: Vec<String> = flags
+        .iter()
+        .map(|f| {
+            let description = f
+                .description
+                .replace('\'', "'\\''")
+                .replace('[', "\\[")
+                .replace(']', "\\]");
+            let action = if f.takes_value { ":value:_files" } else { "" };
+            let spec = format!("[{description}]{action}'");
+            match f.short {
+                Some(s) => format!("  '(-{s} --{long})'{{-{s},--{long}}}'{spec}", long = f.long),
+                None => format!("  '--{}{spec}", f.long),
+            }
+        })
+        .collect();
+    out.push_str(&specs.join(" \\\n"));
+    out.push('\n');
+    out
+}
+
+fn fish(bin: &str, flags: &[Flag]) -> String {
+    flags
+        .iter()
+        .map(|f| {
+            let mut line = format!("complete -c {bin}");
+            if let Some(s) = f.short {
+                line.push_str(&format!(" -s {s}"));
+            }
+            line.push_str(&format!(" -l {}", f.long));
+            if f.takes_value {
+                line.push_str(" -r -F");
+            }
+            line.push_str(&format!(
+                " -d '{}'\n",
+                f.description.replace('\\', "\\\\").replace('\'', "\\'")
+            ));
+            line
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cli;
+    use argh::FromArgs;
+
+    fn cli_flags() -> Vec<Flag> {
+        let help = match Cli::from_args(&["ghost"], &["--help"]) {
+            Err(early_exit) => early_exit.output,
+            Ok(_) => panic!("--help should exit early"),
+        };
+        flags_from_help(&help)
+    }
+
+    #[test]
+    fn test_flags_from_help() {
+        let help = "Usage: ghost --mkdocs-yaml <mkdocs-yaml> [--summary] [-q]\n\
+                    \n\
+                    Audit MkDocs navigation.\n\
+                    \n\
+                    Options:\n  \
+                    --mkdocs-yaml     path to the mkdocs.yml file to read\n  \
+                    --summary         show only summary counts, not\n                    \
+                    individual items\n  \
+                    -q, --quiet       suppress output\n  \
+                    --localhost-links-severity\n                    \
+                    report as a warning or an error\n  \
+                    --help, help      display usage information\n";
+        let flags = flags_from_help(help);
+        let longs: Vec<&str> = flags.iter().map(|f| f.long.as_str()).collect();
+        assert_eq!(
+            longs,
+            vec![
+                "mkdocs-yaml",
+                "summary",
+                "quiet",
+                "localhost-links-severity",
+                "help"
+            ]
+        );
+        assert!(flags[0].takes_value);
+        assert!(!flags[1].takes_value);
+        assert_eq!(
+            flags[1].description,
+            "show only summary counts, not individual items"
+        );
+        assert_eq!(flags[2].short, Some('q'));
+        assert_eq!(flags[3].description, "report as a warning or an error");
+    }
+
+    #[test]
+    fn test_bash_completions_list_flags() {
+        let script = generate(Shell::Bash, "ghost", &cli_flags());
+        for flag in [
+            "--mkdocs-yaml",
+            "--help-urls",
+            "--broken-links",
+            "--summary",
+            "--generate-completions",
+        ] {
+            assert!(script.contains(flag), "{flag} missing from:\n{script}");
+        }
+        assert!(script.contains("complete -o filenames -F _ghost ghost"));
+    }
+
+    #[test]
+    fn test_zsh_and_fish_completions_list_flags() {
+        let flags = cli_flags();
+        let zsh = generate(Shell::Zsh, "ghost", &flags);
+        assert!(zsh.starts_with("#compdef ghost"));
+        assert!(zsh.contains("'--mkdocs-yaml["));
+        let fish = generate(Shell::Fish, "ghost", &flags);
+        assert!(fish.contains("complete -c ghost -l mkdocs-yaml -r -F"));
+        assert!(fish.contains("complete -c ghost -s q -l quiet"));
+    }
+}
@@ -0,0 +1,63 @@
+//! The report categories `--fail-on` can make a run fail on.
+
+use std::str::FromStr;
+
+/// A core report whose issues can fail the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    NavMissing,
+    Ghost,
+    HelpMissing,
+    BrokenLinks,
+    MissingImages,
+    OrphanImages,
+}
+
+impl FromStr for Category {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "nav-missing" => Ok(Category::NavMissing),
+            "ghost" => Ok(Category::Ghost),
+            "help-missing" => Ok(Category::HelpMissing),
+            "broken-links" => Ok(Category::BrokenLinks),
+            "missing-images" => Ok(Category::MissingImages),
+            "orphan-images" => Ok(Category::OrphanImages),
+            other => Err(format!(
+                "unknown category '{other}' (expected nav-missing, ghost, help-missing, \
+                 broken-links, missing-images or orphan-images)"
+            )),
+        }
+    }
+}
+
+/// The comma-separated categories given to `--fail-on`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailOn(pub Vec<Category>);
+
+impl FromStr for FailOn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|c| !c.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map(FailOn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fail_on_from_str() {
+        assert_eq!(
+            "broken-links, Ghost".parse(),
+            Ok(FailOn(vec![Category::BrokenLinks, Category::Ghost]))
+        );
+        assert!("broken-links,typos".parse::<FailOn>().is_err());
+    }
+}
@@ -1,7 +1,29 @@
+mod color;
+mod completions;
+mod fail_on;
+mod output;
+mod progress;
+#[cfg(feature = "watch")]
+mod watch;
+
 use argh::FromArgs;
-use ghost_lib::{TraceOptions, audit_traced};
+use color::ColorChoice;
+use completions::Shell;
+use fail_on::{Category, FailOn};
+use ghost_lib::{
+    AuditCounts, AuditDiff, AuditItems, AuditOptions, AuditResult, BrokenImageItem, BrokenLinkItem,
+    GHOST_TOML, GhostConfig, NavError, NavNode, ProjectConfig, Severity, TitleCaseStyle,
+    TraceOptions, apply_allowlist, audit_with_config_and_options, detect_nav_reordering,
+    diff_audit_results, load_allowlist, load_audit_result, load_config, load_mkdocs_nav,
+    load_nav_baseline, nav_tree, page_dependency_graph, save_audit_result, save_nav_baseline,
+    to_dot, to_sarif,
+};
+use output::OutputFormat;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 use std::process::{Command, ExitCode};
+use std::time::Duration;
 
 #[derive(FromArgs, Debug)]
 /// Audit MkDocs navigation vs on-disk markdown.
@@ -10,12 +32,23 @@ use std::process::{Command, ExitCode};
 /// By default, shows all report types. Use flags to show only specific reports.
 struct Cli {
     #[argh(option, long = "mkdocs-yaml")]
-    /// path to the mkdocs.yml file to read
-    mkdocs_yaml: PathBuf,
+    /// path to the mkdocs.yml file to read; repeat to audit several separate sites
+    mkdocs_yaml: Vec<PathBuf>,
 
     #[argh(option, long = "help-urls")]
-    /// path to the header file containing HELP_URL definitions
-    help_urls: PathBuf,
+    /// path to the header file containing HELP_URL definitions; give one for all sites
+    /// or one per --mkdocs-yaml, in the same order
+    help_urls: Vec<PathBuf>,
+
+    #[argh(option, long = "extra-help-urls")]
+    /// a further header file with HELP_URL definitions, read alongside --help-urls
+    /// (repeatable)
+    extra_help_urls: Vec<PathBuf>,
+
+    #[argh(option, long = "config")]
+    /// read settings from this file in place of the .ghost.toml next to the
+    /// mkdocs.yml
+    config: Option<PathBuf>,
 
     #[argh(switch, long = "nav-missing")]
     /// show files referenced in nav that don't exist on disk
@@ -29,6 +62,15 @@ struct Cli {
     /// show files referenced in help_urls.h that don't exist
     help_missing: bool,
 
+    #[argh(switch, long = "suggest-help-fixes")]
+    /// suggest the nav page a missing help URL was probably meant to be
+    suggest_help_fixes: bool,
+
+    #[argh(switch, long = "suggest-fixes")]
+    /// list the pages each broken link was probably meant to point at (slow on large
+    /// sites)
+    suggest_fixes: bool,
+
     #[argh(switch, long = "broken-links")]
     /// show broken internal links in markdown files
     broken_links: bool,
@@ -41,6 +83,244 @@ struct Cli {
     /// show image files not referenced by any markdown or CSS
     orphan_images: bool,
 
+    #[argh(switch, long = "help-url-nav-check")]
+    /// show pages referenced in help_urls.h that exist but are not in the nav
+    help_url_nav_check: bool,
+
+    #[argh(switch, long = "nav-stub-pages")]
+    /// show nav pages with no headings (empty or front matter only)
+    nav_stub_pages: bool,
+
+    #[argh(switch, long = "repeated-words")]
+    /// show links whose text repeats a word, as in "the the"
+    repeated_words: bool,
+
+    #[argh(switch, long = "subsite-check")]
+    /// show directories with a docs/ folder but no mkdocs.yml
+    subsite_check: bool,
+
+    #[argh(switch, long = "duplicate-sections")]
+    /// show sibling nav sections whose titles give the same URL
+    duplicate_sections: bool,
+
+    #[argh(switch, long = "relay-pages")]
+    /// show pages that are nothing but links to other pages
+    relay_pages: bool,
+
+    #[argh(option, long = "min-non-link-words", default = "1")]
+    /// words of prose outside links a page needs to not count as a relay page (default 1)
+    min_non_link_words: usize,
+
+    #[argh(switch, long = "non-md-nav")]
+    /// show nav entries that point at something other than a .md file
+    non_md_nav: bool,
+
+    #[argh(switch, long = "multiple-h1")]
+    /// show pages with more than one H1, or with a heading before the H1
+    multiple_h1: bool,
+
+    #[argh(switch, long = "dead-css")]
+    /// show CSS class selectors that no page uses
+    dead_css: bool,
+
+    #[argh(switch, long = "absolute-image-paths")]
+    /// show images referenced by an absolute filesystem path, such as /home/... or C:\...
+    absolute_image_paths: bool,
+
+    #[argh(switch, long = "check-svgs")]
+    /// show oversized SVG images and SVGs with embedded raster data
+    check_svgs: bool,
+
+    #[argh(option, long = "max-svg-bytes", default = "102400")]
+    /// the size in bytes above which --check-svgs reports an SVG (default: 102400)
+    max_svg_bytes: u64,
+
+    #[argh(switch, long = "case-check")]
+    /// show paths that differ only in case, which collide on case-insensitive filesystems
+    case_check: bool,
+
+    #[argh(switch, long = "mixed-image-refs")]
+    /// show pages mixing absolute and relative image paths, or markdown and HTML images
+    mixed_image_refs: bool,
+
+    #[argh(switch, long = "orphan-configs")]
+    /// show subsite mkdocs.yml files that the root mkdocs.yml never includes
+    orphan_configs: bool,
+
+    #[argh(switch, long = "cross-file-dup-urls")]
+    /// show docs pages referenced from more than one help URL header
+    cross_file_dup_urls: bool,
+
+    #[argh(switch, long = "frontmatter-todos")]
+    /// show TODOs listed under `todo` in page front matter (as warnings)
+    frontmatter_todos: bool,
+
+    #[argh(switch, long = "fail-on-todos")]
+    /// like --frontmatter-todos, but report the TODOs as errors
+    fail_on_todos: bool,
+
+    #[argh(switch, long = "include-only-sections")]
+    /// show nav sections holding nothing but !include entries, with no page of their own
+    include_only_sections: bool,
+
+    #[argh(switch, long = "duplicate-images")]
+    /// show image files with identical content, such as a logo copied into each subsite
+    duplicate_images: bool,
+
+    #[argh(switch, long = "css-cycles")]
+    /// show import cycles among SCSS stylesheets, which fail the Sass build
+    css_cycles: bool,
+
+    #[argh(switch, long = "outside-docs")]
+    /// show nav entries whose path points outside the docs/ directory
+    outside_docs: bool,
+
+    #[argh(switch, long = "theme-check")]
+    /// show theme.features entries that the Material theme doesn't know
+    theme_check: bool,
+
+    #[argh(switch, long = "empty-sections")]
+    /// show nav sections with no children
+    empty_sections: bool,
+
+    #[argh(switch, long = "broken-anchors")]
+    /// show links whose #anchor matches no heading on the page they point to
+    broken_anchors: bool,
+
+    #[argh(switch, long = "duplicate-nav")]
+    /// show pages listed more than once in the nav
+    duplicate_nav: bool,
+
+    #[argh(option, long = "min-word-count")]
+    /// show pages with fewer than this many words of body text, as stubs
+    min_word_count: Option<usize>,
+
+    #[argh(switch, long = "heading-hierarchy")]
+    /// show headings that skip a level, such as an H3 straight after an H1
+    heading_hierarchy: bool,
+
+    #[argh(switch, long = "duplicate-anchors")]
+    /// show pages with headings that want the same anchor, such as two "## Setup"s
+    duplicate_anchors: bool,
+
+    #[argh(switch, long = "unlabelled-code")]
+    /// show pages with a code block that names no language, so it isn't highlighted
+    unlabelled_code: bool,
+
+    #[argh(option, long = "require-frontmatter")]
+    /// show pages whose front matter lacks this field, e.g. "description" (repeatable)
+    require_frontmatter: Vec<String>,
+
+    #[argh(switch, long = "title-mismatches")]
+    /// show nav pages whose H1 looks unrelated to their nav label
+    title_mismatches: bool,
+
+    #[argh(option, long = "min-title-similarity", default = "0.5")]
+    /// how similar, from 0 to 1, a nav label and its page's H1 must be for
+    /// --title-mismatches to accept them (default 0.5)
+    min_title_similarity: f64,
+
+    #[argh(switch, long = "external-links")]
+    /// request every http(s) link and show those that fail; answers are cached in
+    /// .ghost-external-cache.json beside mkdocs.yml
+    external_links: bool,
+
+    #[argh(option, long = "external-timeout")]
+    /// seconds to wait for each external link (default 10)
+    external_timeout: Option<u64>,
+
+    #[argh(option, long = "external-cache-ttl")]
+    /// hours to trust a cached answer for an external link (default 24)
+    external_cache_ttl: Option<u64>,
+
+    #[argh(switch, long = "absolute-internal")]
+    /// show links written as full URLs on the site's own --base-url
+    absolute_internal: bool,
+
+    #[argh(switch, long = "url-encoded-links")]
+    /// show internal links with percent-encoded characters, e.g. my%20page.md
+    url_encoded_links: bool,
+
+    #[argh(switch, long = "query-string-links")]
+    /// show internal links that carry a ?query string
+    query_string_links: bool,
+
+    #[argh(switch, long = "single-child-sections")]
+    /// show nested nav sections that contain only one entry
+    single_child_sections: bool,
+
+    #[argh(option, long = "title-case")]
+    /// show nav section titles not in the given style: title or sentence
+    title_case: Option<TitleCaseStyle>,
+
+    #[argh(switch, long = "alt-text-quality")]
+    /// show images whose alt text is generic, e.g. "image", "screenshot" or empty
+    alt_text_quality: bool,
+
+    #[argh(option, long = "generic-alt-text")]
+    /// an extra alt text to treat as generic for --alt-text-quality (repeatable)
+    generic_alt_text: Vec<String>,
+
+    #[argh(switch, long = "localhost-links")]
+    /// show links to a development server on localhost
+    localhost_links: bool,
+
+    #[argh(option, long = "localhost-links-severity")]
+    /// report --localhost-links as a warning or an error (default: error)
+    localhost_links_severity: Option<Severity>,
+
+    #[argh(switch, long = "private-ips")]
+    /// show links to hosts on a private network (10.x, 172.16-31.x, 192.168.x)
+    private_ips: bool,
+
+    #[argh(option, long = "private-ips-severity")]
+    /// report --private-ips as a warning or an error (default: warning)
+    private_ips_severity: Option<Severity>,
+
+    #[argh(switch, long = "scheme-relative")]
+    /// show scheme-relative links (//example.com/page), often a typo for /page
+    scheme_relative: bool,
+
+    #[argh(option, long = "scheme-relative-severity")]
+    /// report --scheme-relative as a warning or an error (default: warning)
+    scheme_relative_severity: Option<Severity>,
+
+    #[argh(switch, long = "site-dir-files")]
+    /// show source files inside mkdocs.yml's site_dir, which the build would overwrite
+    site_dir_files: bool,
+
+    #[argh(switch, long = "check-binaries")]
+    /// show files in docs directories with an unexpected extension, e.g. .exe or .pyc
+    check_binaries: bool,
+
+    #[argh(option, long = "allow-extension")]
+    /// an extra file extension to accept for --check-binaries, e.g. "zip" (repeatable)
+    allow_extension: Vec<String>,
+
+    #[argh(switch, long = "unknown-code-languages")]
+    /// show pages with fenced code blocks in a language the highlighter may not know
+    unknown_code_languages: bool,
+
+    #[argh(option, long = "code-language")]
+    /// an extra code block language to accept for --unknown-code-languages (repeatable)
+    code_language: Vec<String>,
+
+    #[argh(option, long = "max-heading-level")]
+    /// show headings deeper than this level (e.g. 3 flags H4 to H6)
+    max_heading_level: Option<usize>,
+
+    #[argh(option, long = "max-blank-lines")]
+    /// show runs of more than this many consecutive blank lines outside code blocks
+    max_blank_lines: Option<usize>,
+
+    #[argh(option, long = "max-include-depth")]
+    /// show !include files nested deeper than this, and leave them out of the audit
+    max_include_depth: Option<usize>,
+
+    #[argh(option, long = "base-url")]
+    /// the published site's base URL; links starting with it are checked as internal
+    base_url: Option<String>,
+
     #[argh(switch, long = "summary")]
     /// show only summary counts, not individual items
     summary: bool,
@@ -49,8 +329,58 @@ struct Cli {
     /// suppress output, exit with non-zero if any issues found
     quiet: bool,
 
+    #[argh(option, long = "color", default = "ColorChoice::Auto")]
+    /// colour the text report: auto (default, when writing to a terminal), always or
+    /// never
+    color: ColorChoice,
+
+    #[argh(option, long = "fail-on")]
+    /// exit non-zero only for issues in these comma-separated reports: nav-missing,
+    /// ghost, help-missing, broken-links, missing-images, orphan-images
+    fail_on: Option<FailOn>,
+
+    #[argh(option, long = "output-format", default = "OutputFormat::Text")]
+    /// write the report as text (default), json or sarif
+    output_format: OutputFormat,
+
+    #[argh(switch, long = "watch")]
+    /// keep running, re-running the audit whenever a markdown, YAML or image file
+    /// changes (needs the `watch` feature)
+    watch: bool,
+
+    #[argh(option, long = "save-result")]
+    /// write the full audit result to this file as JSON, for use with --load-baseline
+    save_result: Option<PathBuf>,
+
+    #[argh(option, long = "export-dot")]
+    /// write the graph of links between pages to this file in Graphviz DOT format
+    export_dot: Option<PathBuf>,
+
+    #[argh(option, long = "load-baseline")]
+    /// compare issue counts against a result saved earlier with --save-result
+    load_baseline: Option<PathBuf>,
+
+    #[argh(option, long = "allowlist")]
+    /// leave out the broken links listed in this file, one `file = ["link", ...]` line
+    /// per page
+    allowlist: Option<PathBuf>,
+
+    #[argh(switch, long = "diff")]
+    /// with --load-baseline, print only the issues that are new or fixed since the
+    /// baseline, each marked [NEW] or [FIXED], in place of the full report
+    diff: bool,
+
+    #[argh(option, long = "nav-order-baseline")]
+    /// compare the order of nav entries against the nav saved in this file
+    nav_order_baseline: Option<PathBuf>,
+
+    #[argh(switch, long = "update-nav-baseline")]
+    /// write the current nav to the --nav-order-baseline file instead of comparing
+    update_nav_baseline: bool,
+
     #[argh(option, long = "exclude")]
-    /// comma-separated list of subsites to exclude from all checks
+    /// comma-separated list of subsites to exclude from all checks; names or glob
+    /// patterns such as `*-legacy`
     exclude: Option<String>,
 
     #[argh(option, long = "trace")]
@@ -66,6 +396,11 @@ struct Cli {
     )]
     /// file to write the processing trace to when --trace is used (default: ghost-trace.log)
     trace_out: PathBuf,
+
+    #[argh(option, long = "generate-completions")]
+    /// print a completion script for the given shell (bash, zsh or fish) and exit
+    #[allow(dead_code)] // handled in `main` before the required arguments are parsed
+    generate_completions: Option<Shell>,
 }
 
 /// Best-effort branch + short hash of the docs repo, for the trace header.
@@ -87,13 +422,16 @@ fn docs_git_info(mkdocs: &Path) -> Option<(String, String)> {
 }
 
 /// A self-contained header prepended to the trace log so it can be forwarded as-is.
-fn trace_header(cli: &Cli) -> String {
+fn trace_header(cli: &Cli, project: &Project) -> String {
     let mut h = String::new();
     h.push_str("=== ghost processing trace ===\n");
     h.push_str(&format!("ghost-cli {}\n", env!("CARGO_PKG_VERSION")));
-    h.push_str(&format!("mkdocs    : {}\n", cli.mkdocs_yaml.display()));
-    h.push_str(&format!("help-urls : {}\n", cli.help_urls.display()));
-    if let Some((branch, hash)) = docs_git_info(&cli.mkdocs_yaml) {
+    h.push_str(&format!("mkdocs    : {}\n", project.mkdocs_yaml.display()));
+    h.push_str(&format!("help-urls : {}\n", project.help_urls.display()));
+    for extra in &cli.extra_help_urls {
+        h.push_str(&format!("          + {}\n", extra.display()));
+    }
+    if let Some((branch, hash)) = docs_git_info(project.mkdocs_yaml) {
         h.push_str(&format!("docs git  : {branch} @ {hash}\n"));
     }
     if let Some(ex) = cli.exclude.as_deref().filter(|s| !s.is_empty()) {
@@ -106,26 +444,431 @@ fn trace_header(cli: &Cli) -> String {
     h
 }
 
+/// Print the completion script for `shell`, built from the flags in `Cli`'s help text.
+fn print_completions(shell: &str) -> ExitCode {
+    let shell: Shell = match shell.parse() {
+        Ok(shell) => shell,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let help = match Cli::from_args(&["ghost"], &["--help"]) {
+        Err(early_exit) => early_exit.output,
+        Ok(_) => unreachable!("--help always exits early"),
+    };
+    let flags = completions::flags_from_help(&help);
+    print!("{}", completions::generate(shell, "ghost", &flags));
+    ExitCode::SUCCESS
+}
+
 fn main() -> ExitCode {
-    let cli: Cli = argh::from_env();
+    // Handled ahead of argh, which would otherwise insist on --mkdocs-yaml and --help-urls.
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(i) = args.iter().position(|a| a == "--generate-completions") {
+        return match args.get(i + 1) {
+            Some(shell) => print_completions(shell),
+            None => {
+                eprintln!("Error: --generate-completions needs a shell: bash, zsh or fish");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let mut cli: Cli = argh::from_env();
+    // The project root is the directory of the (first) mkdocs.yml, not wherever ghost runs.
+    let project_root = cli
+        .mkdocs_yaml
+        .first()
+        .and_then(|path| path.parent())
+        .unwrap_or(Path::new("."))
+        .to_path_buf();
+    let config = match load_config(&project_root, cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            let default_path = project_root.join(GHOST_TOML);
+            let path = cli.config.as_deref().unwrap_or(&default_path);
+            eprintln!("Error: cannot read settings from {}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+    apply_config(&mut cli, &config);
+    // JSON and SARIF reports are printed on their own at the end, in place of the text
+    // sections.
+    if cli.output_format != OutputFormat::Text {
+        cli.quiet = true;
+    }
+    color::set_enabled(cli.output_format == OutputFormat::Text && cli.color.enabled());
+    if cli.diff {
+        if cli.load_baseline.is_none() {
+            eprintln!("Error: --diff needs --load-baseline");
+            return ExitCode::FAILURE;
+        }
+        if cli.output_format != OutputFormat::Text {
+            eprintln!("Error: --diff only writes text output");
+            return ExitCode::FAILURE;
+        }
+        cli.quiet = true;
+    }
+
+    let projects = match projects(&cli, &config) {
+        Ok(projects) => projects,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.watch {
+        return watch_and_run(&cli, &projects);
+    }
+    run(&cli, &projects)
+}
+
+/// Fill in what the command line leaves unset from the project's .ghost.toml, as though
+/// its settings had been given as flags; a switch set in either is on.
+fn apply_config(cli: &mut Cli, config: &ProjectConfig) {
+    if cli.help_urls.is_empty() {
+        cli.help_urls.extend(config.help_urls_path.clone());
+    }
+    if cli.exclude.is_none() && !config.exclude_subsites.is_empty() {
+        cli.exclude = Some(config.exclude_subsites.join(","));
+    }
+    if cli.require_frontmatter.is_empty() {
+        cli.require_frontmatter = config.required_frontmatter_fields.clone();
+    }
+    cli.heading_hierarchy |= config.check_headings;
+    cli.duplicate_anchors |= config.check_duplicate_anchors;
+    cli.unlabelled_code |= config.check_unlabelled_code_blocks;
+    cli.multiple_h1 |= config.check_multiple_h1;
+    cli.duplicate_images |= config.check_duplicate_images;
+    cli.title_mismatches |= config.check_title_consistency;
+    cli.suggest_fixes |= config.suggest_fixes;
+    cli.external_links |= config.check_external_links;
+    cli.external_timeout = cli.external_timeout.or(config.external_link_timeout);
+    cli.external_cache_ttl = cli.external_cache_ttl.or(config.external_link_cache_ttl);
+}
+
+/// One site to audit: a mkdocs.yml, the help_urls.h that goes with it, and the settings
+/// from .ghost.toml.
+struct Project<'a> {
+    mkdocs_yaml: &'a Path,
+    help_urls: &'a Path,
+    config: &'a ProjectConfig,
+}
+
+/// Pair each --mkdocs-yaml with its --help-urls, checking the options that write or
+/// read a single file aren't given with several sites.
+fn projects<'a>(cli: &'a Cli, config: &'a ProjectConfig) -> Result<Vec<Project<'a>>, String> {
+    let help_urls = match (cli.mkdocs_yaml.len(), cli.help_urls.len()) {
+        (0, _) => return Err("--mkdocs-yaml is required".to_string()),
+        (_, 0) => {
+            return Err("--help-urls is required, or help_urls_path in .ghost.toml".to_string());
+        }
+        (_, 1) => vec![cli.help_urls[0].as_path(); cli.mkdocs_yaml.len()],
+        (sites, headers) if sites == headers => {
+            cli.help_urls.iter().map(PathBuf::as_path).collect()
+        }
+        (sites, headers) => {
+            return Err(format!(
+                "{headers} --help-urls given for {sites} --mkdocs-yaml; give one, or one per site"
+            ));
+        }
+    };
+    if cli.mkdocs_yaml.len() > 1 {
+        let single_site = [
+            ("--save-result", cli.save_result.is_some()),
+            ("--export-dot", cli.export_dot.is_some()),
+            ("--load-baseline", cli.load_baseline.is_some()),
+            ("--nav-order-baseline", cli.nav_order_baseline.is_some()),
+            ("--trace", !cli.trace.is_empty()),
+            ("--output-format", cli.output_format != OutputFormat::Text),
+        ];
+        if let Some((flag, _)) = single_site.iter().find(|(_, given)| *given) {
+            return Err(format!("{flag} works with a single --mkdocs-yaml"));
+        }
+    }
+    Ok(cli
+        .mkdocs_yaml
+        .iter()
+        .zip(help_urls)
+        .map(|(mkdocs_yaml, help_urls)| Project {
+            mkdocs_yaml,
+            help_urls,
+            config,
+        })
+        .collect())
+}
+
+#[cfg(feature = "watch")]
+fn watch_and_run(cli: &Cli, projects: &[Project]) -> ExitCode {
+    let mut roots = Vec::new();
+    for project in projects {
+        match ghost_lib::find_include_roots(project.mkdocs_yaml) {
+            Ok(include_roots) => roots.extend(include_roots),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        // The root mkdocs.yml and its own docs/ live beside it.
+        roots.push(
+            project
+                .mkdocs_yaml
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from(".")),
+        );
+    }
+    match watch::watch(&roots, || {
+        run(cli, projects);
+    }) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: watching for changes failed: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(not(feature = "watch"))]
+fn watch_and_run(_cli: &Cli, _projects: &[Project]) -> ExitCode {
+    eprintln!("Error: --watch needs ghost built with the `watch` feature");
+    ExitCode::FAILURE
+}
+
+/// Audit each project in turn and print the reports `cli` asks for, under a heading
+/// per project when there are several.
+fn run(cli: &Cli, projects: &[Project]) -> ExitCode {
+    let [project] = projects else {
+        let mut failed = false;
+        let mut total_issues = 0;
+        for project in projects {
+            if !cli.quiet {
+                println!(
+                    "\n{}",
+                    color::bold(&format!("=== {} ===", project.mkdocs_yaml.display()))
+                );
+            }
+            failed |= run_project(cli, project, &mut total_issues) != ExitCode::SUCCESS;
+        }
+        if !cli.quiet && !cli.summary {
+            println!();
+            println!(
+                "{} {}",
+                color::bold(&format!("Total issues in {} sites:", projects.len())),
+                total_issues
+            );
+        }
+        return if failed {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        };
+    };
+    run_project(cli, project, &mut 0)
+}
 
+/// Run the audit of one project and print its reports, adding its issue count to
+/// `issues_so_far`.
+fn run_project(cli: &Cli, project: &Project, issues_so_far: &mut usize) -> ExitCode {
     // Get the monorepo root (parent of mkdocs.yml) for relative path display
-    let monorepo_root = cli.mkdocs_yaml.parent().map(|p| p.to_path_buf());
+    let monorepo_root = project.mkdocs_yaml.parent().map(|p| p.to_path_buf());
+
+    let baseline = match cli.load_baseline.as_deref().map(load_audit_result) {
+        Some(Ok(b)) => Some(b),
+        Some(Err(e)) => {
+            eprintln!("Error: cannot load baseline: {}", e);
+            return ExitCode::FAILURE;
+        }
+        None => None,
+    };
+
+    let allowlist = match cli.allowlist.as_deref().map(load_allowlist) {
+        Some(Ok(a)) => Some(a),
+        Some(Err(e)) => {
+            eprintln!("Error: cannot load allowlist: {}", e);
+            return ExitCode::FAILURE;
+        }
+        None => None,
+    };
+
+    let nav_order_changes = match &cli.nav_order_baseline {
+        Some(path) => {
+            let nav = match load_mkdocs_nav(project.mkdocs_yaml) {
+                Ok(nav) => nav,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            if cli.update_nav_baseline {
+                if let Err(e) = save_nav_baseline(&nav, path) {
+                    eprintln!(
+                        "Error: cannot save nav baseline to {}: {}",
+                        path.display(),
+                        e
+                    );
+                    return ExitCode::FAILURE;
+                }
+                Vec::new()
+            } else {
+                match load_nav_baseline(path) {
+                    Ok(old) => detect_nav_reordering(&nav, &old),
+                    Err(e) => {
+                        eprintln!("Error: cannot load nav baseline: {}", e);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+        }
+        None => Vec::new(),
+    };
 
     let trace_opts = TraceOptions {
         targets: cli.trace.clone(),
     };
-    let (result, trace) = match audit_traced(&cli.mkdocs_yaml, &cli.help_urls, &trace_opts) {
+    let config = GhostConfig {
+        site_base_url: cli.base_url.clone(),
+        title_case_style: cli.title_case.unwrap_or_default(),
+        generic_alt_texts: cli.generic_alt_text.clone(),
+        localhost_links_severity: cli.localhost_links_severity.unwrap_or_default(),
+        private_ip_links_severity: cli.private_ips_severity.unwrap_or(Severity::Warning),
+        scheme_relative_links_severity: cli.scheme_relative_severity.unwrap_or(Severity::Warning),
+        pages_with_todos_severity: if cli.fail_on_todos {
+            Severity::Error
+        } else {
+            Severity::Warning
+        },
+        allowed_extensions: cli.allow_extension.clone(),
+        code_languages: cli.code_language.clone(),
+        max_heading_level: cli.max_heading_level,
+        max_blank_lines: cli.max_blank_lines,
+        min_non_link_words: cli.min_non_link_words,
+        max_include_depth: cli.max_include_depth.unwrap_or(usize::MAX),
+        max_svg_bytes: cli.max_svg_bytes,
+        extra_help_urls: cli.extra_help_urls.clone(),
+        min_word_count: cli.min_word_count.unwrap_or(0),
+        min_title_similarity: cli.min_title_similarity,
+        ..GhostConfig::default()
+    };
+    let options = project
+        .config
+        .audit_options()
+        .check_headings(cli.heading_hierarchy)
+        .check_duplicate_anchors(cli.duplicate_anchors)
+        .check_unlabelled_code_blocks(cli.unlabelled_code)
+        .check_multiple_h1(cli.multiple_h1)
+        .check_duplicate_images(cli.duplicate_images)
+        // Checks whose reports aren't asked for are skipped, not just left unprinted.
+        .skip_unexpected_binaries(!cli.check_binaries)
+        .skip_subsites_without_mkdocs(!cli.subsite_check)
+        .skip_dead_css_selectors(!cli.dead_css)
+        .skip_svg_checks(!cli.check_svgs)
+        .skip_case_sensitivity(!cli.case_check)
+        .skip_orphan_mkdocs_files(!cli.orphan_configs)
+        .skip_cross_file_help_url_duplicates(!cli.cross_file_dup_urls)
+        .skip_css_import_cycles(!cli.css_cycles)
+        .check_title_consistency(cli.title_mismatches)
+        .suggest_fixes(cli.suggest_fixes)
+        .check_external_links(cli.external_links)
+        .external_link_timeout(Duration::from_secs(cli.external_timeout.unwrap_or(10)))
+        .external_link_cache_ttl(Duration::from_secs(
+            cli.external_cache_ttl.unwrap_or(24) * 60 * 60,
+        ));
+    // The bar is drawn on stderr, so it never mixes with JSON or SARIF on stdout.
+    let bar = progress::bar(!cli.quiet && std::io::stdout().is_terminal());
+    let options = options.on_progress({
+        let bar = bar.clone();
+        move |p| progress::update(&bar, p)
+    });
+    // Fields from .ghost.toml are already in the options, and in cli.require_frontmatter
+    // when the command line gives none.
+    let options = cli
+        .require_frontmatter
+        .iter()
+        .filter(|field| !project.config.required_frontmatter_fields.contains(field))
+        .fold(options, |options, field| {
+            options.require_frontmatter_field(field)
+        })
+        .build();
+    // --exclude replaces the config's exclude_subsites, which apply_config put in it when
+    // it wasn't given.
+    let options = AuditOptions {
+        exclude_subsites: cli
+            .exclude
+            .iter()
+            .flat_map(|s| s.split(','))
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect(),
+        ..options
+    };
+    let (mut result, trace) = match audit_with_config_and_options(
+        project.mkdocs_yaml,
+        project.help_urls,
+        &config,
+        &options,
+        &trace_opts,
+    ) {
         Ok(rt) => rt,
         Err(e) => {
+            bar.finish_and_clear();
             eprintln!("Error: {}", e);
             return ExitCode::FAILURE;
         }
     };
+    bar.finish_and_clear();
+    if let Some(allowlist) = &allowlist {
+        apply_allowlist(&mut result, allowlist);
+    }
+
+    if let Some(ref path) = cli.save_result
+        && let Err(e) = save_audit_result(&result, path)
+    {
+        eprintln!("Error: cannot save result to {}: {}", path.display(), e);
+        return ExitCode::FAILURE;
+    }
+
+    if let Some(ref path) = cli.export_dot {
+        let nav_pages = match load_mkdocs_nav(project.mkdocs_yaml)
+            .and_then(|nav| nav_tree(&nav, monorepo_root.as_deref().unwrap_or(Path::new(""))))
+        {
+            Ok(tree) => {
+                let mut pages = HashSet::new();
+                collect_nav_pages(&tree, &mut pages);
+                pages
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        let scanned_contents: HashMap<PathBuf, String> = result
+            .page_links
+            .iter()
+            .map(|(page, _)| {
+                let content = std::fs::read_to_string(page).unwrap_or_default();
+                (page.clone(), content)
+            })
+            .collect();
+        let dot = to_dot(
+            &page_dependency_graph(&result, &scanned_contents),
+            monorepo_root.as_deref(),
+            &nav_pages,
+            &result.ghost,
+        );
+        if let Err(e) = std::fs::write(path, dot) {
+            eprintln!("Error: cannot write graph to {}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    }
 
     // Write the processing trace (if requested) to a forwardable log file.
     if !cli.trace.is_empty() {
-        let contents = format!("{}{}", trace_header(&cli), trace.text);
+        let contents = format!("{}{}", trace_header(cli, project), trace.text);
         match std::fs::write(&cli.trace_out, contents) {
             Ok(()) => eprintln!("Wrote processing trace to {}", cli.trace_out.display()),
             Err(e) => eprintln!(
@@ -143,18 +886,6 @@ fn main() -> ExitCode {
         .map(|s| s.split(',').map(|x| x.trim()).collect())
         .unwrap_or_default();
 
-    // Helper to check if a path is in an excluded subsite
-    let is_excluded = |p: &PathBuf| -> bool {
-        if let Some(ref root) = monorepo_root
-            && let Ok(rel) = p.strip_prefix(root)
-            && let Some(first_component) = rel.components().next()
-        {
-            let subsite = first_component.as_os_str().to_string_lossy();
-            return excluded.iter().any(|&ex| ex == subsite);
-        }
-        false
-    };
-
     // Helper to display paths relative to monorepo root
     let relative_path = |p: &PathBuf| -> String {
         if let Some(ref root) = monorepo_root {
@@ -172,7 +903,54 @@ fn main() -> ExitCode {
         && !cli.help_missing
         && !cli.broken_links
         && !cli.missing_images
-        && !cli.orphan_images;
+        && !cli.orphan_images
+        && !cli.absolute_internal
+        && !cli.query_string_links
+        && cli.title_case.is_none()
+        && !cli.alt_text_quality
+        && !cli.localhost_links
+        && !cli.check_binaries
+        && !cli.url_encoded_links
+        && !cli.single_child_sections
+        && !cli.unknown_code_languages
+        && !cli.site_dir_files
+        && !cli.help_url_nav_check
+        && !cli.nav_stub_pages
+        && cli.max_heading_level.is_none()
+        && cli.max_blank_lines.is_none()
+        && !cli.repeated_words
+        && !cli.subsite_check
+        && !cli.duplicate_sections
+        && !cli.relay_pages
+        && !cli.non_md_nav
+        && cli.max_include_depth.is_none()
+        && !cli.multiple_h1
+        && !cli.dead_css
+        && !cli.absolute_image_paths
+        && !cli.private_ips
+        && !cli.check_svgs
+        && !cli.case_check
+        && !cli.mixed_image_refs
+        && !cli.orphan_configs
+        && !cli.scheme_relative
+        && !cli.cross_file_dup_urls
+        && !cli.frontmatter_todos
+        && !cli.fail_on_todos
+        && !cli.include_only_sections
+        && !cli.duplicate_images
+        && !cli.css_cycles
+        && !cli.outside_docs
+        && !cli.theme_check
+        && !cli.empty_sections
+        && !cli.broken_anchors
+        && !cli.duplicate_nav
+        && cli.min_word_count.is_none()
+        && !cli.heading_hierarchy
+        && !cli.duplicate_anchors
+        && !cli.unlabelled_code
+        && cli.require_frontmatter.is_empty()
+        && !cli.title_mismatches
+        && !cli.external_links;
 
     let show_nav_missing = show_all || cli.nav_missing;
     let show_ghost = show_all || cli.ghost;
@@ -180,72 +958,243 @@ fn main() -> ExitCode {
     let show_broken_links = show_all || cli.broken_links;
     let show_missing_images = show_all || cli.missing_images;
     let show_orphan_images = show_all || cli.orphan_images;
+    let show_absolute_internal = cli.absolute_internal;
+    let show_query_string_links = cli.query_string_links;
+    let show_title_case = cli.title_case.is_some();
+    let show_alt_text_quality = cli.alt_text_quality;
+    let show_localhost_links = cli.localhost_links;
+    let show_private_ips = cli.private_ips;
+    let show_scheme_relative = cli.scheme_relative;
+    let show_unexpected_binaries = cli.check_binaries;
+    let show_url_encoded_links = cli.url_encoded_links;
+    let show_single_child_sections = cli.single_child_sections;
+    let show_unknown_code_languages = cli.unknown_code_languages;
+    let show_site_dir_files = cli.site_dir_files;
+    let show_help_url_nav_check = cli.help_url_nav_check;
+    let show_nav_stub_pages = cli.nav_stub_pages;
+    let show_deep_headings = cli.max_heading_level.is_some();
+    let show_repeated_words = cli.repeated_words;
+    let show_subsite_check = cli.subsite_check;
+    let show_duplicate_sections = cli.duplicate_sections;
+    let show_relay_pages = cli.relay_pages;
+    let show_non_md_nav = cli.non_md_nav;
+    let show_include_depth = cli.max_include_depth.is_some();
+    let show_multiple_h1 = cli.multiple_h1;
+    let show_dead_css = cli.dead_css;
+    let show_absolute_image_paths = cli.absolute_image_paths;
+    let show_check_svgs = cli.check_svgs;
+    let show_case_check = cli.case_check;
+    let show_mixed_image_refs = cli.mixed_image_refs;
+    let show_orphan_configs = cli.orphan_configs;
+    let show_cross_file_dup_urls = cli.cross_file_dup_urls;
+    let show_frontmatter_todos = cli.frontmatter_todos || cli.fail_on_todos;
+    let show_excess_blank_lines = cli.max_blank_lines.is_some();
+    let show_include_only_sections = cli.include_only_sections;
+    let show_duplicate_images = cli.duplicate_images;
+    let show_css_cycles = cli.css_cycles;
+    let show_outside_docs = cli.outside_docs;
+    let show_theme_check = cli.theme_check;
+    let show_empty_sections = cli.empty_sections;
+    let show_broken_anchors = cli.broken_anchors;
+    let show_duplicate_nav = cli.duplicate_nav;
+    let show_stub_pages = cli.min_word_count.is_some();
+    let show_heading_errors = cli.heading_hierarchy;
+    let show_duplicate_anchors = cli.duplicate_anchors;
+    let show_unlabelled_code = cli.unlabelled_code;
+    let show_missing_frontmatter = !cli.require_frontmatter.is_empty();
+    let show_title_mismatches = cli.title_mismatches;
+    let show_external_links = cli.external_links;
 
-    let mut total_issues = 0;
-
-    // Filter results to exclude specified subsites
-    let nav_missing: Vec<_> = result
-        .nav_missing
-        .iter()
-        .filter(|p| !is_excluded(p))
-        .collect();
-    let ghost: Vec<_> = result.ghost.iter().filter(|p| !is_excluded(p)).collect();
-    let help_missing: Vec<_> = result
-        .help_missing
-        .iter()
-        .filter(|p| !is_excluded(p))
-        .collect();
-    let broken_links: Vec<_> = result
-        .broken_links
-        .iter()
-        .filter(|bl| !is_excluded(&bl.from))
-        .collect();
-    let missing_images: Vec<_> = result
-        .missing_images
-        .iter()
-        .filter(|bi| !is_excluded(&bi.from))
-        .collect();
-    let orphan_images: Vec<_> = result
-        .orphan_images
-        .iter()
-        .filter(|p| !is_excluded(p))
-        .collect();
-
-    if show_nav_missing {
-        total_issues += nav_missing.len();
-        if !cli.quiet {
-            print_section("Missing nav entries", &nav_missing, cli.summary, |p| {
-                relative_path(p)
-            });
+    // Leave out the excluded subsites' files, and the reports that weren't asked for, so
+    // that the totals count only what's shown.
+    let mut shown = match monorepo_root {
+        Some(ref root) => result.clone().filter_by_subsite(&excluded, root),
+        None => result.clone(),
+    };
+    macro_rules! hide_unless {
+        ($($show:expr => [$($field:ident),+]),* $(,)?) => {
+            $(if !$show {
+                $(shown.$field.clear();)+
+            })*
+        };
+    }
+    hide_unless!(
+        show_nav_missing => [nav_missing],
+        show_ghost => [ghost],
+        show_help_missing => [help_missing, malformed_help_urls],
+        show_broken_links => [
+            broken_links,
+            broken_links_in_nav_descriptions,
+            description_broken_links
+        ],
+        show_missing_images => [missing_images],
+        show_orphan_images => [orphan_images],
+        show_absolute_internal => [absolute_internal_links],
+        show_query_string_links => [links_with_query_strings],
+        show_title_case => [title_case_violations],
+        show_alt_text_quality => [poor_alt_text],
+        show_localhost_links => [localhost_links],
+        show_private_ips => [private_ip_links],
+        show_scheme_relative => [scheme_relative_links],
+        show_unexpected_binaries => [unexpected_binaries],
+        show_url_encoded_links => [url_encoded_internal_links],
+        show_single_child_sections => [single_child_sections],
+        show_unknown_code_languages => [pages_with_unknown_code_languages],
+        show_site_dir_files => [files_inside_site_dir],
+        show_help_url_nav_check => [help_url_pages_not_in_nav],
+        show_nav_stub_pages => [nav_pages_without_content],
+        show_deep_headings => [pages_with_deep_headings],
+        show_repeated_words => [links_with_repeated_words],
+        show_subsite_check => [subsites_without_mkdocs],
+        show_duplicate_sections => [duplicate_section_titles],
+        show_relay_pages => [relay_pages],
+        show_non_md_nav => [non_md_nav_items],
+        show_include_depth => [include_depth_violations],
+        show_multiple_h1 => [pages_with_multiple_h1, pages_with_h1_not_first],
+        show_dead_css => [dead_css_selectors],
+        show_absolute_image_paths => [absolute_fs_image_refs],
+        show_check_svgs => [oversized_svgs, svgs_with_embedded_rasters],
+        show_case_check => [case_sensitivity_violations, case_collisions],
+        show_mixed_image_refs => [pages_with_mixed_image_styles],
+        show_orphan_configs => [orphan_mkdocs_files],
+        show_cross_file_dup_urls => [cross_file_help_url_duplicates],
+        show_frontmatter_todos => [pages_with_todos],
+        show_excess_blank_lines => [pages_with_excess_blank_lines],
+        show_include_only_sections => [include_only_sections],
+        show_duplicate_images => [duplicate_image_files],
+        show_css_cycles => [css_import_cycles],
+        show_outside_docs => [nav_entries_outside_docs],
+        show_theme_check => [unknown_theme_features],
+        show_empty_sections => [empty_nav_sections],
+        show_broken_anchors => [broken_anchors],
+        show_duplicate_nav => [duplicate_nav],
+        show_stub_pages => [stub_pages],
+        show_heading_errors => [heading_errors],
+        show_duplicate_anchors => [pages_with_duplicate_anchors],
+        show_unlabelled_code => [pages_with_unlabelled_code],
+        show_missing_frontmatter => [missing_frontmatter_fields],
+        show_title_mismatches => [title_mismatches],
+        show_external_links => [broken_external_links],
+    );
+    // The nav order check runs outside the audit, so its changes are added on below.
+    let mut total_issues = shown.total_issues_with_config(&config);
+    // Findings from reports configured as warnings: shown, but they don't fail the run.
+    let total_warnings = shown.total_warnings(&config);
+    // The JSON output lists every report shown, so its items add up to the totals.
+    let mut other_reports = BTreeMap::new();
+    if cli.output_format == OutputFormat::Json {
+        other_reports = shown.other_report_items(monorepo_root.as_deref());
+        if !nav_order_changes.is_empty() {
+            let changes = nav_order_changes
+                .iter()
+                .filter_map(|change| serde_json::to_value(change).ok())
+                .collect();
+            other_reports.insert("nav_order_changes".to_string(), changes);
         }
     }
+    let nav_missing = shown.nav_missing;
+    let ghost = shown.ghost;
+    let help_missing = shown.help_missing;
+    let broken_links = shown.broken_links;
+    let broken_links_in_nav_descriptions = shown.broken_links_in_nav_descriptions;
+    let missing_images = shown.missing_images;
+    let orphan_images = shown.orphan_images;
+    let absolute_internal_links = shown.absolute_internal_links;
+    let links_with_query_strings = shown.links_with_query_strings;
+    let poor_alt_text = shown.poor_alt_text;
+    let localhost_links = shown.localhost_links;
+    let private_ip_links = shown.private_ip_links;
+    let scheme_relative_links = shown.scheme_relative_links;
+    let unexpected_binaries = shown.unexpected_binaries;
+    let nav_pages_without_content = shown.nav_pages_without_content;
+    let help_url_pages_not_in_nav = shown.help_url_pages_not_in_nav;
+    let files_inside_site_dir = shown.files_inside_site_dir;
+    let absolute_fs_image_refs = shown.absolute_fs_image_refs;
+    let dead_css_selectors = shown.dead_css_selectors;
+    let pages_with_multiple_h1 = shown.pages_with_multiple_h1;
+    let pages_with_h1_not_first = shown.pages_with_h1_not_first;
+    let relay_pages = shown.relay_pages;
+    let subsites_without_mkdocs = shown.subsites_without_mkdocs;
+    let repeated_words = shown.links_with_repeated_words;
+    let deep_headings = shown.pages_with_deep_headings;
+    let unknown_code_languages = shown.pages_with_unknown_code_languages;
+    let url_encoded_links = shown.url_encoded_internal_links;
+    let oversized_svgs = shown.oversized_svgs;
+    let svgs_with_embedded_rasters = shown.svgs_with_embedded_rasters;
+    let case_sensitivity_violations = shown.case_sensitivity_violations;
+    let pages_with_mixed_image_styles = shown.pages_with_mixed_image_styles;
+    let orphan_mkdocs_files = shown.orphan_mkdocs_files;
+    let cross_file_help_url_duplicates = shown.cross_file_help_url_duplicates;
+    let pages_with_todos = shown.pages_with_todos;
+    let pages_with_excess_blank_lines = shown.pages_with_excess_blank_lines;
+    let duplicate_image_files = shown.duplicate_image_files;
+    let css_import_cycles = shown.css_import_cycles;
+    let broken_anchors = shown.broken_anchors;
+    let duplicate_nav = shown.duplicate_nav;
+    let stub_pages = shown.stub_pages;
+    let heading_errors = shown.heading_errors;
+    let duplicate_anchors = shown.pages_with_duplicate_anchors;
+    let unlabelled_code = shown.pages_with_unlabelled_code;
+    let missing_frontmatter = shown.missing_frontmatter_fields;
+    let title_mismatches = shown.title_mismatches;
+    let case_collisions = shown.case_collisions;
+    let broken_external_links = shown.broken_external_links;
 
-    if show_ghost {
-        total_issues += ghost.len();
-        if !cli.quiet {
-            print_section("Ghost files (orphans)", &ghost, cli.summary, |p| {
-                relative_path(p)
-            });
-        }
+    if show_nav_missing && !cli.quiet {
+        print_section("Missing nav entries", &nav_missing, cli.summary, |p| {
+            relative_path(p)
+        });
+    }
+
+    if show_ghost && !cli.quiet {
+        print_section("Ghost files (orphans)", &ghost, cli.summary, |p| {
+            relative_path(p)
+        });
     }
 
     if show_help_missing {
-        total_issues += help_missing.len();
         if !cli.quiet {
-            print_section("Missing help URLs", &help_missing, cli.summary, |p| {
-                relative_path(p)
-            });
+            print_section(
+                "Missing help URLs",
+                &help_missing,
+                cli.summary,
+                |m| match &m.suggested_help_url_fix {
+                    Some(fix) if cli.suggest_help_fixes => {
+                        format!("{} (did you mean {}?)", relative_path(&m.path), fix)
+                    }
+                    _ => relative_path(&m.path),
+                },
+            );
+        }
+        if !cli.quiet {
+            print_section(
+                &severity_title("HELP_URLs already naming docs/", Severity::Warning),
+                &shown.malformed_help_urls,
+                cli.summary,
+                |r| format!("line {}: {}", r.line, r.text),
+            );
         }
     }
 
     if show_broken_links {
-        total_issues += broken_links.len();
+        // Links to a page that is in the nav but missing are fixed along with that nav
+        // entry, so mark them apart from links that are broken in their own right.
+        let nav_missing_targets: HashSet<(&PathBuf, &str)> = result
+            .nav_missing_link_sources
+            .iter()
+            .map(|(from, link)| (from, link.as_str()))
+            .collect();
         if !cli.quiet {
             print_section("Broken links", &broken_links, cli.summary, |bl| {
+                let marker = if nav_missing_targets.contains(&(&bl.from, bl.link.as_str())) {
+                    " [nav-missing]"
+                } else {
+                    ""
+                };
                 // For help-URL-sourced pages, show the actual HELP_URL(...) line(s) from
                 // help_urls.h in place of the page path so the entry is directly locatable.
-                if bl.help_refs.is_empty() {
-                    format!("{} -> {}", relative_path(&bl.from), bl.link)
+                let line = if bl.help_refs.is_empty() {
+                    format!("{} -> {}{}", relative_path(&bl.from), bl.link, marker)
                 } else {
                     let refs = bl
                         .help_refs
@@ -253,55 +1202,1003 @@ fn main() -> ExitCode {
                         .map(|r| r.text.clone())
                         .collect::<Vec<_>>()
                         .join(" | ");
-                    format!("[H] {} -> {}", refs, bl.link)
-                }
+                    format!("[H] {} -> {}{}", refs, bl.link, marker)
+                };
+                bl.suggestions.iter().fold(line, |line, s| {
+                    format!("{line}\n      did you mean {}?", relative_path(s))
+                })
             });
         }
+        // Rare enough that an empty section would only be noise.
+        if !cli.quiet && !broken_links_in_nav_descriptions.is_empty() {
+            print_section(
+                "Broken links in nav descriptions",
+                &broken_links_in_nav_descriptions,
+                cli.summary,
+                |bl| format!("{} -> {}", relative_path(&bl.from), bl.link),
+            );
+        }
+        if !cli.quiet && !shown.description_broken_links.is_empty() {
+            print_section(
+                "Broken links in site_description",
+                &shown.description_broken_links,
+                cli.summary,
+                |link| link.clone(),
+            );
+        }
+    }
+
+    if show_missing_images && !cli.quiet {
+        print_section("Missing images", &missing_images, cli.summary, |bi| {
+            format!("{} -> {}", relative_path(&bi.from), bi.image)
+        });
+    }
+
+    if show_orphan_images && !cli.quiet {
+        print_section("Orphan images", &orphan_images, cli.summary, |p| {
+            relative_path(p)
+        });
+    }
+
+    if show_absolute_internal && !cli.quiet {
+        print_section(
+            "Absolute internal links",
+            &absolute_internal_links,
+            cli.summary,
+            |(from, link)| format!("{} -> {}", relative_path(from), link),
+        );
+    }
+
+    if show_query_string_links && !cli.quiet {
+        print_section(
+            "Links with query strings",
+            &links_with_query_strings,
+            cli.summary,
+            |(from, link)| format!("{} -> {}", relative_path(from), link),
+        );
+    }
+
+    if show_title_case && !cli.quiet {
+        print_section(
+            "Section title case",
+            &shown.title_case_violations,
+            cli.summary,
+            |(path, title)| nav_path(path, title),
+        );
+    }
+
+    if show_alt_text_quality && !cli.quiet {
+        print_section(
+            "Generic image alt text",
+            &poor_alt_text,
+            cli.summary,
+            |(from, src, alt)| format!("{} -> {} (alt: \"{}\")", relative_path(from), src, alt),
+        );
+    }
+
+    if show_localhost_links {
+        let severity = config.localhost_links_severity;
+        if !cli.quiet {
+            print_section(
+                &severity_title("Localhost links", severity),
+                &localhost_links,
+                cli.summary,
+                |(from, link)| format!("{} -> {}", relative_path(from), link),
+            );
+        }
     }
 
-    if show_missing_images {
-        total_issues += missing_images.len();
+    if show_private_ips {
+        let severity = config.private_ip_links_severity;
         if !cli.quiet {
-            print_section("Missing images", &missing_images, cli.summary, |bi| {
-                format!("{} -> {}", relative_path(&bi.from), bi.image)
-            });
+            print_section(
+                &severity_title("Private network links", severity),
+                &private_ip_links,
+                cli.summary,
+                |(from, link)| format!("{} -> {}", relative_path(from), link),
+            );
         }
     }
 
-    if show_orphan_images {
-        total_issues += orphan_images.len();
+    if show_scheme_relative {
+        let severity = config.scheme_relative_links_severity;
         if !cli.quiet {
-            print_section("Orphan images", &orphan_images, cli.summary, |p| {
-                relative_path(p)
-            });
+            print_section(
+                &severity_title("Scheme-relative links", severity),
+                &scheme_relative_links,
+                cli.summary,
+                |(from, link)| format!("{} -> {}", relative_path(from), link),
+            );
+        }
+    }
+
+    if show_unexpected_binaries && !cli.quiet {
+        print_section(
+            "Unexpected files in docs",
+            &unexpected_binaries,
+            cli.summary,
+            |p| relative_path(p),
+        );
+    }
+
+    if show_url_encoded_links && !cli.quiet {
+        print_section(
+            "URL-encoded internal links",
+            &url_encoded_links,
+            cli.summary,
+            |(from, link)| format!("{} -> {}", relative_path(from), link),
+        );
+    }
+
+    if show_single_child_sections && !cli.quiet {
+        print_section(
+            "Single-child sections",
+            &shown.single_child_sections,
+            cli.summary,
+            |(path, child)| format!("{} (only: {})", path.join(" > "), child),
+        );
+    }
+
+    if show_unknown_code_languages && !cli.quiet {
+        print_section(
+            "Unknown code block languages",
+            &unknown_code_languages,
+            cli.summary,
+            |(page, langs)| format!("{}: {}", relative_path(page), langs.join(", ")),
+        );
+    }
+
+    if show_site_dir_files && !cli.quiet {
+        print_section(
+            "Files inside site_dir",
+            &files_inside_site_dir,
+            cli.summary,
+            |p| relative_path(p),
+        );
+    }
+
+    if show_help_url_nav_check && !cli.quiet {
+        print_section(
+            "Help URL pages not in nav",
+            &help_url_pages_not_in_nav,
+            cli.summary,
+            |p| relative_path(p),
+        );
+    }
+
+    if show_nav_stub_pages && !cli.quiet {
+        print_section(
+            "Nav pages without content",
+            &nav_pages_without_content,
+            cli.summary,
+            |p| relative_path(p),
+        );
+    }
+
+    if show_deep_headings && !cli.quiet {
+        print_section(
+            "Headings too deep",
+            &deep_headings,
+            cli.summary,
+            |(page, headings)| {
+                let headings: Vec<String> = headings
+                    .iter()
+                    .map(|(line, level, text)| format!("line {line}: H{level} {text}"))
+                    .collect();
+                format!("{}: {}", relative_path(page), headings.join("; "))
+            },
+        );
+    }
+
+    if show_repeated_words && !cli.quiet {
+        print_section(
+            "Repeated words in link text",
+            &repeated_words,
+            cli.summary,
+            |(page, texts)| format!("{}: [{}]", relative_path(page), texts.join("], [")),
+        );
+    }
+
+    if show_subsite_check && !cli.quiet {
+        print_section(
+            "Subsites without mkdocs.yml",
+            &subsites_without_mkdocs,
+            cli.summary,
+            |p| relative_path(p),
+        );
+    }
+
+    if show_duplicate_sections && !cli.quiet {
+        print_section(
+            "Duplicate section titles",
+            &shown.duplicate_section_titles,
+            cli.summary,
+            |(path, title)| nav_path(path, title),
+        );
+    }
+
+    if show_relay_pages && !cli.quiet {
+        print_section("Relay pages", &relay_pages, cli.summary, |p| {
+            relative_path(p)
+        });
+    }
+
+    if show_non_md_nav && !cli.quiet {
+        print_section(
+            "Non-markdown nav entries",
+            &shown.non_md_nav_items,
+            cli.summary,
+            |item| item.clone(),
+        );
+    }
+
+    // A missing include hides a whole nav, so it's always worth reporting.
+    if !cli.quiet && !shown.missing_includes.is_empty() {
+        print_section(
+            "Missing includes",
+            &shown.missing_includes,
+            cli.summary,
+            |p| relative_path(p),
+        );
+    }
+
+    // MkDocs rejects these, so they're reported whatever was asked for.
+    if !cli.quiet && !shown.nav_errors.is_empty() {
+        print_section(
+            "Invalid nav entries",
+            &shown.nav_errors,
+            cli.summary,
+            |e| match e {
+                NavError::MultipleKeysInPage { keys } => {
+                    format!("page entry with several titles: {}", keys.join(", "))
+                }
+                NavError::Unrecognised { entry } => format!("unrecognised entry: {entry}"),
+            },
+        );
+    }
+
+    if show_include_depth && !cli.quiet {
+        print_section(
+            "Includes nested too deeply",
+            &shown.include_depth_violations,
+            cli.summary,
+            |(file, depth)| format!("{} (depth {})", relative_path(file), depth),
+        );
+    }
+
+    if show_multiple_h1 && !cli.quiet {
+        print_section(
+            "Pages with multiple H1 headings",
+            &pages_with_multiple_h1,
+            cli.summary,
+            |p| relative_path(p),
+        );
+        print_section(
+            "Pages with a heading before the H1",
+            &pages_with_h1_not_first,
+            cli.summary,
+            |p| relative_path(p),
+        );
+    }
+
+    if show_dead_css && !cli.quiet {
+        print_section(
+            "Potentially dead CSS selectors",
+            &dead_css_selectors,
+            cli.summary,
+            |(css, classes)| {
+                let classes: Vec<String> = classes.iter().map(|c| format!(".{c}")).collect();
+                format!("{}: {}", relative_path(css), classes.join(", "))
+            },
+        );
+    }
+
+    if show_absolute_image_paths && !cli.quiet {
+        print_section(
+            "Absolute filesystem image paths",
+            &absolute_fs_image_refs,
+            cli.summary,
+            |(from, src)| format!("{} -> {}", relative_path(from), src),
+        );
+    }
+
+    if show_check_svgs && !cli.quiet {
+        print_section(
+            "Oversized SVGs",
+            &oversized_svgs,
+            cli.summary,
+            |(svg, size)| format!("{} ({} bytes)", relative_path(svg), size),
+        );
+        print_section(
+            "SVGs with embedded raster images",
+            &svgs_with_embedded_rasters,
+            cli.summary,
+            |p| relative_path(p),
+        );
+    }
+
+    if show_case_check {
+        if !cli.quiet {
+            print_section(
+                "Paths differing only in case",
+                &case_sensitivity_violations,
+                cli.summary,
+                |(a, b)| format!("{} <-> {}", relative_path(a), relative_path(b)),
+            );
         }
+        if !cli.quiet {
+            print_section(
+                "Nav entries differing only in case",
+                &case_collisions,
+                cli.summary,
+                |(a, b)| format!("{} <-> {}", relative_path(a), relative_path(b)),
+            );
+        }
+    }
+
+    if show_mixed_image_refs && !cli.quiet {
+        print_section(
+            "Pages with mixed image reference styles",
+            &pages_with_mixed_image_styles,
+            cli.summary,
+            |p| relative_path(p),
+        );
+    }
+
+    if show_orphan_configs && !cli.quiet {
+        print_section(
+            "Orphan mkdocs.yml files",
+            &orphan_mkdocs_files,
+            cli.summary,
+            |p| relative_path(p),
+        );
+    }
+
+    if show_cross_file_dup_urls && !cli.quiet {
+        print_section(
+            "Help URLs shared across headers",
+            &cross_file_help_url_duplicates,
+            cli.summary,
+            |(page, headers)| {
+                let headers: Vec<String> =
+                    headers.iter().map(|h| h.display().to_string()).collect();
+                format!("{} <- {}", relative_path(page), headers.join(", "))
+            },
+        );
+    }
+
+    if show_frontmatter_todos {
+        let severity = config.pages_with_todos_severity;
+        if !cli.quiet {
+            print_section(
+                &severity_title("Pages with front matter TODOs", severity),
+                &pages_with_todos,
+                cli.summary,
+                |(page, todos)| format!("{}: {}", relative_path(page), todos.join("; ")),
+            );
+        }
+    }
+
+    if show_excess_blank_lines && !cli.quiet {
+        print_section(
+            "Pages with excess blank lines",
+            &pages_with_excess_blank_lines,
+            cli.summary,
+            |(page, lines)| {
+                let lines: Vec<String> = lines.iter().map(|l| format!("line {l}")).collect();
+                format!("{}: {}", relative_path(page), lines.join(", "))
+            },
+        );
+    }
+
+    if show_include_only_sections && !cli.quiet {
+        print_section(
+            "Sections with only !include entries",
+            &shown.include_only_sections,
+            cli.summary,
+            |path| path.join(" > "),
+        );
+    }
+
+    if show_duplicate_images && !cli.quiet {
+        print_section(
+            "Duplicate images",
+            &duplicate_image_files,
+            cli.summary,
+            |group| {
+                let copies: Vec<String> = group[1..].iter().map(&relative_path).collect();
+                format!("{} = {}", relative_path(&group[0]), copies.join(", "))
+            },
+        );
+    }
+
+    if show_css_cycles && !cli.quiet {
+        print_section(
+            "SCSS import cycles",
+            &css_import_cycles,
+            cli.summary,
+            |cycle| {
+                let files: Vec<String> = cycle.iter().map(&relative_path).collect();
+                format!("{} -> {}", files.join(" -> "), relative_path(&cycle[0]))
+            },
+        );
+    }
+
+    if show_outside_docs && !cli.quiet {
+        print_section(
+            "Nav entries outside docs/",
+            &shown.nav_entries_outside_docs,
+            cli.summary,
+            |entry| entry.clone(),
+        );
+    }
+
+    if show_theme_check && !cli.quiet {
+        print_section(
+            "Unknown theme features",
+            &shown.unknown_theme_features,
+            cli.summary,
+            |feature| feature.clone(),
+        );
+    }
+
+    total_issues += nav_order_changes.len();
+    if !cli.quiet && !nav_order_changes.is_empty() {
+        print_section(
+            "Nav order changes",
+            &nav_order_changes,
+            cli.summary,
+            |change| {
+                let section = if change.section.is_empty() {
+                    "(top level)".to_string()
+                } else {
+                    change.section.join(" > ")
+                };
+                format!(
+                    "{}: {} -> {}",
+                    section,
+                    change.old_order.join(", "),
+                    change.new_order.join(", ")
+                )
+            },
+        );
+    }
+
+    if show_empty_sections && !cli.quiet {
+        print_section(
+            "Empty nav sections",
+            &shown.empty_nav_sections,
+            cli.summary,
+            |title| title.clone(),
+        );
+    }
+
+    if show_broken_anchors && !cli.quiet {
+        print_section("Broken anchors", &broken_anchors, cli.summary, |ba| {
+            format!("{} -> {}#{}", relative_path(&ba.from), ba.link, ba.anchor)
+        });
+    }
+
+    if show_duplicate_nav && !cli.quiet {
+        print_section("Duplicate nav entries", &duplicate_nav, cli.summary, |p| {
+            relative_path(p)
+        });
+    }
+
+    if show_stub_pages && !cli.quiet {
+        print_section("Stub pages", &stub_pages, cli.summary, |p| relative_path(p));
+    }
+
+    if show_heading_errors && !cli.quiet {
+        print_section(
+            "Headings that skip a level",
+            &heading_errors,
+            cli.summary,
+            |(page, errors)| {
+                let errors: Vec<String> = errors
+                    .iter()
+                    .map(|e| {
+                        format!(
+                            "line {}: H{} (at most H{})",
+                            e.line, e.level, e.max_expected
+                        )
+                    })
+                    .collect();
+                format!("{}: {}", relative_path(page), errors.join("; "))
+            },
+        );
+    }
+
+    if show_duplicate_anchors && !cli.quiet {
+        print_section(
+            "Headings with duplicate anchors",
+            &duplicate_anchors,
+            cli.summary,
+            |(page, duplicates)| {
+                let duplicates: Vec<String> = duplicates
+                    .iter()
+                    .map(|(anchor, first, repeat)| {
+                        format!("#{anchor} on lines {first} and {repeat}")
+                    })
+                    .collect();
+                format!("{}: {}", relative_path(page), duplicates.join("; "))
+            },
+        );
+    }
+
+    if show_unlabelled_code && !cli.quiet {
+        print_section(
+            "Pages with unlabelled code blocks",
+            &unlabelled_code,
+            cli.summary,
+            |p| relative_path(p),
+        );
+    }
+
+    if show_missing_frontmatter && !cli.quiet {
+        print_section(
+            "Pages missing front matter fields",
+            &missing_frontmatter,
+            cli.summary,
+            |(page, fields)| format!("{}: {}", relative_path(page), fields.join(", ")),
+        );
+    }
+
+    if show_title_mismatches && !cli.quiet {
+        print_section(
+            "Nav labels unlike their page's H1",
+            &title_mismatches,
+            cli.summary,
+            |(page, label, h1)| format!("{}: \"{}\" vs \"{}\"", relative_path(page), label, h1),
+        );
+    }
+
+    if show_external_links && !cli.quiet {
+        print_section(
+            "Broken external links",
+            &broken_external_links,
+            cli.summary,
+            |b| format!("{} -> {} ({})", relative_path(&b.from), b.url, b.error),
+        );
+    }
+
+    if !cli.quiet
+        && let (Some(baseline), Some(path)) = (&baseline, &cli.load_baseline)
+    {
+        print_baseline_comparison(path, baseline, &result);
+    }
+    if cli.diff
+        && let (Some(baseline), Some(path)) = (&baseline, &cli.load_baseline)
+    {
+        print_baseline_diff(path, &diff_audit_results(baseline, &result), relative_path);
     }
 
     if !cli.quiet && !cli.summary {
         println!();
-        println!("Total issues: {}", total_issues);
+        let total = total_issues.to_string();
+        let total = if total_issues > 0 {
+            color::red(&total)
+        } else {
+            color::green(&total)
+        };
+        println!("{} {}", color::bold("Total issues:"), total);
+        if total_warnings > 0 {
+            println!(
+                "{} {}",
+                color::bold("Total warnings:"),
+                color::yellow(&total_warnings.to_string())
+            );
+        }
     }
 
-    if total_issues > 0 {
+    if cli.output_format == OutputFormat::Json {
+        let counts = AuditCounts {
+            nav_missing: if show_nav_missing {
+                nav_missing.len()
+            } else {
+                0
+            },
+            ghost: if show_ghost { ghost.len() } else { 0 },
+            help_missing: if show_help_missing {
+                help_missing.len()
+            } else {
+                0
+            },
+            broken_links: if show_broken_links {
+                broken_links.len()
+            } else {
+                0
+            },
+            missing_images: if show_missing_images {
+                missing_images.len()
+            } else {
+                0
+            },
+            orphan_images: if show_orphan_images {
+                orphan_images.len()
+            } else {
+                0
+            },
+            other_reports: other_reports
+                .iter()
+                .map(|(report, items)| (report.clone(), items.len()))
+                .collect(),
+            total: total_issues,
+            warnings: total_warnings,
+            ..AuditCounts::default()
+        };
+        let mut items = AuditItems {
+            other_reports,
+            ..AuditItems::default()
+        };
+        if show_nav_missing {
+            items.nav_missing = nav_missing.iter().map(&relative_path).collect();
+        }
+        if show_ghost {
+            items.ghost = ghost.iter().map(&relative_path).collect();
+        }
+        if show_help_missing {
+            items.help_missing = help_missing
+                .iter()
+                .map(|m| relative_path(&m.path))
+                .collect();
+        }
+        if show_broken_links {
+            items.broken_links = broken_links
+                .iter()
+                .map(|bl| BrokenLinkItem {
+                    from: relative_path(&bl.from),
+                    link: bl.link.clone(),
+                    help_refs: bl.help_refs.clone(),
+                })
+                .collect();
+        }
+        if show_missing_images {
+            items.missing_images = missing_images
+                .iter()
+                .map(|bi| BrokenImageItem {
+                    from: relative_path(&bi.from),
+                    image: bi.image.clone(),
+                })
+                .collect();
+        }
+        if show_orphan_images {
+            items.orphan_images = orphan_images.iter().map(relative_path).collect();
+        }
+        let report = serde_json::json!({ "counts": counts, "items": items });
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error: cannot write JSON report: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if cli.output_format == OutputFormat::Sarif {
+        let mut reported = AuditResult::default();
+        if show_nav_missing {
+            reported.nav_missing = nav_missing.iter().map(|p| (*p).clone()).collect();
+        }
+        if show_ghost {
+            reported.ghost = ghost.iter().map(|p| (*p).clone()).collect();
+        }
+        if show_broken_links {
+            reported.broken_links = broken_links.iter().map(|bl| (*bl).clone()).collect();
+        }
+        if show_missing_images {
+            reported.missing_images = missing_images.iter().map(|bi| (*bi).clone()).collect();
+        }
+        let root = monorepo_root.clone().unwrap_or_default();
+        println!("{}", to_sarif(&reported, &root));
+    }
+
+    let failed = match &cli.fail_on {
+        Some(FailOn(categories)) => categories.iter().any(|c| match c {
+            Category::NavMissing => !nav_missing.is_empty(),
+            Category::Ghost => !ghost.is_empty(),
+            Category::HelpMissing => !help_missing.is_empty(),
+            Category::BrokenLinks => !broken_links.is_empty(),
+            Category::MissingImages => !missing_images.is_empty(),
+            Category::OrphanImages => !orphan_images.is_empty(),
+        }),
+        None => total_issues > 0,
+    };
+    *issues_so_far += total_issues;
+    if failed {
         ExitCode::FAILURE
     } else {
         ExitCode::SUCCESS
     }
 }
 
+/// Issue counts per report, in report order, for comparing two runs.
+fn report_counts(result: &AuditResult) -> [(&'static str, usize); 60] {
+    [
+        ("Missing nav entries", result.nav_missing.len()),
+        ("Ghost files (orphans)", result.ghost.len()),
+        ("Missing help URLs", result.help_missing.len()),
+        (
+            "HELP_URLs already naming docs/",
+            result.malformed_help_urls.len(),
+        ),
+        ("Broken links", result.broken_links.len()),
+        (
+            "Broken links in nav descriptions",
+            result.broken_links_in_nav_descriptions.len(),
+        ),
+        (
+            "Broken links in site_description",
+            result.description_broken_links.len(),
+        ),
+        ("Missing images", result.missing_images.len()),
+        ("Orphan images", result.orphan_images.len()),
+        (
+            "Absolute internal links",
+            result.absolute_internal_links.len(),
+        ),
+        (
+            "Links with query strings",
+            result.links_with_query_strings.len(),
+        ),
+        ("Section title case", result.title_case_violations.len()),
+        ("Generic image alt text", result.poor_alt_text.len()),
+        ("Localhost links", result.localhost_links.len()),
+        ("Private network links", result.private_ip_links.len()),
+        ("Scheme-relative links", result.scheme_relative_links.len()),
+        ("Unexpected files in docs", result.unexpected_binaries.len()),
+        (
+            "URL-encoded internal links",
+            result.url_encoded_internal_links.len(),
+        ),
+        ("Single-child sections", result.single_child_sections.len()),
+        (
+            "Unknown code block languages",
+            result.pages_with_unknown_code_languages.len(),
+        ),
+        ("Files inside site_dir", result.files_inside_site_dir.len()),
+        (
+            "Help URL pages not in nav",
+            result.help_url_pages_not_in_nav.len(),
+        ),
+        (
+            "Nav pages without content",
+            result.nav_pages_without_content.len(),
+        ),
+        ("Headings too deep", result.pages_with_deep_headings.len()),
+        (
+            "Repeated words in link text",
+            result.links_with_repeated_words.len(),
+        ),
+        (
+            "Subsites without mkdocs.yml",
+            result.subsites_without_mkdocs.len(),
+        ),
+        (
+            "Duplicate section titles",
+            result.duplicate_section_titles.len(),
+        ),
+        ("Relay pages", result.relay_pages.len()),
+        ("Non-markdown nav entries", result.non_md_nav_items.len()),
+        (
+            "Includes nested too deeply",
+            result.include_depth_violations.len(),
+        ),
+        (
+            "Pages with multiple H1 headings",
+            result.pages_with_multiple_h1.len(),
+        ),
+        (
+            "Pages with a heading before the H1",
+            result.pages_with_h1_not_first.len(),
+        ),
+        (
+            "Potentially dead CSS selectors",
+            result.dead_css_selectors.len(),
+        ),
+        (
+            "Absolute filesystem image paths",
+            result.absolute_fs_image_refs.len(),
+        ),
+        ("Oversized SVGs", result.oversized_svgs.len()),
+        (
+            "SVGs with embedded raster images",
+            result.svgs_with_embedded_rasters.len(),
+        ),
+        (
+            "Paths differing only in case",
+            result.case_sensitivity_violations.len(),
+        ),
+        (
+            "Pages with mixed image reference styles",
+            result.pages_with_mixed_image_styles.len(),
+        ),
+        ("Orphan mkdocs.yml files", result.orphan_mkdocs_files.len()),
+        (
+            "Help URLs shared across headers",
+            result.cross_file_help_url_duplicates.len(),
+        ),
+        (
+            "Pages with front matter TODOs",
+            result.pages_with_todos.len(),
+        ),
+        (
+            "Pages with excess blank lines",
+            result.pages_with_excess_blank_lines.len(),
+        ),
+        (
+            "Sections with only !include entries",
+            result.include_only_sections.len(),
+        ),
+        ("Duplicate images", result.duplicate_image_files.len()),
+        ("SCSS import cycles", result.css_import_cycles.len()),
+        (
+            "Nav entries outside docs/",
+            result.nav_entries_outside_docs.len(),
+        ),
+        (
+            "Unknown theme features",
+            result.unknown_theme_features.len(),
+        ),
+        ("Empty nav sections", result.empty_nav_sections.len()),
+        ("Broken anchors", result.broken_anchors.len()),
+        ("Duplicate nav entries", result.duplicate_nav.len()),
+        ("Stub pages", result.stub_pages.len()),
+        ("Headings that skip a level", result.heading_errors.len()),
+        (
+            "Headings with duplicate anchors",
+            result.pages_with_duplicate_anchors.len(),
+        ),
+        (
+            "Pages with unlabelled code blocks",
+            result.pages_with_unlabelled_code.len(),
+        ),
+        (
+            "Pages missing front matter fields",
+            result.missing_frontmatter_fields.len(),
+        ),
+        (
+            "Nav labels unlike their page's H1",
+            result.title_mismatches.len(),
+        ),
+        (
+            "Nav entries differing only in case",
+            result.case_collisions.len(),
+        ),
+        ("Broken external links", result.broken_external_links.len()),
+        ("Missing includes", result.missing_includes.len()),
+        ("Invalid nav entries", result.nav_errors.len()),
+    ]
+}
+
+/// Print how each report's count moved since the baseline run. Counts are taken
+/// from the full results, before --exclude filtering.
+fn print_baseline_comparison(path: &Path, baseline: &AuditResult, current: &AuditResult) {
+    println!("\nCompared with baseline {}:", path.display());
+    let changes: Vec<_> = report_counts(baseline)
+        .into_iter()
+        .zip(report_counts(current))
+        .filter(|((_, before), (_, after))| before != after)
+        .collect();
+    if changes.is_empty() {
+        println!("  (no change)");
+    }
+    for ((title, before), (_, after)) in changes {
+        let delta = after as isize - before as isize;
+        println!("  {}: {} -> {} ({:+})", title, before, after, delta);
+    }
+}
+
+/// Print the issues in the main reports that are new or fixed since the baseline run.
+fn print_baseline_diff(path: &Path, diff: &AuditDiff, relative_path: impl Fn(&PathBuf) -> String) {
+    println!("Changes since baseline {}:", path.display());
+    let items = |r: &AuditResult| -> [(&str, Vec<String>); 6] {
+        [
+            (
+                "Missing nav entries",
+                r.nav_missing.iter().map(&relative_path).collect(),
+            ),
+            (
+                "Ghost files (orphans)",
+                r.ghost.iter().map(&relative_path).collect(),
+            ),
+            (
+                "Missing help URLs",
+                r.help_missing
+                    .iter()
+                    .map(|m| relative_path(&m.path))
+                    .collect(),
+            ),
+            (
+                "Broken links",
+                r.broken_links
+                    .iter()
+                    .map(|b| format!("{} -> {}", relative_path(&b.from), b.link))
+                    .collect(),
+            ),
+            (
+                "Missing images",
+                r.missing_images
+                    .iter()
+                    .map(|b| format!("{} -> {}", relative_path(&b.from), b.image))
+                    .collect(),
+            ),
+            (
+                "Orphan images",
+                r.orphan_images.iter().map(&relative_path).collect(),
+            ),
+        ]
+    };
+    let mut changed = false;
+    for ((title, new), (_, fixed)) in items(&diff.new).into_iter().zip(items(&diff.fixed)) {
+        if new.is_empty() && fixed.is_empty() {
+            continue;
+        }
+        changed = true;
+        println!("\n{}:", color::bold(title));
+        for item in new {
+            println!("  {} {}", color::red("[NEW]"), item);
+        }
+        for item in fixed {
+            println!("  {} {}", color::green("[FIXED]"), item);
+        }
+    }
+    if !changed {
+        println!("  (no change)");
+    }
+}
+
+/// The paths of the pages in a nav tree, included subsites' too.
+fn collect_nav_pages(nodes: &[NavNode], pages: &mut HashSet<PathBuf>) {
+    for node in nodes {
+        pages.extend(node.path.clone());
+        collect_nav_pages(&node.children, pages);
+    }
+}
+
+/// A report's section title, marked when its findings are only warnings.
+fn severity_title(title: &str, severity: Severity) -> String {
+    match severity {
+        Severity::Error => title.to_string(),
+        Severity::Warning => format!("{}{}", title, WARNINGS_SUFFIX),
+    }
+}
+
+/// Appended by [`severity_title`] to the title of a report whose findings are warnings.
+const WARNINGS_SUFFIX: &str = " (warnings)";
+
+/// Render a nav entry as its chain of section titles, e.g. `Guide > Advanced topics`.
+fn nav_path(sections: &[String], title: &str) -> String {
+    sections
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(title))
+        .collect::<Vec<_>>()
+        .join(" > ")
+}
+
 fn print_section<T, F>(title: &str, items: &[T], summary_only: bool, format: F)
 where
     F: Fn(&T) -> String,
 {
+    let paint = if title.ends_with(WARNINGS_SUFFIX) {
+        color::yellow
+    } else {
+        color::red
+    };
     if summary_only {
-        println!("{}: {}", title, items.len());
+        let count = match items.len() {
+            0 => color::green("0"),
+            n => paint(&n.to_string()),
+        };
+        println!("{}: {}", color::bold(title), count);
     } else {
-        println!("\n{}:", title);
+        println!("\n{}:", color::bold(title));
         if items.is_empty() {
-            println!("  (none)");
+            println!("  {}", color::green("(none)"));
         } else {
             for item in items {
-                println!("  {}", format(item));
+                println!("  {}", paint(&format(item)));
             }
         }
     }
@@ -0,0 +1,54 @@
+//! The formats `ghost` can write its report in.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// How the report is written to stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable sections, one line per item.
+    #[default]
+    Text,
+    /// A single JSON object with `counts` and `items`, in the shape the GUI uses.
+    Json,
+    /// A SARIF 2.1.0 log, for GitHub code scanning and editors.
+    Sarif,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "sarif" => Ok(OutputFormat::Sarif),
+            other => Err(format!(
+                "unknown output format '{other}' (expected text, json or sarif)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Sarif => "sarif",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_format_from_str() {
+        assert_eq!("json".parse(), Ok(OutputFormat::Json));
+        assert_eq!("TEXT".parse(), Ok(OutputFormat::Text));
+        assert_eq!("sarif".parse(), Ok(OutputFormat::Sarif));
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
@@ -0,0 +1,40 @@
+//! The progress bar drawn on stderr while an audit runs.
+
+use ghost_lib::Progress;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::time::Duration;
+
+/// A bar for one audit run; hidden (drawing nothing) unless `visible`.
+pub fn bar(visible: bool) -> ProgressBar {
+    if !visible {
+        return ProgressBar::hidden();
+    }
+    let bar = ProgressBar::new_spinner();
+    bar.enable_steady_tick(Duration::from_millis(100));
+    bar
+}
+
+/// Show `progress` on `bar`: a spinner while the docs are walked, then a bar over the
+/// pages whose links are analysed.
+pub fn update(bar: &ProgressBar, progress: Progress) {
+    match progress {
+        Progress::Scanning { files_found } => {
+            bar.set_message(format!("Scanning docs: {files_found} files found"));
+        }
+        Progress::Analysing { done, total } => {
+            if bar.length() != Some(total as u64) {
+                if bar.length().is_none() {
+                    bar.set_style(
+                        ProgressStyle::with_template(
+                            "Analysing links: {pos}/{len} files [{bar:40}]",
+                        )
+                        .expect("valid progress template")
+                        .progress_chars("=> "),
+                    );
+                }
+                bar.set_length(total as u64);
+            }
+            bar.set_position(done as u64);
+        }
+    }
+}
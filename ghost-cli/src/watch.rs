@@ -0,0 +1,69 @@
+//! `--watch`: re-run the audit whenever the docs change.
+
+use notify::{Event, RecursiveMode, Watcher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How long the file system must stay quiet before the audit re-runs, so that a bulk
+/// save or a `git checkout` triggers one run rather than dozens.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+const WATCHED_EXTENSIONS: &[&str] = &[
+    "md", "yml", "yaml", "png", "jpg", "jpeg", "gif", "svg", "webp", "ico", "bmp",
+];
+
+/// Call `run` once, then again after every settled burst of changes under `roots`.
+/// Only returns if the watcher fails.
+pub fn watch(roots: &[PathBuf], mut run: impl FnMut()) -> notify::Result<()> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for root in roots {
+        watcher.watch(root, RecursiveMode::Recursive)?;
+    }
+
+    run();
+    while let Ok(event) = rx.recv() {
+        let mut relevant = is_relevant(&event);
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            relevant |= is_relevant(&event);
+        }
+        if relevant {
+            clear_screen();
+            run();
+        }
+    }
+    Ok(())
+}
+
+fn is_relevant(event: &notify::Result<Event>) -> bool {
+    event
+        .as_ref()
+        .is_ok_and(|e| e.paths.iter().any(|p| is_watched_file(p)))
+}
+
+fn is_watched_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| WATCHED_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+fn clear_screen() {
+    print!("\x1b[2J\x1b[H");
+    let _ = std::io::stdout().flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watches_docs_and_images_only() {
+        assert!(is_watched_file(Path::new("docs/index.md")));
+        assert!(is_watched_file(Path::new("mkdocs.yml")));
+        assert!(is_watched_file(Path::new("docs/img/Logo.PNG")));
+        assert!(!is_watched_file(Path::new("target/debug/ghost")));
+        assert!(!is_watched_file(Path::new("site/index.html")));
+    }
+}
@@ -1,6 +1,7 @@
 use ghost_lib::{
-    audit_traced, has_footnotes, has_images, has_links, AuditResult, BrokenImage, BrokenLink,
-    HelpRef, TraceOptions,
+    audit_with_config_and_options, has_footnotes, has_images, has_links, AuditCounts, AuditItems,
+    AuditResult, BrokenImage, BrokenImageItem, BrokenLink, BrokenLinkItem, GhostConfig,
+    TraceOptions,
 };
 use regex::RegexBuilder;
 use serde::{Deserialize, Serialize};
@@ -22,6 +23,12 @@ pub struct AuditOptions {
     pub footnotes: bool,
     pub has_images: bool,
     pub has_links: bool,
+    /// Check that headings nest without skipping a level.
+    #[serde(default)]
+    pub heading_hierarchy: bool,
+    /// Check for pages with more than one H1, or a heading before the H1.
+    #[serde(default)]
+    pub multiple_h1: bool,
     pub summary: bool,
     pub exclude: String,
     /// Newline/comma-separated path suffixes to trace (empty ⇒ no tracing).
@@ -41,38 +48,15 @@ pub struct AuditOutput {
     pub trace: String,
 }
 
-#[derive(Debug, Serialize, Default)]
-pub struct AuditItems {
-    pub nav_missing: Vec<String>,
-    pub ghost: Vec<String>,
-    pub help_missing: Vec<String>,
-    pub broken_links: Vec<BrokenLinkItem>,
-    pub missing_images: Vec<BrokenImageItem>,
-    pub orphan_images: Vec<String>,
-    pub footnotes: Vec<String>,
-    pub has_images: Vec<String>,
-    pub has_links: Vec<String>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct BrokenLinkItem {
-    pub from: String,
-    pub link: String,
-    /// HELP_URL(...) entries in help_urls.h that reference `from` (empty if not a
-    /// help-URL page). Carries the verbatim source text and line number for the report.
-    pub help_refs: Vec<HelpRef>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct BrokenImageItem {
-    pub from: String,
-    pub image: String,
-}
-
 #[derive(Debug, Serialize, Clone)]
 pub struct GitInfo {
     pub branch: String,
     pub hash_short: String,
+    /// Whether the working tree has uncommitted changes, so reviewers can tell an audit
+    /// of a clean checkout from one of work in progress.
+    pub is_dirty: bool,
+    /// Number of changed (including untracked) files per `git status --porcelain`.
+    pub uncommitted_changes: usize,
 }
 
 // Search-related structs
@@ -133,21 +117,26 @@ fn detect_git_info(dir: &Path) -> Option<GitInfo> {
         .filter(|o| o.status.success())
         .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())?;
 
-    Some(GitInfo { branch, hash_short })
+    let uncommitted_changes = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| count_porcelain_changes(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or(0);
+
+    Some(GitInfo {
+        branch,
+        hash_short,
+        is_dirty: uncommitted_changes > 0,
+        uncommitted_changes,
+    })
 }
 
-#[derive(Debug, Serialize, Default)]
-pub struct AuditCounts {
-    pub nav_missing: usize,
-    pub ghost: usize,
-    pub help_missing: usize,
-    pub broken_links: usize,
-    pub missing_images: usize,
-    pub orphan_images: usize,
-    pub footnotes: usize,
-    pub has_images: usize,
-    pub has_links: usize,
-    pub total: usize,
+/// Number of changed files in `git status --porcelain` output: one per non-empty line.
+fn count_porcelain_changes(porcelain: &str) -> usize {
+    porcelain.lines().filter(|l| !l.trim().is_empty()).count()
 }
 
 fn relative_path(p: &Path, root: Option<&Path>) -> String {
@@ -160,18 +149,6 @@ fn relative_path(p: &Path, root: Option<&Path>) -> String {
     }
 }
 
-fn is_excluded(p: &Path, root: Option<&Path>, excluded: &[&str]) -> bool {
-    if let Some(r) = root {
-        if let Ok(rel) = p.strip_prefix(r) {
-            if let Some(first) = rel.components().next() {
-                let subsite = first.as_os_str().to_string_lossy();
-                return excluded.iter().any(|&ex| ex == subsite);
-            }
-        }
-    }
-    false
-}
-
 fn format_result(
     result: &AuditResult,
     options: &AuditOptions,
@@ -184,51 +161,20 @@ fn format_result(
     };
 
     // Filter results
-    let nav_missing: Vec<&PathBuf> = result
-        .nav_missing
-        .iter()
-        .filter(|p| !is_excluded(p, monorepo_root, &excluded))
-        .collect();
-    let ghost: Vec<&PathBuf> = result
-        .ghost
-        .iter()
-        .filter(|p| !is_excluded(p, monorepo_root, &excluded))
-        .collect();
-    let help_missing: Vec<&PathBuf> = result
-        .help_missing
-        .iter()
-        .filter(|p| !is_excluded(p, monorepo_root, &excluded))
-        .collect();
-    let broken_links: Vec<&BrokenLink> = result
-        .broken_links
-        .iter()
-        .filter(|bl| !is_excluded(&bl.from, monorepo_root, &excluded))
-        .collect();
-    let missing_images: Vec<&BrokenImage> = result
-        .missing_images
-        .iter()
-        .filter(|bi| !is_excluded(&bi.from, monorepo_root, &excluded))
-        .collect();
-    let orphan_images: Vec<&PathBuf> = result
-        .orphan_images
-        .iter()
-        .filter(|p| !is_excluded(p, monorepo_root, &excluded))
-        .collect();
-    let footnotes: Vec<&PathBuf> = result
-        .pages_with_footnotes
-        .iter()
-        .filter(|p| !is_excluded(p, monorepo_root, &excluded))
-        .collect();
-    let has_images: Vec<&PathBuf> = result
-        .pages_with_images
-        .iter()
-        .filter(|p| !is_excluded(p, monorepo_root, &excluded))
-        .collect();
-    let has_links: Vec<&PathBuf> = result
-        .pages_with_links
-        .iter()
-        .filter(|p| !is_excluded(p, monorepo_root, &excluded))
-        .collect();
+    let filtered = match monorepo_root {
+        Some(root) => result.clone().filter_by_subsite(&excluded, root),
+        None => result.clone(),
+    };
+    let result = &filtered;
+    let nav_missing = &result.nav_missing;
+    let ghost = &result.ghost;
+    let help_missing: Vec<&PathBuf> = result.help_missing.iter().map(|m| &m.path).collect();
+    let broken_links = &result.broken_links;
+    let missing_images = &result.missing_images;
+    let orphan_images = &result.orphan_images;
+    let footnotes = &result.pages_with_footnotes;
+    let has_images = &result.pages_with_images;
+    let has_links = &result.pages_with_links;
 
     // Determine which reports to show
     let show_all = !options.nav_missing
@@ -253,13 +199,16 @@ fn format_result(
 
     let mut output = String::new();
     let mut counts = AuditCounts::default();
+    // The reports shown, for the total.
+    let mut shown = AuditResult::default();
 
     if show_nav_missing {
         counts.nav_missing = nav_missing.len();
+        shown.nav_missing = nav_missing.clone();
         format_pathbuf_section(
             &mut output,
             "Missing nav entries",
-            &nav_missing,
+            nav_missing,
             options.summary,
             monorepo_root,
         );
@@ -267,10 +216,11 @@ fn format_result(
 
     if show_ghost {
         counts.ghost = ghost.len();
+        shown.ghost = ghost.clone();
         format_pathbuf_section(
             &mut output,
             "Ghost files (orphans)",
-            &ghost,
+            ghost,
             options.summary,
             monorepo_root,
         );
@@ -278,6 +228,7 @@ fn format_result(
 
     if show_help_missing {
         counts.help_missing = help_missing.len();
+        shown.help_missing = result.help_missing.clone();
         format_pathbuf_section(
             &mut output,
             "Missing help URLs",
@@ -289,10 +240,11 @@ fn format_result(
 
     if show_broken_links {
         counts.broken_links = broken_links.len();
+        shown.broken_links = broken_links.clone();
         format_broken_links_section(
             &mut output,
             "Broken links",
-            &broken_links,
+            broken_links,
             options.summary,
             monorepo_root,
         );
@@ -300,10 +252,11 @@ fn format_result(
 
     if show_missing_images {
         counts.missing_images = missing_images.len();
+        shown.missing_images = missing_images.clone();
         format_broken_images_section(
             &mut output,
             "Missing images",
-            &missing_images,
+            missing_images,
             options.summary,
             monorepo_root,
         );
@@ -311,10 +264,11 @@ fn format_result(
 
     if show_orphan_images {
         counts.orphan_images = orphan_images.len();
+        shown.orphan_images = orphan_images.clone();
         format_pathbuf_section(
             &mut output,
             "Orphan images",
-            &orphan_images,
+            orphan_images,
             options.summary,
             monorepo_root,
         );
@@ -325,7 +279,7 @@ fn format_result(
         format_pathbuf_section(
             &mut output,
             "Pages with footnotes",
-            &footnotes,
+            footnotes,
             options.summary,
             monorepo_root,
         );
@@ -336,7 +290,7 @@ fn format_result(
         format_pathbuf_section(
             &mut output,
             "Pages with images",
-            &has_images,
+            has_images,
             options.summary,
             monorepo_root,
         );
@@ -347,18 +301,58 @@ fn format_result(
         format_pathbuf_section(
             &mut output,
             "Pages with links",
-            &has_links,
+            has_links,
+            options.summary,
+            monorepo_root,
+        );
+    }
+
+    if options.heading_hierarchy {
+        let heading_errors = &result.heading_errors;
+        shown.heading_errors = heading_errors.clone();
+        if options.summary {
+            output.push_str(&format!("Headings that skip a level: {}\n", heading_errors.len()));
+        } else {
+            output.push_str("\nHeadings that skip a level:\n");
+            if heading_errors.is_empty() {
+                output.push_str("  (none)\n");
+            }
+            for (page, errors) in heading_errors {
+                let errors: Vec<String> = errors
+                    .iter()
+                    .map(|e| format!("line {}: H{} (at most H{})", e.line, e.level, e.max_expected))
+                    .collect();
+                output.push_str(&format!(
+                    "  {}: {}\n",
+                    relative_path(page, monorepo_root),
+                    errors.join("; ")
+                ));
+            }
+        }
+    }
+
+    if options.multiple_h1 {
+        let multiple_h1 = &result.pages_with_multiple_h1;
+        let h1_not_first = &result.pages_with_h1_not_first;
+        shown.pages_with_multiple_h1 = multiple_h1.clone();
+        shown.pages_with_h1_not_first = h1_not_first.clone();
+        format_pathbuf_section(
+            &mut output,
+            "Pages with multiple H1 headings",
+            multiple_h1,
+            options.summary,
+            monorepo_root,
+        );
+        format_pathbuf_section(
+            &mut output,
+            "Pages with a heading before the H1",
+            h1_not_first,
             options.summary,
             monorepo_root,
         );
     }
 
-    counts.total = counts.nav_missing
-        + counts.ghost
-        + counts.help_missing
-        + counts.broken_links
-        + counts.missing_images
-        + counts.orphan_images;
+    counts.total = shown.total_issues_with_config(&GhostConfig::default());
 
     if !options.summary {
         output.push_str(&format!("\nTotal issues: {}\n", counts.total));
@@ -443,7 +437,7 @@ fn format_result(
 fn format_pathbuf_section(
     output: &mut String,
     title: &str,
-    items: &[&PathBuf],
+    items: &[impl AsRef<Path>],
     summary: bool,
     monorepo_root: Option<&Path>,
 ) {
@@ -455,7 +449,7 @@ fn format_pathbuf_section(
             output.push_str("  (none)\n");
         } else {
             for item in items {
-                output.push_str(&format!("  {}\n", relative_path(item, monorepo_root)));
+                output.push_str(&format!("  {}\n", relative_path(item.as_ref(), monorepo_root)));
             }
         }
     }
@@ -464,7 +458,7 @@ fn format_pathbuf_section(
 fn format_broken_links_section(
     output: &mut String,
     title: &str,
-    items: &[&BrokenLink],
+    items: &[BrokenLink],
     summary: bool,
     monorepo_root: Option<&Path>,
 ) {
@@ -501,7 +495,7 @@ fn format_broken_links_section(
 fn format_broken_images_section(
     output: &mut String,
     title: &str,
-    items: &[&BrokenImage],
+    items: &[BrokenImage],
     summary: bool,
     monorepo_root: Option<&Path>,
 ) {
@@ -821,7 +815,26 @@ fn run_audit(options: AuditOptions) -> AuditOutput {
         .filter(|s| !s.is_empty())
         .collect();
 
-    match audit_traced(&mkdocs_path, &help_urls_path, &TraceOptions { targets }) {
+    // None of the checks below have a section here, so spare the work.
+    let audit_options = ghost_lib::AuditOptions::builder()
+        .check_headings(options.heading_hierarchy)
+        .check_multiple_h1(options.multiple_h1)
+        .skip_unexpected_binaries(true)
+        .skip_subsites_without_mkdocs(true)
+        .skip_dead_css_selectors(true)
+        .skip_svg_checks(true)
+        .skip_case_sensitivity(true)
+        .skip_orphan_mkdocs_files(true)
+        .skip_cross_file_help_url_duplicates(true)
+        .skip_css_import_cycles(true)
+        .build();
+    match audit_with_config_and_options(
+        &mkdocs_path,
+        &help_urls_path,
+        &GhostConfig::default(),
+        &audit_options,
+        &TraceOptions { targets },
+    ) {
         Ok((result, trace)) => {
             let (output, counts, items) = format_result(&result, &options, monorepo_root.as_deref());
             let trace = if trace.text.is_empty() {
@@ -864,7 +877,11 @@ fn trace_header(mkdocs: &Path, help: &Path, targets: &str, git: Option<&GitInfo>
     h.push_str(&format!("mkdocs    : {}\n", mkdocs.display()));
     h.push_str(&format!("help-urls : {}\n", help.display()));
     if let Some(g) = git {
-        h.push_str(&format!("docs git  : {} @ {}\n", g.branch, g.hash_short));
+        h.push_str(&format!("docs git  : {} @ {}", g.branch, g.hash_short));
+        if g.is_dirty {
+            h.push_str(&format!(" (dirty: {} uncommitted change(s))", g.uncommitted_changes));
+        }
+        h.push('\n');
     }
     let targets = targets
         .split(['\n', ','])
@@ -898,3 +915,36 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_porcelain_changes() {
+        assert_eq!(count_porcelain_changes(""), 0);
+        assert_eq!(count_porcelain_changes("\n"), 0);
+        let porcelain =
+            " M docs/index.md\nA  docs/new.md\n?? docs/scratch.md\nR  old.md -> new.md\n";
+        assert_eq!(count_porcelain_changes(porcelain), 4);
+    }
+
+    #[test]
+    fn test_trace_header_marks_dirty_tree() {
+        let mut git = GitInfo {
+            branch: "main".to_string(),
+            hash_short: "abc1234".to_string(),
+            is_dirty: false,
+            uncommitted_changes: 0,
+        };
+        let mkdocs = Path::new("mkdocs.yml");
+        let help = Path::new("help_urls.h");
+        let clean = trace_header(mkdocs, help, "", Some(&git));
+        assert!(clean.contains("docs git  : main @ abc1234\n"));
+
+        git.is_dirty = true;
+        git.uncommitted_changes = 3;
+        let dirty = trace_header(mkdocs, help, "", Some(&git));
+        assert!(dirty.contains("docs git  : main @ abc1234 (dirty: 3 uncommitted change(s))\n"));
+    }
+}
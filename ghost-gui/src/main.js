@@ -26,6 +26,8 @@ const optHelpMissing = document.getElementById('opt-help-missing');
 const optBrokenLinks = document.getElementById('opt-broken-links');
 const optMissingImages = document.getElementById('opt-missing-images');
 const optOrphanImages = document.getElementById('opt-orphan-images');
+const optHeadingHierarchy = document.getElementById('opt-heading-hierarchy');
+const optMultipleH1 = document.getElementById('opt-multiple-h1');
 const optFootnotes = document.getElementById('opt-footnotes');
 const optHasImages = document.getElementById('opt-has-images');
 const optHasLinks = document.getElementById('opt-has-links');
@@ -215,6 +217,8 @@ runAuditBtn.addEventListener('click', async () => {
         footnotes: optFootnotes.checked,
         has_images: optHasImages.checked,
         has_links: optHasLinks.checked,
+        heading_hierarchy: optHeadingHierarchy.checked,
+        multiple_h1: optMultipleH1.checked,
         summary: optSummary.checked,
         exclude: excludeInput.value.toLowerCase(),
         trace: tracePathInput ? tracePathInput.value : ''
@@ -448,8 +452,9 @@ function renderBrokenImagesSection(title, images, sectionKey) {
 
 function displayGitInfo(gitInfo) {
   if (gitInfo) {
-    gitInfoEl.textContent = `${gitInfo.branch} @ ${gitInfo.hash_short}`;
-    gitInfoEl.classList.toggle('git-info-warn', gitInfo.branch !== 'main');
+    const dirty = gitInfo.is_dirty ? ` (${gitInfo.uncommitted_changes} uncommitted)` : '';
+    gitInfoEl.textContent = `${gitInfo.branch} @ ${gitInfo.hash_short}${dirty}`;
+    gitInfoEl.classList.toggle('git-info-warn', gitInfo.branch !== 'main' || gitInfo.is_dirty);
   }
 }
 
@@ -1,29 +1,36 @@
-use pulldown_cmark::{Event, Parser, Tag};
+use glob::Pattern;
+use ignore::WalkBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use percent_encoding::percent_decode_str;
+use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag, TagEnd};
+use rayon::prelude::*;
 use regex::Regex;
 use scraper::{Html, Selector};
-use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_yaml::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
 use std::fs;
 use std::io;
 use std::path::{Component, Path, PathBuf};
-use walkdir::WalkDir;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+use unicode_normalization::UnicodeNormalization;
 
 /// Normalize a path by resolving `.` and `..` components without requiring filesystem access.
 fn normalize_path(path: &Path) -> PathBuf {
     let mut result = Vec::new();
     for component in path.components() {
         match component {
-            Component::ParentDir => {
-                // Pop the last component if it's a Normal component
+            // Pop the last component if it's a Normal component
+            Component::ParentDir
                 if result
                     .last()
-                    .is_some_and(|c| matches!(c, Component::Normal(_)))
-                {
-                    result.pop();
-                } else {
-                    result.push(component);
-                }
+                    .is_some_and(|c| matches!(c, Component::Normal(_))) =>
+            {
+                result.pop();
             }
             Component::CurDir => {
                 // Skip "."
@@ -43,37 +50,892 @@ pub struct MkDocsConfig {
     /// at the slug of its `site_name` (not its directory name), so this drives the URL.
     #[serde(default)]
     pub site_name: Option<String>,
+    /// Where `mkdocs build` writes the site, relative to mkdocs.yml. Anything under it is
+    /// overwritten by the next build.
+    #[serde(default)]
+    pub site_dir: Option<String>,
+    /// The site's meta description. May contain markdown links, which are checked like
+    /// links in nav descriptions.
+    #[serde(default)]
+    pub site_description: Option<String>,
+    #[serde(default)]
+    pub site_author: Option<String>,
+    #[serde(default)]
+    pub theme: Option<ThemeConfig>,
+    /// The directory holding the site's markdown, relative to mkdocs.yml; see
+    /// [`MkDocsConfig::docs_dir`].
+    #[serde(default)]
+    pub docs_dir: Option<String>,
+    /// Where the built site is served (`https://docs.example.com/en/`). Its path part
+    /// is the prefix of every site-absolute link.
+    #[serde(default)]
+    pub site_url: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+/// The docs directory MkDocs uses when mkdocs.yml doesn't set `docs_dir`.
+pub const DEFAULT_DOCS_DIR: &str = "docs";
+
+impl MkDocsConfig {
+    /// The configured `docs_dir`, or [`DEFAULT_DOCS_DIR`].
+    pub fn docs_dir(&self) -> &str {
+        self.docs_dir.as_deref().unwrap_or(DEFAULT_DOCS_DIR)
+    }
+
+    /// The path part of `site_url` (`/en/` for `https://docs.example.com/en/`), if set.
+    pub fn site_path(&self) -> Option<&str> {
+        let url = self.site_url.as_deref()?;
+        let after_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+        Some(after_scheme.find('/').map_or("/", |i| &after_scheme[i..]))
+    }
+}
+
+/// The `theme` of an mkdocs.yml, written either as a bare name (`theme: readthedocs`) or
+/// as a mapping with `name`, `features` and other settings.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "RawThemeConfig")]
+pub struct ThemeConfig {
+    pub name: Option<String>,
+    /// Feature flags, e.g. `navigation.tabs`; only meaningful for the Material theme.
+    pub features: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawThemeConfig {
+    Name(String),
+    Full {
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default)]
+        features: Vec<String>,
+    },
+}
+
+impl From<RawThemeConfig> for ThemeConfig {
+    fn from(raw: RawThemeConfig) -> Self {
+        match raw {
+            RawThemeConfig::Name(name) => ThemeConfig {
+                name: Some(name),
+                features: Vec::new(),
+            },
+            RawThemeConfig::Full { name, features } => ThemeConfig { name, features },
+        }
+    }
+}
+
+/// A nav entry. Deserialized leniently: any string-like scalar is a `PlainPath`, merge
+/// keys (`<<: *anchor`) are applied, and forms a nav can't hold become `Unrecognised`
+/// rather than failing the whole config.
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum NavItem {
     Page(HashMap<String, String>),
     Section(HashMap<String, Vec<NavItem>>),
     PlainPath(String),
+    /// An entry that is none of the above, or whose merge key couldn't be applied, as
+    /// YAML. Otherwise ignored; [`validate_nav_structure`] reports it.
+    Unrecognised(String),
 }
 
-#[derive(Debug)]
+impl<'de> Deserialize<'de> for NavItem {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(nav_item_from_value(Value::deserialize(deserializer)?))
+    }
+}
+
+fn nav_item_from_value(mut value: Value) -> NavItem {
+    if value.apply_merge().is_err() {
+        return unrecognised_nav_entry(&value);
+    }
+    if let Some(path) = scalar_string(&value) {
+        return NavItem::PlainPath(path);
+    }
+    let Value::Mapping(map) = &value else {
+        return unrecognised_nav_entry(&value);
+    };
+    if map.values().any(Value::is_sequence) {
+        let mut sections = HashMap::new();
+        for (title, children) in map {
+            let Some(title) = scalar_string(title) else {
+                return unrecognised_nav_entry(&value);
+            };
+            // A title without a list keeps its place, so the rest of the section stands.
+            let children = match children {
+                Value::Sequence(children) => {
+                    children.iter().cloned().map(nav_item_from_value).collect()
+                }
+                other => vec![unrecognised_nav_entry(other)],
+            };
+            sections.insert(title, children);
+        }
+        NavItem::Section(sections)
+    } else {
+        let mut pages = HashMap::new();
+        for (title, page) in map {
+            let (Some(title), Some(page)) = (scalar_string(title), scalar_string(page)) else {
+                return unrecognised_nav_entry(&value);
+            };
+            pages.insert(title, page);
+        }
+        NavItem::Page(pages)
+    }
+}
+
+/// A scalar as written. A tagged string (an unquoted `!include ./mkdocs.yml`) keeps its
+/// tag, as it would if quoted.
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Tagged(tagged) => match &tagged.value {
+            Value::String(s) => Some(format!("{} {s}", tagged.tag)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn unrecognised_nav_entry(value: &Value) -> NavItem {
+    let entry = serde_yaml::to_string(value).unwrap_or_default();
+    NavItem::Unrecognised(entry.trim().to_string())
+}
+
+/// The outcome of an audit. Serializable so a run can be saved and later compared
+/// against (see [`save_audit_result`] and [`load_audit_result`]); fields missing from
+/// an older saved result load as empty.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AuditResult {
     pub nav_missing: Vec<PathBuf>,
     pub ghost: Vec<PathBuf>,
-    pub help_missing: Vec<PathBuf>,
+    pub help_missing: Vec<MissingHelpUrl>,
     pub broken_links: Vec<BrokenLink>,
+    /// The subset of `broken_links` pointing at a page in `nav_missing`, as (page, link):
+    /// knock-on effects of the missing nav page rather than broken links in their own
+    /// right.
+    pub nav_missing_link_sources: Vec<(PathBuf, String)>,
     pub missing_images: Vec<BrokenImage>,
     pub orphan_images: Vec<PathBuf>,
     pub pages_with_footnotes: Vec<PathBuf>,
     pub pages_with_images: Vec<PathBuf>,
     pub pages_with_links: Vec<PathBuf>,
+    /// Links written as full URLs on the site's own base URL (see
+    /// [`GhostConfig::site_base_url`]); these should be relative links instead.
+    pub absolute_internal_links: Vec<(PathBuf, String)>,
+    /// Nav section titles that break the configured [`TitleCaseStyle`], as (path of
+    /// enclosing section titles, offending title).
+    pub title_case_violations: Vec<(Vec<String>, String)>,
+    /// Internal links carrying a `?query` string, which MkDocs never routes on.
+    pub links_with_query_strings: Vec<(PathBuf, String)>,
+    /// Broken site-absolute links under a top-level prefix that is no longer in the nav,
+    /// with the current prefixes they most resemble (see
+    /// [`find_stale_absolute_prefix_links`]).
+    pub stale_prefix_links: Vec<(PathBuf, String, Vec<String>)>,
+    /// Images whose alt text is generic (`image`, `screenshot`, empty, ...), as (page,
+    /// image src, alt text).
+    pub poor_alt_text: Vec<(PathBuf, String, String)>,
+    /// Links to a development server on localhost, as (page, link).
+    pub localhost_links: Vec<(PathBuf, String)>,
+    /// Links to a host on a private (RFC 1918) network, as (page, link).
+    pub private_ip_links: Vec<(PathBuf, String)>,
+    /// Scheme-relative links (`//example.com/page`), as (page, link).
+    pub scheme_relative_links: Vec<(PathBuf, String)>,
+    /// Files in docs directories with an unexpected extension, such as `.exe` or `.pyc`.
+    pub unexpected_binaries: Vec<PathBuf>,
+    /// Internal links with percent-encoded characters (`my%20page.md`), as (page, link).
+    pub url_encoded_internal_links: Vec<(PathBuf, String)>,
+    /// Nested nav sections with a single child, as (section path, child title).
+    pub single_child_sections: Vec<(Vec<String>, String)>,
+    /// Pages with fenced code blocks in languages outside the known set (see
+    /// [`KNOWN_CODE_LANGUAGES`]), as (page, distinct unknown languages).
+    pub pages_with_unknown_code_languages: Vec<(PathBuf, Vec<String>)>,
+    /// Nav pages and markdown files inside the configured `site_dir`, where the build
+    /// would overwrite them.
+    pub files_inside_site_dir: Vec<PathBuf>,
+    /// Pages referenced by help_urls.h that exist but are not in the nav, so the help
+    /// system can open a page readers can't browse to.
+    pub help_url_pages_not_in_nav: Vec<PathBuf>,
+    /// Nav pages with no headings at all: empty, or nothing but front matter. Unlike a
+    /// ghost page, readers can browse straight to these.
+    pub nav_pages_without_content: Vec<PathBuf>,
+    /// Broken links in markdown descriptions embedded in the nav (see
+    /// [`extract_nav_descriptions`]); `from` is the mkdocs.yml holding the description.
+    pub broken_links_in_nav_descriptions: Vec<BrokenLink>,
+    /// Broken links in the root mkdocs.yml's `site_description`.
+    pub description_broken_links: Vec<String>,
+    /// Pages with headings deeper than [`GhostConfig::max_heading_level`], as (page,
+    /// [(line, level, heading text)]).
+    pub pages_with_deep_headings: Vec<(PathBuf, Vec<DeepHeading>)>,
+    /// Pages with links whose text repeats a word (`[see the the guide](guide.md)`), as
+    /// (page, offending link texts).
+    pub links_with_repeated_words: Vec<(PathBuf, Vec<String>)>,
+    /// Directories under the monorepo root with a `docs/` folder but no mkdocs.yml (see
+    /// [`find_subsites_without_mkdocs`]).
+    pub subsites_without_mkdocs: Vec<PathBuf>,
+    /// Sibling nav sections whose titles slugify the same, as (path of enclosing section
+    /// titles, duplicated title).
+    pub duplicate_section_titles: Vec<(Vec<String>, String)>,
+    /// Pages that are nothing but links to other pages, which a nav section could
+    /// replace.
+    pub relay_pages: Vec<PathBuf>,
+    /// Nav entries pointing at something other than a `.md` file, such as `README.rst`
+    /// or `page.html`.
+    pub non_md_nav_items: Vec<String>,
+    /// `!include`d files nested deeper than [`GhostConfig::max_include_depth`], as (file,
+    /// its depth). Their navs are left out of the audit.
+    pub include_depth_violations: Vec<(PathBuf, usize)>,
+    /// Pages with more than one H1 heading, when [`AuditOptions::check_multiple_h1`] is
+    /// set.
+    pub pages_with_multiple_h1: Vec<PathBuf>,
+    /// Pages whose H1 comes after another heading, when
+    /// [`AuditOptions::check_multiple_h1`] is set.
+    pub pages_with_h1_not_first: Vec<PathBuf>,
+    /// Class selectors in stylesheets that no page uses (see
+    /// [`find_potentially_dead_css_selectors`]), as (stylesheet, classes).
+    pub dead_css_selectors: Vec<(PathBuf, Vec<String>)>,
+    /// Images referenced by an absolute filesystem path (`/home/...`, `C:\...`), which
+    /// only resolve on the author's machine, as (page, image src).
+    pub absolute_fs_image_refs: Vec<(PathBuf, String)>,
+    /// SVG images larger than the configured `max_svg_bytes`, as (image, size in bytes).
+    pub oversized_svgs: Vec<(PathBuf, u64)>,
+    /// SVG images with raster data embedded as a `data:image/...` URI.
+    pub svgs_with_embedded_rasters: Vec<PathBuf>,
+    /// Markdown files whose paths differ only in case, which collide on case-insensitive
+    /// filesystems, and links whose target only exists in a different case, as (path,
+    /// on-disk path it collides with).
+    pub case_sensitivity_violations: Vec<(PathBuf, PathBuf)>,
+    /// `HELP_URL` entries whose URL carries a `#section` anchor.
+    pub help_url_fragments: Vec<HelpUrlEntry>,
+    /// Pages that mix site-absolute and relative image paths, or markdown and HTML image
+    /// syntax (see [`has_mixed_image_ref_styles`]).
+    pub pages_with_mixed_image_styles: Vec<PathBuf>,
+    /// `mkdocs.yml` files in the monorepo that the root config never `!include`s (see
+    /// [`find_orphan_mkdocs_files`]).
+    pub orphan_mkdocs_files: Vec<PathBuf>,
+    /// Docs pages referenced from more than one help URL header (see
+    /// [`GhostConfig::extra_help_urls`]), as (page, headers referencing it).
+    pub cross_file_help_url_duplicates: Vec<(PathBuf, Vec<PathBuf>)>,
+    /// Pages with open TODOs in their front matter (see [`extract_frontmatter_todos`]), as
+    /// (page, TODOs).
+    pub pages_with_todos: Vec<(PathBuf, Vec<String>)>,
+    /// Pages with more than [`GhostConfig::max_blank_lines`] consecutive blank lines, as
+    /// (page, line numbers where the runs start).
+    pub pages_with_excess_blank_lines: Vec<(PathBuf, Vec<usize>)>,
+    /// Sections of the root nav whose children are all `!include`s (see
+    /// [`find_include_only_sections`]), as section title paths.
+    pub include_only_sections: Vec<Vec<String>>,
+    /// Images with identical content, in groups of two or more: the first of each group is
+    /// the canonical copy, the rest duplicates. Only filled when
+    /// [`AuditOptions::check_duplicate_images`] is set.
+    pub duplicate_image_files: Vec<Vec<PathBuf>>,
+    /// Import cycles among SCSS stylesheets (see [`find_scss_import_cycles`]).
+    pub css_import_cycles: Vec<Vec<PathBuf>>,
+    /// Nav entries whose path resolves outside their `docs/` directory (see
+    /// [`find_nav_entries_outside_docs`]), as written in the nav.
+    pub nav_entries_outside_docs: Vec<String>,
+    /// Entries in the root mkdocs.yml's `theme.features` that the Material theme doesn't
+    /// know (see [`find_unknown_theme_features`]).
+    pub unknown_theme_features: Vec<String>,
+    /// Nav sections with no children (see [`find_empty_sections`]), by title.
+    pub empty_nav_sections: Vec<String>,
+    /// `HELP_URL` entries whose path already names the `docs/` component
+    /// (`guide/docs/page`); they are used as written rather than given a second `docs/`.
+    pub malformed_help_urls: Vec<HelpRef>,
+    /// Links whose `#anchor` names no heading on the page they point to.
+    pub broken_anchors: Vec<BrokenAnchor>,
+    /// Pages listed more than once in the nav, directly or through `!include`s (see
+    /// [`find_duplicate_nav_entries`]).
+    pub duplicate_nav: Vec<PathBuf>,
+    /// Pages with fewer than [`GhostConfig::min_word_count`] words of body text (see
+    /// [`word_count`]).
+    pub stub_pages: Vec<PathBuf>,
+    /// Pages whose headings skip a level (an H3 straight after an H1), when
+    /// [`AuditOptions::check_headings`] is set; see [`check_heading_hierarchy`].
+    pub heading_errors: Vec<(PathBuf, Vec<HeadingError>)>,
+    /// Pages missing front matter fields named in
+    /// [`AuditOptions::required_frontmatter_fields`], as (page, missing fields).
+    pub missing_frontmatter_fields: Vec<(PathBuf, Vec<String>)>,
+    /// Nav pages whose first H1 looks unrelated to their nav label, when
+    /// [`AuditOptions::check_title_consistency`] is set, as (page, nav label, H1). See
+    /// [`title_similarity`].
+    pub title_mismatches: Vec<(PathBuf, String, String)>,
+    /// Nav entries whose paths differ only in case, as pairs (see
+    /// [`find_case_collisions`]): on a case-insensitive filesystem both are the same
+    /// page, served under two URLs. Pairs whose both spellings exist on disk are left to
+    /// `case_sensitivity_violations`.
+    pub case_collisions: Vec<(PathBuf, PathBuf)>,
+    /// External links that didn't answer, when [`AuditOptions::check_external_links`]
+    /// is set.
+    pub broken_external_links: Vec<ExternalBrokenLink>,
+    /// Each scanned page with the files its links resolve to, sorted; see
+    /// [`page_dependency_graph`].
+    pub page_links: Vec<(PathBuf, Vec<PathBuf>)>,
+    /// `!include`d config files that don't exist. Their navs are left out of the audit.
+    pub missing_includes: Vec<PathBuf>,
+    /// Nav entries MkDocs won't accept, such as a page entry with two titles; see
+    /// [`validate_nav_structure`].
+    pub nav_errors: Vec<NavError>,
+    /// Pages with headings that want the same anchor, each as (anchor, first line,
+    /// repeat line), when [`AuditOptions::check_duplicate_anchors`] is set.
+    #[allow(clippy::type_complexity)]
+    pub pages_with_duplicate_anchors: Vec<(PathBuf, Vec<(String, usize, usize)>)>,
+    /// Pages with a code block that names no language, when
+    /// [`AuditOptions::check_unlabelled_code_blocks`] is set.
+    pub pages_with_unlabelled_code: Vec<PathBuf>,
+}
+
+impl AuditResult {
+    /// Drop every reported item about a file for which `keep` is false. Items that
+    /// aren't about one file (nav titles, groups of duplicate files) are left as they
+    /// are.
+    pub fn retain_paths(&mut self, keep: impl Fn(&Path) -> bool) {
+        macro_rules! retain {
+            ($($field:ident),* $(,)?) => {
+                $(self.$field.retain(|item| item.reported_path().is_none_or(&keep));)*
+            };
+        }
+        retain!(
+            nav_missing,
+            ghost,
+            help_missing,
+            broken_links,
+            nav_missing_link_sources,
+            missing_images,
+            orphan_images,
+            pages_with_footnotes,
+            pages_with_images,
+            pages_with_links,
+            absolute_internal_links,
+            links_with_query_strings,
+            stale_prefix_links,
+            poor_alt_text,
+            localhost_links,
+            private_ip_links,
+            scheme_relative_links,
+            unexpected_binaries,
+            url_encoded_internal_links,
+            pages_with_unknown_code_languages,
+            files_inside_site_dir,
+            help_url_pages_not_in_nav,
+            nav_pages_without_content,
+            broken_links_in_nav_descriptions,
+            pages_with_deep_headings,
+            links_with_repeated_words,
+            subsites_without_mkdocs,
+            relay_pages,
+            include_depth_violations,
+            pages_with_multiple_h1,
+            pages_with_h1_not_first,
+            dead_css_selectors,
+            absolute_fs_image_refs,
+            oversized_svgs,
+            svgs_with_embedded_rasters,
+            case_sensitivity_violations,
+            help_url_fragments,
+            pages_with_mixed_image_styles,
+            orphan_mkdocs_files,
+            cross_file_help_url_duplicates,
+            pages_with_todos,
+            pages_with_excess_blank_lines,
+            broken_anchors,
+            duplicate_nav,
+            stub_pages,
+            heading_errors,
+            missing_frontmatter_fields,
+            title_mismatches,
+            case_collisions,
+            broken_external_links,
+            page_links,
+            missing_includes,
+            pages_with_duplicate_anchors,
+            pages_with_unlabelled_code,
+        );
+    }
+
+    /// Leave out every reported item about a file of one of the `excluded` subsites,
+    /// the top-level directories of the monorepo at `root`, given by name or glob
+    /// pattern (`*-legacy`). A case-sensitivity
+    /// violation goes if either of its files is excluded; a group of duplicate images or
+    /// a stylesheet import cycle only when all of its files are.
+    pub fn filter_by_subsite(mut self, excluded: &[impl AsRef<str>], root: &Path) -> AuditResult {
+        let patterns = SubsitePatterns::new(excluded);
+        let in_excluded = |p: &Path| patterns.contain(p, root);
+        self.retain_paths(|p| !in_excluded(p));
+        self.case_sensitivity_violations
+            .retain(|(_, other)| !in_excluded(other));
+        self.duplicate_image_files
+            .retain(|group| !group.iter().all(|p| in_excluded(p)));
+        self.css_import_cycles
+            .retain(|cycle| !cycle.iter().all(|p| in_excluded(p)));
+        self
+    }
+
+    /// Combine the reports of two audits, such as those of separate sites from
+    /// [`audit_multiple`], keeping each item once: items of `other` already reported by
+    /// `self` are dropped.
+    pub fn merge(mut self, other: AuditResult) -> AuditResult {
+        macro_rules! merge {
+            ($($field:ident),* $(,)?) => {
+                $(for item in other.$field {
+                    if !self.$field.contains(&item) {
+                        self.$field.push(item);
+                    }
+                })*
+            };
+        }
+        merge!(
+            nav_missing,
+            ghost,
+            help_missing,
+            broken_links,
+            nav_missing_link_sources,
+            missing_images,
+            orphan_images,
+            pages_with_footnotes,
+            pages_with_images,
+            pages_with_links,
+            absolute_internal_links,
+            title_case_violations,
+            links_with_query_strings,
+            stale_prefix_links,
+            poor_alt_text,
+            localhost_links,
+            private_ip_links,
+            scheme_relative_links,
+            unexpected_binaries,
+            url_encoded_internal_links,
+            single_child_sections,
+            pages_with_unknown_code_languages,
+            files_inside_site_dir,
+            help_url_pages_not_in_nav,
+            nav_pages_without_content,
+            broken_links_in_nav_descriptions,
+            description_broken_links,
+            pages_with_deep_headings,
+            links_with_repeated_words,
+            subsites_without_mkdocs,
+            duplicate_section_titles,
+            relay_pages,
+            non_md_nav_items,
+            include_depth_violations,
+            pages_with_multiple_h1,
+            pages_with_h1_not_first,
+            dead_css_selectors,
+            absolute_fs_image_refs,
+            oversized_svgs,
+            svgs_with_embedded_rasters,
+            case_sensitivity_violations,
+            help_url_fragments,
+            pages_with_mixed_image_styles,
+            orphan_mkdocs_files,
+            cross_file_help_url_duplicates,
+            pages_with_todos,
+            pages_with_excess_blank_lines,
+            include_only_sections,
+            duplicate_image_files,
+            css_import_cycles,
+            nav_entries_outside_docs,
+            unknown_theme_features,
+            empty_nav_sections,
+            malformed_help_urls,
+            broken_anchors,
+            duplicate_nav,
+            stub_pages,
+            heading_errors,
+            missing_frontmatter_fields,
+            title_mismatches,
+            case_collisions,
+            broken_external_links,
+            page_links,
+            missing_includes,
+            nav_errors,
+            pages_with_duplicate_anchors,
+            pages_with_unlabelled_code,
+        );
+        self
+    }
+
+    /// Each report [`AuditResult::total_issues`] counts with its number of findings,
+    /// named as in the serialised result.
+    fn issue_counts(&self) -> Vec<(&'static str, usize)> {
+        // Destructured without `..`, so a new report doesn't build until it's listed
+        // here as counted or not.
+        macro_rules! counts {
+            ($($field:ident),* $(,)?; $($skipped:ident),* $(,)?) => {{
+                let AuditResult { $($field,)* $($skipped: _,)* } = self;
+                vec![$((stringify!($field), $field.len())),*]
+            }};
+        }
+        counts!(
+            nav_missing,
+            ghost,
+            help_missing,
+            broken_links,
+            missing_images,
+            orphan_images,
+            absolute_internal_links,
+            title_case_violations,
+            links_with_query_strings,
+            poor_alt_text,
+            localhost_links,
+            private_ip_links,
+            scheme_relative_links,
+            unexpected_binaries,
+            url_encoded_internal_links,
+            single_child_sections,
+            pages_with_unknown_code_languages,
+            files_inside_site_dir,
+            help_url_pages_not_in_nav,
+            nav_pages_without_content,
+            broken_links_in_nav_descriptions,
+            description_broken_links,
+            pages_with_deep_headings,
+            links_with_repeated_words,
+            subsites_without_mkdocs,
+            duplicate_section_titles,
+            relay_pages,
+            non_md_nav_items,
+            include_depth_violations,
+            pages_with_multiple_h1,
+            pages_with_h1_not_first,
+            dead_css_selectors,
+            absolute_fs_image_refs,
+            oversized_svgs,
+            svgs_with_embedded_rasters,
+            case_sensitivity_violations,
+            pages_with_mixed_image_styles,
+            orphan_mkdocs_files,
+            cross_file_help_url_duplicates,
+            pages_with_todos,
+            pages_with_excess_blank_lines,
+            include_only_sections,
+            duplicate_image_files,
+            css_import_cycles,
+            nav_entries_outside_docs,
+            unknown_theme_features,
+            empty_nav_sections,
+            malformed_help_urls,
+            broken_anchors,
+            duplicate_nav,
+            stub_pages,
+            heading_errors,
+            missing_frontmatter_fields,
+            title_mismatches,
+            case_collisions,
+            broken_external_links,
+            missing_includes,
+            nav_errors,
+            pages_with_duplicate_anchors,
+            pages_with_unlabelled_code;
+            pages_with_footnotes,
+            pages_with_images,
+            pages_with_links,
+            page_links,
+            help_url_fragments,
+            nav_missing_link_sources,
+            stale_prefix_links,
+        )
+    }
+
+    /// The number of reported issues, across every report, warnings included. The
+    /// informational lists (pages with footnotes, images or links,
+    /// [`AuditResult::page_links`], `help_url_fragments`) don't count, nor do
+    /// `nav_missing_link_sources` and `stale_prefix_links`, which are already among the
+    /// broken links.
+    pub fn total_issues(&self) -> usize {
+        self.issue_counts()
+            .into_iter()
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// Whether the audit found no issues at all; see [`AuditResult::total_issues`].
+    pub fn is_clean(&self) -> bool {
+        self.total_issues() == 0
+    }
+
+    /// The number of reported issues that fail a run under `config`: those of
+    /// [`AuditResult::total_issues`] less the warnings (see
+    /// [`AuditResult::total_warnings`]). Clear the reports that weren't asked for
+    /// first, as ghost-cli does.
+    pub fn total_issues_with_config(&self, config: &GhostConfig) -> usize {
+        self.total_issues() - self.total_warnings(config)
+    }
+
+    /// The reports [`AuditResult::total_issues`] counts, other than the main six of
+    /// [`AuditItems`], that have findings: each report's items serialised as in a saved
+    /// result, keyed by field name, with the paths under `root` made relative to it.
+    pub fn other_report_items(
+        &self,
+        root: Option<&Path>,
+    ) -> BTreeMap<String, Vec<serde_json::Value>> {
+        const MAIN_REPORTS: [&str; 6] = [
+            "nav_missing",
+            "ghost",
+            "help_missing",
+            "broken_links",
+            "missing_images",
+            "orphan_images",
+        ];
+        let Ok(serde_json::Value::Object(mut fields)) = serde_json::to_value(self) else {
+            return BTreeMap::new();
+        };
+        self.issue_counts()
+            .into_iter()
+            .filter(|(field, count)| *count > 0 && !MAIN_REPORTS.contains(field))
+            .filter_map(|(field, _)| match fields.remove(field)? {
+                serde_json::Value::Array(mut items) => {
+                    if let Some(root) = root {
+                        items
+                            .iter_mut()
+                            .for_each(|item| relativise_json_paths(item, root));
+                    }
+                    Some((field.to_string(), items))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The number of reported findings that are only warnings under `config`: HELP_URLs
+    /// already naming docs/, and the severity-configurable reports set to
+    /// [`Severity::Warning`].
+    pub fn total_warnings(&self, config: &GhostConfig) -> usize {
+        self.malformed_help_urls.len() + self.count_with_severity(config, Severity::Warning)
+    }
+
+    /// The number of findings in the reports whose severity `config` sets to `severity`.
+    fn count_with_severity(&self, config: &GhostConfig, severity: Severity) -> usize {
+        [
+            (config.localhost_links_severity, self.localhost_links.len()),
+            (
+                config.private_ip_links_severity,
+                self.private_ip_links.len(),
+            ),
+            (
+                config.scheme_relative_links_severity,
+                self.scheme_relative_links.len(),
+            ),
+            (
+                config.pages_with_todos_severity,
+                self.pages_with_todos.len(),
+            ),
+        ]
+        .into_iter()
+        .filter(|(s, _)| *s == severity)
+        .map(|(_, count)| count)
+        .sum()
+    }
+
+    /// The main reports as one stream of [`Issue`]s, for reporters that don't need to
+    /// know each report's field: missing nav entries, ghosts, missing help URLs, broken
+    /// links, missing images and orphan images, in that order.
+    pub fn into_flat_issues(self) -> impl Iterator<Item = Issue> {
+        let nav_missing = self.nav_missing.into_iter().map(Issue::NavMissing);
+        let ghost = self.ghost.into_iter().map(Issue::Ghost);
+        let help_missing = self
+            .help_missing
+            .into_iter()
+            .map(|m| Issue::HelpMissing(m.path));
+        let broken_links = self.broken_links.into_iter().map(|bl| Issue::BrokenLink {
+            from_help_url: !bl.help_refs.is_empty(),
+            from: bl.from,
+            link: bl.link,
+        });
+        let missing_images = self
+            .missing_images
+            .into_iter()
+            .map(|bi| Issue::MissingImage {
+                from: bi.from,
+                image: bi.image,
+            });
+        let orphan_images = self.orphan_images.into_iter().map(Issue::OrphanImage);
+        nav_missing
+            .chain(ghost)
+            .chain(help_missing)
+            .chain(broken_links)
+            .chain(missing_images)
+            .chain(orphan_images)
+    }
+}
+
+/// Rewrite every string in `value` that is a path under `root` relative to it.
+fn relativise_json_paths(value: &mut serde_json::Value, root: &Path) {
+    match value {
+        serde_json::Value::String(s) => {
+            if let Ok(rel) = Path::new(s.as_str()).strip_prefix(root) {
+                *s = rel.display().to_string();
+            }
+        }
+        serde_json::Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| relativise_json_paths(item, root)),
+        serde_json::Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| relativise_json_paths(field, root)),
+        _ => {}
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// A report item about one file, for [`AuditResult::retain_paths`].
+trait ReportedPath {
+    fn reported_path(&self) -> Option<&Path>;
+}
+
+impl ReportedPath for PathBuf {
+    fn reported_path(&self) -> Option<&Path> {
+        Some(self)
+    }
+}
+
+impl<T> ReportedPath for (PathBuf, T) {
+    fn reported_path(&self) -> Option<&Path> {
+        Some(&self.0)
+    }
+}
+
+impl<T, U> ReportedPath for (PathBuf, T, U) {
+    fn reported_path(&self) -> Option<&Path> {
+        Some(&self.0)
+    }
+}
+
+impl ReportedPath for MissingHelpUrl {
+    fn reported_path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
+
+impl ReportedPath for HelpUrlEntry {
+    fn reported_path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
+
+impl ReportedPath for BrokenLink {
+    fn reported_path(&self) -> Option<&Path> {
+        Some(&self.from)
+    }
+}
+
+impl ReportedPath for BrokenImage {
+    fn reported_path(&self) -> Option<&Path> {
+        Some(&self.from)
+    }
+}
+
+impl ReportedPath for ExternalBrokenLink {
+    fn reported_path(&self) -> Option<&Path> {
+        Some(&self.from)
+    }
+}
+
+impl ReportedPath for BrokenAnchor {
+    fn reported_path(&self) -> Option<&Path> {
+        Some(&self.from)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BrokenImage {
     pub from: PathBuf,
     pub image: String,
 }
 
+/// One issue from the main reports of an audit; see [`AuditResult::into_flat_issues`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Issue {
+    NavMissing(PathBuf),
+    Ghost(PathBuf),
+    HelpMissing(PathBuf),
+    /// `from_help_url` is set when `from` is pulled in by help_urls.h.
+    BrokenLink {
+        from: PathBuf,
+        link: String,
+        from_help_url: bool,
+    },
+    MissingImage {
+        from: PathBuf,
+        image: String,
+    },
+    OrphanImage(PathBuf),
+}
+
+/// The main reports of an audit with paths made relative to the monorepo root, as the
+/// CLI's JSON output and the GUI both present them. Reports that weren't run are empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditItems {
+    pub nav_missing: Vec<String>,
+    pub ghost: Vec<String>,
+    pub help_missing: Vec<String>,
+    pub broken_links: Vec<BrokenLinkItem>,
+    pub missing_images: Vec<BrokenImageItem>,
+    pub orphan_images: Vec<String>,
+    pub footnotes: Vec<String>,
+    pub has_images: Vec<String>,
+    pub has_links: Vec<String>,
+    /// Every other report with findings, keyed by its [`AuditResult`] field; see
+    /// [`AuditResult::other_report_items`].
+    #[serde(default)]
+    pub other_reports: BTreeMap<String, Vec<serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenLinkItem {
+    pub from: String,
+    pub link: String,
+    /// HELP_URL(...) entries in help_urls.h that reference `from` (empty if not a
+    /// help-URL page). Carries the verbatim source text and line number for the report.
+    pub help_refs: Vec<HelpRef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenImageItem {
+    pub from: String,
+    pub image: String,
+}
+
+/// Item counts for the reports in [`AuditItems`]; `total` is the number of issues that
+/// fail the audit, and `warnings` the number of those reported that don't.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditCounts {
+    pub nav_missing: usize,
+    pub ghost: usize,
+    pub help_missing: usize,
+    pub broken_links: usize,
+    pub missing_images: usize,
+    pub orphan_images: usize,
+    pub footnotes: usize,
+    pub has_images: usize,
+    pub has_links: usize,
+    #[serde(default)]
+    pub other_reports: BTreeMap<String, usize>,
+    pub total: usize,
+    #[serde(default)]
+    pub warnings: usize,
+}
+
+/// A link to a page that exists, but whose `#anchor` matches none of its headings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenAnchor {
+    pub from: PathBuf,
+    pub link: String,
+    /// The anchor, without the `#`.
+    pub anchor: String,
+}
+
+/// A page referenced by help_urls.h that doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissingHelpUrl {
+    pub path: PathBuf,
+    /// The nav page, written as a `HELP_URL` path (`guide/topics/page`), that the missing
+    /// path is most likely a typo of.
+    pub suggested_help_url_fix: Option<String>,
+}
+
 /// A `HELP_URL(...)` entry from help_urls.h that pulls in a page.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HelpRef {
     /// 1-based line number in help_urls.h.
     pub line: usize,
@@ -81,1498 +943,6261 @@ pub struct HelpRef {
     pub text: String,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// A page named by a `HELP_URL(...)` entry. A `#section` on the URL is split off into
+/// `fragment` rather than ending up in the file name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HelpUrlEntry {
+    pub path: PathBuf,
+    /// The anchor after `#`, without the `#`.
+    pub fragment: Option<String>,
+    /// The `HELP_URL` path already named the `docs/` component, so it was used as written.
+    #[serde(default)]
+    pub docs_in_path: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BrokenLink {
     pub from: PathBuf,
     pub link: String,
     /// The `HELP_URL(...)` entries that pull in the `from` page (empty when the page is
     /// not referenced by help_urls.h). Lets the report cite the actual source line.
     pub help_refs: Vec<HelpRef>,
+    /// Up to three pages the link probably meant, closest first, when
+    /// [`AuditOptions::suggest_fixes`] is set (see [`suggest_link_fixes`]).
+    #[serde(default)]
+    pub suggestions: Vec<PathBuf>,
 }
 
-#[derive(Debug, Default)]
-pub struct LinkMaps {
-    pub url_to_src: HashMap<String, PathBuf>,
-    pub src_to_url: HashMap<PathBuf, String>,
+/// Errors from reading or writing ghost's own files, such as a saved [`AuditResult`],
+/// and from a site whose configuration can't be walked.
+#[derive(Debug)]
+pub enum GhostError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// mkdocs.yml files that `!include` each other, in include order; the first file
+    /// is repeated at the end.
+    IncludeCycle(Vec<PathBuf>),
+    /// An allowlist file that isn't TOML of `"file" = ["link", ...]` pairs.
+    Allowlist(toml::de::Error),
+    Toml(toml::de::Error),
+    /// An `!include` of a config file that doesn't exist.
+    MissingInclude(PathBuf),
 }
 
-/// Which source files to produce a detailed processing trace for. Matched by path
-/// *suffix*, case- and separator-insensitive, so a user can paste the tail of a path
-/// shown in a report (e.g. `system-functions/system-functions-by-category.md`). Empty
-/// ⇒ tracing is off.
-#[derive(Debug, Default, Clone)]
-pub struct TraceOptions {
-    pub targets: Vec<String>,
+impl std::fmt::Display for GhostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GhostError::Io(e) => write!(f, "I/O error: {e}"),
+            GhostError::Json(e) => write!(f, "invalid saved result: {e}"),
+            GhostError::IncludeCycle(files) => {
+                let chain: Vec<String> = files.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "circular !include: {}", chain.join(" → "))
+            }
+            GhostError::Allowlist(e) => write!(f, "invalid allowlist: {e}"),
+            GhostError::Toml(e) => write!(f, "invalid {GHOST_TOML}: {e}"),
+            GhostError::MissingInclude(file) => {
+                write!(f, "!include of {}, which doesn't exist", file.display())
+            }
+        }
+    }
 }
 
-/// A rendered, forward-ready processing trace — one section per traced file. Empty when
-/// no targets were requested.
-#[derive(Debug, Default)]
-pub struct AuditTrace {
-    pub text: String,
+impl Error for GhostError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GhostError::Io(e) => Some(e),
+            GhostError::Json(e) => Some(e),
+            GhostError::Toml(e) | GhostError::Allowlist(e) => Some(e),
+            GhostError::IncludeCycle(_) | GhostError::MissingInclude(_) => None,
+        }
+    }
 }
 
-/// Accumulates per-file processing events for the traced source files.
-struct Tracer {
-    /// Normalised (lower-case, `/`-separated) path suffixes to trace.
-    targets: Vec<String>,
-    /// Per-file blow-by-blow lines, keyed by source path.
-    events: HashMap<PathBuf, Vec<String>>,
+impl From<io::Error> for GhostError {
+    fn from(e: io::Error) -> Self {
+        GhostError::Io(e)
+    }
 }
 
-impl Tracer {
-    fn new(targets: &[String]) -> Self {
-        let targets = targets
-            .iter()
-            .map(|t| t.replace('\\', "/").to_lowercase())
-            .filter(|t| !t.is_empty())
-            .collect();
-        Tracer {
-            targets,
-            events: HashMap::new(),
-        }
+impl From<serde_json::Error> for GhostError {
+    fn from(e: serde_json::Error) -> Self {
+        GhostError::Json(e)
     }
+}
 
-    fn active(&self) -> bool {
-        !self.targets.is_empty()
+impl From<toml::de::Error> for GhostError {
+    fn from(e: toml::de::Error) -> Self {
+        GhostError::Toml(e)
     }
+}
 
-    /// Does `path` end with any requested target suffix?
-    fn traces(&self, path: &Path) -> bool {
-        if self.targets.is_empty() {
-            return false;
-        }
-        let s = path.to_string_lossy().replace('\\', "/").to_lowercase();
-        self.targets.iter().any(|t| s.ends_with(t))
-    }
+/// Write `result` to `path` as JSON, for use as a baseline in a later run.
+pub fn save_audit_result(result: &AuditResult, path: &Path) -> Result<(), GhostError> {
+    let json = serde_json::to_string_pretty(result)?;
+    fs::write(path, json)?;
+    Ok(())
+}
 
-    fn record(&mut self, file: &Path, line: impl Into<String>) {
-        self.events
-            .entry(file.to_path_buf())
-            .or_default()
-            .push(line.into());
-    }
+/// Read an [`AuditResult`] previously written by [`save_audit_result`].
+pub fn load_audit_result(path: &Path) -> Result<AuditResult, GhostError> {
+    let json = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
 }
 
-/// Outcome of resolving a `.md` link in the merged monorepo docs tree, carrying enough
-/// detail for the trace to explain *why* a link failed.
-enum MergedResolve {
-    Resolved(PathBuf),
-    /// The link climbed out of the merged docs root (too many `../`).
-    EscapedRoot,
-    /// The first path segment names no known subsite.
-    UnknownSubsite(String),
-    /// The path mapped into a real subsite but no such file exists there.
-    FileMissing(PathBuf),
+/// The issues in the main reports (nav_missing, ghost, help_missing, broken_links,
+/// missing_images and orphan_images) that appeared or went away between two runs; see
+/// [`diff_audit_results`]. The other reports are left empty.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AuditDiff {
+    /// Issues in the current run that the baseline didn't have.
+    pub new: AuditResult,
+    /// Issues in the baseline that the current run no longer has.
+    pub fixed: AuditResult,
 }
 
-impl MergedResolve {
-    fn resolved(&self) -> Option<&PathBuf> {
-        match self {
-            MergedResolve::Resolved(p) => Some(p),
-            _ => None,
-        }
+/// Compare the main reports of `current` with those of a `baseline` run, such as one
+/// loaded with [`load_audit_result`]. Broken links and images are matched on their
+/// page and target only, so a link whose help refs changed isn't reported as new.
+pub fn diff_audit_results(baseline: &AuditResult, current: &AuditResult) -> AuditDiff {
+    fn added<T: Clone, K: Eq + std::hash::Hash>(
+        before: &[T],
+        after: &[T],
+        key: impl Fn(&T) -> K,
+    ) -> Vec<T> {
+        let before: HashSet<K> = before.iter().map(&key).collect();
+        after
+            .iter()
+            .filter(|item| !before.contains(&key(item)))
+            .cloned()
+            .collect()
     }
-
-    fn reason(&self) -> String {
-        match self {
-            MergedResolve::Resolved(p) => format!("resolves → {}", p.display()),
-            MergedResolve::EscapedRoot => {
-                "escapes the merged docs root (too many '../')".to_string()
-            }
-            MergedResolve::UnknownSubsite(s) => format!("'{s}' is not a subsite"),
-            MergedResolve::FileMissing(p) => format!("no such file {}", p.display()),
-        }
+    let diff = |before: &AuditResult, after: &AuditResult| AuditResult {
+        nav_missing: added(&before.nav_missing, &after.nav_missing, PathBuf::clone),
+        ghost: added(&before.ghost, &after.ghost, PathBuf::clone),
+        help_missing: added(&before.help_missing, &after.help_missing, |m| {
+            m.path.clone()
+        }),
+        broken_links: added(&before.broken_links, &after.broken_links, |b| {
+            (b.from.clone(), b.link.clone())
+        }),
+        missing_images: added(&before.missing_images, &after.missing_images, |b| {
+            (b.from.clone(), b.image.clone())
+        }),
+        orphan_images: added(&before.orphan_images, &after.orphan_images, PathBuf::clone),
+        ..AuditResult::default()
+    };
+    AuditDiff {
+        new: diff(baseline, current),
+        fixed: diff(current, baseline),
     }
 }
 
-pub fn audit(mkdocs_yaml: &Path, help_urls: &Path) -> Result<AuditResult, Box<dyn Error>> {
-    let (result, _trace) = audit_traced(mkdocs_yaml, help_urls, &TraceOptions::default())?;
-    Ok(result)
+/// Read an allowlist of broken links to leave out of the report: a TOML file of
+/// `"file" = ["link", ...]` pairs, one per page.
+pub fn load_allowlist(path: &Path) -> Result<HashMap<PathBuf, Vec<String>>, GhostError> {
+    let allowlist: HashMap<PathBuf, Vec<String>> =
+        toml::from_str(&fs::read_to_string(path)?).map_err(GhostError::Allowlist)?;
+    Ok(allowlist)
 }
 
-/// Like [`audit`], but additionally produces an [`AuditTrace`]: a detailed, forward-ready
-/// blow-by-blow of how each file named in `trace_opts` was processed (discovery, role,
-/// and per-link resolution). The trace text is empty when no targets are requested.
-pub fn audit_traced(
-    mkdocs_yaml: &Path,
-    help_urls: &Path,
-    trace_opts: &TraceOptions,
-) -> Result<(AuditResult, AuditTrace), Box<dyn Error>> {
-    let mut tracer = Tracer::new(&trace_opts.targets);
-    let contents = fs::read_to_string(mkdocs_yaml)?;
-    let config: MkDocsConfig = serde_yaml::from_str(&contents)?;
-    let mut pages = HashSet::<PathBuf>::new();
-    let parent = mkdocs_yaml.parent().ok_or_else(|| {
-        io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "mkdocs file must reside within a directory",
-        )
-    })?;
-    collect_pages(&config.nav, &mut pages, parent)?;
-    let nav_missing = missing_files(&pages);
-    let mut markdown_roots = Vec::new();
-    // parent dir MUST NOT BE INCLUDED in markdown_roots!
-    let include_dirs = include_roots(&config.nav, parent);
-    markdown_roots.extend(include_dirs.clone());
-    let files = find_markdown(markdown_roots)?;
-    let files_set: HashSet<PathBuf> = files.iter().cloned().collect();
-    let mut ghost = orphans(&pages, &files); // markdown files in the file system not referenced by nav
-
-    let link_maps = build_link_maps(&config.nav, parent)?;
-    let subsite_map = build_subsite_map(&config.nav, parent);
-
-    // Each HELP_URL entry maps a page to the line it is defined on in help_urls.h.
-    // Group by page so a broken link on a help-referenced page can cite every line that
-    // pulls it in, and so the page is scanned once regardless of how many entries hit it.
-    let help_url_refs = extract_help_url_refs(help_urls, parent);
-    let help_files: Vec<PathBuf> = help_url_refs.iter().map(|(p, _)| p.clone()).collect();
-    let help_missing = missing_files(&help_files);
-    let mut help_refs: HashMap<PathBuf, Vec<HelpRef>> = HashMap::new();
-    for (path, href) in &help_url_refs {
-        help_refs
-            .entry(path.clone())
-            .or_default()
-            .push(href.clone());
-    }
-    for refs in help_refs.values_mut() {
-        refs.sort_by_key(|r| r.line);
-        refs.dedup();
-    }
-
-    // Transitively scan links: start with nav pages AND help_urls references,
-    // then follow links to discover more pages
-    let mut scanned: HashSet<PathBuf> = HashSet::new();
-    let mut to_scan: Vec<PathBuf> = pages
-        .iter()
-        .chain(help_files.iter())
-        .filter(|p| p.is_file())
-        .cloned()
-        .collect();
-    let mut all_referenced: HashSet<PathBuf> = help_files.iter().cloned().collect();
-    let mut all_broken_links: Vec<BrokenLink> = Vec::new();
-
-    while !to_scan.is_empty() {
-        // `scanned.insert` returns false for a path already present, so this both marks
-        // files scanned and de-duplicates within the batch. A page referenced many times
-        // by help_urls (e.g. glyphs.md via a shared macro) must be analysed once, not
-        // once per reference — otherwise its links are reported N times.
-        let file_contents: Vec<(PathBuf, String)> = to_scan
+/// Drop the broken links `allowlist` accepts from `result`. A key matches a page whose
+/// path ends with it, so keys may be relative to any ancestor such as the site root.
+pub fn apply_allowlist(result: &mut AuditResult, allowlist: &HashMap<PathBuf, Vec<String>>) {
+    result.broken_links.retain(|b| {
+        !allowlist
             .iter()
-            .filter(|p| scanned.insert((*p).clone()))
-            .filter_map(|p| fs::read_to_string(p).ok().map(|c| (p.clone(), c)))
-            .collect();
+            .any(|(file, links)| b.from.ends_with(file) && links.contains(&b.link))
+    });
+}
 
-        if file_contents.is_empty() {
-            break;
+/// The SARIF rules [`to_sarif`] reports under: (id, short description).
+const SARIF_RULES: [(&str, &str); 4] = [
+    (
+        "nav-missing",
+        "Nav entry points to a file that does not exist",
+    ),
+    (
+        "ghost-file",
+        "Markdown file is neither in the nav nor linked to",
+    ),
+    ("broken-link", "Internal link does not resolve to a page"),
+    (
+        "missing-image",
+        "Image reference does not resolve to a file",
+    ),
+];
+
+/// Render the nav, orphan, broken link and missing image reports of `result` as a SARIF
+/// 2.1.0 log, for GitHub code scanning and editors. Locations are relative to `root`;
+/// links and images carry the line of their first mention in the source page, and
+/// missing nav pages the mkdocs.yml listing them with the line of their entry, where it
+/// can be found.
+pub fn to_sarif(result: &AuditResult, root: &Path) -> String {
+    let mut contents: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut line_of = |file: &Path, needle: &str| -> Option<usize> {
+        let content = contents
+            .entry(file.to_path_buf())
+            .or_insert_with(|| fs::read_to_string(file).ok());
+        content
+            .as_deref()?
+            .lines()
+            .position(|l| l.contains(needle))
+            .map(|i| i + 1)
+    };
+    let sarif_result = |rule: &str, message: String, file: &Path, line: Option<usize>| {
+        let mut location = serde_json::json!({
+            "artifactLocation": { "uri": fwd_rel(file, root) },
+        });
+        if let Some(line) = line {
+            location["region"] = serde_json::json!({ "startLine": line });
         }
+        serde_json::json!({
+            "ruleId": rule,
+            "level": "error",
+            "message": { "text": message },
+            "locations": [{ "physicalLocation": location }],
+        })
+    };
 
-        let (referenced, broken_links) = analyse_links(
-            &file_contents,
-            &files_set,
-            parent,
-            &include_dirs,
-            &link_maps,
-            &help_refs,
-            &subsite_map,
-            &mut tracer,
-        )?;
-
-        all_broken_links.extend(broken_links);
-
-        // Find newly discovered files to scan
-        to_scan = referenced
-            .iter()
-            .filter(|p| !scanned.contains(*p) && p.is_file())
-            .cloned()
-            .collect();
-
-        all_referenced.extend(referenced);
+    let mut results = Vec::new();
+    for path in &result.nav_missing {
+        // The page doesn't exist, so point at its nav entry instead.
+        let (file, line) = match nav_entry_of(path, root) {
+            Some((mkdocs_yaml, entry)) => {
+                let line = line_of(&mkdocs_yaml, &entry);
+                (mkdocs_yaml, line)
+            }
+            None => (path.clone(), None),
+        };
+        results.push(sarif_result(
+            "nav-missing",
+            format!("{} is in the nav but does not exist", fwd_rel(path, root)),
+            &file,
+            line,
+        ));
+    }
+    for path in &result.ghost {
+        results.push(sarif_result(
+            "ghost-file",
+            format!(
+                "{} is not in the nav and nothing links to it",
+                fwd_rel(path, root)
+            ),
+            path,
+            None,
+        ));
+    }
+    for bl in &result.broken_links {
+        let line = line_of(&bl.from, &bl.link);
+        results.push(sarif_result(
+            "broken-link",
+            format!("Broken link to {}", bl.link),
+            &bl.from,
+            line,
+        ));
+    }
+    for bi in &result.missing_images {
+        let line = line_of(&bi.from, &bi.image);
+        results.push(sarif_result(
+            "missing-image",
+            format!("Missing image {}", bi.image),
+            &bi.from,
+            line,
+        ));
     }
 
-    ghost.retain(|p| !all_referenced.contains(p));
-
-    // Image analysis: find all image assets and check references
-    let image_extensions = ["png", "jpg", "jpeg", "gif", "svg", "webp", "ico", "bmp"];
-    let all_images: HashSet<PathBuf> = include_dirs
+    let rules: Vec<serde_json::Value> = SARIF_RULES
         .iter()
-        .flat_map(|dir| {
-            WalkDir::new(dir)
-                .into_iter()
-                .filter_map(|e| e.ok())
-                .filter(|e| {
-                    e.file_type().is_file()
-                        && e.path()
-                            .extension()
-                            .and_then(|ext| ext.to_str())
-                            .map(|ext| image_extensions.contains(&ext.to_lowercase().as_str()))
-                            .unwrap_or(false)
-                })
-                .map(|e| normalize_path(e.path()))
+        .map(|(id, description)| {
+            serde_json::json!({
+                "id": id,
+                "shortDescription": { "text": description },
+            })
         })
         .collect();
+    let sarif = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "ghost",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    format!("{:#}", sarif)
+}
 
-    // Find CSS files in include dirs and documentation-assets
-    let css_dirs: Vec<PathBuf> = include_dirs
+/// The mkdocs.yml whose nav lists `page`, the nearest one above it within `root`, and
+/// the entry as written there: the page's path relative to that site's docs_dir.
+fn nav_entry_of(page: &Path, root: &Path) -> Option<(PathBuf, String)> {
+    let mkdocs_yaml = page
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(root))
+        .map(|dir| dir.join("mkdocs.yml"))
+        .find(|yml| yml.is_file())?;
+    let config: MkDocsConfig =
+        serde_yaml::from_str(&fs::read_to_string(&mkdocs_yaml).ok()?).ok()?;
+    let docs = mkdocs_yaml.parent()?.join(config.docs_dir());
+    let entry = fwd_rel(page.strip_prefix(&docs).ok()?, Path::new(""));
+    Some((mkdocs_yaml, entry))
+}
+
+/// Render a page link graph (see [`page_dependency_graph`]) as a Graphviz DOT digraph,
+/// one node per page labelled with its path relative to `root`. Nav pages are drawn in
+/// blue, `ghosts` (which nothing links to) in red and pages reached only through links
+/// in grey.
+pub fn to_dot(
+    graph: &HashMap<PathBuf, Vec<PathBuf>>,
+    root: Option<&Path>,
+    nav_pages: &HashSet<PathBuf>,
+    ghosts: &[PathBuf],
+) -> String {
+    let label = |p: &Path| {
+        let rel = root.map_or_else(|| p.to_string_lossy().replace('\\', "/"), |r| fwd_rel(p, r));
+        format!("\"{}\"", rel.replace('"', "\\\""))
+    };
+    let mut nodes: Vec<&PathBuf> = graph
         .iter()
-        .cloned()
-        .chain(std::iter::once(parent.join("documentation-assets")))
-        .filter(|p| p.exists())
+        .flat_map(|(page, targets)| std::iter::once(page).chain(targets))
+        .chain(ghosts)
         .collect();
-
-    let css_files: Vec<PathBuf> = css_dirs
+    nodes.sort();
+    nodes.dedup();
+    let ghosts: HashSet<&PathBuf> = ghosts.iter().collect();
+
+    let mut out = String::from("digraph pages {\n    node [shape=box];\n");
+    for node in nodes {
+        let color = if ghosts.contains(node) {
+            "red"
+        } else if nav_pages.contains(node) {
+            "blue"
+        } else {
+            "grey"
+        };
+        out.push_str(&format!(
+            "    {} [color={color}, fontcolor={color}];\n",
+            label(node)
+        ));
+    }
+    let mut edges: Vec<(&PathBuf, &PathBuf)> = graph
         .iter()
-        .flat_map(|dir| {
-            WalkDir::new(dir)
-                .into_iter()
-                .filter_map(|e| e.ok())
-                .filter(|e| {
-                    e.file_type().is_file()
-                        && e.path()
-                            .extension()
-                            .and_then(|ext| ext.to_str())
-                            .map(|ext| ext == "css" || ext == "scss")
-                            .unwrap_or(false)
-                })
-                .map(|e| e.path().to_path_buf())
-        })
+        .flat_map(|(page, targets)| targets.iter().map(move |t| (page, t)))
         .collect();
+    edges.sort();
+    for (from, to) in edges {
+        out.push_str(&format!("    {} -> {};\n", label(from), label(to)));
+    }
+    out.push_str("}\n");
+    out
+}
 
-    // Analyse image references in ALL markdown files on disk (not just
-    // nav-reachable ones) so that images used by orphaned pages are still
-    // recognised as referenced.
-    let (missing_images, referenced_images) =
-        analyse_image_refs(&files_set, &css_files, &all_images, &include_dirs)?;
+/// The `nav` of an mkdocs.yml as written, without expanding its `!include`s.
+pub fn load_mkdocs_nav(mkdocs_yaml: &Path) -> Result<Vec<NavItem>, Box<dyn Error>> {
+    let contents = fs::read_to_string(mkdocs_yaml)?;
+    let config: MkDocsConfig = serde_yaml::from_str(&contents)?;
+    Ok(config.nav)
+}
 
-    // Find orphan images (images not referenced anywhere)
-    let orphan_images: Vec<PathBuf> = all_images
-        .iter()
-        .filter(|img| !referenced_images.contains(*img))
-        .cloned()
-        .collect();
+/// The directories holding the subsites an mkdocs.yml pulls in with `!include`.
+pub fn find_include_roots(mkdocs_yaml: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let nav = load_mkdocs_nav(mkdocs_yaml)?;
+    let parent = mkdocs_yaml.parent().unwrap_or_else(|| Path::new("."));
+    Ok(include_roots(&nav, parent))
+}
 
-    // Find pages with footnotes
-    let pages_with_footnotes: Vec<PathBuf> = scanned
-        .iter()
-        .filter(|p| {
-            fs::read_to_string(p)
-                .map(|content| has_footnotes(&content))
-                .unwrap_or(false)
-        })
-        .cloned()
-        .collect();
+/// Write `nav` to `path` as JSON, as the baseline for [`detect_nav_reordering`].
+pub fn save_nav_baseline(nav: &[NavItem], path: &Path) -> Result<(), GhostError> {
+    let json = serde_json::to_string_pretty(nav)?;
+    fs::write(path, json)?;
+    Ok(())
+}
 
-    // Find pages with images
-    let pages_with_images: Vec<PathBuf> = scanned
+/// Read a nav previously written by [`save_nav_baseline`].
+pub fn load_nav_baseline(path: &Path) -> Result<Vec<NavItem>, GhostError> {
+    let json = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// A nav level whose entries kept their place in the nav but not their order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NavOrderChange {
+    /// Titles of the enclosing sections; empty for the top level.
+    pub section: Vec<String>,
+    /// The entries' order in the baseline.
+    pub old_order: Vec<String>,
+    /// The entries' order now.
+    pub new_order: Vec<String>,
+}
+
+/// Compare the order of nav entries in `current` against `baseline`, level by level.
+/// Entries are identified by title (or path, for untitled ones) and sections matched by
+/// title; added and removed entries don't count as reordering, so each change lists only
+/// the entries present in both. `!include`s are compared as entries, not expanded.
+pub fn detect_nav_reordering(current: &[NavItem], baseline: &[NavItem]) -> Vec<NavOrderChange> {
+    let mut out = Vec::new();
+    collect_nav_reordering(current, baseline, &mut Vec::new(), &mut out);
+    out
+}
+
+fn collect_nav_reordering(
+    current: &[NavItem],
+    baseline: &[NavItem],
+    section: &mut Vec<String>,
+    out: &mut Vec<NavOrderChange>,
+) {
+    let current_keys: Vec<String> = current.iter().flat_map(nav_entry_keys).collect();
+    let baseline_keys: Vec<String> = baseline.iter().flat_map(nav_entry_keys).collect();
+    let new_order: Vec<String> = current_keys
         .iter()
-        .filter(|p| {
-            fs::read_to_string(p)
-                .map(|content| has_images(&content))
-                .unwrap_or(false)
-        })
+        .filter(|k| baseline_keys.contains(k))
         .cloned()
         .collect();
-
-    // Find pages with links
-    let pages_with_links: Vec<PathBuf> = scanned
+    let old_order: Vec<String> = baseline_keys
         .iter()
-        .filter(|p| {
-            fs::read_to_string(p)
-                .map(|content| has_links(&content))
-                .unwrap_or(false)
-        })
+        .filter(|k| current_keys.contains(k))
         .cloned()
         .collect();
+    if new_order != old_order {
+        out.push(NavOrderChange {
+            section: section.clone(),
+            old_order,
+            new_order,
+        });
+    }
 
-    let trace = AuditTrace {
-        text: render_trace(
-            &tracer,
-            &trace_opts.targets,
-            parent,
-            &pages,
-            &help_files,
-            &help_refs,
-            &all_referenced,
-            &scanned,
-            &files_set,
-            &all_broken_links,
-        ),
+    let sections = |items: &[NavItem]| -> Vec<(String, Vec<NavItem>)> {
+        let mut found = Vec::new();
+        for item in items {
+            if let NavItem::Section(map) = item {
+                let mut titles: Vec<&String> = map.keys().collect();
+                titles.sort();
+                found.extend(titles.into_iter().map(|t| (t.clone(), map[t].clone())));
+            }
+        }
+        found
     };
+    let baseline_sections = sections(baseline);
+    for (title, children) in sections(current) {
+        if let Some((_, old_children)) = baseline_sections.iter().find(|(t, _)| *t == title) {
+            section.push(title);
+            collect_nav_reordering(&children, old_children, section, out);
+            section.pop();
+        }
+    }
+}
 
-    Ok((
-        AuditResult {
-            nav_missing,
-            ghost,
-            help_missing,
-            broken_links: all_broken_links,
-            missing_images,
-            orphan_images,
-            pages_with_footnotes,
-            pages_with_images,
-            pages_with_links,
-        },
-        trace,
-    ))
+/// How an entry is identified when comparing nav order: its title, or its path if it has
+/// none.
+fn nav_entry_keys(item: &NavItem) -> Vec<String> {
+    let mut keys: Vec<String> = match item {
+        NavItem::Page(map) => map.keys().cloned().collect(),
+        NavItem::Section(map) => map.keys().cloned().collect(),
+        NavItem::PlainPath(path) | NavItem::Unrecognised(path) => vec![path.clone()],
+    };
+    keys.sort();
+    keys
 }
 
-/// Render `p` relative to `base` using forward slashes, so trace output is identical and
-/// portable across platforms (Windows `Path::display` would emit backslashes).
-fn fwd_rel(p: &Path, base: &Path) -> String {
-    p.strip_prefix(base)
-        .unwrap_or(p)
-        .to_string_lossy()
-        .replace('\\', "/")
+#[derive(Debug, Default)]
+pub struct LinkMaps {
+    pub url_to_src: HashMap<String, PathBuf>,
+    pub src_to_url: HashMap<PathBuf, String>,
 }
 
-/// Assemble the human-readable, forward-ready trace text from the collected per-file
-/// events plus the file's role in the audit (discovery, reachability, analysis).
-#[allow(clippy::too_many_arguments)]
-fn render_trace(
-    tracer: &Tracer,
-    targets: &[String],
-    mkdocs_dir: &Path,
-    pages: &HashSet<PathBuf>,
-    help_files: &[PathBuf],
-    help_refs: &HashMap<PathBuf, Vec<HelpRef>>,
-    all_referenced: &HashSet<PathBuf>,
-    scanned: &HashSet<PathBuf>,
-    files_set: &HashSet<PathBuf>,
-    broken_links: &[BrokenLink],
-) -> String {
-    use std::fmt::Write;
+/// Settings for the optional checks. The default, which the plain [`audit`] runs with,
+/// leaves every check that needs configuring switched off.
+#[derive(Debug, Clone)]
+pub struct GhostConfig {
+    /// The published site's base URL, e.g. `https://docs.example.com/`. Links starting
+    /// with it point back into this site: they are reported as absolute internal links
+    /// and validated as site-absolute paths rather than skipped as external.
+    pub site_base_url: Option<String>,
+    /// The capitalisation nav section titles must follow.
+    pub title_case_style: TitleCaseStyle,
+    /// Alt texts to reject as generic, on top of [`GENERIC_ALT_TEXTS`].
+    pub generic_alt_texts: Vec<String>,
+    /// How links to localhost are reported; an error by default, since such a link is
+    /// always wrong in published docs.
+    pub localhost_links_severity: Severity,
+    /// How links to private network addresses are reported; a warning by default, since
+    /// such a link may be deliberate in docs for an on-premises install.
+    pub private_ip_links_severity: Severity,
+    /// How scheme-relative links are reported; a warning by default, since such a link
+    /// is usually a mistyped site-absolute one but may point at a CDN on purpose.
+    pub scheme_relative_links_severity: Severity,
+    /// How pages with front matter TODOs are reported; a warning by default, since a
+    /// TODO is a note for the authors rather than something readers see.
+    pub pages_with_todos_severity: Severity,
+    /// File extensions to accept in docs directories, on top of [`DOCS_EXTENSIONS`].
+    pub allowed_extensions: Vec<String>,
+    /// Fenced code block languages to accept, on top of [`KNOWN_CODE_LANGUAGES`].
+    pub code_languages: Vec<String>,
+    /// Front matter fields whose values are image paths (MkDocs Material's `hero_image`
+    /// and the like); they are checked alongside images in the page body.
+    pub frontmatter_image_fields: Vec<String>,
+    /// The deepest heading level pages may use (3 to forbid H4 and below); `None` leaves
+    /// heading depth unchecked.
+    pub max_heading_level: Option<usize>,
+    /// The most consecutive blank lines pages may have outside code blocks; `None` leaves
+    /// blank lines unchecked.
+    pub max_blank_lines: Option<usize>,
+    /// Words of prose, outside links and headings, a page needs so as not to count as a
+    /// relay page (see [`is_relay_page_with`]).
+    pub min_non_link_words: usize,
+    /// How deeply `!include`s may nest: 1 allows the root mkdocs.yml to include subsites
+    /// but not those subsites to include others. Includes past the limit are reported
+    /// and not followed. Unlimited by default.
+    pub max_include_depth: usize,
+    /// The size in bytes above which an SVG image is reported as oversized.
+    pub max_svg_bytes: u64,
+    /// Further `HELP_URL` headers, for products that ship their own, read alongside the
+    /// main help_urls.h.
+    pub extra_help_urls: Vec<PathBuf>,
+    /// Words of body text a page needs so as not to be reported as a stub (see
+    /// [`word_count`]); 0 leaves pages unchecked.
+    pub min_word_count: usize,
+    /// How similar (see [`title_similarity`]) a nav label and its page's H1 must be for
+    /// the title consistency check to accept them, from 0 to 1.
+    pub min_title_similarity: f64,
+}
 
-    if !tracer.active() {
-        return String::new();
+impl Default for GhostConfig {
+    fn default() -> Self {
+        GhostConfig {
+            site_base_url: None,
+            title_case_style: TitleCaseStyle::default(),
+            generic_alt_texts: Vec::new(),
+            localhost_links_severity: Severity::Error,
+            private_ip_links_severity: Severity::Warning,
+            scheme_relative_links_severity: Severity::Warning,
+            pages_with_todos_severity: Severity::Warning,
+            allowed_extensions: Vec::new(),
+            code_languages: Vec::new(),
+            frontmatter_image_fields: ["hero_image", "thumbnail", "og_image"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            max_heading_level: None,
+            max_blank_lines: None,
+            min_non_link_words: 1,
+            max_include_depth: usize::MAX,
+            max_svg_bytes: 100 * 1024,
+            extra_help_urls: Vec::new(),
+            min_word_count: 0,
+            min_title_similarity: 0.5,
+        }
     }
+}
 
-    // Every path ghost is aware of — files on disk, nav pages, help targets, scanned
-    // pages — so a target can match even a file that is unreachable or missing from disk
-    // (which is itself the useful diagnostic).
-    let mut known: Vec<PathBuf> = files_set.iter().cloned().collect();
-    for p in pages.iter().chain(help_files.iter()).chain(scanned.iter()) {
-        known.push(p.clone());
-    }
-    known.sort();
-    known.dedup();
+/// How seriously a report is taken: errors fail the run, warnings are only shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Severity {
+    Warning,
+    #[default]
+    Error,
+}
 
-    let mut out = String::new();
-    for target in targets {
-        let norm = target.replace('\\', "/").to_lowercase();
-        if norm.is_empty() {
-            continue;
-        }
-        let matches: Vec<&PathBuf> = known
-            .iter()
-            .filter(|p| {
-                p.to_string_lossy()
-                    .replace('\\', "/")
-                    .to_lowercase()
-                    .ends_with(&norm)
-            })
-            .collect();
+impl std::str::FromStr for Severity {
+    type Err = String;
 
-        if matches.is_empty() {
-            let _ = writeln!(out, "FILE (no file known to ghost matched \"{target}\")\n");
-            continue;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "warning" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            other => Err(format!(
+                "unknown severity '{other}' (expected warning or error)"
+            )),
         }
+    }
+}
 
-        for f in matches {
-            let rel = fwd_rel(f, mkdocs_dir);
-            let _ = writeln!(out, "FILE {rel}");
-            let _ = writeln!(out, "  exists on disk : {}", f.is_file());
-
-            let mut reached = Vec::new();
-            if pages.contains(f) {
-                reached.push("nav".to_string());
-            }
-            if let Some(refs) = help_refs.get(f) {
-                let lines: Vec<String> = refs.iter().map(|r| r.line.to_string()).collect();
-                reached.push(format!("help_urls.h (line {})", lines.join(", ")));
-            }
-            if all_referenced.contains(f) && !pages.contains(f) && !help_refs.contains_key(f) {
-                reached.push("linked from another scanned page".to_string());
-            }
-            if reached.is_empty() {
-                let _ = writeln!(
-                    out,
-                    "  reached by     : NOT REACHED — not in nav, not in help_urls.h, not linked \
-                     from any scanned page (so its links are never checked)"
-                );
-            } else {
-                let _ = writeln!(out, "  reached by     : {}", reached.join(" + "));
-            }
-            let _ = writeln!(out, "  analysed       : {}", scanned.contains(f));
-
-            if let Some(events) = tracer.events.get(f) {
-                for e in events {
-                    let _ = writeln!(out, "  {e}");
-                }
-            }
+/// House style for nav section titles.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TitleCaseStyle {
+    /// "Title Case Section Names": every word capitalised except short stopwords.
+    AllTitle,
+    /// "Sentence case section names": only the first word capitalised.
+    AllSentence,
+    /// No house style; nothing is checked.
+    #[default]
+    Mixed,
+}
 
-            let broken_here = broken_links.iter().filter(|b| &b.from == f).count();
-            let _ = writeln!(
-                out,
-                "  SUMMARY        : {broken_here} broken link(s) from this file"
-            );
-            let _ = writeln!(out);
+impl std::str::FromStr for TitleCaseStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "title" => Ok(TitleCaseStyle::AllTitle),
+            "sentence" => Ok(TitleCaseStyle::AllSentence),
+            "mixed" => Ok(TitleCaseStyle::Mixed),
+            other => Err(format!(
+                "unknown title case style '{other}' (expected title, sentence or mixed)"
+            )),
         }
     }
+}
 
-    out
+/// Which source files to produce a detailed processing trace for. Matched by path
+/// *suffix*, case- and separator-insensitive, so a user can paste the tail of a path
+/// shown in a report (e.g. `system-functions/system-functions-by-category.md`). Empty
+/// ⇒ tracing is off.
+#[derive(Debug, Default, Clone)]
+pub struct TraceOptions {
+    pub targets: Vec<String>,
 }
 
-/// Check if markdown content contains footnote references or definitions.
-/// Footnotes use syntax like `[^1]` for references and `[^1]:` for definitions.
-pub fn has_footnotes(markdown: &str) -> bool {
-    // Match footnote references [^identifier] or definitions [^identifier]:
-    // The identifier can be alphanumeric with hyphens/underscores
-    let footnote_re = Regex::new(r"\[\^[^\]]+\]").unwrap();
-    footnote_re.is_match(markdown)
+/// Which parts of an audit to run, for callers of [`audit_with_options`] that only want
+/// some reports. A skipped check does no work and leaves its report empty. Build one
+/// with [`AuditOptions::builder`]; the default runs everything but the `check_*` checks,
+/// which are opt-in.
+#[derive(Debug, Clone)]
+pub struct AuditOptions {
+    /// Leave `broken_links` empty. Links are still resolved, since that is how pages
+    /// reached only by links are told apart from ghosts.
+    pub skip_broken_links: bool,
+    /// Leave `missing_images` empty; with `skip_orphan_images` too, no page or
+    /// stylesheet is read for image references.
+    pub skip_missing_images: bool,
+    /// Leave `orphan_images` empty.
+    pub skip_orphan_images: bool,
+    /// Leave `unexpected_binaries` empty, without walking the docs directories for them.
+    pub skip_unexpected_binaries: bool,
+    /// Leave `subsites_without_mkdocs` empty, without walking the monorepo for them.
+    pub skip_subsites_without_mkdocs: bool,
+    /// Leave `dead_css_selectors` empty, without reading the stylesheets.
+    pub skip_dead_css_selectors: bool,
+    /// Leave `oversized_svgs` and `svgs_with_embedded_rasters` empty, without reading the
+    /// SVGs.
+    pub skip_svg_checks: bool,
+    /// Leave `case_sensitivity_violations` and `case_collisions` empty.
+    pub skip_case_sensitivity: bool,
+    /// Leave `orphan_mkdocs_files` empty, without walking the monorepo for them.
+    pub skip_orphan_mkdocs_files: bool,
+    /// Leave `cross_file_help_url_duplicates` empty, without reading the help URL
+    /// headers again.
+    pub skip_cross_file_help_url_duplicates: bool,
+    /// Leave `css_import_cycles` empty, without reading the SCSS files.
+    pub skip_css_import_cycles: bool,
+    /// How many links away from the nav and help URL pages to follow: 0 scans only
+    /// those pages. Unlimited when `None`.
+    pub max_scan_depth: Option<usize>,
+    /// Subsites (top-level directories beside mkdocs.yml) to leave out: their pages
+    /// aren't scanned and nothing under them is reported. Each is a name or a glob
+    /// pattern such as `*-legacy`.
+    pub exclude_subsites: Vec<String>,
+    /// Keep each scanned page's links here between runs, keyed by the page's
+    /// modification time, so the next audit only re-parses the pages that changed.
+    pub cache_path: Option<PathBuf>,
+    /// Fill `heading_errors` by checking that headings nest without gaps.
+    pub check_headings: bool,
+    /// Fill `pages_with_multiple_h1` and `pages_with_h1_not_first`.
+    pub check_multiple_h1: bool,
+    /// Fill `pages_with_duplicate_anchors` with headings that want the same anchor.
+    pub check_duplicate_anchors: bool,
+    /// Fill `pages_with_unlabelled_code` with pages that have a code block naming no
+    /// language, which MkDocs Material renders without highlighting.
+    pub check_unlabelled_code_blocks: bool,
+    /// Fill `duplicate_image_files` by hashing every image.
+    pub check_duplicate_images: bool,
+    /// Front matter fields every scanned page must set (`title`, `description`, ...);
+    /// pages that don't are reported in `missing_frontmatter_fields`.
+    pub required_frontmatter_fields: Vec<String>,
+    /// Fill `title_mismatches` by comparing each nav label with its page's H1.
+    pub check_title_consistency: bool,
+    /// Attach likely targets to each broken link. This compares every broken link with
+    /// every page, so it is slow on a large site.
+    pub suggest_fixes: bool,
+    /// Told how the audit is getting on, for showing a progress bar.
+    pub progress: Option<ProgressHandler>,
+    /// Request every `http(s)` URL linked from a scanned page and fill
+    /// `broken_external_links` with those that fail. Answers are kept in
+    /// [`EXTERNAL_LINK_CACHE`] beside mkdocs.yml and reused for
+    /// `external_link_cache_ttl`.
+    pub check_external_links: bool,
+    /// How long to wait for each external URL; 10 seconds when `None`.
+    pub external_link_timeout: Option<Duration>,
+    /// How long a cached answer for an external URL is trusted; 24 hours when `None`.
+    pub external_link_cache_ttl: Option<Duration>,
+    /// Leave files that `.gitignore` (or `.ignore`) rules exclude, and hidden
+    /// directories, out of every walk of the docs and the monorepo: the search for ghost
+    /// pages, images, stylesheets and stray files, subsites and mkdocs.yml files, so
+    /// `node_modules/*/README.md` isn't reported. On by default.
+    pub respect_gitignore: bool,
 }
 
-/// Check if markdown content contains image references (markdown or HTML).
-pub fn has_images(markdown: &str) -> bool {
-    !extract_image_refs(markdown).is_empty()
+impl Default for AuditOptions {
+    fn default() -> Self {
+        AuditOptions {
+            skip_broken_links: false,
+            skip_missing_images: false,
+            skip_orphan_images: false,
+            skip_unexpected_binaries: false,
+            skip_subsites_without_mkdocs: false,
+            skip_dead_css_selectors: false,
+            skip_svg_checks: false,
+            skip_case_sensitivity: false,
+            skip_orphan_mkdocs_files: false,
+            skip_cross_file_help_url_duplicates: false,
+            skip_css_import_cycles: false,
+            max_scan_depth: None,
+            exclude_subsites: Vec::new(),
+            cache_path: None,
+            check_headings: false,
+            check_multiple_h1: false,
+            check_duplicate_anchors: false,
+            check_unlabelled_code_blocks: false,
+            check_duplicate_images: false,
+            required_frontmatter_fields: Vec::new(),
+            check_title_consistency: false,
+            suggest_fixes: false,
+            progress: None,
+            check_external_links: false,
+            external_link_timeout: None,
+            external_link_cache_ttl: None,
+            respect_gitignore: true,
+        }
+    }
 }
 
-/// Check if markdown content contains links (markdown or HTML).
-pub fn has_links(markdown: &str) -> bool {
-    !extract_links(markdown).is_empty()
+/// How far an audit has got, as passed to [`AuditOptions::progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// The walk of the docs directories found this many markdown files.
+    Scanning { files_found: usize },
+    /// Links of `done` of the `total` pages known so far have been analysed. `total`
+    /// grows as links lead to pages the nav and help URLs didn't list.
+    Analysing { done: usize, total: usize },
 }
 
-pub fn extract_links(markdown: &str) -> Vec<String> {
-    let mut links = Vec::new();
-    let parser = Parser::new(markdown);
-    let link_selector = Selector::parse("a[href]").unwrap();
+/// A callback for [`Progress`] reports. It is called from rayon's worker threads.
+#[derive(Clone)]
+pub struct ProgressHandler(Arc<dyn Fn(Progress) + Send + Sync>);
 
-    for event in parser {
-        match event {
-            Event::Start(Tag::Link { dest_url, .. }) => {
-                links.push(dest_url.into_string());
-            }
-            Event::Html(html) | Event::InlineHtml(html) => {
-                let fragment = Html::parse_fragment(&html);
-                for el in fragment.select(&link_selector) {
-                    if let Some(href) = el.value().attr("href") {
-                        links.push(href.to_string());
-                    }
-                }
-            }
-            _ => {}
-        }
+impl std::fmt::Debug for ProgressHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressHandler(..)")
     }
-
-    links
 }
 
-/// Extract image references from markdown content.
-/// Handles both markdown syntax ![alt](path) and HTML <img src="path">
-pub fn extract_image_refs(markdown: &str) -> Vec<String> {
-    let mut images = HashSet::new();
-    let parser = Parser::new(markdown);
-    let img_selector = Selector::parse("img[src]").unwrap();
+impl AuditOptions {
+    pub fn builder() -> AuditOptionsBuilder {
+        AuditOptionsBuilder::default()
+    }
 
-    for event in parser {
-        match event {
-            Event::Start(Tag::Image { dest_url, .. }) => {
-                images.insert(dest_url.into_string());
-            }
-            Event::Html(html) | Event::InlineHtml(html) => {
-                let fragment = Html::parse_fragment(&html);
-                for el in fragment.select(&img_selector) {
-                    if let Some(src) = el.value().attr("src") {
-                        images.insert(src.to_string());
-                    }
-                }
-            }
-            _ => {}
+    fn report(&self, progress: Progress) {
+        if let Some(ProgressHandler(handler)) = &self.progress {
+            handler(progress);
         }
     }
+}
 
-    // Regex fallback: pulldown_cmark can misparse markdown image syntax as
-    // code/text when raw HTML blocks precede fenced code blocks that contain
-    // blank lines.  A direct regex scan catches what the AST walk misses.
-    let md_img_re = Regex::new(r"!\[[^\]]*\]\(([^)]+)\)").unwrap();
-    for cap in md_img_re.captures_iter(markdown) {
-        if let Some(m) = cap.get(1) {
-            images.insert(m.as_str().to_string());
-        }
+/// Subsite names and glob patterns (`*-legacy`, `archived-?`), compiled once for
+/// matching many paths.
+struct SubsitePatterns(Vec<Pattern>);
+
+impl SubsitePatterns {
+    /// A name that isn't a valid pattern (an unclosed `[`) matches only itself.
+    fn new(subsites: &[impl AsRef<str>]) -> Self {
+        SubsitePatterns(
+            subsites
+                .iter()
+                .map(|s| {
+                    let s = s.as_ref();
+                    Pattern::new(s).unwrap_or_else(|_| {
+                        Pattern::new(&Pattern::escape(s)).expect("escaped pattern is valid")
+                    })
+                })
+                .collect(),
+        )
     }
 
-    images.into_iter().collect()
+    /// Whether `path` lies in a matching subsite of the monorepo at `root`.
+    fn contain(&self, path: &Path, root: &Path) -> bool {
+        path.strip_prefix(root)
+            .ok()
+            .and_then(|rel| rel.components().next())
+            .is_some_and(|first| {
+                let subsite = first.as_os_str().to_string_lossy();
+                self.0.iter().any(|pattern| pattern.matches(&subsite))
+            })
+    }
 }
 
-/// Extract image references from CSS content.
-/// Handles url() references in background-image, content, etc.
-pub fn extract_css_image_refs(css: &str) -> Vec<String> {
-    let url_re = Regex::new(r#"url\s*\(\s*['"]?([^'")]+)['"]?\s*\)"#).unwrap();
-    url_re
-        .captures_iter(css)
-        .filter_map(|cap| {
-            let url = cap.get(1)?.as_str().trim();
-            // Skip data URIs and external URLs
-            if url.starts_with("data:") || url.starts_with("http://") || url.starts_with("https://")
-            {
-                return None;
-            }
-            Some(url.to_string())
-        })
-        .collect()
+/// Builds an [`AuditOptions`], starting from the default.
+#[derive(Debug, Default, Clone)]
+pub struct AuditOptionsBuilder {
+    options: AuditOptions,
 }
 
-/// Normalise image paths - filter out external URLs
-fn normalise_image_refs<I>(refs: I) -> Vec<String>
-where
-    I: IntoIterator<Item = String>,
-{
-    refs.into_iter()
-        .filter(|r| {
-            !r.starts_with("http://") && !r.starts_with("https://") && !r.starts_with("data:")
-        })
-        .collect()
-}
+impl AuditOptionsBuilder {
+    pub fn skip_broken_links(mut self, skip: bool) -> Self {
+        self.options.skip_broken_links = skip;
+        self
+    }
 
-/// Analyse image references in markdown files and CSS files.
-/// Returns (missing_images, referenced_images).
-fn analyse_image_refs(
-    markdown_files: &HashSet<PathBuf>,
-    css_files: &[PathBuf],
-    all_images: &HashSet<PathBuf>,
-    include_dirs: &[PathBuf],
-) -> io::Result<(Vec<BrokenImage>, HashSet<PathBuf>)> {
-    let mut missing = Vec::new();
-    let mut referenced = HashSet::new();
+    pub fn skip_missing_images(mut self, skip: bool) -> Self {
+        self.options.skip_missing_images = skip;
+        self
+    }
 
-    // Process markdown files
-    for src in markdown_files {
-        if let Ok(content) = fs::read_to_string(src) {
-            let image_refs = normalise_image_refs(extract_image_refs(&content));
-            for img_ref in image_refs {
-                if let Some(resolved) = resolve_image_ref(src, &img_ref, all_images, include_dirs) {
-                    referenced.insert(resolved);
-                } else {
-                    missing.push(BrokenImage {
-                        from: src.clone(),
-                        image: img_ref,
-                    });
-                }
-            }
-        }
+    pub fn skip_orphan_images(mut self, skip: bool) -> Self {
+        self.options.skip_orphan_images = skip;
+        self
     }
 
-    // Process CSS files
-    for css_path in css_files {
-        if let Ok(content) = fs::read_to_string(css_path) {
-            let image_refs = extract_css_image_refs(&content);
-            for img_ref in image_refs {
-                if let Some(resolved) =
-                    resolve_image_ref(css_path, &img_ref, all_images, include_dirs)
-                {
-                    referenced.insert(resolved);
-                }
-                // Don't report CSS broken images for now - they may reference build artifacts
-            }
-        }
+    pub fn skip_unexpected_binaries(mut self, skip: bool) -> Self {
+        self.options.skip_unexpected_binaries = skip;
+        self
     }
 
-    Ok((missing, referenced))
-}
+    pub fn skip_subsites_without_mkdocs(mut self, skip: bool) -> Self {
+        self.options.skip_subsites_without_mkdocs = skip;
+        self
+    }
 
-/// Resolve an image reference to an absolute path.
-fn resolve_image_ref(
-    src: &Path,
-    img_ref: &str,
-    all_images: &HashSet<PathBuf>,
-    include_dirs: &[PathBuf],
-) -> Option<PathBuf> {
-    // Handle absolute paths (starting with /)
-    if let Some(abs_rel) = img_ref.strip_prefix('/') {
-        // Try each include dir as potential root
-        for dir in include_dirs {
-            // The root for absolute paths is typically the docs/ subdirectory
-            let docs_dir = dir.join("docs");
-            let candidate = if docs_dir.exists() {
-                normalize_path(&docs_dir.join(abs_rel))
-            } else {
-                normalize_path(&dir.join(abs_rel))
-            };
-            if all_images.contains(&candidate) {
-                return Some(candidate);
-            }
-        }
-        return None;
+    pub fn skip_dead_css_selectors(mut self, skip: bool) -> Self {
+        self.options.skip_dead_css_selectors = skip;
+        self
     }
 
-    // Handle relative paths
-    if let Some(parent) = src.parent() {
-        let candidate = normalize_path(&parent.join(img_ref));
-        if all_images.contains(&candidate) {
-            return Some(candidate);
-        }
-        // Also check if it exists on disk (might not be in our include_dirs scan)
-        if candidate.is_file() {
-            return Some(candidate);
-        }
+    pub fn skip_svg_checks(mut self, skip: bool) -> Self {
+        self.options.skip_svg_checks = skip;
+        self
     }
 
-    // Try from each include dir's docs folder
-    for dir in include_dirs {
-        let docs_dir = dir.join("docs");
-        if docs_dir.exists() {
-            let candidate = normalize_path(&docs_dir.join(img_ref));
-            if all_images.contains(&candidate) {
-                return Some(candidate);
-            }
-        }
+    pub fn skip_case_sensitivity(mut self, skip: bool) -> Self {
+        self.options.skip_case_sensitivity = skip;
+        self
     }
 
-    None
-}
+    pub fn skip_orphan_mkdocs_files(mut self, skip: bool) -> Self {
+        self.options.skip_orphan_mkdocs_files = skip;
+        self
+    }
 
-/// A normalised internal link target.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Link {
-    /// Target path, always ending in `.md` (anchor stripped, extension added if absent).
-    pub target: String,
-    /// Whether the author wrote an explicit `.md` extension on the original link.
-    /// Directory-style (`foo/`) and extensionless (`foo`) links are `false` — MkDocs
-    /// serves those as directory URLs, whereas an explicit `.md` is only rewritten when
-    /// the target resolves within the same subsite.
-    pub had_md: bool,
-}
+    pub fn skip_cross_file_help_url_duplicates(mut self, skip: bool) -> Self {
+        self.options.skip_cross_file_help_url_duplicates = skip;
+        self
+    }
 
-/// Normalise a single raw link, classifying whether it carried an explicit `.md`
-/// extension. Returns `None` for external, mailto, empty, or non-markdown links
-/// (which we don't check).
-fn normalise_one(link: &str) -> Option<Link> {
-    // drop page-internal anchors first
-    let mut link = link.split('#').next().unwrap_or("").trim().to_string();
-    if link.is_empty() {
-        return None;
+    pub fn skip_css_import_cycles(mut self, skip: bool) -> Self {
+        self.options.skip_css_import_cycles = skip;
+        self
     }
 
-    // skip externals and mailto
-    if link.starts_with("http") || link.starts_with("mailto:") {
-        return None;
+    pub fn max_scan_depth(mut self, depth: usize) -> Self {
+        self.options.max_scan_depth = Some(depth);
+        self
     }
 
-    // trailing slash → directory style; strip and add .md
-    if link.ends_with('/') {
-        link = link.trim_end_matches('/').to_string();
-        if link.is_empty() {
-            return None;
-        }
-        link.push_str(".md");
-        return Some(Link {
-            target: link,
-            had_md: false,
-        });
+    /// Leave out one more subsite, or every subsite a glob pattern matches; may be called
+    /// repeatedly.
+    pub fn exclude_subsite(mut self, subsite: impl Into<String>) -> Self {
+        self.options.exclude_subsites.push(subsite.into());
+        self
     }
 
-    let path = Path::new(&link);
-    match path.extension() {
-        Some(ext) if ext.eq_ignore_ascii_case("md") => Some(Link {
-            target: link,
-            had_md: true,
-        }),
-        Some(_) => None, // non-markdown => drop
-        None => {
-            // add .md when no extension (directory/page-style link)
-            let mut with_ext = link;
-            with_ext.push_str(".md");
-            Some(Link {
-                target: with_ext,
-                had_md: false,
-            })
-        }
+    pub fn cache_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.options.cache_path = Some(path.into());
+        self
     }
-}
 
-pub fn normalise_links<I>(links: I) -> Vec<String>
-where
-    I: IntoIterator<Item = String>,
-{
-    links
-        .into_iter()
-        .filter_map(|link| normalise_one(&link).map(|l| l.target))
-        .collect()
+    pub fn check_headings(mut self, check: bool) -> Self {
+        self.options.check_headings = check;
+        self
+    }
+
+    pub fn check_multiple_h1(mut self, check: bool) -> Self {
+        self.options.check_multiple_h1 = check;
+        self
+    }
+
+    pub fn check_duplicate_anchors(mut self, check: bool) -> Self {
+        self.options.check_duplicate_anchors = check;
+        self
+    }
+
+    pub fn check_unlabelled_code_blocks(mut self, check: bool) -> Self {
+        self.options.check_unlabelled_code_blocks = check;
+        self
+    }
+
+    pub fn check_duplicate_images(mut self, check: bool) -> Self {
+        self.options.check_duplicate_images = check;
+        self
+    }
+
+    /// Require one more front matter field; may be called repeatedly.
+    pub fn require_frontmatter_field(mut self, field: impl Into<String>) -> Self {
+        self.options.required_frontmatter_fields.push(field.into());
+        self
+    }
+
+    pub fn check_title_consistency(mut self, check: bool) -> Self {
+        self.options.check_title_consistency = check;
+        self
+    }
+
+    pub fn suggest_fixes(mut self, suggest: bool) -> Self {
+        self.options.suggest_fixes = suggest;
+        self
+    }
+
+    pub fn check_external_links(mut self, check: bool) -> Self {
+        self.options.check_external_links = check;
+        self
+    }
+
+    pub fn external_link_timeout(mut self, timeout: Duration) -> Self {
+        self.options.external_link_timeout = Some(timeout);
+        self
+    }
+
+    pub fn external_link_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.options.external_link_cache_ttl = Some(ttl);
+        self
+    }
+
+    pub fn respect_gitignore(mut self, respect: bool) -> Self {
+        self.options.respect_gitignore = respect;
+        self
+    }
+
+    pub fn on_progress(mut self, handler: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        self.options.progress = Some(ProgressHandler(Arc::new(handler)));
+        self
+    }
+
+    pub fn build(self) -> AuditOptions {
+        self.options
+    }
 }
 
-/// MkDocs filenames are mandated lower-case, so any internal link whose path
-/// contains an upper-case ASCII letter is broken on the (case-sensitive) production
-/// server even if it resolves on a case-insensitive developer filesystem.
-fn link_has_mixed_case(target: &str) -> bool {
-    target.chars().any(|c| c.is_ascii_uppercase())
+/// The per-project settings file, read from the project root by [`load_config`].
+pub const GHOST_TOML: &str = ".ghost.toml";
+
+/// Settings kept in [`GHOST_TOML`] so they needn't be given on every run: the
+/// [`AuditOptions`] that can be written down, and the help_urls.h to read. Every key is
+/// optional.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// The help_urls.h of the project, relative to the directory holding the file.
+    pub help_urls_path: Option<PathBuf>,
+    pub skip_broken_links: bool,
+    pub skip_missing_images: bool,
+    pub skip_orphan_images: bool,
+    pub skip_unexpected_binaries: bool,
+    pub skip_subsites_without_mkdocs: bool,
+    pub skip_dead_css_selectors: bool,
+    pub skip_svg_checks: bool,
+    pub skip_case_sensitivity: bool,
+    pub skip_orphan_mkdocs_files: bool,
+    pub skip_cross_file_help_url_duplicates: bool,
+    pub skip_css_import_cycles: bool,
+    pub max_scan_depth: Option<usize>,
+    pub exclude_subsites: Vec<String>,
+    /// Relative to the directory holding the file, like `help_urls_path`.
+    pub cache_path: Option<PathBuf>,
+    pub check_headings: bool,
+    pub check_multiple_h1: bool,
+    pub check_duplicate_anchors: bool,
+    pub check_unlabelled_code_blocks: bool,
+    pub check_duplicate_images: bool,
+    pub required_frontmatter_fields: Vec<String>,
+    pub check_title_consistency: bool,
+    pub suggest_fixes: bool,
+    pub check_external_links: bool,
+    /// In seconds.
+    pub external_link_timeout: Option<u64>,
+    /// In hours.
+    pub external_link_cache_ttl: Option<u64>,
+    /// On when unset, as in [`AuditOptions`].
+    pub respect_gitignore: Option<bool>,
 }
 
-/// Normalise a relative path, returning `None` if a `..` component escapes the root
-/// instead of silently clamping. (`normalise_url` clamps, which is correct for browser-
-/// style links that legitimately stop at the site root, but wrong for the MkDocs source-
-/// relative `.md` resolution below, where escaping the merged root means "unresolvable".)
-fn normalise_rel_strict(path: &Path) -> Option<String> {
-    let mut parts: Vec<String> = Vec::new();
-    for comp in path.components() {
-        match comp {
-            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
-            Component::ParentDir => {
-                parts.pop()?;
-            }
-            Component::Normal(c) => parts.push(c.to_string_lossy().into_owned()),
+impl ProjectConfig {
+    /// A builder starting from these settings, for further options to go on top.
+    pub fn audit_options(&self) -> AuditOptionsBuilder {
+        AuditOptionsBuilder {
+            options: AuditOptions {
+                skip_broken_links: self.skip_broken_links,
+                skip_missing_images: self.skip_missing_images,
+                skip_orphan_images: self.skip_orphan_images,
+                skip_unexpected_binaries: self.skip_unexpected_binaries,
+                skip_subsites_without_mkdocs: self.skip_subsites_without_mkdocs,
+                skip_dead_css_selectors: self.skip_dead_css_selectors,
+                skip_svg_checks: self.skip_svg_checks,
+                skip_case_sensitivity: self.skip_case_sensitivity,
+                skip_orphan_mkdocs_files: self.skip_orphan_mkdocs_files,
+                skip_cross_file_help_url_duplicates: self.skip_cross_file_help_url_duplicates,
+                skip_css_import_cycles: self.skip_css_import_cycles,
+                max_scan_depth: self.max_scan_depth,
+                exclude_subsites: self.exclude_subsites.clone(),
+                cache_path: self.cache_path.clone(),
+                check_headings: self.check_headings,
+                check_multiple_h1: self.check_multiple_h1,
+                check_duplicate_anchors: self.check_duplicate_anchors,
+                check_unlabelled_code_blocks: self.check_unlabelled_code_blocks,
+                check_duplicate_images: self.check_duplicate_images,
+                required_frontmatter_fields: self.required_frontmatter_fields.clone(),
+                check_title_consistency: self.check_title_consistency,
+                suggest_fixes: self.suggest_fixes,
+                progress: None,
+                check_external_links: self.check_external_links,
+                external_link_timeout: self.external_link_timeout.map(Duration::from_secs),
+                external_link_cache_ttl: self
+                    .external_link_cache_ttl
+                    .map(|hours| Duration::from_secs(hours * 60 * 60)),
+                respect_gitignore: self.respect_gitignore.unwrap_or(true),
+            },
         }
     }
-    Some(parts.join("/"))
 }
 
-/// Resolve a `.md` link the way MkDocs (with the monorepo plugin) rewrites it: source-
-/// relative within the *merged* docs tree, where each subsite's `docs/` is mounted at
-/// `<merged>/<subsite>/`. Returns the on-disk target when it resolves to a real markdown
-/// file (so MkDocs rewrites the link to a working URL), or `None` when it escapes the
-/// merged root or no such file exists — in which case MkDocs leaves the literal `.md`
-/// href, which 404s on the directory-URL site (issue #876).
-fn resolve_md_link_merged(
-    src: &Path,
-    link: &str,
-    files_set: &HashSet<PathBuf>,
-    subsite_map: &HashMap<String, PathBuf>,
-) -> MergedResolve {
-    // Structural fallback: if src isn't under a subsite `docs/` dir we can't map it.
-    let Some(docs_dir) = src
-        .ancestors()
-        .find(|a| a.file_name() == Some("docs".as_ref()))
-    else {
-        return MergedResolve::FileMissing(PathBuf::from(link));
-    };
-    let (Some(subsite_dir), Some(subsite_name)) = (
-        docs_dir.parent(),
-        docs_dir.parent().and_then(|d| d.file_name()),
-    ) else {
-        return MergedResolve::FileMissing(PathBuf::from(link));
-    };
-    let Some(monorepo_root) = subsite_dir.parent() else {
-        return MergedResolve::FileMissing(PathBuf::from(link));
-    };
-    let Ok(path_within_docs) = src.strip_prefix(docs_dir) else {
-        return MergedResolve::FileMissing(PathBuf::from(link));
+/// Read the settings file `config_path`, or when that is `None` the [`GHOST_TOML`] in
+/// `project_root`; a project without one gets the default settings. A file that can't be
+/// read or parsed is an error, as is a missing `config_path`. `help_urls_path` and
+/// `cache_path` come back joined to the file's directory.
+pub fn load_config(
+    project_root: &Path,
+    config_path: Option<&Path>,
+) -> Result<ProjectConfig, GhostError> {
+    let path = match config_path {
+        Some(path) => path.to_path_buf(),
+        None => project_root.join(GHOST_TOML),
     };
+    if config_path.is_none() && !path.is_file() {
+        return Ok(ProjectConfig::default());
+    }
+    let mut config: ProjectConfig = toml::from_str(&fs::read_to_string(&path)?)?;
+    if let Some(dir) = path.parent() {
+        for relative in [&mut config.help_urls_path, &mut config.cache_path]
+            .into_iter()
+            .flatten()
+        {
+            *relative = dir.join(&*relative);
+        }
+    }
+    Ok(config)
+}
 
-    // Merged-tree directory containing the source file: `<subsite>/<dir within docs>`.
-    let src_merged = Path::new(subsite_name)
-        .join(path_within_docs)
-        .with_extension("");
-    let merged_dir = src_merged.parent().unwrap_or_else(|| Path::new(""));
-
-    let link_no_ext = Path::new(link).with_extension("");
-    let merged_target = if link.starts_with('/') {
-        // Site-absolute link: relative to the merged root.
-        link_no_ext
-            .strip_prefix("/")
-            .unwrap_or(&link_no_ext)
-            .to_path_buf()
-    } else {
-        merged_dir.join(&link_no_ext)
-    };
+/// The links extracted from each page on an earlier run, with the page's modification
+/// time then. Saved as JSON at [`AuditOptions::cache_path`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LinkCache {
+    version: u32,
+    files: HashMap<PathBuf, CachedLinks>,
+}
 
-    let Some(normalized) = normalise_rel_strict(&merged_target) else {
-        return MergedResolve::EscapedRoot;
-    };
+/// A page's modification time and the links [`extract_hrefs`] found in it then, less
+/// those its ghost directives switch off.
+type CachedLinks = (SystemTime, Vec<String>);
+
+impl LinkCache {
+    /// Bump whenever what is cached, or how links are extracted, changes: a cache from
+    /// another version is discarded rather than trusted.
+    const VERSION: u32 = 2;
+
+    /// The cache saved at `path`, or an empty one if there is none, it can't be read,
+    /// or it was written by another version.
+    fn load(path: &Path) -> LinkCache {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<LinkCache>(&json).ok())
+            .filter(|cache| cache.version == Self::VERSION)
+            .unwrap_or(LinkCache {
+                version: Self::VERSION,
+                files: HashMap::new(),
+            })
+    }
 
-    // Map merged path `<subsite>/<rest>` back to `<subsite dir>/docs/<rest>.md`. The
-    // first segment is a slugified site_name, which may differ from the directory name.
-    let mut comps = normalized.split('/').filter(|s| !s.is_empty());
-    let Some(first) = comps.next() else {
-        return MergedResolve::FileMissing(PathBuf::from(link));
-    };
-    let is_subsite =
-        subsite_map.contains_key(first) || monorepo_root.join(first).join("docs").is_dir();
-    let subsite_dir_for_first = subsite_map
-        .get(first)
-        .cloned()
-        .unwrap_or_else(|| monorepo_root.join(first));
-    let candidate = comps
-        .fold(subsite_dir_for_first.join("docs"), |acc, c| acc.join(c))
-        .with_extension("md")
-        .components()
-        .collect::<PathBuf>();
+    fn save(&self, path: &Path) -> Result<(), GhostError> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
 
-    match check_with_index_fallback(&candidate, files_set) {
-        Some(p) => MergedResolve::Resolved(p),
-        None if !is_subsite => MergedResolve::UnknownSubsite(first.to_string()),
-        None => MergedResolve::FileMissing(candidate),
+    /// The links cached for `src`, if it hasn't been modified since.
+    fn links(&self, src: &Path, modified: SystemTime) -> Option<&[String]> {
+        self.files
+            .get(src)
+            .filter(|(cached_at, _)| *cached_at == modified)
+            .map(|(_, links)| links.as_slice())
     }
 }
 
-/// Map each subsite's slugified `site_name` (and its directory name, as a fallback) to
-/// the subsite's root directory. The mkdocs-monorepo plugin mounts each `!include`d
-/// subsite at the slug of its `site_name`, which need not match the directory name —
-/// e.g. `.NET Interface Guide` → `net-interface-guide` while the directory is
-/// `dotnet-interface-guide`. Cross-subsite links use the slug, so resolution must be
-/// able to map that slug back to the directory on disk.
-pub fn build_subsite_map(nav: &[NavItem], mkdocs_dir: &Path) -> HashMap<String, PathBuf> {
-    let mut map = HashMap::new();
-    collect_subsite_mounts(nav, mkdocs_dir, &mut map);
-    map
+/// The file beside mkdocs.yml where answers from external URLs are cached.
+pub const EXTERNAL_LINK_CACHE: &str = ".ghost-external-cache.json";
+
+/// An external URL linked from a page that couldn't be fetched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalBrokenLink {
+    pub from: PathBuf,
+    pub url: String,
+    /// The HTTP status (`404 Not Found`) or the reason the request failed.
+    pub error: String,
 }
 
-fn collect_subsite_mounts(nav: &[NavItem], mkdocs_dir: &Path, map: &mut HashMap<String, PathBuf>) {
-    for item in nav {
-        match item {
-            NavItem::Page(m) => {
-                for value in m.values() {
-                    if let Some(include_path) = parse_include_target(value) {
-                        register_subsite_mount(include_path, mkdocs_dir, map);
-                    }
-                }
-            }
-            NavItem::Section(m) => {
-                for children in m.values() {
-                    collect_subsite_mounts(children, mkdocs_dir, map);
-                }
-            }
-            NavItem::PlainPath(_) => {}
-        }
-    }
+/// When each external URL was last requested, and why it failed (`None` when it
+/// worked). Saved as JSON at [`EXTERNAL_LINK_CACHE`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExternalLinkCache {
+    version: u32,
+    urls: HashMap<String, (SystemTime, Option<String>)>,
 }
 
-fn register_subsite_mount(
-    include_path: &str,
-    mkdocs_dir: &Path,
-    map: &mut HashMap<String, PathBuf>,
-) {
-    let include_file = mkdocs_dir.join(include_path);
-    let Some(parent) = include_file.parent() else {
-        return;
-    };
-    let subsite_dir: PathBuf = parent.components().collect();
+impl ExternalLinkCache {
+    const VERSION: u32 = 1;
+
+    fn load(path: &Path) -> ExternalLinkCache {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<ExternalLinkCache>(&json).ok())
+            .filter(|cache| cache.version == Self::VERSION)
+            .unwrap_or(ExternalLinkCache {
+                version: Self::VERSION,
+                urls: HashMap::new(),
+            })
+    }
 
-    let Ok(contents) = fs::read_to_string(&include_file) else {
-        return;
-    };
-    let Ok(config) = serde_yaml::from_str::<MkDocsConfig>(&contents) else {
-        return;
-    };
+    fn save(&self, path: &Path) -> Result<(), GhostError> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
 
-    // The directory name is a valid mount key when site_name slugifies to it.
-    if let Some(dir_name) = subsite_dir.file_name().and_then(|s| s.to_str()) {
-        map.entry(dir_name.to_string())
-            .or_insert_with(|| subsite_dir.clone());
+    /// The cached answer for `url`, if it was fetched less than `ttl` ago.
+    fn answer(&self, url: &str, ttl: Duration) -> Option<&Option<String>> {
+        self.urls
+            .get(url)
+            .filter(|(checked_at, _)| checked_at.elapsed().is_ok_and(|age| age < ttl))
+            .map(|(_, error)| error)
     }
-    // The slugified site_name is the actual URL mount point.
-    if let Some(site_name) = &config.site_name {
-        map.entry(slugify(site_name))
-            .or_insert_with(|| subsite_dir.clone());
+}
+
+/// The `http` and `https` links of a page, without their `#fragment`.
+pub fn external_links(markdown: &str) -> Vec<String> {
+    extract_hrefs(markdown)
+        .into_iter()
+        .filter(|l| l.starts_with("http://") || l.starts_with("https://"))
+        .map(|l| match l.split_once('#') {
+            Some((url, _)) => url.to_string(),
+            None => l,
+        })
+        .collect()
+}
+
+/// Why `url` can't be fetched, or `None` if it can. A `HEAD` request is tried first;
+/// servers that refuse or mishandle `HEAD` get a `GET`.
+fn fetch_error(agent: &ureq::Agent, url: &str) -> Option<String> {
+    let status = |response: Result<ureq::http::Response<ureq::Body>, ureq::Error>| {
+        response.map(|r| r.status()).map_err(|e| e.to_string())
+    };
+    match status(agent.head(url).call()) {
+        Ok(s) if s.is_success() || s.is_redirection() => None,
+        _ => match status(agent.get(url).call()) {
+            Ok(s) if s.is_success() || s.is_redirection() => None,
+            Ok(s) => Some(s.to_string()),
+            Err(e) => Some(e),
+        },
     }
-    // Nested includes, if any.
-    collect_subsite_mounts(&config.nav, &subsite_dir, map);
 }
 
-// Complex. Map the "virtual" hierarchy as defined by the nav onto the file system so that
-// we can check links for validity.
-pub fn build_link_maps(nav: &[NavItem], mkdocs_dir: &Path) -> Result<LinkMaps, Box<dyn Error>> {
-    let mut maps = LinkMaps::default();
-    build_link_maps_inner(
-        nav,
-        mkdocs_dir,
-        mkdocs_dir,
-        Path::new(""),
-        &mut maps.url_to_src,
-        &mut maps.src_to_url,
-    )?;
-    Ok(maps)
+/// The external links of `pages` that can't be fetched. Each URL is requested once,
+/// in parallel, unless `cache` has a recent enough answer; new answers are added to it.
+fn find_broken_external_links(
+    pages: &[(PathBuf, String)],
+    options: &AuditOptions,
+    cache: &mut ExternalLinkCache,
+) -> Vec<ExternalBrokenLink> {
+    let ttl = options
+        .external_link_cache_ttl
+        .unwrap_or(Duration::from_secs(24 * 60 * 60));
+    let links: Vec<(&PathBuf, String)> = pages
+        .iter()
+        .flat_map(|(page, content)| external_links(content).into_iter().map(move |u| (page, u)))
+        .collect();
+    let mut unchecked: Vec<&str> = links
+        .iter()
+        .map(|(_, url)| url.as_str())
+        .filter(|url| cache.answer(url, ttl).is_none())
+        .collect();
+    unchecked.sort();
+    unchecked.dedup();
+
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(
+            options
+                .external_link_timeout
+                .unwrap_or(Duration::from_secs(10)),
+        ))
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let answers: Vec<(String, Option<String>)> = unchecked
+        .par_iter()
+        .map(|url| (url.to_string(), fetch_error(&agent, url)))
+        .collect();
+    let now = SystemTime::now();
+    for (url, error) in answers {
+        cache.urls.insert(url, (now, error));
+    }
+
+    links
+        .into_iter()
+        .filter_map(|(page, url)| {
+            let error = cache.urls.get(&url)?.1.clone()?;
+            Some(ExternalBrokenLink {
+                from: page.clone(),
+                url,
+                error,
+            })
+        })
+        .collect()
 }
 
+/// A rendered, forward-ready processing trace — one section per traced file. Empty when
+/// no targets were requested.
+#[derive(Debug, Default)]
+pub struct AuditTrace {
+    pub text: String,
+}
+
+/// Accumulates per-file processing events for the traced source files.
+struct Tracer {
+    /// Normalised (lower-case, `/`-separated) path suffixes to trace.
+    targets: Vec<String>,
+    /// Per-file blow-by-blow lines, keyed by source path.
+    events: HashMap<PathBuf, Vec<String>>,
+}
+
+impl Tracer {
+    fn new(targets: &[String]) -> Self {
+        let targets = targets
+            .iter()
+            .map(|t| t.replace('\\', "/").to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+        Tracer {
+            targets,
+            events: HashMap::new(),
+        }
+    }
+
+    fn active(&self) -> bool {
+        !self.targets.is_empty()
+    }
+
+    /// Does `path` end with any requested target suffix?
+    fn traces(&self, path: &Path) -> bool {
+        if self.targets.is_empty() {
+            return false;
+        }
+        let s = path.to_string_lossy().replace('\\', "/").to_lowercase();
+        self.targets.iter().any(|t| s.ends_with(t))
+    }
+
+    fn record(&mut self, file: &Path, line: impl Into<String>) {
+        self.events
+            .entry(file.to_path_buf())
+            .or_default()
+            .push(line.into());
+    }
+
+    /// An empty tracer with the same targets, for recording one file's events on a
+    /// worker thread; [`Tracer::merge`] brings them back.
+    fn fork(&self) -> Self {
+        Tracer {
+            targets: self.targets.clone(),
+            events: HashMap::new(),
+        }
+    }
+
+    fn merge(&mut self, other: Tracer) {
+        for (file, lines) in other.events {
+            self.events.entry(file).or_default().extend(lines);
+        }
+    }
+}
+
+/// Outcome of resolving a `.md` link in the merged monorepo docs tree, carrying enough
+/// detail for the trace to explain *why* a link failed.
+enum MergedResolve {
+    Resolved(PathBuf),
+    /// The link climbed out of the merged docs root (too many `../`).
+    EscapedRoot,
+    /// The first path segment names no known subsite.
+    UnknownSubsite(String),
+    /// The path mapped into a real subsite but no such file exists there.
+    FileMissing(PathBuf),
+}
+
+impl MergedResolve {
+    fn resolved(&self) -> Option<&PathBuf> {
+        match self {
+            MergedResolve::Resolved(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    fn reason(&self) -> String {
+        match self {
+            MergedResolve::Resolved(p) => format!("resolves → {}", p.display()),
+            MergedResolve::EscapedRoot => {
+                "escapes the merged docs root (too many '../')".to_string()
+            }
+            MergedResolve::UnknownSubsite(s) => format!("'{s}' is not a subsite"),
+            MergedResolve::FileMissing(p) => format!("no such file {}", p.display()),
+        }
+    }
+}
+
+pub fn audit(mkdocs_yaml: &Path, help_urls: &Path) -> Result<AuditResult, Box<dyn Error>> {
+    let (result, _trace) = audit_traced(mkdocs_yaml, help_urls, &TraceOptions::default())?;
+    Ok(result)
+}
+
+/// Like [`audit`], but additionally produces an [`AuditTrace`]: a detailed, forward-ready
+/// blow-by-blow of how each file named in `trace_opts` was processed (discovery, role,
+/// and per-link resolution). The trace text is empty when no targets are requested.
+pub fn audit_traced(
+    mkdocs_yaml: &Path,
+    help_urls: &Path,
+    trace_opts: &TraceOptions,
+) -> Result<(AuditResult, AuditTrace), Box<dyn Error>> {
+    audit_with_config(mkdocs_yaml, help_urls, &GhostConfig::default(), trace_opts)
+}
+
+/// Like [`audit_traced`], with the optional checks configured by `config`.
+pub fn audit_with_config(
+    mkdocs_yaml: &Path,
+    help_urls: &Path,
+    config: &GhostConfig,
+    trace_opts: &TraceOptions,
+) -> Result<(AuditResult, AuditTrace), Box<dyn Error>> {
+    run_audit(
+        mkdocs_yaml,
+        help_urls,
+        config,
+        &AuditOptions::default(),
+        trace_opts,
+    )
+}
+
+/// Like [`audit_with_config`], running only the parts selected by `options`.
+pub fn audit_with_config_and_options(
+    mkdocs_yaml: &Path,
+    help_urls: &Path,
+    config: &GhostConfig,
+    options: &AuditOptions,
+    trace_opts: &TraceOptions,
+) -> Result<(AuditResult, AuditTrace), Box<dyn Error>> {
+    run_audit(mkdocs_yaml, help_urls, config, options, trace_opts)
+}
+
+/// Like [`audit`], running only the parts selected by `options`.
+pub fn audit_with_options(
+    mkdocs_yaml: &Path,
+    help_urls: &Path,
+    options: &AuditOptions,
+) -> Result<AuditResult, Box<dyn Error>> {
+    let (result, _trace) = run_audit(
+        mkdocs_yaml,
+        help_urls,
+        &GhostConfig::default(),
+        options,
+        &TraceOptions::default(),
+    )?;
+    Ok(result)
+}
+
+/// Audit several unrelated sites, each given as its `(mkdocs.yml, help_urls.h)`, and
+/// return each result keyed by its mkdocs.yml. The sites are audited one after
+/// another, since each audit already spreads its pages over rayon's threads; the first
+/// that fails to load stops the run.
+pub fn audit_multiple(
+    projects: &[(&Path, &Path)],
+    options: &AuditOptions,
+) -> Result<Vec<(PathBuf, AuditResult)>, Box<dyn Error>> {
+    projects
+        .iter()
+        .map(|&(mkdocs_yaml, help_urls)| {
+            let result = audit_with_options(mkdocs_yaml, help_urls, options)?;
+            Ok((mkdocs_yaml.to_path_buf(), result))
+        })
+        .collect()
+}
+
+fn run_audit(
+    mkdocs_yaml: &Path,
+    help_urls: &Path,
+    config: &GhostConfig,
+    options: &AuditOptions,
+    trace_opts: &TraceOptions,
+) -> Result<(AuditResult, AuditTrace), Box<dyn Error>> {
+    let mut tracer = Tracer::new(&trace_opts.targets);
+    let contents = fs::read_to_string(mkdocs_yaml)?;
+    let mkdocs_config: MkDocsConfig = serde_yaml::from_str(&contents)?;
+    let mut pages = HashSet::<PathBuf>::new();
+    let parent = mkdocs_yaml.parent().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "mkdocs file must reside within a directory",
+        )
+    })?;
+    let mut include_depth = IncludeDepth::new(config.max_include_depth)
+        .starting_at(mkdocs_yaml)
+        .skipping_missing();
+    let mut nav_entries = Vec::new();
+    collect_pages_within(
+        &mkdocs_config.nav,
+        &mut nav_entries,
+        parent,
+        mkdocs_config.docs_dir(),
+        &mut include_depth,
+    )?;
+    let duplicate_nav = duplicate_paths(&nav_entries);
+    pages.extend(nav_entries);
+    let include_depth_violations = include_depth.exceeded;
+    let mut missing_includes = include_depth.missing;
+    missing_includes.sort();
+    let nav_missing = missing_files(&pages);
+    let mut markdown_roots = Vec::new();
+    // parent dir MUST NOT BE INCLUDED in markdown_roots!
+    let include_dirs = include_roots(&mkdocs_config.nav, parent);
+    // A missing include is reported, not walked.
+    markdown_roots.extend(include_dirs.iter().filter(|dir| dir.is_dir()).cloned());
+    let files = find_markdown(markdown_roots, options.respect_gitignore)?;
+    options.report(Progress::Scanning {
+        files_found: files.len(),
+    });
+    let files_set: HashSet<PathBuf> = files.iter().cloned().collect();
+    let mut ghost = orphans(&pages, &files); // markdown files in the file system not referenced by nav
+
+    // Sources inside the build output directory: nav pages, discovered markdown, and any
+    // markdown under site_dir itself (which need not lie in a subsite).
+    let mut files_inside_site_dir: Vec<PathBuf> = match &mkdocs_config.site_dir {
+        Some(site_dir) => {
+            let site_root = normalize_path(&parent.join(site_dir));
+            let mut inside: Vec<PathBuf> = pages
+                .iter()
+                .chain(files.iter())
+                .filter(|p| normalize_path(p).starts_with(&site_root))
+                .cloned()
+                .collect();
+            // site_dir is usually gitignored, which is no reason to overlook it here.
+            if site_root.is_dir() {
+                inside.extend(find_markdown([&site_root], false)?);
+            }
+            inside
+        }
+        None => Vec::new(),
+    };
+    files_inside_site_dir.sort();
+    files_inside_site_dir.dedup();
+
+    let link_maps = build_link_maps_within(
+        &mkdocs_config.nav,
+        parent,
+        mkdocs_config.docs_dir(),
+        IncludeDepth::new(config.max_include_depth).skipping_missing(),
+    )?;
+    let layout = site_layout(
+        &mkdocs_config.nav,
+        parent,
+        mkdocs_config.docs_dir(),
+        config.max_include_depth,
+    );
+
+    // Each HELP_URL entry maps a page to the line it is defined on in help_urls.h.
+    // Group by page so a broken link on a help-referenced page can cite every line that
+    // pulls it in, and so the page is scanned once regardless of how many entries hit it.
+    let help_headers: Vec<PathBuf> = std::iter::once(help_urls.to_path_buf())
+        .chain(config.extra_help_urls.iter().cloned())
+        .collect();
+    let mut help_url_refs: Vec<(HelpUrlEntry, HelpRef)> = Vec::new();
+    for header in &help_headers {
+        help_url_refs.extend(extract_help_url_refs(header, parent)?);
+    }
+    let help_files: Vec<PathBuf> = help_url_refs
+        .iter()
+        .map(|(entry, _)| entry.path.clone())
+        .collect();
+    // Kept for checking the anchors against the pages' headings.
+    let help_url_fragments: Vec<HelpUrlEntry> = help_url_refs
+        .iter()
+        .filter(|(entry, _)| entry.fragment.is_some())
+        .map(|(entry, _)| entry.clone())
+        .collect();
+    let mut malformed_help_urls: Vec<HelpRef> = help_url_refs
+        .iter()
+        .filter(|(entry, _)| entry.docs_in_path)
+        .map(|(_, href)| href.clone())
+        .collect();
+    malformed_help_urls.sort_by_key(|r| r.line);
+    let help_missing: Vec<MissingHelpUrl> = missing_files(&help_files)
+        .into_iter()
+        .map(|path| MissingHelpUrl {
+            suggested_help_url_fix: suggest_help_url_fix(&path, parent, &link_maps),
+            path,
+        })
+        .collect();
+    let mut help_refs: HashMap<PathBuf, Vec<HelpRef>> = HashMap::new();
+    for (entry, href) in &help_url_refs {
+        help_refs
+            .entry(entry.path.clone())
+            .or_default()
+            .push(href.clone());
+    }
+    for refs in help_refs.values_mut() {
+        refs.sort_by_key(|r| r.line);
+        refs.dedup();
+    }
+
+    let nav_pages: HashSet<PathBuf> = pages.iter().map(|p| normalize_path(p)).collect();
+    let mut help_url_pages_not_in_nav: Vec<PathBuf> = help_files
+        .iter()
+        .filter(|p| p.is_file() && !nav_pages.contains(&normalize_path(p)))
+        .cloned()
+        .collect();
+    help_url_pages_not_in_nav.sort();
+    help_url_pages_not_in_nav.dedup();
+
+    // Transitively scan links: start with nav pages AND help_urls references,
+    // then follow links to discover more pages
+    let excluded_subsites = SubsitePatterns::new(&options.exclude_subsites);
+    let mut scanned: HashSet<PathBuf> = HashSet::new();
+    let mut to_scan: Vec<PathBuf> = pages
+        .iter()
+        .chain(help_files.iter())
+        .filter(|p| p.is_file() && !excluded_subsites.contain(p, parent))
+        .cloned()
+        .collect();
+    let mut all_referenced: HashSet<PathBuf> = help_files.iter().cloned().collect();
+    let mut all_broken_links: Vec<BrokenLink> = Vec::new();
+    let mut all_anchored_links: Vec<AnchoredLink> = Vec::new();
+    let mut all_page_links: Vec<PageLinks> = Vec::new();
+    // Contents of every analysed page, kept so the per-page checks below don't re-read
+    // each file from disk.
+    let mut scanned_contents: Vec<(PathBuf, String)> = Vec::new();
+    // Rounds of link following so far; the first scans the nav and help URL pages.
+    let mut scan_depth = 0;
+    let mut link_cache = options.cache_path.as_deref().map(LinkCache::load);
+    let mut analysed = 0;
+
+    while !to_scan.is_empty() {
+        if options.max_scan_depth.is_some_and(|max| scan_depth > max) {
+            break;
+        }
+        scan_depth += 1;
+
+        // `scanned.insert` returns false for a path already present, so this both marks
+        // files scanned and de-duplicates within the batch. A page referenced many times
+        // by help_urls (e.g. glyphs.md via a shared macro) must be analysed once, not
+        // once per reference — otherwise its links are reported N times.
+        let batch: Vec<ScannedFile> = to_scan
+            .iter()
+            .filter(|p| scanned.insert((*p).clone()))
+            .filter_map(|p| ScannedFile::read(p, link_cache.as_ref()))
+            .collect();
+
+        if batch.is_empty() {
+            break;
+        }
+
+        let total = analysed + batch.len();
+        let done = AtomicUsize::new(analysed);
+        let file_done = || {
+            let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+            options.report(Progress::Analysing { done, total });
+        };
+        let (referenced, broken_links, anchored_links, page_links) = analyse_links(
+            &batch,
+            &files_set,
+            parent,
+            &include_dirs,
+            &link_maps,
+            &help_refs,
+            &layout,
+            mkdocs_config.site_path().map(Path::new),
+            config,
+            &mut link_cache,
+            &file_done,
+            &mut tracer,
+        )?;
+        analysed = total;
+
+        all_broken_links.extend(broken_links);
+        all_page_links.extend(page_links);
+        all_anchored_links.extend(anchored_links);
+        scanned_contents.extend(batch.into_iter().map(|file| (file.path, file.content)));
+
+        // Find newly discovered files to scan
+        to_scan = referenced
+            .iter()
+            .filter(|p| {
+                !scanned.contains(*p) && p.is_file() && !excluded_subsites.contain(p, parent)
+            })
+            .cloned()
+            .collect();
+
+        all_referenced.extend(referenced);
+    }
+
+    if options.suggest_fixes {
+        all_broken_links
+            .par_iter_mut()
+            .for_each(|bl| bl.suggestions = suggest_link_fixes(&bl.link, &files));
+    }
+
+    if let (Some(mut cache), Some(path)) = (link_cache, &options.cache_path) {
+        // Drop pages that are gone or no longer reached, so the cache doesn't grow
+        // without bound.
+        cache.files.retain(|p, _| scanned.contains(p));
+        cache.save(path)?;
+    }
+
+    ghost.retain(|p| !all_referenced.contains(p));
+    scanned_contents.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // Image analysis: find all image assets and check references
+    let image_extensions = ["png", "jpg", "jpeg", "gif", "svg", "webp", "ico", "bmp"];
+    let all_images: HashSet<PathBuf> = include_dirs
+        .iter()
+        .flat_map(|dir| {
+            walker(dir, options.respect_gitignore)
+                .build()
+                .filter_map(|e| e.ok())
+                .filter(|e| {
+                    e.file_type().is_some_and(|t| t.is_file())
+                        && e.path()
+                            .extension()
+                            .and_then(|ext| ext.to_str())
+                            .map(|ext| image_extensions.contains(&ext.to_lowercase().as_str()))
+                            .unwrap_or(false)
+                })
+                .map(|e| normalize_path(e.path()))
+        })
+        .collect();
+
+    // Find CSS files in include dirs and documentation-assets
+    let css_dirs: Vec<PathBuf> = include_dirs
+        .iter()
+        .cloned()
+        .chain(std::iter::once(parent.join("documentation-assets")))
+        .filter(|p| p.exists())
+        .collect();
+
+    let css_files: Vec<PathBuf> = css_dirs
+        .iter()
+        .flat_map(|dir| {
+            walker(dir, options.respect_gitignore)
+                .build()
+                .filter_map(|e| e.ok())
+                .filter(|e| {
+                    e.file_type().is_some_and(|t| t.is_file())
+                        && e.path()
+                            .extension()
+                            .and_then(|ext| ext.to_str())
+                            .map(|ext| ext == "css" || ext == "scss")
+                            .unwrap_or(false)
+                })
+                .map(|e| e.path().to_path_buf())
+        })
+        .collect();
+
+    // Analyse image references in ALL markdown files on disk (not just
+    // nav-reachable ones) so that images used by orphaned pages are still
+    // recognised as referenced.
+    let (mut missing_images, referenced_images) =
+        if options.skip_missing_images && options.skip_orphan_images {
+            (Vec::new(), HashSet::new())
+        } else {
+            analyse_image_refs(
+                &files_set,
+                &css_files,
+                &all_images,
+                &include_dirs,
+                &layout,
+                &config.frontmatter_image_fields,
+            )?
+        };
+    if options.skip_missing_images {
+        missing_images.clear();
+    }
+
+    // Find orphan images (images not referenced anywhere)
+    let orphan_images: Vec<PathBuf> = if options.skip_orphan_images {
+        Vec::new()
+    } else {
+        all_images
+            .iter()
+            .filter(|img| !referenced_images.contains(*img))
+            .cloned()
+            .collect()
+    };
+
+    // Find pages with footnotes
+    let pages_with_footnotes: Vec<PathBuf> = scanned_contents
+        .iter()
+        .filter(|(_, content)| has_footnotes(content))
+        .map(|(p, _)| p.clone())
+        .collect();
+
+    // Find pages with images
+    let pages_with_images: Vec<PathBuf> = scanned_contents
+        .iter()
+        .filter(|(_, content)| has_images(content))
+        .map(|(p, _)| p.clone())
+        .collect();
+
+    // Find pages with links
+    let pages_with_links: Vec<PathBuf> = scanned_contents
+        .iter()
+        .filter(|(_, content)| has_links(content))
+        .map(|(p, _)| p.clone())
+        .collect();
+
+    let full_nav = expand_nav_includes(&mkdocs_config.nav, parent, config.max_include_depth);
+    let title_case_violations = find_title_case_violations(&full_nav, config.title_case_style);
+    let single_child_sections = find_single_child_sections(&full_nav);
+    let duplicate_section_titles = find_duplicate_section_titles(&full_nav);
+    let non_md_nav_items = find_non_md_nav_items(&full_nav);
+    let nav_errors = validate_nav_structure(&full_nav);
+
+    let links_with_query_strings: Vec<(PathBuf, String)> = scanned_contents
+        .iter()
+        .flat_map(|(p, content)| {
+            find_links_with_query_strings(content)
+                .into_iter()
+                .map(move |l| (p.clone(), l))
+        })
+        .collect();
+
+    let absolute_internal_links: Vec<(PathBuf, String)> = match &config.site_base_url {
+        Some(base) => scanned_contents
+            .iter()
+            .flat_map(|(p, content)| {
+                find_absolute_internal_links(content, base)
+                    .into_iter()
+                    .map(move |l| (p.clone(), l))
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let poor_alt_text: Vec<(PathBuf, String, String)> = scanned_contents
+        .iter()
+        .flat_map(|(p, content)| {
+            find_poor_alt_text_with(content, &config.generic_alt_texts)
+                .into_iter()
+                .map(move |(src, alt)| (p.clone(), src, alt))
+        })
+        .collect();
+
+    let localhost_links: Vec<(PathBuf, String)> = scanned_contents
+        .iter()
+        .flat_map(|(p, content)| {
+            find_localhost_links(content)
+                .into_iter()
+                .map(move |l| (p.clone(), l))
+        })
+        .collect();
+
+    let private_ip_links: Vec<(PathBuf, String)> = scanned_contents
+        .iter()
+        .flat_map(|(p, content)| {
+            find_private_ip_links(content)
+                .into_iter()
+                .map(move |l| (p.clone(), l))
+        })
+        .collect();
+
+    let scheme_relative_links: Vec<(PathBuf, String)> = scanned_contents
+        .iter()
+        .flat_map(|(p, content)| {
+            find_scheme_relative_links(content)
+                .into_iter()
+                .map(move |l| (p.clone(), l))
+        })
+        .collect();
+
+    let allowed_extensions: Vec<&str> = DOCS_EXTENSIONS
+        .iter()
+        .copied()
+        .chain(config.allowed_extensions.iter().map(String::as_str))
+        .collect();
+    let unexpected_binaries: Vec<PathBuf> = if options.skip_unexpected_binaries {
+        Vec::new()
+    } else {
+        include_dirs
+            .iter()
+            .flat_map(|dir| {
+                find_unexpected_binaries(dir, &allowed_extensions, options.respect_gitignore)
+            })
+            .collect()
+    };
+
+    let url_encoded_internal_links: Vec<(PathBuf, String)> = scanned_contents
+        .iter()
+        .flat_map(|(p, content)| {
+            find_url_encoded_internal_links(content)
+                .into_iter()
+                .map(move |l| (p.clone(), l))
+        })
+        .collect();
+
+    let known_languages: HashSet<String> = KNOWN_CODE_LANGUAGES
+        .iter()
+        .map(|l| l.to_string())
+        .chain(config.code_languages.iter().map(|l| l.to_lowercase()))
+        .collect();
+    let pages_with_unknown_code_languages: Vec<(PathBuf, Vec<String>)> = scanned_contents
+        .iter()
+        .filter_map(|(p, content)| {
+            let mut langs: Vec<String> = Vec::new();
+            for (_, lang) in find_unknown_fence_languages(content, &known_languages) {
+                if !langs.contains(&lang) {
+                    langs.push(lang);
+                }
+            }
+            (!langs.is_empty()).then(|| (p.clone(), langs))
+        })
+        .collect();
+
+    let nav_pages_without_content: Vec<PathBuf> = scanned_contents
+        .iter()
+        .filter(|(p, content)| nav_pages.contains(&normalize_path(p)) && !has_headings(content))
+        .map(|(p, _)| p.clone())
+        .collect();
+
+    let pages_with_deep_headings: Vec<(PathBuf, Vec<DeepHeading>)> = match config.max_heading_level
+    {
+        Some(max_level) => scanned_contents
+            .iter()
+            .filter_map(|(p, content)| {
+                let deep = find_deep_headings(content, max_level);
+                (!deep.is_empty()).then(|| (p.clone(), deep))
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let links_with_repeated_words: Vec<(PathBuf, Vec<String>)> = scanned_contents
+        .iter()
+        .filter_map(|(p, content)| {
+            let texts: Vec<String> = find_repeated_words_in_link_text(content)
+                .into_iter()
+                .map(|(_, text)| text)
+                .collect();
+            (!texts.is_empty()).then(|| (p.clone(), texts))
+        })
+        .collect();
+
+    let absolute_fs_image_refs: Vec<(PathBuf, String)> = scanned_contents
+        .iter()
+        .flat_map(|(p, content)| {
+            find_absolute_fs_image_refs(content)
+                .into_iter()
+                .map(move |src| (p.clone(), src))
+        })
+        .collect();
+
+    let mut dead_css_selectors: Vec<(PathBuf, Vec<String>)> = Vec::new();
+    if !options.skip_dead_css_selectors {
+        let used_classes: HashSet<String> = scanned_contents
+            .iter()
+            .flat_map(|(_, content)| extract_used_classes(content))
+            .collect();
+        dead_css_selectors = css_files
+            .iter()
+            .filter_map(|css_path| {
+                let css = fs::read_to_string(css_path).ok()?;
+                let dead = find_potentially_dead_css_selectors(&css, &used_classes);
+                (!dead.is_empty()).then(|| (css_path.clone(), dead))
+            })
+            .collect();
+        dead_css_selectors.sort();
+    }
+
+    let h1_check = |check: fn(&str) -> bool| -> Vec<PathBuf> {
+        if !options.check_multiple_h1 {
+            return Vec::new();
+        }
+        scanned_contents
+            .iter()
+            .filter(|(_, content)| check(content))
+            .map(|(p, _)| p.clone())
+            .collect()
+    };
+    let pages_with_multiple_h1 = h1_check(has_multiple_h1);
+    let pages_with_h1_not_first = h1_check(has_h1_not_first);
+
+    let relay_pages: Vec<PathBuf> = scanned_contents
+        .iter()
+        .filter(|(_, content)| is_relay_page_with(content, config.min_non_link_words))
+        .map(|(p, _)| p.clone())
+        .collect();
+
+    let subsites_without_mkdocs = if options.skip_subsites_without_mkdocs {
+        Vec::new()
+    } else {
+        find_subsites_without_mkdocs(parent, options.respect_gitignore)
+    };
+
+    let broken_links_in_nav_descriptions = find_broken_nav_description_links(
+        mkdocs_yaml,
+        &mkdocs_config.nav,
+        &files_set,
+        &link_maps,
+        &layout,
+        config.max_include_depth,
+    );
+    let description_broken_links = find_broken_site_description_links(
+        parent,
+        mkdocs_config
+            .site_description
+            .as_deref()
+            .unwrap_or_default(),
+        &files_set,
+        &link_maps,
+        &layout,
+    );
+
+    let stale_prefix_links = find_stale_absolute_prefix_links(&all_broken_links, &link_maps);
+    let nav_missing_link_sources =
+        find_nav_missing_link_sources(&all_broken_links, &nav_missing, &link_maps);
+
+    let mut svg_paths: Vec<PathBuf> = if options.skip_svg_checks {
+        Vec::new()
+    } else {
+        all_images
+            .iter()
+            .filter(|p| {
+                p.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
+            })
+            .cloned()
+            .collect()
+    };
+    svg_paths.sort();
+    let oversized_svgs = find_oversized_svgs(&svg_paths, config.max_svg_bytes);
+    let svgs_with_embedded_rasters = find_svgs_with_embedded_rasters(&svg_paths);
+
+    let mut case_sensitivity_violations = Vec::new();
+    if !options.skip_case_sensitivity {
+        case_sensitivity_violations = find_case_sensitivity_issues(&files_set);
+        // A mixed-case link is already a broken link: leave it to that report unless
+        // broken links are skipped.
+        let reported_broken: &[BrokenLink] = if options.skip_broken_links {
+            &[]
+        } else {
+            &all_broken_links
+        };
+        case_sensitivity_violations.extend(find_case_mismatched_link_targets(
+            &scanned_contents,
+            &files_set,
+            reported_broken,
+        ));
+        case_sensitivity_violations.sort();
+        case_sensitivity_violations.dedup();
+    }
+
+    let pages_with_mixed_image_styles: Vec<PathBuf> = scanned_contents
+        .iter()
+        .filter(|(_, content)| has_mixed_image_ref_styles(content))
+        .map(|(p, _)| p.clone())
+        .collect();
+
+    let orphan_mkdocs_files = if options.skip_orphan_mkdocs_files {
+        Vec::new()
+    } else {
+        find_orphan_mkdocs_files(parent, mkdocs_yaml, options.respect_gitignore)
+    };
+
+    let cross_file_help_url_duplicates = if options.skip_cross_file_help_url_duplicates {
+        Vec::new()
+    } else {
+        find_cross_file_help_url_duplicates(&help_headers, parent)?
+    };
+
+    let pages_with_todos: Vec<(PathBuf, Vec<String>)> = scanned_contents
+        .iter()
+        .filter_map(|(p, content)| {
+            let todos = extract_frontmatter_todos(content);
+            (!todos.is_empty()).then(|| (p.clone(), todos))
+        })
+        .collect();
+
+    let pages_with_excess_blank_lines: Vec<(PathBuf, Vec<usize>)> = match config.max_blank_lines {
+        Some(max_allowed) => scanned_contents
+            .iter()
+            .filter_map(|(p, content)| {
+                let lines = find_consecutive_blank_lines(content, max_allowed);
+                (!lines.is_empty()).then(|| (p.clone(), lines))
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let include_only_sections = find_include_only_sections(&mkdocs_config.nav);
+
+    let duplicate_image_files = if options.check_duplicate_images {
+        let mut image_paths: Vec<PathBuf> = all_images.iter().cloned().collect();
+        image_paths.sort();
+        find_duplicate_images(&image_paths)
+    } else {
+        Vec::new()
+    };
+
+    // Plain CSS has no imports that can cycle, so only SCSS is checked.
+    let scss_files: Vec<PathBuf> = css_files
+        .iter()
+        .filter(|p| p.extension().is_some_and(|ext| ext == "scss"))
+        .cloned()
+        .collect();
+    let css_import_cycles = if options.skip_css_import_cycles || scss_files.is_empty() {
+        Vec::new()
+    } else {
+        find_scss_import_cycles(&scss_files)
+    };
+
+    let nav_entries_outside_docs =
+        find_nav_entries_outside_docs(&mkdocs_config.nav, &parent.join(mkdocs_config.docs_dir()));
+
+    let unknown_theme_features = mkdocs_config
+        .theme
+        .as_ref()
+        .map(find_unknown_theme_features)
+        .unwrap_or_default();
+
+    let empty_nav_sections = find_empty_sections(&mkdocs_config.nav);
+
+    // Each linked-to page's anchors, found once however many links point into it. Pages
+    // already scanned aren't read again.
+    let mut page_anchors: HashMap<PathBuf, Option<HashSet<String>>> = HashMap::new();
+    let mut broken_anchors: Vec<BrokenAnchor> = Vec::new();
+    for linked in &all_anchored_links {
+        let anchors = page_anchors
+            .entry(linked.target.clone())
+            .or_insert_with(|| {
+                match scanned_contents.binary_search_by(|(p, _)| p.cmp(&linked.target)) {
+                    Ok(i) => Some(heading_anchors(&scanned_contents[i].1)),
+                    Err(_) => fs::read_to_string(&linked.target)
+                        .ok()
+                        .map(|c| heading_anchors(&c)),
+                }
+            });
+        if anchors
+            .as_ref()
+            .is_some_and(|a| !a.contains(&linked.anchor))
+        {
+            broken_anchors.push(BrokenAnchor {
+                from: linked.from.clone(),
+                link: linked.link.clone(),
+                anchor: linked.anchor.clone(),
+            });
+        }
+    }
+    broken_anchors
+        .sort_by(|a, b| (&a.from, &a.link, &a.anchor).cmp(&(&b.from, &b.link, &b.anchor)));
+    broken_anchors.dedup();
+
+    let stub_pages: Vec<PathBuf> = match config.min_word_count {
+        0 => Vec::new(),
+        min => scanned_contents
+            .iter()
+            .filter(|(_, content)| page_stats(content).word_count < min)
+            .map(|(p, _)| p.clone())
+            .collect(),
+    };
+
+    let heading_errors: Vec<(PathBuf, Vec<HeadingError>)> = if options.check_headings {
+        scanned_contents
+            .iter()
+            .filter_map(|(p, content)| {
+                let errors = check_heading_hierarchy(content);
+                (!errors.is_empty()).then(|| (p.clone(), errors))
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let pages_with_duplicate_anchors = if options.check_duplicate_anchors {
+        scanned_contents
+            .iter()
+            .filter_map(|(p, content)| {
+                let duplicates = find_duplicate_anchors(content);
+                (!duplicates.is_empty()).then(|| (p.clone(), duplicates))
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let pages_with_unlabelled_code: Vec<PathBuf> = if options.check_unlabelled_code_blocks {
+        scanned_contents
+            .iter()
+            .filter(|(_, content)| {
+                extract_code_blocks(content)
+                    .iter()
+                    .any(|block| block.lang.as_deref().is_none_or(str::is_empty))
+            })
+            .map(|(p, _)| p.clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let required_fields: Vec<&str> = options
+        .required_frontmatter_fields
+        .iter()
+        .map(String::as_str)
+        .collect();
+    let missing_frontmatter_fields: Vec<(PathBuf, Vec<String>)> = if required_fields.is_empty() {
+        Vec::new()
+    } else {
+        scanned_contents
+            .iter()
+            .filter_map(|(p, content)| {
+                let frontmatter = extract_frontmatter(content).unwrap_or(serde_yaml::Value::Null);
+                let missing = validate_frontmatter(&frontmatter, &required_fields);
+                (!missing.is_empty()).then(|| (p.clone(), missing))
+            })
+            .collect()
+    };
+
+    let title_mismatches: Vec<(PathBuf, String, String)> = if options.check_title_consistency {
+        let contents: HashMap<&PathBuf, &String> =
+            scanned_contents.iter().map(|(p, c)| (p, c)).collect();
+        titled_nav_pages(
+            &mkdocs_config.nav,
+            parent,
+            mkdocs_config.docs_dir(),
+            config.max_include_depth,
+        )
+        .into_iter()
+        .filter_map(|(label, page)| {
+            let h1 = extract_headings(contents.get(&page)?)
+                .into_iter()
+                .find(|h| h.level == 1)?
+                .text;
+            (title_similarity(&label, &h1) < config.min_title_similarity)
+                .then_some((page, label, h1))
+        })
+        .collect()
+    } else {
+        Vec::new()
+    };
+
+    let case_collisions: Vec<(PathBuf, PathBuf)> = if options.skip_case_sensitivity {
+        Vec::new()
+    } else {
+        find_case_collisions(&pages)
+            .into_iter()
+            .filter(|(a, b)| !(files_set.contains(a) && files_set.contains(b)))
+            .collect()
+    };
+
+    let broken_external_links = if options.check_external_links {
+        let cache_path = parent.join(EXTERNAL_LINK_CACHE);
+        let mut cache = ExternalLinkCache::load(&cache_path);
+        let broken = find_broken_external_links(&scanned_contents, options, &mut cache);
+        cache.save(&cache_path)?;
+        broken
+    } else {
+        Vec::new()
+    };
+
+    let trace = AuditTrace {
+        text: render_trace(
+            &tracer,
+            &trace_opts.targets,
+            parent,
+            &pages,
+            &help_files,
+            &help_refs,
+            &all_referenced,
+            &scanned,
+            &files_set,
+            &all_broken_links,
+        ),
+    };
+
+    if options.skip_broken_links {
+        all_broken_links.clear();
+    }
+
+    let mut result = AuditResult {
+        nav_missing,
+        ghost,
+        help_missing,
+        broken_links: all_broken_links,
+        nav_missing_link_sources,
+        missing_images,
+        orphan_images,
+        pages_with_footnotes,
+        pages_with_images,
+        pages_with_links,
+        absolute_internal_links,
+        title_case_violations,
+        links_with_query_strings,
+        stale_prefix_links,
+        poor_alt_text,
+        localhost_links,
+        private_ip_links,
+        scheme_relative_links,
+        unexpected_binaries,
+        url_encoded_internal_links,
+        single_child_sections,
+        pages_with_unknown_code_languages,
+        files_inside_site_dir,
+        help_url_pages_not_in_nav,
+        nav_pages_without_content,
+        broken_links_in_nav_descriptions,
+        description_broken_links,
+        pages_with_deep_headings,
+        links_with_repeated_words,
+        subsites_without_mkdocs,
+        duplicate_section_titles,
+        relay_pages,
+        non_md_nav_items,
+        include_depth_violations,
+        pages_with_multiple_h1,
+        pages_with_h1_not_first,
+        dead_css_selectors,
+        absolute_fs_image_refs,
+        oversized_svgs,
+        svgs_with_embedded_rasters,
+        case_sensitivity_violations,
+        help_url_fragments,
+        pages_with_mixed_image_styles,
+        orphan_mkdocs_files,
+        cross_file_help_url_duplicates,
+        pages_with_todos,
+        pages_with_excess_blank_lines,
+        include_only_sections,
+        duplicate_image_files,
+        css_import_cycles,
+        nav_entries_outside_docs,
+        unknown_theme_features,
+        empty_nav_sections,
+        malformed_help_urls,
+        broken_anchors,
+        duplicate_nav,
+        stub_pages,
+        heading_errors,
+        missing_frontmatter_fields,
+        title_mismatches,
+        case_collisions,
+        broken_external_links,
+        page_links: all_page_links,
+        missing_includes,
+        nav_errors,
+        pages_with_duplicate_anchors,
+        pages_with_unlabelled_code,
+    };
+    // Reports of excluded subsites' files, which can surface without their pages being
+    // scanned (ghosts, or links into them).
+    if !options.exclude_subsites.is_empty() {
+        result = result.filter_by_subsite(&options.exclude_subsites, parent);
+    }
+
+    if let Some(ignore) = load_ghostignore(parent)? {
+        result.retain_paths(|p| {
+            let p = normalize_path(p);
+            !(p.starts_with(ignore.path())
+                && ignore
+                    .matched_path_or_any_parents(&p, p.is_dir())
+                    .is_ignore())
+        });
+    }
+
+    Ok((result, trace))
+}
+
+/// The file beside mkdocs.yml listing paths to leave out of every report.
+pub const GHOSTIGNORE: &str = ".ghostignore";
+
+/// The patterns of the [`GHOSTIGNORE`] file in `dir`, if there is one. They use
+/// gitignore syntax (`#` comments, `!` to re-include, a trailing `/` for directories)
+/// and are relative to `dir`.
+fn load_ghostignore(dir: &Path) -> Result<Option<Gitignore>, ignore::Error> {
+    let path = dir.join(GHOSTIGNORE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let mut builder = GitignoreBuilder::new(dir);
+    if let Some(e) = builder.add(&path) {
+        return Err(e);
+    }
+    builder.build().map(Some)
+}
+
+/// The link graph of an audit: each page of `scanned_contents` mapped to the files its
+/// links resolve to, sorted. A page the audit scanned keeps the targets the broken-link
+/// check resolved its links to (see [`AuditResult::page_links`]). Any other page, whose
+/// site layout the result doesn't record, has its links read from its contents and
+/// resolved like that check's last resort: against the page's directory, falling back
+/// to `page/index.md`. Pages whose links all broke map to an empty list. Inverting the
+/// map (pushing each page onto the list of every target it names) gives each page's
+/// incoming links, e.g. to find the most-linked page or pages no other page links to.
+pub fn page_dependency_graph(
+    audit_result: &AuditResult,
+    scanned_contents: &HashMap<PathBuf, String>,
+) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let audited: HashMap<&PathBuf, &Vec<PathBuf>> = audit_result
+        .page_links
+        .iter()
+        .map(|(page, targets)| (page, targets))
+        .collect();
+    let no_files = HashSet::new();
+    scanned_contents
+        .iter()
+        .map(|(page, content)| {
+            let targets = match audited.get(page) {
+                Some(targets) => (*targets).clone(),
+                None => {
+                    let dir = page.parent().unwrap_or_else(|| Path::new(""));
+                    let mut targets: Vec<PathBuf> = extract_hrefs(content)
+                        .iter()
+                        .filter_map(|l| normalise_one(l))
+                        .filter(|link| !link.target.starts_with('/'))
+                        .filter_map(|link| {
+                            check_with_index_fallback(&dir.join(&link.target), &no_files)
+                        })
+                        .collect();
+                    targets.sort();
+                    targets.dedup();
+                    targets
+                }
+            };
+            (page.clone(), targets)
+        })
+        .collect()
+}
+
+/// Render `p` relative to `base` using forward slashes, so trace output is identical and
+/// portable across platforms (Windows `Path::display` would emit backslashes).
+fn fwd_rel(p: &Path, base: &Path) -> String {
+    p.strip_prefix(base)
+        .unwrap_or(p)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Assemble the human-readable, forward-ready trace text from the collected per-file
+/// events plus the file's role in the audit (discovery, reachability, analysis).
+#[allow(clippy::too_many_arguments)]
+fn render_trace(
+    tracer: &Tracer,
+    targets: &[String],
+    mkdocs_dir: &Path,
+    pages: &HashSet<PathBuf>,
+    help_files: &[PathBuf],
+    help_refs: &HashMap<PathBuf, Vec<HelpRef>>,
+    all_referenced: &HashSet<PathBuf>,
+    scanned: &HashSet<PathBuf>,
+    files_set: &HashSet<PathBuf>,
+    broken_links: &[BrokenLink],
+) -> String {
+    use std::fmt::Write;
+
+    if !tracer.active() {
+        return String::new();
+    }
+
+    // Every path ghost is aware of — files on disk, nav pages, help targets, scanned
+    // pages — so a target can match even a file that is unreachable or missing from disk
+    // (which is itself the useful diagnostic).
+    let mut known: Vec<PathBuf> = files_set.iter().cloned().collect();
+    for p in pages.iter().chain(help_files.iter()).chain(scanned.iter()) {
+        known.push(p.clone());
+    }
+    known.sort();
+    known.dedup();
+
+    let mut out = String::new();
+    for target in targets {
+        let norm = target.replace('\\', "/").to_lowercase();
+        if norm.is_empty() {
+            continue;
+        }
+        let matches: Vec<&PathBuf> = known
+            .iter()
+            .filter(|p| {
+                p.to_string_lossy()
+                    .replace('\\', "/")
+                    .to_lowercase()
+                    .ends_with(&norm)
+            })
+            .collect();
+
+        if matches.is_empty() {
+            let _ = writeln!(out, "FILE (no file known to ghost matched \"{target}\")\n");
+            continue;
+        }
+
+        for f in matches {
+            let rel = fwd_rel(f, mkdocs_dir);
+            let _ = writeln!(out, "FILE {rel}");
+            let _ = writeln!(out, "  exists on disk : {}", f.is_file());
+
+            let mut reached = Vec::new();
+            if pages.contains(f) {
+                reached.push("nav".to_string());
+            }
+            if let Some(refs) = help_refs.get(f) {
+                let lines: Vec<String> = refs.iter().map(|r| r.line.to_string()).collect();
+                reached.push(format!("help_urls.h (line {})", lines.join(", ")));
+            }
+            if all_referenced.contains(f) && !pages.contains(f) && !help_refs.contains_key(f) {
+                reached.push("linked from another scanned page".to_string());
+            }
+            if reached.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "  reached by     : NOT REACHED — not in nav, not in help_urls.h, not linked \
+                     from any scanned page (so its links are never checked)"
+                );
+            } else {
+                let _ = writeln!(out, "  reached by     : {}", reached.join(" + "));
+            }
+            let _ = writeln!(out, "  analysed       : {}", scanned.contains(f));
+
+            if let Some(events) = tracer.events.get(f) {
+                for e in events {
+                    let _ = writeln!(out, "  {e}");
+                }
+            }
+
+            let broken_here = broken_links.iter().filter(|b| &b.from == f).count();
+            let _ = writeln!(
+                out,
+                "  SUMMARY        : {broken_here} broken link(s) from this file"
+            );
+            let _ = writeln!(out);
+        }
+    }
+
+    out
+}
+
+/// Check if markdown content contains footnote references or definitions.
+/// Footnotes use syntax like `[^1]` for references and `[^1]:` for definitions.
+pub fn has_footnotes(markdown: &str) -> bool {
+    // Match footnote references [^identifier] or definitions [^identifier]:
+    // The identifier can be alphanumeric with hyphens/underscores
+    let footnote_re = Regex::new(r"\[\^[^\]]+\]").unwrap();
+    footnote_re.is_match(markdown)
+}
+
+/// Check if markdown content contains image references (markdown or HTML).
+pub fn has_images(markdown: &str) -> bool {
+    !extract_image_refs(markdown).is_empty()
+}
+
+/// Check if markdown content has at least one heading, ignoring any front matter (whose
+/// closing `---` would otherwise read as a setext underline).
+pub fn has_headings(markdown: &str) -> bool {
+    !extract_headings(markdown).is_empty()
+}
+
+/// A markdown heading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    /// 1-based line number in the page, front matter included.
+    pub line: usize,
+    /// 1 for `#`/H1 through 6 for `######`/H6.
+    pub level: usize,
+    /// The heading's plain text, without markup.
+    pub text: String,
+    /// The id MkDocs gives the heading, to link to it as `page.md#anchor`.
+    pub anchor: String,
+}
+
+/// The headings of a page, ATX (`## Title`) and setext alike, in document order. Front
+/// matter is skipped, as is anything inside an HTML comment. Inline code, emphasis and
+/// link text count towards a heading's text; the markup around them doesn't.
+///
+/// Each heading's anchor is the one MkDocs generates (see [`heading_slug`]), with `_1`,
+/// `_2`, ... added to repeats. A heading with an explicit `{#id}` attribute gets that id
+/// instead.
+pub fn extract_headings(markdown: &str) -> Vec<Heading> {
+    let body_start = frontmatter_span(markdown).map_or(0, |(_, _, body)| body);
+    let mut headings = Vec::new();
+    let mut current: Option<Heading> = None;
+    for (event, range) in Parser::new(&markdown[body_start..]).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                current = Some(Heading {
+                    line: line_at(markdown, body_start + range.start),
+                    level: level as usize,
+                    text: String::new(),
+                    anchor: String::new(),
+                });
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = current.as_mut() {
+                    heading.text.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some(mut heading) = current.take() {
+                    heading.text = heading.text.trim().to_string();
+                    headings.push(heading);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let attr_re = heading_id_regex();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for heading in &mut headings {
+        if let Some(cap) = attr_re.captures(&heading.text) {
+            heading.anchor = cap[1].to_string();
+            continue;
+        }
+        let slug = heading_slug(&heading.text);
+        let count = seen.entry(slug.clone()).or_insert(0);
+        heading.anchor = if *count == 0 {
+            slug
+        } else {
+            format!("{}_{}", slug, count)
+        };
+        *count += 1;
+    }
+    headings
+}
+
+/// The anchor Python-Markdown's `toc` extension, which MkDocs uses, makes of a heading's
+/// text: folded to ASCII (NFKD, dropping what doesn't decompose), stripped of all but
+/// word characters, spaces and hyphens, lower-cased, and each run of spaces and hyphens
+/// turned into a single `-`. "What's new in v1.2" becomes `whats-new-in-v12`.
+pub fn heading_slug(text: &str) -> String {
+    let kept: String = text
+        .nfkd()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-' || c.is_ascii_whitespace())
+        .collect();
+    let separators = Regex::new(r"[-\s]+").unwrap();
+    separators
+        .replace_all(kept.trim(), "-")
+        .to_ascii_lowercase()
+}
+
+/// Matches an explicit `{#id}` attribute at the end of a heading's text.
+fn heading_id_regex() -> Regex {
+    Regex::new(r"\{[^}]*#([A-Za-z0-9_-]+)[^}]*\}\s*$").unwrap()
+}
+
+/// The anchors a page's headings get, as [`extract_headings`] works them out. `id` and
+/// `name` attributes in inline HTML count as anchors too.
+pub fn heading_anchors(markdown: &str) -> HashSet<String> {
+    let html_re = Regex::new(r#"<[^>]*\b(?:id|name)\s*=\s*"([^"]+)""#).unwrap();
+    let mut anchors: HashSet<String> = extract_headings(markdown)
+        .into_iter()
+        .map(|h| h.anchor)
+        .collect();
+    anchors.extend(
+        html_re
+            .captures_iter(markdown)
+            .map(|cap| cap[1].to_string()),
+    );
+    anchors
+}
+
+/// Headings of a page that want the same anchor, as (anchor, line of the first heading,
+/// line of the repeat), 1-based. MkDocs gives the repeat `anchor_1` instead, so links
+/// written for it land on the first heading, and an explicit `{#id}` that matches
+/// another heading's anchor shadows it outright. Each repeat is reported once.
+pub fn find_duplicate_anchors(markdown: &str) -> Vec<(String, usize, usize)> {
+    let attr_re = heading_id_regex();
+    let mut first_lines: HashMap<String, usize> = HashMap::new();
+    let mut out = Vec::new();
+    for heading in extract_headings(markdown) {
+        let anchor = match attr_re.captures(&heading.text) {
+            Some(cap) => cap[1].to_string(),
+            None => heading_slug(&heading.text),
+        };
+        match first_lines.get(&anchor) {
+            Some(&first) => out.push((anchor, first, heading.line)),
+            None => {
+                first_lines.insert(anchor, heading.line);
+            }
+        }
+    }
+    out
+}
+
+/// Whether a page has more than one H1. A page should have exactly one, its title. A
+/// `# comment` in a fenced code block is not a heading.
+pub fn has_multiple_h1(markdown: &str) -> bool {
+    extract_headings(markdown)
+        .iter()
+        .filter(|h| h.level == 1)
+        .count()
+        > 1
+}
+
+/// Whether a page's first H1 comes after some other heading, e.g. an H2 above the title.
+/// Pages without an H1 pass.
+pub fn has_h1_not_first(markdown: &str) -> bool {
+    let headings = extract_headings(markdown);
+    headings
+        .iter()
+        .position(|h| h.level == 1)
+        .is_some_and(|first_h1| first_h1 > 0)
+}
+
+/// A heading deeper than the allowed level, as (line, level, heading text).
+pub type DeepHeading = (usize, usize, String);
+
+/// Find headings deeper than `max_level`, as (line, level, heading text). Style guides
+/// often stop at H3, since deeper headings are hard to tell apart and suggest the page
+/// should be split.
+pub fn find_deep_headings(markdown: &str, max_level: usize) -> Vec<DeepHeading> {
+    extract_headings(markdown)
+        .into_iter()
+        .filter(|h| h.level > max_level)
+        .map(|h| (h.line, h.level, h.text))
+        .collect()
+}
+
+/// A heading more than one level below the heading before it, as in an H1 followed
+/// directly by an H3.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeadingError {
+    /// 1-based line number in the page, front matter included.
+    pub line: usize,
+    pub level: usize,
+    /// The deepest level the heading could have had: one below the heading before it.
+    pub max_expected: usize,
+}
+
+/// Find headings that skip a level, so that headings nest without gaps as accessibility
+/// guidelines require. The first heading may be at any level; each later one may be at
+/// most one level deeper than the heading before it.
+pub fn check_heading_hierarchy(markdown: &str) -> Vec<HeadingError> {
+    extract_headings(markdown)
+        .windows(2)
+        .filter(|pair| pair[1].level > pair[0].level + 1)
+        .map(|pair| HeadingError {
+            line: pair[1].line,
+            level: pair[1].level,
+            max_expected: pair[0].level + 1,
+        })
+        .collect()
+}
+
+/// Whether a page is a navigation relay: links to other pages and nothing else, besides
+/// front matter and headings. Such a page could be replaced by a nav section.
+pub fn is_relay_page(markdown: &str) -> bool {
+    is_relay_page_with(markdown, 1)
+}
+
+/// Like [`is_relay_page`], but a page with up to `min_non_link_words - 1` words of prose
+/// outside links and headings (a short "See also:", say) still counts as a relay. A page
+/// without any links is never a relay.
+pub fn is_relay_page_with(markdown: &str, min_non_link_words: usize) -> bool {
+    let body_start = frontmatter_span(markdown).map_or(0, |(_, _, body)| body);
+    let mut links = 0;
+    let mut words = 0;
+    let mut link_depth = 0;
+    let mut in_heading = false;
+    for event in Parser::new(&markdown[body_start..]) {
+        match event {
+            Event::Start(Tag::Link { .. }) => {
+                links += 1;
+                link_depth += 1;
+            }
+            Event::End(TagEnd::Link) => link_depth -= 1,
+            Event::Start(Tag::Heading { .. }) => in_heading = true,
+            Event::End(TagEnd::Heading(_)) => in_heading = false,
+            Event::Text(text) | Event::Code(text) if link_depth == 0 && !in_heading => {
+                words += text
+                    .split_whitespace()
+                    .filter(|w| w.chars().any(char::is_alphanumeric))
+                    .count();
+            }
+            _ => {}
+        }
+    }
+    links > 0 && words < min_non_link_words
+}
+
+/// The words of body text in a page: headings, code blocks, HTML (comments included) and
+/// front matter don't count, so neither does a page that is only `# Title` and a
+/// comment. Link text and inline code do.
+pub fn word_count(markdown: &str) -> usize {
+    page_stats(markdown).word_count
+}
+
+/// How much of each kind of content a page has; see [`page_stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageStats {
+    /// Words of body text, as [`word_count`] counts them.
+    pub word_count: usize,
+    /// Markdown links and HTML `<a href>`s.
+    pub link_count: usize,
+    /// Markdown images and HTML `<img>`s.
+    pub image_count: usize,
+    /// Fenced and indented code blocks.
+    pub code_block_count: usize,
+    pub heading_count: usize,
+}
+
+/// Count the words, links, images, code blocks and headings of a page. Front matter
+/// isn't counted.
+pub fn page_stats(markdown: &str) -> PageStats {
+    let body_start = frontmatter_span(markdown).map_or(0, |(_, _, body)| body);
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let image_selector = Selector::parse("img").unwrap();
+    let mut stats = PageStats::default();
+    let mut in_heading = false;
+    let mut in_code_block = false;
+    for event in Parser::new(&markdown[body_start..]) {
+        match event {
+            Event::Start(Tag::Heading { .. }) => {
+                in_heading = true;
+                stats.heading_count += 1;
+            }
+            Event::End(TagEnd::Heading(_)) => in_heading = false,
+            Event::Start(Tag::CodeBlock(_)) => {
+                in_code_block = true;
+                stats.code_block_count += 1;
+            }
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Start(Tag::Link { .. }) => stats.link_count += 1,
+            Event::Start(Tag::Image { .. }) => stats.image_count += 1,
+            Event::Html(html) | Event::InlineHtml(html) => {
+                let fragment = Html::parse_fragment(&html);
+                stats.link_count += fragment.select(&link_selector).count();
+                stats.image_count += fragment.select(&image_selector).count();
+            }
+            Event::Text(text) if !in_heading && !in_code_block => {
+                stats.word_count += text
+                    .split_whitespace()
+                    .filter(|w| w.chars().any(char::is_alphanumeric))
+                    .count();
+            }
+            // APL primitives are words here, symbols or not
+            Event::Code(code) if !in_heading => {
+                stats.word_count += code.split_whitespace().count();
+            }
+            _ => {}
+        }
+    }
+    stats
+}
+
+/// Check if markdown content contains links (markdown or HTML).
+pub fn has_links(markdown: &str) -> bool {
+    !extract_hrefs(markdown).is_empty()
+}
+
+/// A link in a page: its destination as written, less any `#section`, and the section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Link {
+    pub url: String,
+    /// The `#section` the link points at, without the `#`; `None` when it names none.
+    pub anchor: Option<String>,
+}
+
+/// The links of a page, markdown and HTML, in page order.
+pub fn extract_links(markdown: &str) -> Vec<Link> {
+    extract_hrefs(markdown)
+        .into_iter()
+        .map(|href| match href.split_once('#') {
+            Some((url, anchor)) => Link {
+                url: url.to_string(),
+                anchor: Some(anchor.to_string()).filter(|a| !a.is_empty()),
+            },
+            None => Link {
+                url: href,
+                anchor: None,
+            },
+        })
+        .collect()
+}
+
+/// The destinations of a page's links as written, `#section` included.
+fn extract_hrefs(markdown: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let parser = Parser::new(markdown);
+    let link_selector = Selector::parse("a[href]").unwrap();
+
+    for event in parser {
+        match event {
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                links.push(dest_url.into_string());
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                let fragment = Html::parse_fragment(&html);
+                for el in fragment.select(&link_selector) {
+                    if let Some(href) = el.value().attr("href") {
+                        links.push(href.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    links
+}
+
+/// A page's `<!-- ghost: ... -->` comments; see [`extract_ghost_directives`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GhostDirectives {
+    /// The page opens with `<!-- ghost: ignore-all-links -->`: none of its links are
+    /// checked.
+    pub ignore_all_links: bool,
+    /// The destinations of links that follow `<!-- ghost: ignore-next-link -->` in the
+    /// same paragraph, in page order. These links are not checked.
+    pub ignored_links: Vec<String>,
+}
+
+/// Find the directives in a page's HTML comments that switch off link checks, for links
+/// that won't exist until a later publication. `ignore-all-links` counts only as the
+/// first thing in the page body, after any front matter.
+pub fn extract_ghost_directives(markdown: &str) -> GhostDirectives {
+    let body = &markdown[frontmatter_span(markdown).map_or(0, |(_, _, body)| body)..];
+    let mut directives = GhostDirectives::default();
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let mut ignore_next = false;
+    for (i, event) in Parser::new(body).enumerate() {
+        match event {
+            Event::Html(html) | Event::InlineHtml(html) => match ghost_directive(&html) {
+                Some("ignore-all-links") if i <= 1 => directives.ignore_all_links = true,
+                Some("ignore-next-link") => ignore_next = true,
+                _ if ignore_next => {
+                    let fragment = Html::parse_fragment(&html);
+                    if let Some(href) = fragment
+                        .select(&link_selector)
+                        .find_map(|el| el.value().attr("href"))
+                    {
+                        directives.ignored_links.push(href.to_string());
+                        ignore_next = false;
+                    }
+                }
+                _ => {}
+            },
+            Event::Start(Tag::Link { dest_url, .. }) if ignore_next => {
+                directives.ignored_links.push(dest_url.into_string());
+                ignore_next = false;
+            }
+            Event::End(TagEnd::Paragraph | TagEnd::HtmlBlock) => ignore_next = false,
+            _ => {}
+        }
+    }
+    directives
+}
+
+/// The directive of a `<!-- ghost: directive -->` comment.
+fn ghost_directive(html: &str) -> Option<&str> {
+    let comment = html.trim().strip_prefix("<!--")?.strip_suffix("-->")?;
+    Some(comment.trim().strip_prefix("ghost:")?.trim())
+}
+
+/// Markdown links with their text, as (line, link text, destination). Inline code in
+/// the text keeps its backticks; other markup is dropped. HTML `<a>` links are not
+/// included.
+pub fn extract_links_with_text(markdown: &str) -> Vec<(usize, String, String)> {
+    let mut links = Vec::new();
+    let mut open: Vec<(usize, String, String)> = Vec::new();
+    for (event, range) in Parser::new(markdown).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                open.push((
+                    line_at(markdown, range.start),
+                    String::new(),
+                    dest_url.into_string(),
+                ));
+            }
+            Event::Text(text) => {
+                if let Some((_, link_text, _)) = open.last_mut() {
+                    link_text.push_str(&text);
+                }
+            }
+            Event::Code(code) => {
+                if let Some((_, link_text, _)) = open.last_mut() {
+                    link_text.push('`');
+                    link_text.push_str(&code);
+                    link_text.push('`');
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some((_, link_text, _)) = open.last_mut() {
+                    link_text.push(' ');
+                }
+            }
+            Event::End(TagEnd::Link) => {
+                if let Some(link) = open.pop() {
+                    links.push(link);
+                }
+            }
+            _ => {}
+        }
+    }
+    links
+}
+
+/// Find links whose text repeats a word, as in `[see the the guide](guide.md)`, as
+/// (line, link text). Only alphabetic words count, compared case-insensitively, and
+/// inline code is ignored, so link texts like ``the `x x` idiom`` or `version 2 2` pass.
+pub fn find_repeated_words_in_link_text(markdown: &str) -> Vec<(usize, String)> {
+    extract_links_with_text(markdown)
+        .into_iter()
+        .filter(|(_, text, _)| has_repeated_word(text))
+        .map(|(line, text, _)| (line, text))
+        .collect()
+}
+
+/// Whether `text` has the same word twice in a row with only whitespace between, as the
+/// pattern `\b(\w+)\s+\1\b` would match (the regex crate has no backreferences).
+fn has_repeated_word(text: &str) -> bool {
+    let prose: String = text
+        .split('`')
+        .enumerate()
+        .map(|(i, part)| if i % 2 == 0 { part } else { " ` " })
+        .collect();
+    let tokens: Vec<&str> = prose.split_whitespace().collect();
+    tokens.windows(2).any(|pair| {
+        let word = &pair[0][pair[0].trim_end_matches(char::is_alphanumeric).len()..];
+        let rest = pair[1].trim_start_matches(char::is_alphanumeric);
+        let next = &pair[1][..pair[1].len() - rest.len()];
+        !word.is_empty()
+            && word.chars().all(char::is_alphabetic)
+            && word.to_lowercase() == next.to_lowercase()
+    })
+}
+
+/// Find internal links with a query string (`page.md?version=2`). MkDocs routes internal
+/// pages by path alone, so the query is almost certainly a mistake — and the link isn't
+/// checked at all, because the query hides its `.md` extension. Query strings on
+/// external links are normal and ignored, as is a `?` inside the `#fragment`.
+pub fn find_links_with_query_strings(markdown: &str) -> Vec<String> {
+    extract_hrefs(markdown)
+        .into_iter()
+        .filter(|l| !is_external_link(l) && l.split('#').next().unwrap_or("").contains('?'))
+        .collect()
+}
+
+/// Find internal links with percent-encoded characters in their path (`my%20page.md`).
+/// They resolve, since MkDocs decodes them, but are harder to read and grep for than
+/// the plain path.
+pub fn find_url_encoded_internal_links(markdown: &str) -> Vec<String> {
+    let encoded_re = Regex::new(r"%[0-9A-Fa-f]{2}").unwrap();
+    extract_hrefs(markdown)
+        .into_iter()
+        .filter(|l| !is_external_link(l) && encoded_re.is_match(l.split('#').next().unwrap_or("")))
+        .collect()
+}
+
+/// The `theme.features` flags MkDocs Material understands.
+pub const MATERIAL_THEME_FEATURES: &[&str] = &[
+    "announce.dismiss",
+    "content.action.edit",
+    "content.action.view",
+    "content.code.annotate",
+    "content.code.copy",
+    "content.code.select",
+    "content.footnote.tooltips",
+    "content.tabs.link",
+    "content.tooltips",
+    "header.autohide",
+    "navigation.expand",
+    "navigation.footer",
+    "navigation.indexes",
+    "navigation.instant",
+    "navigation.instant.prefetch",
+    "navigation.instant.preview",
+    "navigation.instant.progress",
+    "navigation.path",
+    "navigation.prune",
+    "navigation.sections",
+    "navigation.tabs",
+    "navigation.tabs.sticky",
+    "navigation.top",
+    "navigation.tracking",
+    "search.highlight",
+    "search.share",
+    "search.suggest",
+    "toc.follow",
+    "toc.integrate",
+];
+
+/// Find `theme.features` entries that aren't Material theme features (see
+/// [`MATERIAL_THEME_FEATURES`]), such as a misspelt `navigation.tab`, which Material
+/// ignores without a word. Other themes' features are not checked.
+pub fn find_unknown_theme_features(theme: &ThemeConfig) -> Vec<String> {
+    if theme.name.as_deref() != Some("material") {
+        return Vec::new();
+    }
+    theme
+        .features
+        .iter()
+        .filter(|f| !MATERIAL_THEME_FEATURES.contains(&f.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Fenced code block languages the highlighter (Pygments, as used by MkDocs) is expected
+/// to know. Compared case-insensitively.
+pub const KNOWN_CODE_LANGUAGES: &[&str] = &[
+    "apl",
+    "bash",
+    "batch",
+    "c",
+    "cmd",
+    "console",
+    "cpp",
+    "csharp",
+    "cs",
+    "css",
+    "csv",
+    "diff",
+    "dockerfile",
+    "go",
+    "html",
+    "ini",
+    "java",
+    "javascript",
+    "js",
+    "json",
+    "jsx",
+    "kotlin",
+    "latex",
+    "lua",
+    "make",
+    "makefile",
+    "markdown",
+    "md",
+    "mermaid",
+    "nginx",
+    "perl",
+    "php",
+    "plaintext",
+    "powershell",
+    "ps1",
+    "python",
+    "py",
+    "r",
+    "ruby",
+    "rust",
+    "rs",
+    "scala",
+    "scss",
+    "sh",
+    "shell",
+    "sql",
+    "swift",
+    "text",
+    "toml",
+    "ts",
+    "tsx",
+    "txt",
+    "typescript",
+    "vb",
+    "xml",
+    "yaml",
+    "yml",
+    "zsh",
+];
+
+/// Find fenced code blocks whose language is not in `known_languages`, as (1-based line
+/// of the opening fence, language). Only the first word of the info string is the
+/// language, so `python title="x.py"` is `python`; fences without one are not reported.
+/// Compare in lower case: `known_languages` must hold lower-case names.
+pub fn find_unknown_fence_languages(
+    markdown: &str,
+    known_languages: &HashSet<String>,
+) -> Vec<(usize, String)> {
+    Parser::new(markdown)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                let lang = fence_language(&info)?;
+                (!known_languages.contains(&lang.to_lowercase()))
+                    .then(|| (line_at(markdown, range.start), lang))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The language of a fence's info string: its first word, without the braces and dot
+/// of the attribute form (`{.python}`).
+fn fence_language(info: &str) -> Option<String> {
+    let lang = info
+        .split_whitespace()
+        .next()?
+        .trim_matches(['{', '}', '.']);
+    (!lang.is_empty()).then(|| lang.to_string())
+}
+
+/// A code block of a page, fenced or indented.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// The language named on the opening fence, as written. `None` for an indented block
+    /// or a fence without one.
+    pub lang: Option<String>,
+    /// The code, without the fences; indented blocks lose their indent.
+    pub content: String,
+    /// 1-based line of the opening fence, or of an indented block's first line.
+    pub start_line: usize,
+}
+
+/// The code blocks of a page in document order, for checking the examples in them or
+/// reading config snippets out of a page. Blocks inside lists and block quotes count.
+pub fn extract_code_blocks(markdown: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<CodeBlock> = None;
+    for (event, range) in Parser::new(markdown).into_offset_iter() {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                current = Some(CodeBlock {
+                    lang: match kind {
+                        CodeBlockKind::Fenced(info) => fence_language(&info),
+                        CodeBlockKind::Indented => None,
+                    },
+                    content: String::new(),
+                    start_line: line_at(markdown, range.start),
+                });
+            }
+            Event::Text(text) => {
+                if let Some(block) = current.as_mut() {
+                    block.content.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => blocks.extend(current.take()),
+            _ => {}
+        }
+    }
+    blocks
+}
+
+/// Find runs of more than `max_allowed` consecutive blank lines outside code blocks, as
+/// the 1-based line number where each run starts. They render as a single break, so
+/// the extra lines are noise, usually left over from cut and paste.
+pub fn find_consecutive_blank_lines(markdown: &str, max_allowed: usize) -> Vec<usize> {
+    let code_lines: Vec<(usize, usize)> = Parser::new(markdown)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::CodeBlock(_)) => Some((
+                line_at(markdown, range.start),
+                line_at(markdown, range.end.saturating_sub(1)),
+            )),
+            _ => None,
+        })
+        .collect();
+    let in_code = |line: usize| {
+        code_lines
+            .iter()
+            .any(|&(start, end)| (start..=end).contains(&line))
+    };
+
+    let mut runs = Vec::new();
+    let mut run_start = 0;
+    let mut run_len = 0;
+    // A sentinel non-blank line closes a run at the end of the file.
+    for (i, line) in markdown.lines().chain(std::iter::once("-")).enumerate() {
+        let line_no = i + 1;
+        if line.trim().is_empty() && !in_code(line_no) {
+            if run_len == 0 {
+                run_start = line_no;
+            }
+            run_len += 1;
+        } else {
+            if run_len > max_allowed {
+                runs.push(run_start);
+            }
+            run_len = 0;
+        }
+    }
+    runs
+}
+
+/// The 1-based line number of byte `offset` in `text`.
+fn line_at(text: &str, offset: usize) -> usize {
+    text[..offset].matches('\n').count() + 1
+}
+
+/// Find links to a development server on the local machine (`http://localhost:8000/`,
+/// `http://127.0.0.1/...`, `http://0.0.0.0:3000`). They never work for readers of the
+/// published site.
+pub fn find_localhost_links(markdown: &str) -> Vec<String> {
+    let localhost_re =
+        Regex::new(r"(?i)^https?://(localhost|127\.0\.0\.1|0\.0\.0\.0)(:\d+)?([/?#]|$)").unwrap();
+    extract_hrefs(markdown)
+        .into_iter()
+        .filter(|l| localhost_re.is_match(l))
+        .collect()
+}
+
+/// Find links to a host on a private network (`http://10.0.0.5/`, `http://172.16.1.1:8080`,
+/// `https://192.168.1.20/admin`): the RFC 1918 ranges 10.0.0.0/8, 172.16.0.0/12 and
+/// 192.168.0.0/16. They don't work for readers outside that network and give away its
+/// layout.
+pub fn find_private_ip_links(markdown: &str) -> Vec<String> {
+    let octet = r"(25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)";
+    let private_re = Regex::new(&format!(
+        r"(?i)^https?://(10\.{octet}|172\.(1[6-9]|2\d|3[01])|192\.168)\.{octet}\.{octet}(:\d+)?([/?#]|$)"
+    ))
+    .unwrap();
+    extract_hrefs(markdown)
+        .into_iter()
+        .filter(|l| private_re.is_match(l))
+        .collect()
+}
+
+/// Find scheme-relative links (`//example.com/page.md`), which take `http:` or `https:`
+/// from the page. Rare on purpose in docs; mostly a site-absolute `/guide/page.md` typed
+/// with one slash too many, which then points at a host named `guide`.
+pub fn find_scheme_relative_links(markdown: &str) -> Vec<String> {
+    extract_hrefs(markdown)
+        .into_iter()
+        .filter(|l| l.starts_with("//"))
+        .collect()
+}
+
+/// Find links written as full URLs on the site's own `site_base_url` (e.g.
+/// `https://docs.example.com/guide/page.md`). They work, but break on staging builds and
+/// local previews, so they should be written as relative links.
+pub fn find_absolute_internal_links(markdown: &str, site_base_url: &str) -> Vec<String> {
+    extract_hrefs(markdown)
+        .into_iter()
+        .filter(|l| strip_site_base_url(l, site_base_url).is_some())
+        .collect()
+}
+
+/// If `link` lies on `site_base_url`, return it as a site-absolute path (`/guide/page.md`).
+/// The base matches whole path segments only, so `https://docs.example.com/guide` does not
+/// claim `https://docs.example.com/guidelines`.
+fn strip_site_base_url(link: &str, site_base_url: &str) -> Option<String> {
+    let base = site_base_url.trim_end_matches('/');
+    if base.is_empty() {
+        return None;
+    }
+    let rest = link.strip_prefix(base)?;
+    if rest.is_empty() || rest.starts_with(['#', '?']) {
+        return Some(format!("/{rest}"));
+    }
+    rest.starts_with('/').then(|| rest.to_string())
+}
+
+/// Find the broken links that point at a page listed in the nav but missing on disk, as
+/// (page, link). They are a knock-on effect of the nav entry rather than a separate
+/// mistake, and are fixed along with it.
+pub fn find_nav_missing_link_sources(
+    broken_links: &[BrokenLink],
+    nav_missing: &[PathBuf],
+    maps: &LinkMaps,
+) -> Vec<(PathBuf, String)> {
+    let missing: HashSet<PathBuf> = nav_missing.iter().map(|p| normalize_path(p)).collect();
+    broken_links
+        .iter()
+        .filter(|bl| {
+            let target = bl.link.split(['#', '?']).next().unwrap_or_default();
+            if target.is_empty() {
+                return false;
+            }
+            let relative = bl
+                .from
+                .parent()
+                .map(|dir| normalize_path(&dir.join(target)));
+            relative.is_some_and(|p| missing.contains(&p))
+                || resolve_link(&bl.from, target, maps)
+                    .is_some_and(|p| missing.contains(&normalize_path(&p)))
+        })
+        .map(|bl| (bl.from.clone(), bl.link.clone()))
+        .collect()
+}
+
+/// Find broken site-absolute links (`/guide/page.md`) whose first path component is no
+/// longer a top-level nav prefix, which is what happens to every such link when a nav
+/// section is renamed or removed. Each is returned as (page, link, current prefixes the
+/// stale one most resembles), closest first; the list is empty when nothing is similar.
+pub fn find_stale_absolute_prefix_links(
+    broken_links: &[BrokenLink],
+    maps: &LinkMaps,
+) -> Vec<(PathBuf, String, Vec<String>)> {
+    let prefixes: HashSet<&str> = maps
+        .url_to_src
+        .keys()
+        .filter_map(|url| url.split('/').find(|c| !c.is_empty()))
+        .collect();
+    let mut prefixes: Vec<&str> = prefixes.into_iter().collect();
+    prefixes.sort();
+
+    broken_links
+        .iter()
+        .filter_map(|bl| {
+            let rest = bl.link.strip_prefix('/')?;
+            let (first, tail) = rest.split_once('/')?;
+            if first.is_empty() || tail.is_empty() || prefixes.contains(&first) {
+                return None;
+            }
+            let mut similar: Vec<(usize, &str)> = prefixes
+                .iter()
+                .filter_map(|&p| prefix_similarity(first, p).map(|d| (d, p)))
+                .collect();
+            similar.sort();
+            Some((
+                bl.from.clone(),
+                bl.link.clone(),
+                similar.into_iter().map(|(_, p)| p.to_string()).collect(),
+            ))
+        })
+        .collect()
+}
+
+/// How closely a stale URL prefix resembles a current one, lower being closer, or `None`
+/// if they are unrelated. A prefix that gained or lost words (`guide` -> `user-guide`)
+/// counts as close; otherwise the edit distance must be at most half the longer name.
+fn prefix_similarity(stale: &str, current: &str) -> Option<usize> {
+    let distance = edit_distance(stale, current);
+    let stale_words: HashSet<&str> = stale.split('-').collect();
+    let current_words: HashSet<&str> = current.split('-').collect();
+    if stale_words.is_subset(&current_words) || current_words.is_subset(&stale_words) {
+        return Some(1);
+    }
+    let longest = stale.chars().count().max(current.chars().count());
+    (distance * 2 <= longest).then_some(distance)
+}
+
+/// The (at most three) `pages` a broken `link` most likely meant, closest first: those
+/// whose name is within an edit distance of half the longer name from the link's last
+/// path segment. A page's name is its file stem, or its directory's for an `index.md`.
+pub fn suggest_link_fixes(link: &str, pages: &[PathBuf]) -> Vec<PathBuf> {
+    let Some(wanted) = link
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.strip_suffix(".md").unwrap_or(s).to_lowercase())
+    else {
+        return Vec::new();
+    };
+    let mut close: Vec<(usize, &PathBuf)> = pages
+        .iter()
+        .filter_map(|page| {
+            let stem = page.file_stem()?.to_string_lossy();
+            let name = if stem == "index" {
+                page.parent()?.file_name()?.to_string_lossy()
+            } else {
+                stem
+            }
+            .to_lowercase();
+            let distance = edit_distance(&wanted, &name);
+            let longest = wanted.chars().count().max(name.chars().count());
+            (distance * 2 <= longest).then_some((distance, page))
+        })
+        .collect();
+    close.sort();
+    close
+        .into_iter()
+        .take(3)
+        .map(|(_, page)| page.clone())
+        .collect()
+}
+
+/// How alike a nav label and a page title are, from 0 (nothing in common) to 1, ignoring
+/// case: 1 when either contains the other (`Ravel` and `The Ravel Function (,)`),
+/// otherwise one less the edit distance over the longer length.
+pub fn title_similarity(label: &str, title: &str) -> f64 {
+    let label = label.trim().to_lowercase();
+    let title = title.trim().to_lowercase();
+    if label.contains(&title) || title.contains(&label) {
+        return 1.0;
+    }
+    let longest = label.chars().count().max(title.chars().count());
+    1.0 - edit_distance(&label, &title) as f64 / longest as f64
+}
+
+/// Levenshtein distance between `a` and `b`, counted in chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// An image reference found in markdown, with its alt text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageRef {
+    pub src: String,
+    /// The alt text; `None` for an HTML `<img>` with no `alt` attribute.
+    pub alt: Option<String>,
+    /// Whether the image was written as markdown or as an HTML `<img>`.
+    pub syntax: ImageSyntax,
+}
+
+/// How an image reference is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSyntax {
+    /// `![alt](src)`
+    Markdown,
+    /// `<img src="...">`
+    Html,
+}
+
+impl ImageRef {
+    /// Whether `src` is site-absolute (`/img/logo.png`).
+    pub fn is_absolute(&self) -> bool {
+        self.src.starts_with('/') && !self.src.starts_with("//")
+    }
+
+    /// Whether `src` is relative to the page (`img/logo.png`, `../img/logo.png`), as
+    /// opposed to site-absolute, an external URL or a data URI.
+    pub fn is_relative(&self) -> bool {
+        !self.is_absolute() && !is_external_link(&self.src) && !self.src.starts_with("data:")
+    }
+}
+
+/// Extract image references from markdown content.
+/// Handles both markdown syntax ![alt](path) and HTML <img src="path">
+pub fn extract_image_refs(markdown: &str) -> Vec<String> {
+    let images: HashSet<String> = extract_image_refs_with_alt(markdown)
+        .into_iter()
+        .map(|r| r.src)
+        .collect();
+    images.into_iter().collect()
+}
+
+/// Like [`extract_image_refs`], but keeps each reference's alt text. References are
+/// returned in document order, without duplicates.
+pub fn extract_image_refs_with_alt(markdown: &str) -> Vec<ImageRef> {
+    let mut images: Vec<ImageRef> = Vec::new();
+    let mut push = |image: ImageRef| {
+        if !images.contains(&image) {
+            images.push(image);
+        }
+    };
+    let img_selector = Selector::parse("img[src]").unwrap();
+
+    // Alt text of the markdown image currently open, with its src.
+    let mut open_image: Option<(String, String)> = None;
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                open_image = Some((dest_url.into_string(), String::new()));
+            }
+            Event::End(TagEnd::Image) => {
+                if let Some((src, alt)) = open_image.take() {
+                    push(ImageRef {
+                        src,
+                        alt: Some(alt),
+                        syntax: ImageSyntax::Markdown,
+                    });
+                }
+            }
+            Event::Text(text) | Event::Code(text) if open_image.is_some() => {
+                if let Some((_, alt)) = open_image.as_mut() {
+                    alt.push_str(&text);
+                }
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                let fragment = Html::parse_fragment(&html);
+                for el in fragment.select(&img_selector) {
+                    if let Some(src) = el.value().attr("src") {
+                        push(ImageRef {
+                            src: src.to_string(),
+                            alt: el.value().attr("alt").map(str::to_string),
+                            syntax: ImageSyntax::Html,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Regex fallback: pulldown_cmark can misparse markdown image syntax as
+    // code/text when raw HTML blocks precede fenced code blocks that contain
+    // blank lines.  A direct regex scan catches what the AST walk misses.
+    let md_img_re = Regex::new(r"!\[([^\]]*)\]\(([^)]+)\)").unwrap();
+    for cap in md_img_re.captures_iter(markdown) {
+        let src = &cap[2];
+        if !images.iter().any(|r| r.src == src) {
+            images.push(ImageRef {
+                src: src.to_string(),
+                alt: Some(cap[1].to_string()),
+                syntax: ImageSyntax::Markdown,
+            });
+        }
+    }
+
+    images
+}
+
+/// Find pairs of paths that differ only in case (`docs/Intro.md` and `docs/intro.md`).
+/// Both exist on Linux, but on a case-insensitive filesystem (macOS, Windows) they are
+/// the same file, so one silently replaces the other. Each path is paired with the
+/// first of its group in sorted order; see [`find_case_collisions`].
+pub fn find_case_sensitivity_issues(pages: &HashSet<PathBuf>) -> Vec<(PathBuf, PathBuf)> {
+    find_case_collisions(pages)
+}
+
+/// Group `pages` by their lowercased path and return the pairs that collide, each path
+/// paired with the first of its group in sorted order. On a case-insensitive filesystem
+/// `foo.md` and `FOO.md` are one file, reachable under two URLs.
+pub fn find_case_collisions(pages: &HashSet<PathBuf>) -> Vec<(PathBuf, PathBuf)> {
+    let mut by_lowercase: HashMap<String, Vec<&PathBuf>> = HashMap::new();
+    for page in pages {
+        by_lowercase
+            .entry(page.to_string_lossy().to_lowercase())
+            .or_default()
+            .push(page);
+    }
+    let mut issues: Vec<(PathBuf, PathBuf)> = by_lowercase
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .flat_map(|mut group| {
+            group.sort();
+            let first = group[0].clone();
+            group
+                .into_iter()
+                .skip(1)
+                .map(move |other| (first.clone(), other.clone()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    issues.sort();
+    issues
+}
+
+/// Relative links whose target doesn't exist as written but does in a different case,
+/// as (target as written, file on disk). They work on a case-insensitive filesystem and
+/// break on a case-sensitive one such as a Linux CI runner. Links already among
+/// `broken_links` are left out, so each is reported once.
+fn find_case_mismatched_link_targets(
+    contents: &[(PathBuf, String)],
+    files_set: &HashSet<PathBuf>,
+    broken_links: &[BrokenLink],
+) -> Vec<(PathBuf, PathBuf)> {
+    let by_lowercase: HashMap<String, &PathBuf> = files_set
+        .iter()
+        .map(|p| (p.to_string_lossy().to_lowercase(), p))
+        .collect();
+    let by_lowercase = &by_lowercase;
+    contents
+        .iter()
+        .flat_map(|(src, content)| {
+            let dir = src.parent().unwrap_or_else(|| Path::new(""));
+            extract_hrefs(content)
+                .into_iter()
+                .filter_map(|l| normalise_one(&l))
+                .filter(|link| !link.target.starts_with('/'))
+                .filter(move |link| {
+                    !broken_links
+                        .iter()
+                        .any(|bl| bl.from == *src && bl.link == link.target)
+                })
+                .filter_map(move |link| {
+                    let target = normalize_path(&dir.join(&link.target));
+                    if files_set.contains(&target) {
+                        return None;
+                    }
+                    let actual = by_lowercase.get(&target.to_string_lossy().to_lowercase())?;
+                    Some((target, (*actual).clone()))
+                })
+        })
+        .collect()
+}
+
+/// Group `image_paths` by identical content, returning each group of two or more files
+/// in the order given: the first file is the canonical copy, the rest duplicates of it.
+/// Each file is streamed once through SHA-256 and grouped by digest. Files that can't be
+/// read are skipped.
+pub fn find_duplicate_images(image_paths: &[PathBuf]) -> Vec<Vec<PathBuf>> {
+    let mut groups: HashMap<[u8; 32], Vec<PathBuf>> = HashMap::new();
+    let mut order: Vec<[u8; 32]> = Vec::new();
+    for path in image_paths {
+        let Ok(digest) = sha256_file(path) else {
+            continue;
+        };
+        let group = groups.entry(digest).or_default();
+        if group.is_empty() {
+            order.push(digest);
+        }
+        group.push(path.clone());
+    }
+    order
+        .into_iter()
+        .filter_map(|digest| groups.remove(&digest).filter(|group| group.len() > 1))
+        .collect()
+}
+
+fn sha256_file(path: &Path) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().into())
+}
+
+/// Find the SVG files among `svg_paths` larger than `max_bytes`, with their size. Files
+/// that can't be read are skipped.
+pub fn find_oversized_svgs(svg_paths: &[PathBuf], max_bytes: u64) -> Vec<(PathBuf, u64)> {
+    svg_paths
+        .iter()
+        .filter_map(|p| {
+            let len = fs::metadata(p).ok()?.len();
+            (len > max_bytes).then(|| (p.clone(), len))
+        })
+        .collect()
+}
+
+/// Find the SVG files among `svg_paths` that embed a raster image as a data URI
+/// (`<image href="data:image/png;base64,...">`). A bitmap inside an SVG loses the
+/// format's scalability and is usually what makes it huge; nested SVG data URIs
+/// (`data:image/svg+xml`) are not counted.
+pub fn find_svgs_with_embedded_rasters(svg_paths: &[PathBuf]) -> Vec<PathBuf> {
+    svg_paths
+        .iter()
+        .filter(|p| {
+            fs::read_to_string(p).is_ok_and(|content| {
+                let content = content.to_ascii_lowercase();
+                content
+                    .match_indices("data:image/")
+                    .any(|(i, m)| !content[i + m.len()..].starts_with("svg"))
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// Whether a page mixes image reference styles: site-absolute (`/img/a.png`) alongside
+/// page-relative (`img/b.png`) paths, or markdown `![](...)` alongside HTML `<img>`.
+/// External URLs and data URIs count towards the syntax check only.
+pub fn has_mixed_image_ref_styles(markdown: &str) -> bool {
+    let refs = extract_image_refs_with_alt(markdown);
+    let mixed_paths =
+        refs.iter().any(ImageRef::is_absolute) && refs.iter().any(ImageRef::is_relative);
+    let mixed_syntax = refs.iter().any(|r| r.syntax == ImageSyntax::Markdown)
+        && refs.iter().any(|r| r.syntax == ImageSyntax::Html);
+    mixed_paths || mixed_syntax
+}
+
+/// Find images referenced by an absolute filesystem path: a Windows drive (`C:\`,
+/// `D:/`), a UNC share (`\\server\share`), a `file://` URL, or a Unix path under a
+/// directory that holds user or machine files (`/home/`, `/Users/`, `/tmp/`, ...).
+/// Site-absolute refs like `/img/logo.png` are left alone.
+pub fn find_absolute_fs_image_refs(markdown: &str) -> Vec<String> {
+    const UNIX_ROOTS: &[&str] = &[
+        "/home/",
+        "/Users/",
+        "/root/",
+        "/tmp/",
+        "/var/",
+        "/opt/",
+        "/mnt/",
+        "/media/",
+        "/Volumes/",
+        "/private/",
+    ];
+    let windows_re = Regex::new(r"^(?:[A-Za-z]:[\\/]|\\\\)").unwrap();
+    extract_image_refs_with_alt(markdown)
+        .into_iter()
+        .map(|r| r.src)
+        .filter(|src| {
+            windows_re.is_match(src)
+                || src.to_ascii_lowercase().starts_with("file:")
+                || UNIX_ROOTS.iter().any(|root| src.starts_with(root))
+        })
+        .collect()
+}
+
+/// Alt texts that say nothing about the image. Matched case-insensitively against the
+/// whole (trimmed) alt text; the empty string catches `![](...)` and `alt=""`.
+pub const GENERIC_ALT_TEXTS: &[&str] = &[
+    "",
+    "image",
+    "img",
+    "picture",
+    "photo",
+    "screenshot",
+    "screen shot",
+    "logo",
+    "icon",
+    "diagram",
+    "figure",
+    "graphic",
+    "alt",
+    "alt text",
+    "placeholder",
+];
+
+/// Find images whose alt text is one of [`GENERIC_ALT_TEXTS`], as (src, alt text).
+pub fn find_poor_alt_text(markdown: &str) -> Vec<(String, String)> {
+    find_poor_alt_text_with(markdown, &[])
+}
+
+/// Like [`find_poor_alt_text`], also treating each of `extra` as generic.
+pub fn find_poor_alt_text_with(markdown: &str, extra: &[String]) -> Vec<(String, String)> {
+    extract_image_refs_with_alt(markdown)
+        .into_iter()
+        .filter_map(|r| {
+            let alt = r.alt?;
+            let key = alt.trim().to_lowercase();
+            let generic = GENERIC_ALT_TEXTS.contains(&key.as_str())
+                || extra.iter().any(|e| e.trim().to_lowercase() == key);
+            generic.then_some((r.src, alt))
+        })
+        .collect()
+}
+
+/// File extensions expected in a docs directory: pages, images, styles, scripts, fonts
+/// and the like. Anything else is likely an accidental commit (see
+/// [`find_unexpected_binaries`]).
+pub const DOCS_EXTENSIONS: &[&str] = &[
+    "md", "markdown", "png", "jpg", "jpeg", "gif", "svg", "webp", "ico", "bmp", "avif", "css",
+    "scss", "js", "mjs", "map", "json", "yml", "yaml", "html", "htm", "txt", "xml", "csv", "pdf",
+    "woff", "woff2", "ttf", "otf", "eot", "mp4", "webm",
+];
+
+/// Walk `docs_dir` and return the files whose extension is not in `allowed_extensions`
+/// (compared case-insensitively), e.g. a stray `.exe` or `.pyc`. Files without an
+/// extension, such as `CNAME` or `.pages`, are not reported. With `respect_gitignore`,
+/// files that gitignore rules exclude are passed over (see [`AuditOptions`]).
+pub fn find_unexpected_binaries(
+    docs_dir: &Path,
+    allowed_extensions: &[&str],
+    respect_gitignore: bool,
+) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = walker(docs_dir, respect_gitignore)
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_type().is_some_and(|t| t.is_file())
+                && e.path()
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| {
+                        !allowed_extensions
+                            .iter()
+                            .any(|allowed| allowed.eq_ignore_ascii_case(ext))
+                    })
+                    .unwrap_or(false)
+        })
+        .map(|e| normalize_path(e.path()))
+        .collect();
+    found.sort();
+    found
+}
+
+/// The YAML front matter of a page, the block between a leading `---` line and the
+/// next `---` (or `...`) line, parsed; `None` when there is none, or it isn't valid
+/// YAML.
+pub fn extract_frontmatter(markdown: &str) -> Option<serde_yaml::Value> {
+    serde_yaml::from_str(frontmatter_text(markdown)?).ok()
+}
+
+/// The unparsed front matter of a page; see [`extract_frontmatter`].
+fn frontmatter_text(markdown: &str) -> Option<&str> {
+    frontmatter_span(markdown).map(|(start, end, _)| &markdown[start..end])
+}
+
+/// The `required_fields` that `frontmatter` doesn't set, in the order given. A field
+/// that is present but null or empty (`description:`) counts as missing.
+pub fn validate_frontmatter(
+    frontmatter: &serde_yaml::Value,
+    required_fields: &[&str],
+) -> Vec<String> {
+    required_fields
+        .iter()
+        .filter(|field| match frontmatter.get(**field) {
+            None | Some(serde_yaml::Value::Null) => true,
+            Some(serde_yaml::Value::String(s)) => s.trim().is_empty(),
+            Some(serde_yaml::Value::Sequence(items)) => items.is_empty(),
+            Some(_) => false,
+        })
+        .map(|field| field.to_string())
+        .collect()
+}
+
+/// Byte offsets of the front matter: (start of the YAML, end of the YAML, start of the
+/// page body after the closing line).
+fn frontmatter_span(markdown: &str) -> Option<(usize, usize, usize)> {
+    let mut lines = markdown.split_inclusive('\n');
+    let first = lines.next()?;
+    if first.trim_end() != "---" {
+        return None;
+    }
+    let start = first.len();
+    let mut end = start;
+    for line in lines {
+        if matches!(line.trim_end(), "---" | "...") {
+            return Some((start, end, end + line.len()));
+        }
+        end += line.len();
+    }
+    None
+}
+
+/// The open TODOs listed under `todo` in a page's front matter, given either as a list
+/// (`todo: ["Add examples", "Fix broken link"]`) or as a single string.
+pub fn extract_frontmatter_todos(markdown: &str) -> Vec<String> {
+    let Some(frontmatter) = extract_frontmatter(markdown) else {
+        return Vec::new();
+    };
+    match frontmatter.get("todo") {
+        Some(serde_yaml::Value::String(todo)) => vec![todo.clone()],
+        Some(serde_yaml::Value::Sequence(todos)) => todos
+            .iter()
+            .filter_map(|t| t.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Image paths given in the front matter `fields` of a page, e.g. `hero_image:
+/// img/hero.png`. A field may hold a single path or a list of them.
+pub fn extract_frontmatter_image_refs(markdown: &str, fields: &[String]) -> Vec<String> {
+    let Some(frontmatter) = extract_frontmatter(markdown) else {
+        return Vec::new();
+    };
+    fields
+        .iter()
+        .filter_map(|field| frontmatter.get(field.as_str()))
+        .flat_map(|value| match value {
+            serde_yaml::Value::String(path) => vec![path.clone()],
+            serde_yaml::Value::Sequence(paths) => paths
+                .iter()
+                .filter_map(|p| p.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// Extract image references from CSS content.
+/// Handles url() references in background-image, content, etc.
+pub fn extract_css_image_refs(css: &str) -> Vec<String> {
+    let url_re = Regex::new(r#"url\s*\(\s*['"]?([^'")]+)['"]?\s*\)"#).unwrap();
+    url_re
+        .captures_iter(css)
+        .filter_map(|cap| {
+            let url = cap.get(1)?.as_str().trim();
+            // Skip data URIs and external URLs
+            if url.starts_with("data:") || url.starts_with("http://") || url.starts_with("https://")
+            {
+                return None;
+            }
+            Some(url.to_string())
+        })
+        .collect()
+}
+
+/// The modules a SCSS stylesheet loads with `@use`, `@forward` or `@import`, as written
+/// (`"variables"`, `'../base/mixins'`). Built-in modules (`sass:math`), plain CSS
+/// imports (`url(...)`, `*.css`, external URLs) and commented-out lines are skipped.
+pub fn extract_scss_imports(scss: &str) -> Vec<String> {
+    let statement_re = Regex::new(r"@(?:use|forward|import)\s+([^;\n]+)").unwrap();
+    let quoted_re = Regex::new(r#"["']([^"']+)["']"#).unwrap();
+    let scss = strip_c_comments(scss);
+    let mut imports = Vec::new();
+    for statement in statement_re.captures_iter(&scss) {
+        // `@use "a" as b with (...)`: only the leading quoted strings name modules.
+        let names = statement[1]
+            .split(" as ")
+            .next()
+            .unwrap_or_default()
+            .split(" with ")
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        for name in quoted_re.captures_iter(&names) {
+            let name = &name[1];
+            if name.starts_with("sass:")
+                || name.ends_with(".css")
+                || name.contains("://")
+                || names.contains("url(")
+            {
+                continue;
+            }
+            imports.push(name.to_string());
+        }
+    }
+    imports
+}
+
+/// The stylesheet an SCSS import in `from` refers to, following Sass's lookup: `name`
+/// may be written without its extension or the `_` of a partial, or name a directory
+/// with an `_index.scss`.
+fn resolve_scss_import(from: &Path, name: &str, scss_files: &HashSet<PathBuf>) -> Option<PathBuf> {
+    let base = normalize_path(&from.parent()?.join(name));
+    let dir = base.parent()?;
+    let stem = base.file_name()?.to_string_lossy().into_owned();
+    let stem = stem.strip_suffix(".scss").unwrap_or(&stem);
+    [
+        dir.join(format!("{stem}.scss")),
+        dir.join(format!("_{stem}.scss")),
+        base.join("_index.scss"),
+        base.join("index.scss"),
+    ]
+    .into_iter()
+    .find(|candidate| scss_files.contains(candidate))
+}
+
+/// Find import cycles among SCSS stylesheets (`a.scss` uses `b.scss`, which uses
+/// `a.scss`), which fail the Sass build. Each cycle is reported once, starting from its
+/// lowest path, e.g. `[a.scss, b.scss]`.
+pub fn find_scss_import_cycles(scss_files: &[PathBuf]) -> Vec<Vec<PathBuf>> {
+    let file_set: HashSet<PathBuf> = scss_files.iter().map(|p| normalize_path(p)).collect();
+    let mut edges: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    for file in &file_set {
+        let Ok(scss) = fs::read_to_string(file) else {
+            continue;
+        };
+        let targets = extract_scss_imports(&scss)
+            .iter()
+            .filter_map(|name| resolve_scss_import(file, name, &file_set))
+            .collect();
+        edges.insert(file.clone(), targets);
+    }
+
+    let mut files: Vec<&PathBuf> = file_set.iter().collect();
+    files.sort();
+    let mut finished: HashSet<PathBuf> = HashSet::new();
+    let mut cycles: Vec<Vec<PathBuf>> = Vec::new();
+    for start in files {
+        let mut stack: Vec<PathBuf> = Vec::new();
+        visit_scss_imports(start, &edges, &mut stack, &mut finished, &mut cycles);
+    }
+    cycles.sort();
+    cycles.dedup();
+    cycles
+}
+
+/// Depth-first walk for [`find_scss_import_cycles`]: `stack` is the current import
+/// chain, and a file already on it closes a cycle.
+fn visit_scss_imports(
+    file: &PathBuf,
+    edges: &HashMap<PathBuf, Vec<PathBuf>>,
+    stack: &mut Vec<PathBuf>,
+    finished: &mut HashSet<PathBuf>,
+    cycles: &mut Vec<Vec<PathBuf>>,
+) {
+    if let Some(pos) = stack.iter().position(|p| p == file) {
+        let mut cycle = stack[pos..].to_vec();
+        let lowest = (0..cycle.len()).min_by_key(|&i| &cycle[i]).unwrap_or(0);
+        cycle.rotate_left(lowest);
+        cycles.push(cycle);
+        return;
+    }
+    if finished.contains(file) {
+        return;
+    }
+    stack.push(file.clone());
+    for target in edges.get(file).into_iter().flatten() {
+        visit_scss_imports(target, edges, stack, finished, cycles);
+    }
+    stack.pop();
+    finished.insert(file.clone());
+}
+
+/// The classes used in a stylesheet's selectors, in order of first appearance: `.note`
+/// and `.md-nav__item--active` in `.note > .md-nav__item--active { ... }`. Selectors of
+/// at-rules (`@media (min-width: 76.25em)`) and anything inside declarations are ignored.
+pub fn extract_css_class_selectors(css: &str) -> Vec<String> {
+    let comment_re = Regex::new(r"(?s)/\*.*?\*/").unwrap();
+    let class_re = Regex::new(r"\.(-?[_a-zA-Z][_a-zA-Z0-9-]*)").unwrap();
+    let css = comment_re.replace_all(css, "");
+    let mut classes: Vec<String> = Vec::new();
+    let mut prelude = String::new();
+    for c in css.chars() {
+        match c {
+            '{' => {
+                let selector = prelude.trim();
+                if !selector.starts_with('@') {
+                    for cap in class_re.captures_iter(selector) {
+                        let class = cap[1].to_string();
+                        if !classes.contains(&class) {
+                            classes.push(class);
+                        }
+                    }
+                }
+                prelude.clear();
+            }
+            '}' | ';' => prelude.clear(),
+            _ => prelude.push(c),
+        }
+    }
+    classes
+}
+
+/// The CSS classes a page uses: `class="..."` attributes in inline HTML and `.class`
+/// entries in attribute lists (`{ .md-button }`, `{: .note }`).
+pub fn extract_used_classes(markdown: &str) -> Vec<String> {
+    let attr_re = Regex::new(r#"class\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+    let attr_list_re = Regex::new(r"\{:?([^{}\n]*)\}").unwrap();
+    let mut classes: Vec<String> = attr_re
+        .captures_iter(markdown)
+        .filter_map(|cap| cap.get(1).or_else(|| cap.get(2)))
+        .flat_map(|m| m.as_str().split_whitespace().map(String::from))
+        .collect();
+    for cap in attr_list_re.captures_iter(markdown) {
+        classes.extend(
+            cap[1]
+                .split_whitespace()
+                .filter_map(|token| token.strip_prefix('.'))
+                .filter(|class| !class.is_empty())
+                .map(String::from),
+        );
+    }
+    classes
+}
+
+/// Class selectors in `css` for classes not in `used_classes`. Typically left behind by
+/// a theme switch: `.wy-nav-content` outlives the ReadTheDocs theme. MkDocs Material's
+/// own `md-*` classes are left out, as overriding them is what an `extra.css` is for;
+/// other classes a theme adds at render time never appear in the markdown either, so
+/// these are only candidates.
+pub fn find_potentially_dead_css_selectors(
+    css: &str,
+    used_classes: &HashSet<String>,
+) -> Vec<String> {
+    extract_css_class_selectors(css)
+        .into_iter()
+        .filter(|class| !class.starts_with(MATERIAL_CLASS_PREFIX))
+        .filter(|class| !used_classes.contains(class))
+        .collect()
+}
+
+/// The prefix of the classes MkDocs Material generates (`md-header`, `md-nav__item`).
+const MATERIAL_CLASS_PREFIX: &str = "md-";
+
+/// Normalise image paths - filter out external URLs
+fn normalise_image_refs<I>(refs: I) -> Vec<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    refs.into_iter()
+        .filter(|r| {
+            !r.starts_with("http://") && !r.starts_with("https://") && !r.starts_with("data:")
+        })
+        .collect()
+}
+
+/// Analyse image references in markdown files and CSS files.
+/// Returns (missing_images, referenced_images).
+fn analyse_image_refs(
+    markdown_files: &HashSet<PathBuf>,
+    css_files: &[PathBuf],
+    all_images: &HashSet<PathBuf>,
+    include_dirs: &[PathBuf],
+    layout: &SiteLayout,
+    frontmatter_image_fields: &[String],
+) -> io::Result<(Vec<BrokenImage>, HashSet<PathBuf>)> {
+    let mut missing = Vec::new();
+    let mut referenced = HashSet::new();
+
+    // Process markdown files
+    for src in markdown_files {
+        if let Ok(content) = fs::read_to_string(src) {
+            let mut image_refs = extract_image_refs(&content);
+            image_refs.extend(extract_frontmatter_image_refs(
+                &content,
+                frontmatter_image_fields,
+            ));
+            image_refs.sort();
+            image_refs.dedup();
+            let image_refs = normalise_image_refs(image_refs);
+            for img_ref in image_refs {
+                if let Some(resolved) =
+                    resolve_image_ref(src, &img_ref, all_images, include_dirs, layout)
+                {
+                    referenced.insert(resolved);
+                } else {
+                    missing.push(BrokenImage {
+                        from: src.clone(),
+                        image: img_ref,
+                    });
+                }
+            }
+        }
+    }
+
+    // Process CSS files
+    for css_path in css_files {
+        if let Ok(content) = fs::read_to_string(css_path) {
+            let image_refs = extract_css_image_refs(&content);
+            for img_ref in image_refs {
+                if let Some(resolved) =
+                    resolve_image_ref(css_path, &img_ref, all_images, include_dirs, layout)
+                {
+                    referenced.insert(resolved);
+                }
+                // Don't report CSS broken images for now - they may reference build artifacts
+            }
+        }
+    }
+
+    Ok((missing, referenced))
+}
+
+/// Resolve an image reference to an absolute path.
+fn resolve_image_ref(
+    src: &Path,
+    img_ref: &str,
+    all_images: &HashSet<PathBuf>,
+    include_dirs: &[PathBuf],
+    layout: &SiteLayout,
+) -> Option<PathBuf> {
+    let img_ref = &decode_link_path(img_ref);
+
+    // Handle absolute paths (starting with /)
+    if let Some(abs_rel) = img_ref.strip_prefix('/') {
+        // The docs dir of the source's own site first
+        if let Some(docs_dir) = layout.docs_ancestor(src) {
+            let candidate = normalize_path(&docs_dir.join(abs_rel));
+            if all_images.contains(&candidate) {
+                return Some(candidate);
+            }
+        }
+        // Then each include dir as potential root
+        for dir in include_dirs {
+            // The root for absolute paths is typically the docs subdirectory
+            let docs_dir = layout.docs_dir(dir);
+            let candidate = if docs_dir.exists() {
+                normalize_path(&docs_dir.join(abs_rel))
+            } else {
+                normalize_path(&dir.join(abs_rel))
+            };
+            if all_images.contains(&candidate) {
+                return Some(candidate);
+            }
+        }
+        return None;
+    }
+
+    // Handle relative paths
+    if let Some(parent) = src.parent() {
+        let candidate = normalize_path(&parent.join(img_ref));
+        if all_images.contains(&candidate) {
+            return Some(candidate);
+        }
+        // Also check if it exists on disk (might not be in our include_dirs scan)
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+    }
+
+    // Try from each include dir's docs folder
+    for dir in include_dirs {
+        let docs_dir = layout.docs_dir(dir);
+        if docs_dir.exists() {
+            let candidate = normalize_path(&docs_dir.join(img_ref));
+            if all_images.contains(&candidate) {
+                return Some(candidate);
+            }
+        }
+    }
+
+    None
+}
+
+/// A normalised internal link target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NormalisedLink {
+    /// Target path, always ending in `.md` (anchor stripped, extension added if absent).
+    pub target: String,
+    /// Whether the author wrote an explicit `.md` extension on the original link.
+    /// Directory-style (`foo/`) and extensionless (`foo`) links are `false` — MkDocs
+    /// serves those as directory URLs, whereas an explicit `.md` is only rewritten when
+    /// the target resolves within the same subsite.
+    pub had_md: bool,
+    /// The `#section` the link points at, without the `#`.
+    pub anchor: Option<String>,
+}
+
+/// URI schemes, besides `http(s)`, for links that never point at a docs page.
+const NON_DOCUMENT_SCHEMES: &[&str] =
+    &["mailto:", "tel:", "sms:", "ftp:", "ftps:", "irc:", "xmpp:"];
+
+/// Links that leave the docs tree (web pages, mail addresses), which ghost doesn't check.
+fn is_external_link(link: &str) -> bool {
+    link.starts_with("http")
+        // Scheme-relative (`//host/path`): another host, whatever the scheme.
+        || link.starts_with("//")
+        || NON_DOCUMENT_SCHEMES.iter().any(|scheme| {
+            link.get(..scheme.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+        })
+}
+
+/// Percent-decode a link or image path (`my%20page.md` → `my page.md`) one `/`-separated
+/// segment at a time. A `%2F` inside a segment (`guide%2Fpage.md`) decodes to `/` like
+/// any other character, but one at either end of a segment is left encoded: decoded, it
+/// would give an empty path component, or turn a relative link into an absolute one.
+fn decode_link_path(link: &str) -> String {
+    if !link.contains('%') {
+        return link.to_string();
+    }
+    let is_slash = |s: &str| s.eq_ignore_ascii_case("%2F");
+    link.split('/')
+        .map(|segment| {
+            let mut start = 0;
+            while segment.get(start..start + 3).is_some_and(is_slash) {
+                start += 3;
+            }
+            let mut end = segment.len();
+            while end >= start + 3 && segment.get(end - 3..end).is_some_and(is_slash) {
+                end -= 3;
+            }
+            format!(
+                "{}{}{}",
+                &segment[..start],
+                percent_decode_str(&segment[start..end]).decode_utf8_lossy(),
+                &segment[end..]
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Normalise a single raw link, classifying whether it carried an explicit `.md`
+/// extension. Returns `None` for external, mailto, empty, or non-markdown links
+/// (which we don't check).
+fn normalise_one(link: &str) -> Option<NormalisedLink> {
+    // split off the anchor first; a page-internal `#section` alone isn't checked
+    let (link, anchor) = match link.split_once('#') {
+        Some((link, anchor)) => (
+            link,
+            Some(anchor.trim().to_string()).filter(|a| !a.is_empty()),
+        ),
+        None => (link, None),
+    };
+    let mut link = link.trim().to_string();
+    if link.is_empty() {
+        return None;
+    }
+
+    if is_external_link(&link) {
+        return None;
+    }
+
+    // MkDocs decodes `my%20page.md` to `my page.md` before looking the page up
+    link = decode_link_path(&link);
+
+    // trailing slash → directory style; strip and add .md
+    if link.ends_with('/') {
+        link = link.trim_end_matches('/').to_string();
+        if link.is_empty() {
+            return None;
+        }
+        link.push_str(".md");
+        return Some(NormalisedLink {
+            target: link,
+            had_md: false,
+            anchor,
+        });
+    }
+
+    let path = Path::new(&link);
+    match path.extension() {
+        Some(ext) if ext.eq_ignore_ascii_case("md") => Some(NormalisedLink {
+            target: link,
+            had_md: true,
+            anchor,
+        }),
+        Some(_) => None, // non-markdown => drop
+        None => {
+            // add .md when no extension (directory/page-style link)
+            let mut with_ext = link;
+            with_ext.push_str(".md");
+            Some(NormalisedLink {
+                target: with_ext,
+                had_md: false,
+                anchor,
+            })
+        }
+    }
+}
+
+pub fn normalise_links<I>(links: I) -> Vec<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    links
+        .into_iter()
+        .filter_map(|link| normalise_one(&link).map(|l| l.target))
+        .collect()
+}
+
+/// MkDocs filenames are mandated lower-case, so any internal link whose path
+/// contains an upper-case ASCII letter is broken on the (case-sensitive) production
+/// server even if it resolves on a case-insensitive developer filesystem.
+fn link_has_mixed_case(target: &str) -> bool {
+    target.chars().any(|c| c.is_ascii_uppercase())
+}
+
+/// Normalise a relative path, returning `None` if a `..` component escapes the root
+/// instead of silently clamping. (`normalise_url` clamps, which is correct for browser-
+/// style links that legitimately stop at the site root, but wrong for the MkDocs source-
+/// relative `.md` resolution below, where escaping the merged root means "unresolvable".)
+fn normalise_rel_strict(path: &Path) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    for comp in path.components() {
+        match comp {
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            Component::Normal(c) => parts.push(c.to_string_lossy().into_owned()),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// Resolve a `.md` link the way MkDocs (with the monorepo plugin) rewrites it: source-
+/// relative within the *merged* docs tree, where each subsite's docs directory is
+/// mounted at `<merged>/<subsite>/`. Returns the on-disk target when it resolves to a real markdown
+/// file (so MkDocs rewrites the link to a working URL), or `None` when it escapes the
+/// merged root or no such file exists — in which case MkDocs leaves the literal `.md`
+/// href, which 404s on the directory-URL site (issue #876).
+fn resolve_md_link_merged(
+    src: &Path,
+    link: &str,
+    files_set: &HashSet<PathBuf>,
+    layout: &SiteLayout,
+) -> MergedResolve {
+    // Structural fallback: if src isn't under a subsite's docs dir we can't map it.
+    let Some(docs_dir) = layout.docs_ancestor(src) else {
+        return MergedResolve::FileMissing(PathBuf::from(link));
+    };
+    let (Some(subsite_dir), Some(subsite_name)) = (
+        docs_dir.parent(),
+        docs_dir.parent().and_then(|d| d.file_name()),
+    ) else {
+        return MergedResolve::FileMissing(PathBuf::from(link));
+    };
+    let Some(monorepo_root) = subsite_dir.parent() else {
+        return MergedResolve::FileMissing(PathBuf::from(link));
+    };
+    let Ok(path_within_docs) = src.strip_prefix(docs_dir) else {
+        return MergedResolve::FileMissing(PathBuf::from(link));
+    };
+
+    // Merged-tree directory containing the source file: `<subsite>/<dir within docs>`.
+    let src_merged = Path::new(subsite_name)
+        .join(path_within_docs)
+        .with_extension("");
+    let merged_dir = src_merged.parent().unwrap_or_else(|| Path::new(""));
+
+    let link_no_ext = Path::new(link).with_extension("");
+    let merged_target = if link.starts_with('/') {
+        // Site-absolute link: relative to the merged root.
+        link_no_ext
+            .strip_prefix("/")
+            .unwrap_or(&link_no_ext)
+            .to_path_buf()
+    } else {
+        merged_dir.join(&link_no_ext)
+    };
+
+    let Some(normalized) = normalise_rel_strict(&merged_target) else {
+        return MergedResolve::EscapedRoot;
+    };
+
+    // Map merged path `<subsite>/<rest>` back to `<subsite docs dir>/<rest>.md`. The
+    // first segment is a slugified site_name, which may differ from the directory name.
+    let mut comps = normalized.split('/').filter(|s| !s.is_empty());
+    let Some(first) = comps.next() else {
+        return MergedResolve::FileMissing(PathBuf::from(link));
+    };
+    let is_subsite =
+        layout.mounts.contains_key(first) || layout.docs_dir(&monorepo_root.join(first)).is_dir();
+    let subsite_dir_for_first = layout
+        .mounts
+        .get(first)
+        .cloned()
+        .unwrap_or_else(|| monorepo_root.join(first));
+    let candidate = comps
+        .fold(layout.docs_dir(&subsite_dir_for_first), |acc, c| {
+            acc.join(c)
+        })
+        .with_extension("md")
+        .components()
+        .collect::<PathBuf>();
+
+    match check_with_index_fallback(&candidate, files_set) {
+        Some(p) => MergedResolve::Resolved(p),
+        None if !is_subsite => MergedResolve::UnknownSubsite(first.to_string()),
+        None => MergedResolve::FileMissing(candidate),
+    }
+}
+
+/// Map each subsite's slugified `site_name` (and its directory name, as a fallback) to
+/// the subsite's root directory. The mkdocs-monorepo plugin mounts each `!include`d
+/// subsite at the slug of its `site_name`, which need not match the directory name —
+/// e.g. `.NET Interface Guide` → `net-interface-guide` while the directory is
+/// `dotnet-interface-guide`. Cross-subsite links use the slug, so resolution must be
+/// able to map that slug back to the directory on disk.
+pub fn build_subsite_map(nav: &[NavItem], mkdocs_dir: &Path) -> HashMap<String, PathBuf> {
+    site_layout(nav, mkdocs_dir, DEFAULT_DOCS_DIR, usize::MAX).mounts
+}
+
+/// Where the sites of a monorepo keep their pages: each subsite's mount (see
+/// [`build_subsite_map`]) and the `docs_dir` of every site directory, the root's
+/// included. A directory without a known mkdocs.yml is taken to use [`DEFAULT_DOCS_DIR`].
+#[derive(Debug, Default, Clone)]
+struct SiteLayout {
+    mounts: HashMap<String, PathBuf>,
+    docs_dirs: HashMap<PathBuf, PathBuf>,
+}
+
+impl SiteLayout {
+    fn set_docs_dir(&mut self, site_dir: &Path, docs_dir: &str) {
+        self.docs_dirs
+            .entry(site_dir.components().collect())
+            .or_insert_with(|| Path::new(docs_dir).components().collect());
+    }
+
+    /// The docs directory of the site in `site_dir`.
+    fn docs_dir(&self, site_dir: &Path) -> PathBuf {
+        let site_dir: PathBuf = site_dir.components().collect();
+        let docs_dir = self
+            .docs_dirs
+            .get(&site_dir)
+            .map_or(Path::new(DEFAULT_DOCS_DIR), PathBuf::as_path);
+        site_dir.join(docs_dir)
+    }
+
+    /// The docs directory `path` lies in, the nearest if they nest; `path` itself counts.
+    fn docs_ancestor<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        path.ancestors().skip(1).find_map(|site_dir| {
+            let docs_dir = self.docs_dir(site_dir);
+            path.ancestors().find(|a| *a == docs_dir)
+        })
+    }
+}
+
+/// The [`SiteLayout`] of the site whose mkdocs.yml, in `mkdocs_dir`, has `nav` and sets
+/// `docs_dir`.
+fn site_layout(
+    nav: &[NavItem],
+    mkdocs_dir: &Path,
+    docs_dir: &str,
+    max_include_depth: usize,
+) -> SiteLayout {
+    let mut layout = SiteLayout::default();
+    layout.set_docs_dir(mkdocs_dir, docs_dir);
+    collect_subsite_mounts(
+        nav,
+        mkdocs_dir,
+        &mut layout,
+        &mut IncludeDepth::new(max_include_depth),
+    );
+    layout
+}
+
+fn collect_subsite_mounts(
+    nav: &[NavItem],
+    mkdocs_dir: &Path,
+    layout: &mut SiteLayout,
+    depth: &mut IncludeDepth,
+) {
+    for item in nav {
+        match item {
+            NavItem::Page(m) => {
+                for value in m.values() {
+                    if let Some(include_path) = parse_include_target(value) {
+                        register_subsite_mount(include_path, mkdocs_dir, layout, depth);
+                    }
+                }
+            }
+            NavItem::Section(m) => {
+                for children in m.values() {
+                    collect_subsite_mounts(children, mkdocs_dir, layout, depth);
+                }
+            }
+            NavItem::PlainPath(_) | NavItem::Unrecognised(_) => {}
+        }
+    }
+}
+
+fn register_subsite_mount(
+    include_path: &str,
+    mkdocs_dir: &Path,
+    layout: &mut SiteLayout,
+    depth: &mut IncludeDepth,
+) {
+    let include_file = mkdocs_dir.join(include_path);
+    let Some(parent) = include_file.parent() else {
+        return;
+    };
+    let subsite_dir: PathBuf = parent.components().collect();
+
+    let Ok(contents) = fs::read_to_string(&include_file) else {
+        return;
+    };
+    let Ok(config) = serde_yaml::from_str::<MkDocsConfig>(&contents) else {
+        return;
+    };
+    // Cycles are reported by the page walk; here they are just not followed.
+    if !depth.enter(&include_file).unwrap_or(false) {
+        return;
+    }
+
+    layout.set_docs_dir(&subsite_dir, config.docs_dir());
+    // The directory name is a valid mount key when site_name slugifies to it.
+    if let Some(dir_name) = subsite_dir.file_name().and_then(|s| s.to_str()) {
+        layout
+            .mounts
+            .entry(dir_name.to_string())
+            .or_insert_with(|| subsite_dir.clone());
+    }
+    // The slugified site_name is the actual URL mount point.
+    if let Some(site_name) = &config.site_name {
+        layout
+            .mounts
+            .entry(slugify(site_name))
+            .or_insert_with(|| subsite_dir.clone());
+    }
+    // Nested includes, if any.
+    collect_subsite_mounts(&config.nav, &subsite_dir, layout, depth);
+    depth.leave();
+}
+
+// Complex. Map the "virtual" hierarchy as defined by the nav onto the file system so that
+// we can check links for validity.
+pub fn build_link_maps(nav: &[NavItem], mkdocs_dir: &Path) -> Result<LinkMaps, Box<dyn Error>> {
+    build_link_maps_within(
+        nav,
+        mkdocs_dir,
+        DEFAULT_DOCS_DIR,
+        IncludeDepth::new(usize::MAX),
+    )
+}
+
+fn build_link_maps_within(
+    nav: &[NavItem],
+    mkdocs_dir: &Path,
+    docs_dir: &str,
+    mut depth: IncludeDepth,
+) -> Result<LinkMaps, Box<dyn Error>> {
+    let mut maps = LinkMaps::default();
+    build_link_maps_inner(
+        nav,
+        mkdocs_dir,
+        docs_dir,
+        mkdocs_dir,
+        Path::new(""),
+        &mut maps.url_to_src,
+        &mut maps.src_to_url,
+        &mut depth,
+    )?;
+    Ok(maps)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn build_link_maps_inner(
     nav: &[NavItem],
     mkdocs_dir: &Path,
-    site_root: &Path,
-    url_prefix: &Path,
-    url_to_src: &mut HashMap<String, PathBuf>,
-    src_to_url: &mut HashMap<PathBuf, String>,
-) -> Result<(), Box<dyn Error>> {
-    for item in nav {
-        match item {
-            NavItem::Page(map) => {
-                for path in map.values() {
-                    if let Some(include_path) = parse_include_target(path) {
-                        let include_file = mkdocs_dir.join(include_path);
-                        let include_contents = fs::read_to_string(&include_file)?;
-                        let include_config: MkDocsConfig = serde_yaml::from_str(&include_contents)?;
-                        let include_parent = include_file
-                            .parent()
-                            .ok_or_else(|| io::Error::other("include has no parent"))?
-                            .components()
-                            .collect::<PathBuf>();
-                        let mut child_prefix = url_prefix.to_path_buf();
-                        if let Ok(rel) = include_parent.strip_prefix(site_root) {
-                            child_prefix = child_prefix.join(rel);
-                        }
-                        build_link_maps_inner(
-                            &include_config.nav,
-                            &include_parent,
-                            site_root,
-                            &child_prefix,
-                            url_to_src,
-                            src_to_url,
-                        )?;
+    docs_dir: &str,
+    site_root: &Path,
+    url_prefix: &Path,
+    url_to_src: &mut HashMap<String, PathBuf>,
+    src_to_url: &mut HashMap<PathBuf, String>,
+    depth: &mut IncludeDepth,
+) -> Result<(), Box<dyn Error>> {
+    for item in nav {
+        match item {
+            NavItem::Page(map) => {
+                for path in map.values() {
+                    if let Some(include_path) = parse_include_target(path) {
+                        let include_file = mkdocs_dir.join(include_path);
+                        if !depth.enter(&include_file)? {
+                            continue;
+                        }
+                        let include_contents = fs::read_to_string(&include_file)?;
+                        let include_config: MkDocsConfig = serde_yaml::from_str(&include_contents)?;
+                        let include_parent = include_file
+                            .parent()
+                            .ok_or_else(|| io::Error::other("include has no parent"))?
+                            .components()
+                            .collect::<PathBuf>();
+                        let mut child_prefix = url_prefix.to_path_buf();
+                        if let Ok(rel) = include_parent.strip_prefix(site_root) {
+                            child_prefix = child_prefix.join(rel);
+                        }
+                        build_link_maps_inner(
+                            &include_config.nav,
+                            &include_parent,
+                            include_config.docs_dir(),
+                            site_root,
+                            &child_prefix,
+                            url_to_src,
+                            src_to_url,
+                            depth,
+                        )?;
+                        depth.leave();
+                    } else if !is_nav_description(path) {
+                        insert_mapping(
+                            path, mkdocs_dir, docs_dir, url_prefix, url_to_src, src_to_url,
+                        );
+                    }
+                }
+            }
+            NavItem::Section(map) => {
+                for (section, children) in map {
+                    let slug = slugify(section);
+                    let new_prefix = url_prefix.join(slug);
+                    build_link_maps_inner(
+                        children,
+                        mkdocs_dir,
+                        docs_dir,
+                        site_root,
+                        &new_prefix,
+                        url_to_src,
+                        src_to_url,
+                        depth,
+                    )?;
+                }
+            }
+            NavItem::PlainPath(path) if !is_nav_description(path) => {
+                insert_mapping(
+                    path, mkdocs_dir, docs_dir, url_prefix, url_to_src, src_to_url,
+                );
+            }
+            NavItem::PlainPath(_) | NavItem::Unrecognised(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn insert_mapping(
+    nav_path: &str,
+    mkdocs_dir: &Path,
+    docs_dir: &str,
+    url_prefix: &Path,
+    url_to_src: &mut HashMap<String, PathBuf>,
+    src_to_url: &mut HashMap<PathBuf, String>,
+) {
+    let fs_path = mkdocs_dir
+        .join(docs_dir)
+        .join(nav_path)
+        .components()
+        .collect::<PathBuf>();
+    let rendered = if url_prefix.as_os_str().is_empty() {
+        normalise_url(&Path::new(nav_path).with_extension(""))
+    } else {
+        let stem = Path::new(nav_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| nav_path.to_string());
+        normalise_url(&url_prefix.join(stem))
+    };
+    url_to_src
+        .entry(rendered.clone())
+        .or_insert(fs_path.clone());
+    src_to_url.entry(fs_path).or_insert(rendered);
+}
+
+fn slugify(s: &str) -> String {
+    let re = Regex::new(r"[^A-Za-z0-9]+").unwrap();
+    let slug = re.replace_all(s, "-");
+    slug.trim_matches('-').to_ascii_lowercase()
+}
+
+fn normalise_url(path: &Path) -> String {
+    let mut parts = Vec::new();
+    for comp in path.components() {
+        match comp {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                parts.pop();
+            }
+            _ => parts.push(comp.as_os_str().to_string_lossy().into_owned()),
+        }
+    }
+    parts.join("/")
+}
+
+pub fn resolve_link(from_src: &Path, link: &str, maps: &LinkMaps) -> Option<PathBuf> {
+    let rendered = rendered_url_for_link(from_src, link, maps)?;
+    lookup_url(&rendered, &maps.url_to_src)
+}
+
+fn rendered_url_for_link(from_src: &Path, link: &str, maps: &LinkMaps) -> Option<String> {
+    let from_url = maps.src_to_url.get(from_src)?;
+    let target = link.trim_start_matches('/');
+    let base_dir = Path::new(from_url)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    let mut joined = if link.starts_with('/') {
+        PathBuf::from(target)
+    } else {
+        base_dir.join(target)
+    };
+    joined = joined.with_extension("");
+    Some(normalise_url(&joined))
+}
+
+fn lookup_url(rendered: &str, url_to_src: &HashMap<String, PathBuf>) -> Option<PathBuf> {
+    if let Some(p) = url_to_src.get(rendered) {
+        return Some(p.clone());
+    }
+    let mut alt = rendered.trim_end_matches('/').to_string();
+    if let Some(p) = url_to_src.get(&alt) {
+        return Some(p.clone());
+    }
+    alt.push_str("/index");
+    url_to_src.get(&alt).cloned()
+}
+
+/// Check if a candidate path exists, falling back to {path_without_ext}/index.md
+/// This handles MkDocs convention where `foo.md` can also be `foo/index.md`
+fn check_with_index_fallback(candidate: &Path, files_set: &HashSet<PathBuf>) -> Option<PathBuf> {
+    // Normalize the candidate path to resolve any ".." components
+    let normalized = normalize_path(candidate);
+
+    // Try the path as-is
+    if normalized.is_file() || files_set.contains(&normalized) {
+        return Some(normalized);
+    }
+
+    // Try {stem}/index.md fallback
+    let stem = normalized.with_extension("");
+    let index_candidate = stem.join("index.md");
+    if index_candidate.is_file() || files_set.contains(&index_candidate) {
+        return Some(index_candidate);
+    }
+
+    None
+}
+
+/// A page read for [`analyse_links`]. With a link cache, its modification time is taken
+/// before it is read, so a page changed while being read is parsed again on the next
+/// run, and its links come from the cache when that time matches.
+struct ScannedFile {
+    path: PathBuf,
+    content: String,
+    modified: Option<SystemTime>,
+    cached_links: Option<Vec<String>>,
+}
+
+impl ScannedFile {
+    fn read(path: &Path, link_cache: Option<&LinkCache>) -> Option<ScannedFile> {
+        let modified = link_cache.and_then(|_| fs::metadata(path).and_then(|m| m.modified()).ok());
+        let cached_links = link_cache
+            .zip(modified)
+            .and_then(|(cache, modified)| cache.links(path, modified))
+            .map(<[String]>::to_vec);
+        let content = fs::read_to_string(path).ok()?;
+        Some(ScannedFile {
+            path: path.to_path_buf(),
+            content,
+            modified,
+            cached_links,
+        })
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn analyse_links(
+    files: &[ScannedFile],
+    files_set: &HashSet<PathBuf>,
+    mkdocs_dir: &Path,
+    include_dirs: &[PathBuf],
+    link_maps: &LinkMaps,
+    help_refs: &HashMap<PathBuf, Vec<HelpRef>>,
+    layout: &SiteLayout,
+    site_path: Option<&Path>,
+    config: &GhostConfig,
+    link_cache: &mut Option<LinkCache>,
+    file_done: &(dyn Fn() + Sync),
+    tracer: &mut Tracer,
+) -> io::Result<BatchAnalysis> {
+    // Files are analysed in parallel, each into its own results and tracer, then merged
+    // in file order so the output doesn't depend on scheduling.
+    let shared: &Tracer = tracer;
+    let per_file: Vec<(LinkAnalysis, Tracer, Option<CachedLinks>)> = files
+        .par_iter()
+        .map(|file| {
+            let mut file_tracer = shared.fork();
+            // Cached links already have the page's ghost directives applied.
+            let (links, fresh) = match &file.cached_links {
+                Some(links) => (links.clone(), None),
+                None => {
+                    let links = extract_hrefs(&file.content);
+                    let links = if file.content.contains("ghost:") {
+                        without_ignored_links(links, extract_ghost_directives(&file.content))
                     } else {
-                        insert_mapping(path, mkdocs_dir, url_prefix, url_to_src, src_to_url);
+                        links
+                    };
+                    let fresh = file.modified.map(|m| (m, links.clone()));
+                    (links, fresh)
+                }
+            };
+            let analysis = analyse_file_links(
+                &file.path,
+                links,
+                files_set,
+                mkdocs_dir,
+                include_dirs,
+                link_maps,
+                help_refs,
+                layout,
+                site_path,
+                config,
+                &mut file_tracer,
+            );
+            file_done();
+            (analysis, file_tracer, fresh)
+        })
+        .collect();
+
+    let mut referenced = HashSet::new();
+    let mut broken_links = Vec::new();
+    let mut anchored_links = Vec::new();
+    let mut page_links = Vec::new();
+    for (file, (analysis, file_tracer, fresh)) in files.iter().zip(per_file) {
+        let src = &file.path;
+        let (file_referenced, file_broken_links, file_anchored_links) = analysis;
+        let mut targets: Vec<PathBuf> = file_referenced.iter().cloned().collect();
+        targets.sort();
+        page_links.push((src.clone(), targets));
+        referenced.extend(file_referenced);
+        broken_links.extend(file_broken_links);
+        anchored_links.extend(file_anchored_links);
+        tracer.merge(file_tracer);
+        if let (Some(cache), Some(entry)) = (link_cache.as_mut(), fresh) {
+            cache.files.insert(src.clone(), entry);
+        }
+    }
+
+    Ok((referenced, broken_links, anchored_links, page_links))
+}
+
+/// `links` less those that `directives` switch off, each ignored link once.
+fn without_ignored_links(mut links: Vec<String>, directives: GhostDirectives) -> Vec<String> {
+    if directives.ignore_all_links {
+        return Vec::new();
+    }
+    for ignored in directives.ignored_links {
+        if let Some(i) = links.iter().position(|l| *l == ignored) {
+            links.remove(i);
+        }
+    }
+    links
+}
+
+/// A link with a `#section` that resolved to a page, for checking the section exists.
+struct AnchoredLink {
+    from: PathBuf,
+    link: String,
+    target: PathBuf,
+    anchor: String,
+}
+
+/// What [`analyse_file_links`] finds in one file: the files its links resolve to, the
+/// links that resolve to nothing, and the resolved links that carry an anchor.
+type LinkAnalysis = (HashSet<PathBuf>, Vec<BrokenLink>, Vec<AnchoredLink>);
+
+/// A page and the files its links resolve to, as kept in [`AuditResult::page_links`].
+type PageLinks = (PathBuf, Vec<PathBuf>);
+
+/// What [`analyse_links`] finds in a batch of files: as [`LinkAnalysis`] for all of
+/// them together, and each file's resolved links.
+type BatchAnalysis = (
+    HashSet<PathBuf>,
+    Vec<BrokenLink>,
+    Vec<AnchoredLink>,
+    Vec<PageLinks>,
+);
+
+/// The links of one file for [`analyse_links`], as [`extract_hrefs`] found them: the
+/// files they resolve to, and those that resolve to nothing.
+#[allow(clippy::too_many_arguments)]
+fn analyse_file_links(
+    src: &PathBuf,
+    raw_links: Vec<String>,
+    files_set: &HashSet<PathBuf>,
+    mkdocs_dir: &Path,
+    include_dirs: &[PathBuf],
+    link_maps: &LinkMaps,
+    help_refs: &HashMap<PathBuf, Vec<HelpRef>>,
+    layout: &SiteLayout,
+    site_path: Option<&Path>,
+    config: &GhostConfig,
+    tracer: &mut Tracer,
+) -> LinkAnalysis {
+    let mut referenced = HashSet::new();
+    let mut broken_links = Vec::new();
+    let mut anchored_links = Vec::new();
+
+    // Render a resolved target relative to the monorepo root (forward slashes) for
+    // readable, portable trace output.
+    let rel = |p: &Path| -> String { fwd_rel(p, mkdocs_dir) };
+
+    let src_help_refs = help_refs.get(src).cloned().unwrap_or_default();
+    // Full URLs on the site's own base URL are internal links in disguise: check them
+    // as site-absolute paths instead of skipping them as external.
+    let links: Vec<NormalisedLink> = raw_links
+        .into_iter()
+        .map(|l| match &config.site_base_url {
+            Some(base) => strip_site_base_url(&l, base).unwrap_or(l),
+            None => l,
+        })
+        .filter_map(|l| normalise_one(&l))
+        .collect();
+    let tracing = tracer.traces(src);
+    if tracing {
+        tracer.record(src, format!("internal links: {}", links.len()));
+    }
+    for NormalisedLink {
+        target: link,
+        had_md,
+        anchor,
+    } in links
+    {
+        if tracing {
+            tracer.record(src, format!("LINK  {link}  (had_md={had_md})"));
+        }
+
+        let resolved: Option<PathBuf> = 'resolve: {
+            // 0a) Filenames are mandated lower-case: a mixed-case link is broken on
+            // the case-sensitive production server even if it resolves locally.
+            if link_has_mixed_case(&link) {
+                if tracing {
+                    tracer.record(src, "  mixed-case → BROKEN (lower-case is mandated)");
+                }
+                break 'resolve None;
+            }
+
+            // 0b) MkDocs rewrites a `.md` link only when it resolves source-relative
+            // within the merged monorepo docs tree (each subsite's docs/ mounted at
+            // <merged>/<subsite>/). If it doesn't, the literal `.md` href is left in
+            // place and 404s on the directory-URL site. Bare/directory-style links are
+            // served as real URLs (resolved by the browser), so this gate only applies
+            // to links the author wrote with an explicit `.md`.
+            if had_md {
+                let outcome = resolve_md_link_merged(src, &link, files_set, layout);
+                match outcome.resolved() {
+                    Some(target) => {
+                        if tracing {
+                            tracer.record(src, format!("  .md merged-tree: {}", outcome.reason()));
+                        }
+                        break 'resolve Some(target.clone());
+                    }
+                    None => {
+                        if tracing {
+                            tracer.record(
+                                src,
+                                format!("  .md merged-tree: {} → BROKEN", outcome.reason()),
+                            );
+                        }
+                        break 'resolve None;
                     }
                 }
             }
-            NavItem::Section(map) => {
-                for (section, children) in map {
-                    let slug = slugify(section);
-                    let new_prefix = url_prefix.join(slug);
-                    build_link_maps_inner(
-                        children,
-                        mkdocs_dir,
-                        site_root,
-                        &new_prefix,
-                        url_to_src,
-                        src_to_url,
-                    )?;
+
+            // 1) Try nav-based resolution
+            if let Some(target) = resolve_link(src, &link, link_maps) {
+                if tracing {
+                    tracer.record(src, format!("  resolved via nav → {}", rel(&target)));
                 }
+                break 'resolve Some(target);
             }
-            NavItem::PlainPath(path) => {
-                insert_mapping(path, mkdocs_dir, url_prefix, url_to_src, src_to_url);
+
+            // 2) Try URL-space resolution (handles cross-subsite links and sibling files)
+            // Try both page-as-directory model (how browsers resolve) and parent-dir model
+            let url_candidates =
+                resolve_link_via_url_space(src, &link, mkdocs_dir, site_path, layout);
+            for candidate in url_candidates {
+                if let Some(resolved) = check_with_index_fallback(&candidate, files_set) {
+                    if tracing {
+                        tracer.record(
+                            src,
+                            format!("  resolved via url-space → {}", rel(&resolved)),
+                        );
+                    }
+                    break 'resolve Some(resolved);
+                }
+            }
+
+            // 3) Try include directories using rendered URL path
+            if let Some(rendered) = rendered_url_for_link(src, &link, link_maps) {
+                // same-doc-root guess (if src lies in a docs dir)
+                if let Some(docs_dir) = layout.docs_ancestor(src) {
+                    let candidate = docs_dir
+                        .join(&rendered)
+                        .with_extension("md")
+                        .components()
+                        .collect::<PathBuf>();
+                    if let Some(resolved) = check_with_index_fallback(&candidate, files_set) {
+                        if tracing {
+                            tracer.record(
+                                src,
+                                format!("  resolved via doc root → {}", rel(&resolved)),
+                            );
+                        }
+                        break 'resolve Some(resolved);
+                    }
+                }
+
+                for dir in include_dirs {
+                    let candidate = layout
+                        .docs_dir(dir)
+                        .join(&rendered)
+                        .with_extension("md")
+                        .components()
+                        .collect::<PathBuf>();
+                    if let Some(resolved) = check_with_index_fallback(&candidate, files_set) {
+                        if tracing {
+                            tracer.record(
+                                src,
+                                format!("  resolved via include dir → {}", rel(&resolved)),
+                            );
+                        }
+                        break 'resolve Some(resolved);
+                    }
+                }
+            }
+
+            // 4) Final fallback: resolve on filesystem relative to source doc root
+            if let Some(fs_candidate) = fs_path_from_link(src, &link, layout)
+                && let Some(resolved) = check_with_index_fallback(&fs_candidate, files_set)
+            {
+                if tracing {
+                    tracer.record(
+                        src,
+                        format!("  resolved via fs fallback → {}", rel(&resolved)),
+                    );
+                }
+                break 'resolve Some(resolved);
+            }
+
+            // 5) Last resort: plain filesystem relative to source parent
+            if let Some(parent) = src.parent() {
+                let candidate = parent.join(&link).components().collect::<PathBuf>();
+                if let Some(resolved) = check_with_index_fallback(&candidate, files_set) {
+                    if tracing {
+                        tracer.record(
+                            src,
+                            format!("  resolved via parent fallback → {}", rel(&resolved)),
+                        );
+                    }
+                    break 'resolve Some(resolved);
+                }
+            }
+
+            // Unresolved
+            if tracing {
+                tracer.record(src, "  no strategy resolved it → BROKEN");
+            }
+            None
+        };
+
+        match resolved {
+            Some(target) => {
+                if let Some(anchor) = anchor {
+                    anchored_links.push(AnchoredLink {
+                        from: src.clone(),
+                        link: link.clone(),
+                        target: target.clone(),
+                        anchor,
+                    });
+                }
+                referenced.insert(target);
             }
+            None => broken_links.push(BrokenLink {
+                from: src.clone(),
+                link: link.clone(),
+                help_refs: src_help_refs.clone(),
+                suggestions: Vec::new(),
+            }),
         }
     }
 
-    Ok(())
+    (referenced, broken_links, anchored_links)
+}
+
+/// The subsite directory of a markdown file: the parent of its nearest `docs/` ancestor
+/// (the path itself counts). For `guide/docs/topics/page.md` this is `guide`; `None`
+/// when the path has no `docs` component.
+pub fn docs_root_for(path: &Path) -> Option<PathBuf> {
+    docs_ancestor(path).and_then(|docs| docs.parent().map(|p| p.to_path_buf()))
+}
+
+/// The name of the subsite a markdown file belongs to: the directory holding its nearest
+/// `docs/` ancestor. For `guide/docs/topics/page.md` this is `guide`.
+pub fn subsite_name_for(path: &Path) -> Option<&str> {
+    docs_ancestor(path)?.parent()?.file_name()?.to_str()
+}
+
+fn docs_ancestor(path: &Path) -> Option<&Path> {
+    SiteLayout::default().docs_ancestor(path)
+}
+
+fn fs_path_from_link(src: &Path, link: &str, layout: &SiteLayout) -> Option<PathBuf> {
+    let link_path = Path::new(link);
+    if link_path.is_absolute() {
+        let docs_dir = layout.docs_ancestor(src)?;
+        return Some(
+            docs_dir
+                .join(link_path.strip_prefix("/").unwrap_or(link_path))
+                .components()
+                .collect::<PathBuf>(),
+        );
+    }
+
+    let base = src.parent()?;
+    Some(base.join(link_path).components().collect::<PathBuf>())
 }
 
-fn insert_mapping(
-    nav_path: &str,
-    mkdocs_dir: &Path,
-    url_prefix: &Path,
-    url_to_src: &mut HashMap<String, PathBuf>,
-    src_to_url: &mut HashMap<PathBuf, String>,
-) {
-    let fs_path = mkdocs_dir
-        .join("docs")
-        .join(nav_path)
-        .components()
-        .collect::<PathBuf>();
-    let rendered = if url_prefix.as_os_str().is_empty() {
-        normalise_url(&Path::new(nav_path).with_extension(""))
+/// Resolve a relative link in URL space, then map back to filesystem.
+/// Returns multiple candidates because MkDocs supports two resolution models:
+/// 1. Page-as-directory (browser behavior): ../sibling from dir/page/ -> dir/sibling/
+/// 2. Filesystem-relative: ../sibling from dir/page.md -> sibling.md (at parent level)
+///
+/// Example (cross-subsite):
+///   src: /base/release-notes/docs/new-enhanced.md
+///   link: ../../programming-reference-guide/intro/foo.md
+///
+///   URL space: release-notes/new-enhanced + ../../programming-reference-guide/intro/foo
+///            = programming-reference-guide/intro/foo
+///
+///   Filesystem: /base/programming-reference-guide/docs/intro/foo.md
+///
+/// Example (sibling via page-as-directory):
+///   src: /base/guide/docs/config/aplan-output.md
+///   link: ../aplan-editor.md
+///
+///   URL space (page-as-dir): guide/config/aplan-output + ../aplan-editor
+///                          = guide/config/aplan-editor
+///
+///   Filesystem: /base/guide/docs/config/aplan-editor.md
+///
+/// A site-absolute link (`/guide/page`) is taken from the root of the URL space, less
+/// `site_path` (the path of the root mkdocs.yml's `site_url`) when there is one; a link
+/// outside `site_path` leaves the site and resolves to nothing.
+fn resolve_link_via_url_space(
+    src: &Path,
+    link: &str,
+    monorepo_root: &Path,
+    site_path: Option<&Path>,
+    layout: &SiteLayout,
+) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+
+    // Find the docs directory containing src
+    let Some(docs_dir) = layout.docs_ancestor(src) else {
+        return candidates;
+    };
+    let Some(subsite_dir) = docs_dir.parent() else {
+        return candidates;
+    };
+
+    // Compute source's URL: subsite_name + path_within_docs (minus .md)
+    let Some(subsite_name) = subsite_dir.file_name().and_then(|s| s.to_str()) else {
+        return candidates;
+    };
+    let Ok(path_within_docs) = src.strip_prefix(docs_dir) else {
+        return candidates;
+    };
+    let src_url_path = Path::new(subsite_name)
+        .join(path_within_docs)
+        .with_extension("");
+
+    let link_path = Path::new(link).with_extension("");
+
+    // Try both resolution models for relative links
+    let base_paths: Vec<&Path> = if link.starts_with('/') {
+        vec![] // Absolute links don't need multiple bases
     } else {
-        let stem = Path::new(nav_path)
-            .file_stem()
-            .map(|s| s.to_string_lossy().into_owned())
-            .unwrap_or_else(|| nav_path.to_string());
-        normalise_url(&url_prefix.join(stem))
+        // 1. Page-as-directory model (browser behavior)
+        // 2. Parent-directory model (filesystem-like)
+        vec![
+            src_url_path.as_path(),
+            src_url_path.parent().unwrap_or(Path::new("")),
+        ]
     };
-    url_to_src
-        .entry(rendered.clone())
-        .or_insert(fs_path.clone());
-    src_to_url.entry(fs_path).or_insert(rendered);
-}
 
-fn slugify(s: &str) -> String {
-    let re = Regex::new(r"[^A-Za-z0-9]+").unwrap();
-    let slug = re.replace_all(s, "-");
-    slug.trim_matches('-').to_ascii_lowercase()
-}
+    // Handle absolute links
+    if link.starts_with('/') {
+        let Ok(resolved) = link_path.strip_prefix(site_path.unwrap_or(Path::new("/"))) else {
+            return candidates;
+        };
+        if let Some(fs_path) = url_to_filesystem(
+            &normalise_url(resolved),
+            subsite_name,
+            docs_dir,
+            monorepo_root,
+            layout,
+        ) {
+            candidates.push(fs_path);
+        }
+        return candidates;
+    }
 
-fn normalise_url(path: &Path) -> String {
-    let mut parts = Vec::new();
-    for comp in path.components() {
-        match comp {
-            std::path::Component::CurDir => {}
-            std::path::Component::ParentDir => {
-                parts.pop();
-            }
-            _ => parts.push(comp.as_os_str().to_string_lossy().into_owned()),
+    // Try each base path
+    for base in base_paths {
+        let resolved_url = base.join(&link_path);
+        let normalized_url = normalise_url(&resolved_url);
+        if normalized_url.is_empty() {
+            continue;
+        }
+
+        if let Some(fs_path) = url_to_filesystem(
+            &normalized_url,
+            subsite_name,
+            docs_dir,
+            monorepo_root,
+            layout,
+        ) && !candidates.contains(&fs_path)
+        {
+            candidates.push(fs_path);
         }
     }
-    parts.join("/")
-}
 
-pub fn resolve_link(from_src: &Path, link: &str, maps: &LinkMaps) -> Option<PathBuf> {
-    let rendered = rendered_url_for_link(from_src, link, maps)?;
-    lookup_url(&rendered, &maps.url_to_src)
+    candidates
 }
 
-fn rendered_url_for_link(from_src: &Path, link: &str, maps: &LinkMaps) -> Option<String> {
-    let from_url = maps.src_to_url.get(from_src)?;
-    let target = link.trim_start_matches('/');
-    let base_dir = Path::new(from_url)
-        .parent()
-        .unwrap_or_else(|| Path::new(""));
-    let mut joined = if link.starts_with('/') {
-        PathBuf::from(target)
-    } else {
-        base_dir.join(target)
+/// Map a normalized URL path back to a filesystem path
+fn url_to_filesystem(
+    normalized_url: &str,
+    subsite_name: &str,
+    docs_dir: &Path,
+    monorepo_root: &Path,
+    layout: &SiteLayout,
+) -> Option<PathBuf> {
+    let mut url_parts = normalized_url.split('/');
+    let first_component = url_parts.next()?;
+    let rest: Vec<&str> = url_parts.collect();
+
+    // Resolve the first URL segment to a subsite directory. The monorepo plugin mounts
+    // subsites at their slugified site_name, which may differ from the directory name
+    // (e.g. net-interface-guide → dir dotnet-interface-guide), so consult the
+    // site_name→dir map before falling back to an identically-named directory.
+    let target_subsite_dir = layout.mounts.get(first_component).cloned().or_else(|| {
+        let dir = monorepo_root.join(first_component);
+        layout.docs_dir(&dir).is_dir().then_some(dir)
+    });
+
+    let fs_path = match target_subsite_dir {
+        Some(dir) if first_component != subsite_name => {
+            // Cross-subsite link: the rest lies in the target subsite's docs dir
+            rest.iter()
+                .fold(layout.docs_dir(&dir), |acc, part| acc.join(part))
+        }
+        _ => {
+            // Same subsite: the resolved URL path is relative to source's docs dir
+            docs_dir.join(normalized_url.trim_start_matches(&format!("{}/", subsite_name)))
+        }
     };
-    joined = joined.with_extension("");
-    Some(normalise_url(&joined))
+
+    Some(fs_path.with_extension("md").components().collect())
 }
 
-fn lookup_url(rendered: &str, url_to_src: &HashMap<String, PathBuf>) -> Option<PathBuf> {
-    if let Some(p) = url_to_src.get(rendered) {
-        return Some(p.clone());
-    }
-    let mut alt = rendered.trim_end_matches('/').to_string();
-    if let Some(p) = url_to_src.get(&alt) {
-        return Some(p.clone());
+pub fn collect_pages(
+    items: &[NavItem],
+    pages: &mut HashSet<PathBuf>,
+    prefix: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut entries = Vec::new();
+    collect_pages_within(
+        items,
+        &mut entries,
+        prefix,
+        DEFAULT_DOCS_DIR,
+        &mut IncludeDepth::new(usize::MAX),
+    )?;
+    pages.extend(entries);
+    Ok(())
+}
+
+/// Find pages the nav lists more than once, such as a page in one section that an
+/// `!include`d subsite also lists. The nav still builds, but the page shows up twice.
+pub fn find_duplicate_nav_entries(
+    items: &[NavItem],
+    prefix: &Path,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut entries = Vec::new();
+    collect_pages_within(
+        items,
+        &mut entries,
+        prefix,
+        DEFAULT_DOCS_DIR,
+        &mut IncludeDepth::new(usize::MAX),
+    )?;
+    Ok(duplicate_paths(&entries))
+}
+
+/// The paths occurring more than once in `paths` once `..` is resolved, each once,
+/// sorted.
+fn duplicate_paths(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut counts: HashMap<PathBuf, usize> = HashMap::new();
+    for path in paths {
+        *counts.entry(normalize_path(path)).or_insert(0) += 1;
     }
-    alt.push_str("/index");
-    url_to_src.get(&alt).cloned()
+    let mut duplicates: Vec<PathBuf> = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(path, _)| path)
+        .collect();
+    duplicates.sort();
+    duplicates
 }
 
-/// Check if a candidate path exists, falling back to {path_without_ext}/index.md
-/// This handles MkDocs convention where `foo.md` can also be `foo/index.md`
-fn check_with_index_fallback(candidate: &Path, files_set: &HashSet<PathBuf>) -> Option<PathBuf> {
-    // Normalize the candidate path to resolve any ".." components
-    let normalized = normalize_path(candidate);
+/// Tracks how deeply `!include`s nest while a nav is walked. An include that would go
+/// past `max` is recorded in `exceeded` instead of being followed; one that is already
+/// being walked is an include cycle. An include of a file that doesn't exist is an
+/// error, or with `skip_missing` is recorded in `missing` and passed over.
+struct IncludeDepth {
+    max: usize,
+    current: usize,
+    exceeded: Vec<(PathBuf, usize)>,
+    skip_missing: bool,
+    missing: Vec<PathBuf>,
+    /// The config files being walked, outermost first.
+    chain: Vec<PathBuf>,
+}
 
-    // Try the path as-is
-    if normalized.is_file() || files_set.contains(&normalized) {
-        return Some(normalized);
+impl IncludeDepth {
+    fn new(max: usize) -> Self {
+        IncludeDepth {
+            max,
+            current: 0,
+            exceeded: Vec::new(),
+            skip_missing: false,
+            missing: Vec::new(),
+            chain: Vec::new(),
+        }
     }
 
-    // Try {stem}/index.md fallback
-    let stem = normalized.with_extension("");
-    let index_candidate = stem.join("index.md");
-    if index_candidate.is_file() || files_set.contains(&index_candidate) {
-        return Some(index_candidate);
+    fn skipping_missing(mut self) -> Self {
+        self.skip_missing = true;
+        self
+    }
+
+    /// Start the chain at the root mkdocs.yml, so an include of it is seen as a cycle.
+    fn starting_at(mut self, root_config: &Path) -> Self {
+        self.chain.push(normalize_path(root_config));
+        self
+    }
+
+    /// Step into `include_file`, or record it and return false if it is too deep or, when
+    /// skipping them, missing. An include of a file already being walked is an error.
+    fn enter(&mut self, include_file: &Path) -> Result<bool, GhostError> {
+        let include_file = normalize_path(include_file);
+        if let Some(start) = self.chain.iter().position(|f| *f == include_file) {
+            let mut cycle = self.chain[start..].to_vec();
+            cycle.push(include_file);
+            return Err(GhostError::IncludeCycle(cycle));
+        }
+        if !include_file.is_file() {
+            if !self.skip_missing {
+                return Err(GhostError::MissingInclude(include_file));
+            }
+            if !self.missing.contains(&include_file) {
+                self.missing.push(include_file);
+            }
+            return Ok(false);
+        }
+        if self.current >= self.max {
+            self.exceeded.push((include_file, self.current + 1));
+            return Ok(false);
+        }
+        self.current += 1;
+        self.chain.push(include_file);
+        Ok(true)
+    }
+
+    fn leave(&mut self) {
+        self.current -= 1;
+        self.chain.pop();
+    }
+}
+
+/// Walk the nav, following `!include`s, and list every page in it. A page listed twice
+/// appears twice.
+fn collect_pages_within(
+    items: &[NavItem],
+    pages: &mut Vec<PathBuf>,
+    prefix: &Path,
+    docs_dir: &str,
+    depth: &mut IncludeDepth,
+) -> Result<(), Box<dyn Error>> {
+    for item in items {
+        match item {
+            NavItem::Page(map) => {
+                for path in map.values() {
+                    if let Some(include_path) = parse_include_target(path) {
+                        collect_include(include_path, pages, prefix, depth)?;
+                    } else if !is_nav_description(path) {
+                        let full_path = prefix.join(docs_dir).join(path);
+                        let normalised = full_path.components().collect::<PathBuf>(); // removes "./"
+                        pages.push(normalised);
+                    }
+                }
+            }
+            NavItem::Section(map) => {
+                for children in map.values() {
+                    collect_pages_within(children, pages, prefix, docs_dir, depth)?;
+                }
+            }
+            NavItem::PlainPath(path) if !is_nav_description(path) => {
+                let full_path = prefix.join(docs_dir).join(path);
+                let normalised = full_path.components().collect::<PathBuf>(); // removes "./"
+                pages.push(normalised);
+            }
+            NavItem::PlainPath(_) | NavItem::Unrecognised(_) => {}
+        }
     }
 
-    None
+    Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
-fn analyse_links(
-    files: &[(PathBuf, String)],
-    files_set: &HashSet<PathBuf>,
-    mkdocs_dir: &Path,
-    include_dirs: &[PathBuf],
-    link_maps: &LinkMaps,
-    help_refs: &HashMap<PathBuf, Vec<HelpRef>>,
-    subsite_map: &HashMap<String, PathBuf>,
-    tracer: &mut Tracer,
-) -> io::Result<(HashSet<PathBuf>, Vec<BrokenLink>)> {
-    let mut referenced = HashSet::new();
-    let mut broken_links = Vec::new();
+/// An entry of the nav as [`nav_tree`] returns it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NavNode {
+    /// The nav label; `None` for a bare `- page.md` entry.
+    pub title: Option<String>,
+    /// The page's markdown file; `None` for a section or an `!include`.
+    pub path: Option<PathBuf>,
+    /// Where the page ends up in the built site, as ghost resolves links against it
+    /// (`guide/topics/page`); empty for a section or an `!include`.
+    pub url: String,
+    /// A section's entries, or the nav of an `!include`d subsite.
+    pub children: Vec<NavNode>,
+}
 
-    // Render a resolved target relative to the monorepo root (forward slashes) for
-    // readable, portable trace output.
-    let rel = |p: &Path| -> String { fwd_rel(p, mkdocs_dir) };
+/// The nav of the mkdocs.yml in `prefix` as a tree, with each `!include` replaced by a
+/// node holding the included nav. Description entries (inline markdown) are left out.
+/// Fails on an include that can't be read or parsed, or that includes itself.
+pub fn nav_tree(nav: &[NavItem], prefix: &Path) -> Result<Vec<NavNode>, Box<dyn Error>> {
+    let maps = build_link_maps(nav, prefix)?;
+    nav_nodes(
+        nav,
+        prefix,
+        DEFAULT_DOCS_DIR,
+        &maps,
+        &mut IncludeDepth::new(usize::MAX),
+    )
+}
 
-    for (src, content) in files {
-        let src_help_refs = help_refs.get(src).cloned().unwrap_or_default();
-        let links: Vec<Link> = extract_links(content)
-            .into_iter()
-            .filter_map(|l| normalise_one(&l))
-            .collect();
-        let tracing = tracer.traces(src);
-        if tracing {
-            tracer.record(src, format!("internal links: {}", links.len()));
+fn nav_nodes(
+    items: &[NavItem],
+    prefix: &Path,
+    docs_dir: &str,
+    maps: &LinkMaps,
+    depth: &mut IncludeDepth,
+) -> Result<Vec<NavNode>, Box<dyn Error>> {
+    let page_node = |title: Option<&String>, value: &str| {
+        let path: PathBuf = prefix.join(docs_dir).join(value).components().collect();
+        NavNode {
+            title: title.cloned(),
+            url: maps.src_to_url.get(&path).cloned().unwrap_or_default(),
+            path: Some(path),
+            children: Vec::new(),
         }
-        for Link {
-            target: link,
-            had_md,
-        } in links
-        {
-            if tracing {
-                tracer.record(src, format!("LINK  {link}  (had_md={had_md})"));
-            }
-
-            // 0a) Filenames are mandated lower-case: a mixed-case link is broken on
-            // the case-sensitive production server even if it resolves locally.
-            if link_has_mixed_case(&link) {
-                if tracing {
-                    tracer.record(src, "  mixed-case → BROKEN (lower-case is mandated)");
-                }
-                broken_links.push(BrokenLink {
-                    from: src.clone(),
-                    link: link.clone(),
-                    help_refs: src_help_refs.clone(),
-                });
-                continue;
-            }
-
-            // 0b) MkDocs rewrites a `.md` link only when it resolves source-relative
-            // within the merged monorepo docs tree (each subsite's docs/ mounted at
-            // <merged>/<subsite>/). If it doesn't, the literal `.md` href is left in
-            // place and 404s on the directory-URL site. Bare/directory-style links are
-            // served as real URLs (resolved by the browser), so this gate only applies
-            // to links the author wrote with an explicit `.md`.
-            if had_md {
-                let outcome = resolve_md_link_merged(src, &link, files_set, subsite_map);
-                match outcome.resolved() {
-                    Some(target) => {
-                        if tracing {
-                            tracer.record(src, format!("  .md merged-tree: {}", outcome.reason()));
-                        }
-                        referenced.insert(target.clone());
-                    }
-                    None => {
-                        if tracing {
-                            tracer.record(
-                                src,
-                                format!("  .md merged-tree: {} → BROKEN", outcome.reason()),
-                            );
+    };
+    let mut nodes = Vec::new();
+    for item in items {
+        match item {
+            NavItem::Page(map) => {
+                for (title, value) in map {
+                    if let Some(include_path) = parse_include_target(value) {
+                        let include_file = prefix.join(include_path);
+                        if !depth.enter(&include_file)? {
+                            continue;
                         }
-                        broken_links.push(BrokenLink {
-                            from: src.clone(),
-                            link: link.clone(),
-                            help_refs: src_help_refs.clone(),
+                        let include_config: MkDocsConfig =
+                            serde_yaml::from_str(&fs::read_to_string(&include_file)?)?;
+                        let include_parent = include_file
+                            .parent()
+                            .ok_or_else(|| io::Error::other("include has no parent"))?
+                            .components()
+                            .collect::<PathBuf>();
+                        let children = nav_nodes(
+                            &include_config.nav,
+                            &include_parent,
+                            include_config.docs_dir(),
+                            maps,
+                            depth,
+                        )?;
+                        depth.leave();
+                        nodes.push(NavNode {
+                            title: Some(title.clone()),
+                            path: None,
+                            url: String::new(),
+                            children,
                         });
+                    } else if !is_nav_description(value) {
+                        nodes.push(page_node(Some(title), value));
                     }
                 }
-                continue;
-            }
-
-            // 1) Try nav-based resolution
-            if let Some(target) = resolve_link(src, &link, link_maps) {
-                if tracing {
-                    tracer.record(src, format!("  resolved via nav → {}", rel(&target)));
-                }
-                referenced.insert(target);
-                continue;
             }
-
-            // 2) Try URL-space resolution (handles cross-subsite links and sibling files)
-            // Try both page-as-directory model (how browsers resolve) and parent-dir model
-            let url_candidates = resolve_link_via_url_space(src, &link, mkdocs_dir, subsite_map);
-            let mut url_resolved = false;
-            for candidate in url_candidates {
-                if let Some(resolved) = check_with_index_fallback(&candidate, files_set) {
-                    if tracing {
-                        tracer.record(
-                            src,
-                            format!("  resolved via url-space → {}", rel(&resolved)),
-                        );
-                    }
-                    referenced.insert(resolved);
-                    url_resolved = true;
-                    break;
+            NavItem::Section(map) => {
+                for (title, children) in map {
+                    nodes.push(NavNode {
+                        title: Some(title.clone()),
+                        path: None,
+                        url: String::new(),
+                        children: nav_nodes(children, prefix, docs_dir, maps, depth)?,
+                    });
                 }
             }
-            if url_resolved {
-                continue;
+            NavItem::PlainPath(path) if !is_nav_description(path) => {
+                nodes.push(page_node(None, path));
             }
+            NavItem::PlainPath(_) | NavItem::Unrecognised(_) => {}
+        }
+    }
+    Ok(nodes)
+}
 
-            // 3) Try include directories using rendered URL path
-            if let Some(rendered) = rendered_url_for_link(src, &link, link_maps) {
-                // same-doc-root guess (if path contains /docs/)
-                if let Some(doc_root) = docs_root_for(src) {
-                    let candidate = doc_root
-                        .join("docs")
-                        .join(&rendered)
-                        .with_extension("md")
-                        .components()
-                        .collect::<PathBuf>();
-                    if let Some(resolved) = check_with_index_fallback(&candidate, files_set) {
-                        if tracing {
-                            tracer.record(
-                                src,
-                                format!("  resolved via doc root → {}", rel(&resolved)),
-                            );
-                        }
-                        referenced.insert(resolved);
-                        continue;
-                    }
-                }
+/// The nav's titled pages (`- Title: page.md`), those of `!include`d subsites too, as
+/// (title, path). Includes that can't be read or parsed, or that nest deeper than
+/// `max_depth`, are skipped.
+fn titled_nav_pages(
+    items: &[NavItem],
+    prefix: &Path,
+    docs_dir: &str,
+    max_depth: usize,
+) -> Vec<(String, PathBuf)> {
+    let mut pages = Vec::new();
+    collect_titled_nav_pages(
+        items,
+        prefix,
+        docs_dir,
+        &mut Vec::new(),
+        max_depth,
+        &mut pages,
+    );
+    pages
+}
 
-                let mut hit = false;
-                for dir in include_dirs {
-                    let candidate = dir
-                        .join("docs")
-                        .join(&rendered)
-                        .with_extension("md")
-                        .components()
-                        .collect::<PathBuf>();
-                    if let Some(resolved) = check_with_index_fallback(&candidate, files_set) {
-                        if tracing {
-                            tracer.record(
-                                src,
-                                format!("  resolved via include dir → {}", rel(&resolved)),
-                            );
+fn collect_titled_nav_pages(
+    items: &[NavItem],
+    prefix: &Path,
+    docs_dir: &str,
+    chain: &mut Vec<PathBuf>,
+    max_depth: usize,
+    pages: &mut Vec<(String, PathBuf)>,
+) {
+    for item in items {
+        match item {
+            NavItem::Page(map) => {
+                for (title, value) in map {
+                    if let Some(include_path) = parse_include_target(value) {
+                        let include_file = normalize_path(&prefix.join(include_path));
+                        // A file already on the include chain would be walked forever.
+                        if chain.contains(&include_file) || chain.len() >= max_depth {
+                            continue;
                         }
-                        referenced.insert(resolved);
-                        hit = true;
-                        break;
+                        let Some(config) = fs::read_to_string(&include_file)
+                            .ok()
+                            .and_then(|c| serde_yaml::from_str::<MkDocsConfig>(&c).ok())
+                        else {
+                            continue;
+                        };
+                        let include_dir = include_file.parent().unwrap_or(prefix).to_path_buf();
+                        chain.push(include_file);
+                        collect_titled_nav_pages(
+                            &config.nav,
+                            &include_dir,
+                            config.docs_dir(),
+                            chain,
+                            max_depth,
+                            pages,
+                        );
+                        chain.pop();
+                    } else if !is_nav_description(value) {
+                        let path = prefix.join(docs_dir).join(value).components().collect();
+                        pages.push((title.clone(), path));
                     }
                 }
-                if hit {
-                    continue;
-                }
             }
-
-            // 4) Final fallback: resolve on filesystem relative to source doc root
-            if let Some(fs_candidate) = fs_path_from_link(src, &link)
-                && let Some(resolved) = check_with_index_fallback(&fs_candidate, files_set)
-            {
-                if tracing {
-                    tracer.record(
-                        src,
-                        format!("  resolved via fs fallback → {}", rel(&resolved)),
-                    );
+            NavItem::Section(map) => {
+                for children in map.values() {
+                    collect_titled_nav_pages(children, prefix, docs_dir, chain, max_depth, pages);
                 }
-                referenced.insert(resolved);
-                continue;
             }
+            NavItem::PlainPath(_) | NavItem::Unrecognised(_) => {}
+        }
+    }
+}
 
-            // 5) Last resort: plain filesystem relative to source parent
-            if let Some(parent) = src.parent() {
-                let candidate = parent.join(&link).components().collect::<PathBuf>();
-                if let Some(resolved) = check_with_index_fallback(&candidate, files_set) {
-                    if tracing {
-                        tracer.record(
-                            src,
-                            format!("  resolved via parent fallback → {}", rel(&resolved)),
-                        );
+fn include_roots(items: &[NavItem], prefix: &Path) -> Vec<PathBuf> {
+    let mut roots = HashSet::new();
+    for item in items {
+        match item {
+            NavItem::Page(map) => {
+                for path in map.values() {
+                    if let Some(include_path) = parse_include_target(path) {
+                        let include_dir = prefix.join(include_path);
+                        if let Some(parent) = include_dir.parent() {
+                            roots.insert(parent.components().collect::<PathBuf>());
+                        }
                     }
-                    referenced.insert(resolved);
-                    continue;
                 }
             }
-
-            // Unresolved
-            if tracing {
-                tracer.record(src, "  no strategy resolved it → BROKEN");
+            NavItem::Section(map) => {
+                for children in map.values() {
+                    roots.extend(include_roots(children, prefix));
+                }
             }
-            broken_links.push(BrokenLink {
-                from: src.clone(),
-                link: link.clone(),
-                help_refs: src_help_refs.clone(),
-            });
+            NavItem::PlainPath(_) | NavItem::Unrecognised(_) => {}
         }
     }
+    roots.into_iter().collect()
+}
 
-    Ok((referenced, broken_links))
+/// The nav with every `!include` entry replaced by a section (titled by the include's
+/// nav label) holding the included subsite's nav, so structural checks see the whole
+/// site as one tree. Includes that can't be read or parsed, or that nest deeper than
+/// `max_depth`, are left as they are.
+fn expand_nav_includes(items: &[NavItem], mkdocs_dir: &Path, max_depth: usize) -> Vec<NavItem> {
+    expand_nav_includes_inner(items, mkdocs_dir, &mut Vec::new(), max_depth)
 }
 
-fn docs_root_for(path: &Path) -> Option<PathBuf> {
-    for ancestor in path.ancestors() {
-        if let Some(file_name) = ancestor.file_name()
-            && file_name == "docs"
-        {
-            return ancestor.parent().map(|p| p.to_path_buf());
-        }
-    }
-    None
+fn expand_nav_includes_inner(
+    items: &[NavItem],
+    mkdocs_dir: &Path,
+    chain: &mut Vec<PathBuf>,
+    max_depth: usize,
+) -> Vec<NavItem> {
+    items
+        .iter()
+        .map(|item| match item {
+            NavItem::Page(map) => {
+                let included: Option<(String, Vec<NavItem>)> =
+                    map.iter().find_map(|(title, value)| {
+                        let include_file =
+                            normalize_path(&mkdocs_dir.join(parse_include_target(value)?));
+                        // A file already on the include chain would expand forever.
+                        if chain.contains(&include_file) || chain.len() >= max_depth {
+                            return None;
+                        }
+                        let contents = fs::read_to_string(&include_file).ok()?;
+                        let config: MkDocsConfig = serde_yaml::from_str(&contents).ok()?;
+                        let include_dir = include_file.parent()?.to_path_buf();
+                        chain.push(include_file);
+                        let nav =
+                            expand_nav_includes_inner(&config.nav, &include_dir, chain, max_depth);
+                        chain.pop();
+                        Some((title.clone(), nav))
+                    });
+                match included {
+                    Some((title, nav)) => NavItem::Section(HashMap::from([(title, nav)])),
+                    None => item.clone(),
+                }
+            }
+            NavItem::Section(map) => NavItem::Section(
+                map.iter()
+                    .map(|(title, children)| {
+                        (
+                            title.clone(),
+                            expand_nav_includes_inner(children, mkdocs_dir, chain, max_depth),
+                        )
+                    })
+                    .collect(),
+            ),
+            NavItem::PlainPath(_) | NavItem::Unrecognised(_) => item.clone(),
+        })
+        .collect()
 }
 
-fn fs_path_from_link(src: &Path, link: &str) -> Option<PathBuf> {
-    let link_path = Path::new(link);
-    if link_path.is_absolute() {
-        let doc_root = docs_root_for(src)?;
-        return Some(
-            doc_root
-                .join("docs")
-                .join(link_path.strip_prefix("/").unwrap_or(link_path))
-                .components()
-                .collect::<PathBuf>(),
-        );
+/// Short words that stay lower-case inside a title-cased heading.
+const TITLE_CASE_STOPWORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "nor", "of", "on",
+    "or", "per", "the", "to", "vs", "via", "with",
+];
+
+/// Find nav section titles that don't follow `style`, as (path of enclosing section
+/// titles, offending title). Words that don't start with a letter (`⎕NULL`, `2D`) and
+/// words with capitals after the first letter (`API`, `macOS`) are left alone, since
+/// they are usually symbols, acronyms or product names rather than style choices.
+pub fn find_title_case_violations(
+    items: &[NavItem],
+    style: TitleCaseStyle,
+) -> Vec<(Vec<String>, String)> {
+    let mut out = Vec::new();
+    if style != TitleCaseStyle::Mixed {
+        collect_title_case_violations(items, style, &mut Vec::new(), &mut out);
     }
+    out
+}
 
-    let base = src.parent()?;
-    Some(base.join(link_path).components().collect::<PathBuf>())
+fn collect_title_case_violations(
+    items: &[NavItem],
+    style: TitleCaseStyle,
+    path: &mut Vec<String>,
+    out: &mut Vec<(Vec<String>, String)>,
+) {
+    for item in items {
+        if let NavItem::Section(map) = item {
+            let mut titles: Vec<&String> = map.keys().collect();
+            titles.sort();
+            for title in titles {
+                if !title_follows_style(title, style) {
+                    out.push((path.clone(), title.clone()));
+                }
+                path.push(title.clone());
+                collect_title_case_violations(&map[title], style, path, out);
+                path.pop();
+            }
+        }
+    }
 }
 
-/// Resolve a relative link in URL space, then map back to filesystem.
-/// Returns multiple candidates because MkDocs supports two resolution models:
-/// 1. Page-as-directory (browser behavior): ../sibling from dir/page/ -> dir/sibling/
-/// 2. Filesystem-relative: ../sibling from dir/page.md -> sibling.md (at parent level)
-///
-/// Example (cross-subsite):
-///   src: /base/release-notes/docs/new-enhanced.md
-///   link: ../../programming-reference-guide/intro/foo.md
-///
-///   URL space: release-notes/new-enhanced + ../../programming-reference-guide/intro/foo
-///            = programming-reference-guide/intro/foo
-///
-///   Filesystem: /base/programming-reference-guide/docs/intro/foo.md
-///
-/// Example (sibling via page-as-directory):
-///   src: /base/guide/docs/config/aplan-output.md
-///   link: ../aplan-editor.md
-///
-///   URL space (page-as-dir): guide/config/aplan-output + ../aplan-editor
-///                          = guide/config/aplan-editor
-///
-///   Filesystem: /base/guide/docs/config/aplan-editor.md
-fn resolve_link_via_url_space(
-    src: &Path,
-    link: &str,
-    monorepo_root: &Path,
-    subsite_map: &HashMap<String, PathBuf>,
-) -> Vec<PathBuf> {
-    let mut candidates = Vec::new();
+/// Find nested sections with exactly one child, usually a sign of premature structure:
+/// the child could sit directly in the parent section. Returned as (path of section
+/// titles down to and including the section, title of its sole child). Top-level
+/// sections are not checked, since they often hold a single `!include`d subsite.
+pub fn find_single_child_sections(items: &[NavItem]) -> Vec<(Vec<String>, String)> {
+    let mut out = Vec::new();
+    collect_single_child_sections(items, &mut Vec::new(), &mut out);
+    out
+}
 
-    // Find the docs/ directory containing src
-    let Some(docs_dir) = src
-        .ancestors()
-        .find(|a| a.file_name() == Some("docs".as_ref()))
-    else {
-        return candidates;
-    };
-    let Some(subsite_dir) = docs_dir.parent() else {
-        return candidates;
-    };
+fn collect_single_child_sections(
+    items: &[NavItem],
+    path: &mut Vec<String>,
+    out: &mut Vec<(Vec<String>, String)>,
+) {
+    for item in items {
+        if let NavItem::Section(map) = item {
+            let mut titles: Vec<&String> = map.keys().collect();
+            titles.sort();
+            for title in titles {
+                let children = &map[title];
+                path.push(title.clone());
+                if path.len() >= 2
+                    && let [only] = children.as_slice()
+                {
+                    out.push((path.clone(), nav_item_title(only)));
+                }
+                collect_single_child_sections(children, path, out);
+                path.pop();
+            }
+        }
+    }
+}
 
-    // Compute source's URL: subsite_name + path_within_docs (minus .md)
-    let Some(subsite_name) = subsite_dir.file_name().and_then(|s| s.to_str()) else {
-        return candidates;
-    };
-    let Ok(path_within_docs) = src.strip_prefix(docs_dir) else {
-        return candidates;
-    };
-    let src_url_path = Path::new(subsite_name)
-        .join(path_within_docs)
-        .with_extension("");
+/// Find sibling sections whose titles give the same URL slug (`Examples` and `examples`,
+/// or `C++ API` and `C API`), where MkDocs silently drops all but one. Returned as (path
+/// of enclosing section titles, title of each later duplicate).
+pub fn find_duplicate_section_titles(items: &[NavItem]) -> Vec<(Vec<String>, String)> {
+    let mut out = Vec::new();
+    collect_duplicate_section_titles(items, &mut Vec::new(), &mut out);
+    out
+}
 
-    let link_path = Path::new(link).with_extension("");
+fn collect_duplicate_section_titles(
+    items: &[NavItem],
+    path: &mut Vec<String>,
+    out: &mut Vec<(Vec<String>, String)>,
+) {
+    let mut seen = HashSet::new();
+    for item in items {
+        if let NavItem::Section(map) = item {
+            let mut titles: Vec<&String> = map.keys().collect();
+            titles.sort();
+            for title in titles {
+                if !seen.insert(slugify(title)) {
+                    out.push((path.clone(), title.clone()));
+                }
+                path.push(title.clone());
+                collect_duplicate_section_titles(&map[title], path, out);
+                path.pop();
+            }
+        }
+    }
+}
 
-    // Try both resolution models for relative links
-    let base_paths: Vec<&Path> = if link.starts_with('/') {
-        vec![] // Absolute links don't need multiple bases
-    } else {
-        // 1. Page-as-directory model (browser behavior)
-        // 2. Parent-directory model (filesystem-like)
-        vec![
-            src_url_path.as_path(),
-            src_url_path.parent().unwrap_or(Path::new("")),
-        ]
-    };
+/// Find sections whose children are all `!include`s, as the path of section titles down
+/// to and including the section. Valid, but nothing lives at such a section's own URL,
+/// so it may want a landing page.
+pub fn find_include_only_sections(items: &[NavItem]) -> Vec<Vec<String>> {
+    let mut out = Vec::new();
+    collect_include_only_sections(items, &mut Vec::new(), &mut out);
+    out
+}
 
-    // Handle absolute links
-    if link.starts_with('/') {
-        let resolved = link_path
-            .strip_prefix("/")
-            .unwrap_or(&link_path)
-            .to_path_buf();
-        if let Some(fs_path) = url_to_filesystem(
-            &normalise_url(&resolved),
-            subsite_name,
-            docs_dir,
-            monorepo_root,
-            subsite_map,
-        ) {
-            candidates.push(fs_path);
+fn collect_include_only_sections(
+    items: &[NavItem],
+    path: &mut Vec<String>,
+    out: &mut Vec<Vec<String>>,
+) {
+    let is_include = |item: &NavItem| match item {
+        NavItem::Page(map) => {
+            !map.is_empty() && map.values().all(|v| parse_include_target(v).is_some())
+        }
+        NavItem::PlainPath(value) => parse_include_target(value).is_some(),
+        NavItem::Section(_) | NavItem::Unrecognised(_) => false,
+    };
+    for item in items {
+        if let NavItem::Section(map) = item {
+            let mut titles: Vec<&String> = map.keys().collect();
+            titles.sort();
+            for title in titles {
+                let children = &map[title];
+                path.push(title.clone());
+                if !children.is_empty() && children.iter().all(is_include) {
+                    out.push(path.clone());
+                }
+                collect_include_only_sections(children, path, out);
+                path.pop();
+            }
         }
-        return candidates;
     }
+}
 
-    // Try each base path
-    for base in base_paths {
-        let resolved_url = base.join(&link_path);
-        let normalized_url = normalise_url(&resolved_url);
-        if normalized_url.is_empty() {
-            continue;
+/// Find nav sections with no children (`- Section: []`), which some MkDocs versions
+/// reject. Nested sections are checked too; titles are returned as written.
+pub fn find_empty_sections(items: &[NavItem]) -> Vec<String> {
+    let mut out = Vec::new();
+    for item in items {
+        if let NavItem::Section(map) = item {
+            let mut titles: Vec<&String> = map.keys().collect();
+            titles.sort();
+            for title in titles {
+                let children = &map[title];
+                if children.is_empty() {
+                    out.push(title.clone());
+                }
+                out.extend(find_empty_sections(children));
+            }
         }
+    }
+    out
+}
 
-        if let Some(fs_path) = url_to_filesystem(
-            &normalized_url,
-            subsite_name,
-            docs_dir,
-            monorepo_root,
-            subsite_map,
-        ) && !candidates.contains(&fs_path)
-        {
-            candidates.push(fs_path);
+/// A nav entry MkDocs won't accept as written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NavError {
+    /// A page entry with more than one title (`- {A: a.md, B: b.md}`); MkDocs takes
+    /// exactly one. Keys are sorted.
+    MultipleKeysInPage { keys: Vec<String> },
+    /// An entry that is not a page, a section or a path (see
+    /// [`NavItem::Unrecognised`]), as YAML.
+    Unrecognised { entry: String },
+}
+
+/// Check that the nav is shaped the way MkDocs expects it. Nested sections are checked
+/// too.
+pub fn validate_nav_structure(nav: &[NavItem]) -> Vec<NavError> {
+    let mut out = Vec::new();
+    for item in nav {
+        match item {
+            NavItem::Page(map) if map.len() > 1 => {
+                let mut keys: Vec<String> = map.keys().cloned().collect();
+                keys.sort();
+                out.push(NavError::MultipleKeysInPage { keys });
+            }
+            NavItem::Unrecognised(entry) => {
+                out.push(NavError::Unrecognised {
+                    entry: entry.clone(),
+                });
+            }
+            NavItem::Section(map) => {
+                let mut titles: Vec<&String> = map.keys().collect();
+                titles.sort();
+                for title in titles {
+                    out.extend(validate_nav_structure(&map[title]));
+                }
+            }
+            _ => {}
         }
     }
+    out
+}
 
-    candidates
+/// Find nav entries whose path resolves outside `docs_dir` (`../shared/page.md`), which
+/// MkDocs refuses to build, as written in the nav. The entries of an `!include`d
+/// mkdocs.yml are checked against that config's own [`MkDocsConfig::docs_dir`].
+pub fn find_nav_entries_outside_docs(items: &[NavItem], docs_dir: &Path) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut seen = HashSet::new();
+    collect_nav_entries_outside_docs(items, &normalize_path(docs_dir), &mut seen, &mut out);
+    out
 }
 
-/// Map a normalized URL path back to a filesystem path
-fn url_to_filesystem(
-    normalized_url: &str,
-    subsite_name: &str,
+fn collect_nav_entries_outside_docs(
+    items: &[NavItem],
     docs_dir: &Path,
-    monorepo_root: &Path,
-    subsite_map: &HashMap<String, PathBuf>,
-) -> Option<PathBuf> {
-    let mut url_parts = normalized_url.split('/');
-    let first_component = url_parts.next()?;
-    let rest: Vec<&str> = url_parts.collect();
-
-    // Resolve the first URL segment to a subsite directory. The monorepo plugin mounts
-    // subsites at their slugified site_name, which may differ from the directory name
-    // (e.g. net-interface-guide → dir dotnet-interface-guide), so consult the
-    // site_name→dir map before falling back to an identically-named directory.
-    let target_subsite_dir = subsite_map.get(first_component).cloned().or_else(|| {
-        let dir = monorepo_root.join(first_component);
-        dir.join("docs").is_dir().then_some(dir)
-    });
+    seen: &mut HashSet<PathBuf>,
+    out: &mut Vec<String>,
+) {
+    for item in items {
+        match item {
+            NavItem::Page(map) => {
+                let mut titles: Vec<&String> = map.keys().collect();
+                titles.sort();
+                for title in titles {
+                    check_nav_entry_inside_docs(&map[title], docs_dir, seen, out);
+                }
+            }
+            NavItem::Section(map) => {
+                let mut titles: Vec<&String> = map.keys().collect();
+                titles.sort();
+                for title in titles {
+                    collect_nav_entries_outside_docs(&map[title], docs_dir, seen, out);
+                }
+            }
+            NavItem::PlainPath(path) => check_nav_entry_inside_docs(path, docs_dir, seen, out),
+            NavItem::Unrecognised(_) => {}
+        }
+    }
+}
 
-    let fs_path = match target_subsite_dir {
-        Some(dir) if first_component != subsite_name => {
-            // Cross-subsite link: insert docs/ after the target subsite
-            rest.iter()
-                .fold(dir.join("docs"), |acc, part| acc.join(part))
+fn check_nav_entry_inside_docs(
+    value: &str,
+    docs_dir: &Path,
+    seen: &mut HashSet<PathBuf>,
+    out: &mut Vec<String>,
+) {
+    if let Some(target) = parse_include_target(value) {
+        let Some(mkdocs_dir) = docs_dir.parent() else {
+            return;
+        };
+        let include_file = normalize_path(&mkdocs_dir.join(target));
+        if !seen.insert(include_file.clone()) {
+            return;
         }
-        _ => {
-            // Same subsite: the resolved URL path is relative to source's docs/
-            docs_dir.join(normalized_url.trim_start_matches(&format!("{}/", subsite_name)))
+        if let Some(include_dir) = include_file.parent()
+            && let Ok(contents) = fs::read_to_string(&include_file)
+            && let Ok(config) = serde_yaml::from_str::<MkDocsConfig>(&contents)
+        {
+            collect_nav_entries_outside_docs(
+                &config.nav,
+                &include_dir.join(config.docs_dir()),
+                seen,
+                out,
+            );
         }
-    };
-
-    Some(fs_path.with_extension("md").components().collect())
+    } else if !is_external_link(value)
+        && !is_nav_description(value)
+        && !normalize_path(&docs_dir.join(value)).starts_with(docs_dir)
+    {
+        out.push(value.to_string());
+    }
 }
 
-pub fn collect_pages(
-    items: &[NavItem],
-    pages: &mut HashSet<PathBuf>,
-    prefix: &Path,
-) -> Result<(), Box<dyn Error>> {
+/// Find nav entries whose path doesn't have a `.md` extension (compared
+/// case-insensitively), e.g. `README.rst` or `page.html`. `!include`s, external URLs and
+/// inline descriptions are not paths and are skipped.
+pub fn find_non_md_nav_items(items: &[NavItem]) -> Vec<String> {
+    let is_non_md = |value: &str| {
+        parse_include_target(value).is_none()
+            && !is_external_link(value)
+            && !is_nav_description(value)
+            && !Path::new(value)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+    };
+    let mut out = Vec::new();
     for item in items {
         match item {
             NavItem::Page(map) => {
-                for path in map.values() {
-                    if let Some(include_path) = parse_include_target(path) {
-                        collect_include(include_path, pages, prefix)?;
-                    } else {
-                        let full_path = prefix.join("docs").join(path);
-                        let normalised = full_path.components().collect::<PathBuf>(); // removes "./"
-                        pages.insert(normalised);
-                    }
-                }
+                let mut titles: Vec<&String> = map.keys().collect();
+                titles.sort();
+                out.extend(
+                    titles
+                        .into_iter()
+                        .map(|title| &map[title])
+                        .filter(|value| is_non_md(value))
+                        .cloned(),
+                );
             }
             NavItem::Section(map) => {
-                for children in map.values() {
-                    collect_pages(children, pages, prefix)?;
+                let mut titles: Vec<&String> = map.keys().collect();
+                titles.sort();
+                for title in titles {
+                    out.extend(find_non_md_nav_items(&map[title]));
                 }
             }
             NavItem::PlainPath(path) => {
-                let full_path = prefix.join("docs").join(path);
-                let normalised = full_path.components().collect::<PathBuf>(); // removes "./"
-                pages.insert(normalised);
+                if is_non_md(path) {
+                    out.push(path.clone());
+                }
             }
+            NavItem::Unrecognised(_) => {}
         }
     }
+    out
+}
 
-    Ok(())
+/// The title a nav entry is shown under: its key, or the path itself for a bare path.
+fn nav_item_title(item: &NavItem) -> String {
+    match item {
+        NavItem::Page(map) => map
+            .iter()
+            .find(|(_, value)| !is_nav_description(value))
+            .or_else(|| map.iter().next())
+            .map(|(title, _)| title.clone())
+            .unwrap_or_default(),
+        NavItem::Section(map) => map.keys().next().cloned().unwrap_or_default(),
+        NavItem::PlainPath(path) => path.clone(),
+        NavItem::Unrecognised(_) => String::new(),
+    }
 }
 
-fn include_roots(items: &[NavItem], prefix: &Path) -> Vec<PathBuf> {
-    let mut roots = HashSet::new();
+fn title_follows_style(title: &str, style: TitleCaseStyle) -> bool {
+    title.split_whitespace().enumerate().all(|(i, word)| {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        let mut chars = word.chars();
+        let Some(first) = chars.next().filter(|c| c.is_alphabetic()) else {
+            return true;
+        };
+        if chars.any(|c| c.is_uppercase()) {
+            return true;
+        }
+        match style {
+            TitleCaseStyle::AllTitle => {
+                first.is_uppercase()
+                    || (i > 0 && TITLE_CASE_STOPWORDS.contains(&word.to_lowercase().as_str()))
+            }
+            TitleCaseStyle::AllSentence => (i == 0) == first.is_uppercase(),
+            TitleCaseStyle::Mixed => true,
+        }
+    })
+}
+
+/// Whether a nav value is inline markdown, such as a description or summary with links,
+/// rather than a page path or `!include`.
+fn is_nav_description(value: &str) -> bool {
+    value.contains('[') && value.contains("](")
+}
+
+/// Markdown descriptions embedded in the nav, e.g. a `description:` value alongside a
+/// page entry. Only values that look like markdown with links (containing `[` and `](`)
+/// are returned; `!include`d navs are not followed.
+pub fn extract_nav_descriptions(items: &[NavItem]) -> Vec<String> {
+    let mut out = Vec::new();
     for item in items {
         match item {
             NavItem::Page(map) => {
-                for path in map.values() {
-                    if let Some(include_path) = parse_include_target(path) {
-                        let include_dir = prefix.join(include_path);
-                        if let Some(parent) = include_dir.parent() {
-                            roots.insert(parent.components().collect::<PathBuf>());
-                        }
-                    }
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                out.extend(
+                    keys.into_iter()
+                        .map(|k| &map[k])
+                        .filter(|v| is_nav_description(v))
+                        .cloned(),
+                );
+            }
+            NavItem::Section(map) => {
+                let mut titles: Vec<&String> = map.keys().collect();
+                titles.sort();
+                for title in titles {
+                    out.extend(extract_nav_descriptions(&map[title]));
+                }
+            }
+            NavItem::PlainPath(path) => {
+                if is_nav_description(path) {
+                    out.push(path.clone());
+                }
+            }
+            NavItem::Unrecognised(_) => {}
+        }
+    }
+    out
+}
+
+/// Check the links in every nav description, following `!include`s. A relative link
+/// resolves against the docs directory of the mkdocs.yml holding the description; a
+/// site-absolute one against the rendered URL space.
+fn find_broken_nav_description_links(
+    mkdocs_yaml: &Path,
+    nav: &[NavItem],
+    files_set: &HashSet<PathBuf>,
+    maps: &LinkMaps,
+    layout: &SiteLayout,
+    max_include_depth: usize,
+) -> Vec<BrokenLink> {
+    let mut out = Vec::new();
+    let mut seen = HashSet::new();
+    let mut queue = vec![(normalize_path(mkdocs_yaml), nav.to_vec(), 0)];
+    while let Some((config_file, nav, depth)) = queue.pop() {
+        if !seen.insert(config_file.clone()) {
+            continue;
+        }
+        let Some(mkdocs_dir) = config_file.parent() else {
+            continue;
+        };
+        for description in extract_nav_descriptions(&nav) {
+            for link in extract_hrefs(&description) {
+                if !config_link_resolves(&link, mkdocs_dir, files_set, maps, layout) {
+                    out.push(BrokenLink {
+                        from: config_file.clone(),
+                        link,
+                        help_refs: Vec::new(),
+                        suggestions: Vec::new(),
+                    });
                 }
             }
+        }
+        if depth >= max_include_depth {
+            continue;
+        }
+        for target in nav_include_targets(&nav) {
+            let include_file = normalize_path(&mkdocs_dir.join(target));
+            if let Ok(contents) = fs::read_to_string(&include_file)
+                && let Ok(config) = serde_yaml::from_str::<MkDocsConfig>(&contents)
+            {
+                queue.push((include_file, config.nav, depth + 1));
+            }
+        }
+    }
+    out.sort_by(|a, b| (&a.from, &a.link).cmp(&(&b.from, &b.link)));
+    out
+}
+
+/// Find directories under `monorepo_root` that look like a subsite, having a `docs/`
+/// subdirectory, but have no `mkdocs.yml` (or `mkdocs.yaml`): usually a new subsite that
+/// hasn't been configured yet. Hidden directories and the contents of `docs/` folders
+/// are not searched, nor with `respect_gitignore` what gitignore rules exclude.
+pub fn find_subsites_without_mkdocs(monorepo_root: &Path, respect_gitignore: bool) -> Vec<PathBuf> {
+    let mut found: Vec<PathBuf> = walker(monorepo_root, respect_gitignore)
+        .filter_entry(|e| e.depth() == 0 || !is_hidden_or_docs(e))
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_some_and(|t| t.is_dir()))
+        .map(|e| e.into_path())
+        .filter(|dir| {
+            dir.join("docs").is_dir()
+                && !dir.join("mkdocs.yml").is_file()
+                && !dir.join("mkdocs.yaml").is_file()
+        })
+        .map(|dir| normalize_path(&dir))
+        .collect();
+    found.sort();
+    found
+}
+
+/// Find `mkdocs.yml` (or `mkdocs.yaml`) files under `monorepo_root` that `root_config`
+/// never reaches through its chain of `!include`s: subsites that are configured but not
+/// published, usually abandoned or not yet wired in. Hidden directories and the contents
+/// of `docs/` folders are not searched, nor with `respect_gitignore` what gitignore rules
+/// exclude.
+pub fn find_orphan_mkdocs_files(
+    monorepo_root: &Path,
+    root_config: &Path,
+    respect_gitignore: bool,
+) -> Vec<PathBuf> {
+    let mut reachable: HashSet<PathBuf> = HashSet::new();
+    let mut queue = vec![normalize_path(root_config)];
+    while let Some(config_file) = queue.pop() {
+        if !reachable.insert(config_file.clone()) {
+            continue;
+        }
+        let Some(mkdocs_dir) = config_file.parent() else {
+            continue;
+        };
+        if let Ok(contents) = fs::read_to_string(&config_file)
+            && let Ok(config) = serde_yaml::from_str::<MkDocsConfig>(&contents)
+        {
+            queue.extend(
+                nav_include_targets(&config.nav)
+                    .into_iter()
+                    .map(|target| normalize_path(&mkdocs_dir.join(target))),
+            );
+        }
+    }
+
+    let mut orphans: Vec<PathBuf> = walker(monorepo_root, respect_gitignore)
+        .filter_entry(|e| e.depth() == 0 || !is_hidden_or_docs(e))
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_type().is_some_and(|t| t.is_file())
+                && (e.file_name() == "mkdocs.yml" || e.file_name() == "mkdocs.yaml")
+        })
+        .map(|e| normalize_path(e.path()))
+        .filter(|p| !reachable.contains(p))
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+/// Whether the monorepo searches pass over `entry`: a hidden file or directory, or a
+/// `docs/` folder, whose contents are pages rather than subsites.
+fn is_hidden_or_docs(entry: &ignore::DirEntry) -> bool {
+    entry.file_name() == "docs" || entry.file_name().to_string_lossy().starts_with('.')
+}
+
+/// The links in a `site_description` (markdown) that don't resolve, checked as for nav
+/// descriptions in the mkdocs.yml in `mkdocs_dir`.
+fn find_broken_site_description_links(
+    mkdocs_dir: &Path,
+    description: &str,
+    files_set: &HashSet<PathBuf>,
+    maps: &LinkMaps,
+    layout: &SiteLayout,
+) -> Vec<String> {
+    extract_hrefs(description)
+        .into_iter()
+        .filter(|link| !config_link_resolves(link, mkdocs_dir, files_set, maps, layout))
+        .collect()
+}
+
+/// Whether a link written in the mkdocs.yml in `mkdocs_dir` resolves: site-absolute
+/// links against the site's URLs, relative ones against that config's docs directory.
+/// External and non-markdown links are not checked and count as resolving.
+fn config_link_resolves(
+    link: &str,
+    mkdocs_dir: &Path,
+    files_set: &HashSet<PathBuf>,
+    maps: &LinkMaps,
+    layout: &SiteLayout,
+) -> bool {
+    let Some(normalised) = normalise_one(link) else {
+        return true;
+    };
+    match normalised.target.strip_prefix('/') {
+        Some(abs) => {
+            let url = normalise_url(&Path::new(abs).with_extension(""));
+            lookup_url(&url, &maps.url_to_src).is_some()
+        }
+        None => {
+            let target = layout.docs_dir(mkdocs_dir).join(&normalised.target);
+            check_with_index_fallback(&target, files_set).is_some()
+        }
+    }
+}
+
+/// The `!include` targets anywhere in `items`, as written.
+fn nav_include_targets(items: &[NavItem]) -> Vec<&str> {
+    let mut out = Vec::new();
+    for item in items {
+        match item {
+            NavItem::Page(map) => out.extend(map.values().filter_map(|v| parse_include_target(v))),
             NavItem::Section(map) => {
                 for children in map.values() {
-                    roots.extend(include_roots(children, prefix));
+                    out.extend(nav_include_targets(children));
                 }
             }
-            NavItem::PlainPath(_) => {}
+            NavItem::PlainPath(_) | NavItem::Unrecognised(_) => {}
         }
     }
-    roots.into_iter().collect()
+    out
 }
 
 fn parse_include_target(value: &str) -> Option<&str> {
@@ -1584,10 +7209,14 @@ fn parse_include_target(value: &str) -> Option<&str> {
 
 fn collect_include(
     include_path: &str,
-    pages: &mut HashSet<PathBuf>,
+    pages: &mut Vec<PathBuf>,
     prefix: &Path,
+    depth: &mut IncludeDepth,
 ) -> Result<(), Box<dyn Error>> {
     let include_file = prefix.join(include_path);
+    if !depth.enter(&include_file)? {
+        return Ok(());
+    }
     let include_contents = fs::read_to_string(&include_file)?;
     let include_config: MkDocsConfig = serde_yaml::from_str(&include_contents)?;
     let include_parent = include_file.parent().ok_or_else(|| {
@@ -1596,7 +7225,14 @@ fn collect_include(
             "included mkdocs file must reside within a directory",
         )
     })?;
-    collect_pages(&include_config.nav, pages, include_parent)?;
+    collect_pages_within(
+        &include_config.nav,
+        pages,
+        include_parent,
+        include_config.docs_dir(),
+        depth,
+    )?;
+    depth.leave();
     Ok(())
 }
 
@@ -1643,26 +7279,56 @@ fn strip_c_comments(content: &str) -> String {
     result
 }
 
-pub fn extract_help_urls<P1, P2>(path: P1, doc_root: P2) -> Vec<PathBuf>
+/// Find the docs pages referenced from more than one of `header_files`, each with the
+/// headers that reference it (in the order given). Pages referenced several times from
+/// a single header are not counted. Fails on the first header that can't be read.
+pub fn find_cross_file_help_url_duplicates(
+    header_files: &[PathBuf],
+    doc_root: &Path,
+) -> io::Result<Vec<(PathBuf, Vec<PathBuf>)>> {
+    let mut headers_by_page: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    for header in header_files {
+        for entry in extract_help_urls(header, doc_root)? {
+            let headers = headers_by_page.entry(entry.path).or_default();
+            if !headers.contains(header) {
+                headers.push(header.clone());
+            }
+        }
+    }
+    let mut duplicates: Vec<(PathBuf, Vec<PathBuf>)> = headers_by_page
+        .into_iter()
+        .filter(|(_, headers)| headers.len() > 1)
+        .collect();
+    duplicates.sort();
+    Ok(duplicates)
+}
+
+pub fn extract_help_urls<P1, P2>(path: P1, doc_root: P2) -> io::Result<Vec<HelpUrlEntry>>
 where
     P1: AsRef<Path>,
     P2: AsRef<Path>,
 {
-    extract_help_url_refs(path, doc_root)
+    Ok(extract_help_url_refs(path, doc_root)?
         .into_iter()
-        .map(|(p, _)| p)
-        .collect()
+        .map(|(entry, _)| entry)
+        .collect())
 }
 
 /// Like [`extract_help_urls`], but also returns the source [`HelpRef`] (line number and
 /// verbatim `HELP_URL(...)` text) for each entry, so broken links on help-referenced
-/// pages can be traced back to — and show — their `help_urls.h` definition.
-pub fn extract_help_url_refs<P1, P2>(path: P1, doc_root: P2) -> Vec<(PathBuf, HelpRef)>
+/// pages can be traced back to — and show — their `help_urls.h` definition. A header
+/// that can't be read, or isn't UTF-8, fails with an error naming it.
+pub fn extract_help_url_refs<P1, P2>(
+    path: P1,
+    doc_root: P2,
+) -> io::Result<Vec<(HelpUrlEntry, HelpRef)>>
 where
     P1: AsRef<Path>,
     P2: AsRef<Path>,
 {
-    let raw_content = fs::read_to_string(path).expect("failed to read file");
+    let path = path.as_ref();
+    let raw_content = fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
     // strip_c_comments preserves newlines, so offsets still map to original line numbers.
     let content = strip_c_comments(&raw_content);
 
@@ -1683,7 +7349,7 @@ where
     // We need to match the quoted first argument, then capture everything after the comma
     let url_re = Regex::new(r#"HELP_URL\s*\("([^"]|\\")*"\s*,\s*([^)]+)\)"#).unwrap();
 
-    url_re
+    Ok(url_re
         .captures_iter(&content)
         .map(|cap| {
             let whole = cap.get(0).unwrap();
@@ -1695,14 +7361,68 @@ where
             let text = whole.as_str().to_string();
             let raw = cap.get(2).unwrap().as_str().trim();
             let expanded = expand_url(raw, &macros);
-            let with_docs = inject_docs(&expanded);
-            let relative_path = with_docs + ".md";
+            // Split off any `#section` before the path is turned into a file name.
+            let (url, fragment) = match expanded.split_once('#') {
+                Some((url, fragment)) => {
+                    (url, Some(fragment.to_string()).filter(|f| !f.is_empty()))
+                }
+                None => (expanded.as_str(), None),
+            };
+            // A path that already names `docs/` would otherwise come out as `docs/docs/`.
+            let docs_in_path = has_docs_component(url);
+            let relative_path = if docs_in_path {
+                url.to_string()
+            } else {
+                inject_docs(url)
+            } + ".md";
             (
-                doc_root.as_ref().join(relative_path),
+                HelpUrlEntry {
+                    path: doc_root.as_ref().join(relative_path),
+                    fragment,
+                    docs_in_path,
+                },
                 HelpRef { line, text },
             )
         })
-        .collect()
+        .collect())
+}
+
+/// A docs file written the way `HELP_URL` spells it: `guide/docs/topics/page.md` under
+/// `monorepo_root` becomes `guide/topics/page`. The inverse of [`inject_docs`].
+fn help_url_for(path: &Path, monorepo_root: &Path) -> Option<String> {
+    let rel = path.strip_prefix(monorepo_root).ok()?.with_extension("");
+    let mut comps = rel.iter().map(|c| c.to_string_lossy());
+    let subsite = comps.next()?;
+    if comps.next()? != "docs" {
+        return None;
+    }
+    Some(
+        std::iter::once(subsite)
+            .chain(comps)
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
+
+/// The nav page whose `HELP_URL` path is closest to that of the missing `path`, if it is
+/// close enough to be a likely typo: an edit distance of at most a quarter of the
+/// longer path.
+fn suggest_help_url_fix(path: &Path, monorepo_root: &Path, maps: &LinkMaps) -> Option<String> {
+    let missing = help_url_for(path, monorepo_root)?;
+    let mut candidates: Vec<String> = maps
+        .src_to_url
+        .keys()
+        .filter_map(|src| help_url_for(src, monorepo_root))
+        .collect();
+    candidates.sort();
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&missing, &candidate), candidate))
+        .filter(|(distance, candidate)| {
+            *distance > 0 && distance * 4 <= missing.chars().count().max(candidate.chars().count())
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
 }
 
 fn expand_url(raw: &str, macros: &HashMap<String, String>) -> String {
@@ -1721,6 +7441,15 @@ fn expand_url(raw: &str, macros: &HashMap<String, String>) -> String {
     result
 }
 
+/// Whether a `HELP_URL` path already has `docs` as its second component, the place
+/// [`inject_docs`] would put it.
+fn has_docs_component(path: &str) -> bool {
+    path.split(['/', '\\'])
+        .filter(|s| !s.is_empty())
+        .nth(1)
+        .is_some_and(|c| c == "docs")
+}
+
 fn inject_docs(path: &str) -> String {
     // Inject /docs/ after the first path component
     // e.g., "language-reference-guide/symbols/comma" -> "language-reference-guide/docs/symbols/comma"
@@ -1748,35 +7477,56 @@ where
         .collect()
 }
 
-fn find_markdown<P, I>(roots: I) -> io::Result<Vec<PathBuf>>
+/// The markdown files under `roots`. With `respect_gitignore`, files that gitignore
+/// rules exclude and hidden directories are passed over, as `git status` would.
+fn find_markdown<P, I>(roots: I, respect_gitignore: bool) -> io::Result<Vec<PathBuf>>
 where
     P: AsRef<Path>,
     I: IntoIterator<Item = P>,
 {
-    let mut out = Vec::new();
+    let roots: Vec<PathBuf> = roots
+        .into_iter()
+        .map(|r| r.as_ref().to_path_buf())
+        .collect();
+    // Each root is walked on its own thread; the results keep the roots' order.
+    let per_root: Vec<Vec<PathBuf>> = roots
+        .par_iter()
+        .map(|root| {
+            walker(root, respect_gitignore)
+                .build()
+                .map(|res| {
+                    let entry = res.map_err(ignore_error)?;
+                    let path = entry.path();
+                    if entry.file_type().is_some_and(|t| t.is_file())
+                        && path.extension().is_some_and(|ext| ext == "md")
+                    {
+                        let normalised = path.components().collect::<PathBuf>();
+                        Ok(Some(normalised))
+                    } else {
+                        Ok(None)
+                    }
+                })
+                .filter_map(Result::transpose) // flatten Option<Result<_>> to Result<Option<_>>
+                .collect::<io::Result<Vec<_>>>() // error-propagating collect
+        })
+        .collect::<io::Result<_>>()?;
 
-    for root in roots {
-        let mut paths = WalkDir::new(root.as_ref())
-            .into_iter()
-            .map(|res| {
-                let entry = res.map_err(walkdir_error)?;
-                let path = entry.path();
-                if entry.file_type().is_file() && path.extension().is_some_and(|ext| ext == "md") {
-                    let normalised = path.components().collect::<PathBuf>();
-                    Ok(Some(normalised))
-                } else {
-                    Ok(None)
-                }
-            })
-            .filter_map(Result::transpose) // flatten Option<Result<_>> to Result<Option<_>>
-            .collect::<io::Result<Vec<_>>>()?; // error-propagating collect
-        out.append(&mut paths);
-    }
+    Ok(per_root.concat())
+}
 
-    Ok(out)
+/// A walk of `root`, the one every search of the docs and the monorepo goes through.
+/// With `respect_gitignore`, what gitignore (or `.ignore`) rules exclude and hidden
+/// entries are passed over, as `git status` would, whether or not `root` is in a git
+/// repository.
+fn walker(root: &Path, respect_gitignore: bool) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .standard_filters(respect_gitignore)
+        .require_git(false);
+    builder
 }
 
-fn walkdir_error(err: walkdir::Error) -> io::Error {
+fn ignore_error(err: ignore::Error) -> io::Error {
     let msg = err.to_string();
     err.into_io_error().unwrap_or_else(|| io::Error::other(msg))
 }
@@ -1796,1385 +7546,4 @@ pub fn orphans(nav: &HashSet<PathBuf>, files: &[PathBuf]) -> Vec<PathBuf> {
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_collect_pages_with_page() {
-        let nav = vec![NavItem::Page({
-            let mut map = HashMap::new();
-            map.insert("Title".to_string(), "path/to/file.md".to_string());
-            map
-        })];
-
-        let mut pages = HashSet::new();
-        let prefix = Path::new("/tmp/docs");
-
-        collect_pages(&nav, &mut pages, prefix).unwrap();
-
-        assert_eq!(pages.len(), 1);
-        assert!(pages.contains(&PathBuf::from("/tmp/docs/docs/path/to/file.md")));
-    }
-
-    #[test]
-    fn test_collect_pages_with_plain_path() {
-        let nav = vec![NavItem::PlainPath("example.md".to_string())];
-
-        let mut pages = HashSet::new();
-        let prefix = Path::new("/tmp/docs");
-
-        collect_pages(&nav, &mut pages, prefix).unwrap();
-
-        assert_eq!(pages.len(), 1);
-        assert!(pages.contains(&PathBuf::from("/tmp/docs/docs/example.md")));
-    }
-
-    #[test]
-    fn test_collect_pages_with_section() {
-        let nav = vec![NavItem::Section({
-            let mut map = HashMap::new();
-            map.insert(
-                "Section".to_string(),
-                vec![NavItem::Page({
-                    let mut inner = HashMap::new();
-                    inner.insert("Page1".to_string(), "page1.md".to_string());
-                    inner
-                })],
-            );
-            map
-        })];
-
-        let mut pages = HashSet::new();
-        let prefix = Path::new("/tmp/docs");
-
-        collect_pages(&nav, &mut pages, prefix).unwrap();
-
-        assert_eq!(pages.len(), 1);
-        assert!(pages.contains(&PathBuf::from("/tmp/docs/docs/page1.md")));
-    }
-
-    #[test]
-    fn test_collect_pages_nested_sections() {
-        let nav = vec![NavItem::Section({
-            let mut map = HashMap::new();
-            map.insert(
-                "Outer".to_string(),
-                vec![NavItem::Section({
-                    let mut inner_map = HashMap::new();
-                    inner_map.insert(
-                        "Inner".to_string(),
-                        vec![NavItem::PlainPath("nested/file.md".to_string())],
-                    );
-                    inner_map
-                })],
-            );
-            map
-        })];
-
-        let mut pages = HashSet::new();
-        let prefix = Path::new("/base");
-
-        collect_pages(&nav, &mut pages, prefix).unwrap();
-
-        assert_eq!(pages.len(), 1);
-        assert!(pages.contains(&PathBuf::from("/base/docs/nested/file.md")));
-    }
-
-    #[test]
-    fn test_collect_pages_multiple_items() {
-        let nav = vec![
-            NavItem::PlainPath("first.md".to_string()),
-            NavItem::Page({
-                let mut map = HashMap::new();
-                map.insert("Second".to_string(), "second.md".to_string());
-                map
-            }),
-            NavItem::Section({
-                let mut map = HashMap::new();
-                map.insert(
-                    "Section".to_string(),
-                    vec![NavItem::PlainPath("third.md".to_string())],
-                );
-                map
-            }),
-        ];
-
-        let mut pages = HashSet::new();
-        let prefix = Path::new("/root");
-
-        collect_pages(&nav, &mut pages, prefix).unwrap();
-
-        assert_eq!(pages.len(), 3);
-        assert!(pages.contains(&PathBuf::from("/root/docs/first.md")));
-        assert!(pages.contains(&PathBuf::from("/root/docs/second.md")));
-        assert!(pages.contains(&PathBuf::from("/root/docs/third.md")));
-    }
-
-    #[test]
-    fn test_collect_pages_empty_nav() {
-        let nav = vec![];
-        let mut pages = HashSet::new();
-        let prefix = Path::new("/tmp");
-
-        collect_pages(&nav, &mut pages, prefix).unwrap();
-
-        assert_eq!(pages.len(), 0);
-    }
-
-    #[test]
-    fn test_collect_pages_with_include() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let prefix = temp_dir.path();
-
-        let include_dir = prefix.join("release-notes");
-        fs::create_dir_all(include_dir.join("docs")).unwrap();
-        let include_yaml = r#"
-nav:
-  - Child Page: child.md
-"#;
-        fs::write(include_dir.join("mkdocs.yml"), include_yaml).unwrap();
-
-        let nav = vec![NavItem::Page({
-            let mut map = HashMap::new();
-            map.insert(
-                "Release Notes".to_string(),
-                "!include ./release-notes/mkdocs.yml".to_string(),
-            );
-            map
-        })];
-
-        let mut pages = HashSet::new();
-
-        collect_pages(&nav, &mut pages, prefix).unwrap();
-
-        assert_eq!(pages.len(), 1);
-        assert!(pages.contains(&include_dir.join("docs").join("child.md")));
-    }
-
-    #[test]
-    fn test_missing_files_all_exist() {
-        let mut pages = HashSet::new();
-        pages.insert(PathBuf::from("src/lib.rs"));
-
-        let missing = missing_files(&pages);
-
-        assert!(missing.is_empty());
-    }
-
-    #[test]
-    fn test_missing_files_detects_missing() {
-        let mut pages = HashSet::new();
-        pages.insert(PathBuf::from("/nonexistent/file.md"));
-
-        let missing = missing_files(&pages);
-
-        assert_eq!(missing.len(), 1);
-        assert_eq!(missing[0], PathBuf::from("/nonexistent/file.md"));
-    }
-
-    #[test]
-    fn test_orphans_no_orphans() {
-        let mut nav = HashSet::new();
-        nav.insert(PathBuf::from("/docs/file1.md"));
-        nav.insert(PathBuf::from("/docs/file2.md"));
-
-        let files = vec![
-            PathBuf::from("/docs/file1.md"),
-            PathBuf::from("/docs/file2.md"),
-        ];
-
-        let orphan_files = orphans(&nav, &files);
-
-        assert_eq!(orphan_files.len(), 0);
-    }
-
-    #[test]
-    fn test_orphans_some_orphans() {
-        let mut nav = HashSet::new();
-        nav.insert(PathBuf::from("/docs/included.md"));
-
-        let files = vec![
-            PathBuf::from("/docs/included.md"),
-            PathBuf::from("/docs/orphan1.md"),
-            PathBuf::from("/docs/orphan2.md"),
-        ];
-
-        let orphan_files = orphans(&nav, &files);
-
-        assert_eq!(orphan_files.len(), 2);
-        assert!(orphan_files.contains(&PathBuf::from("/docs/orphan1.md")));
-        assert!(orphan_files.contains(&PathBuf::from("/docs/orphan2.md")));
-    }
-
-    #[test]
-    fn test_orphans_all_orphans() {
-        let nav = HashSet::new();
-
-        let files = vec![
-            PathBuf::from("/docs/orphan1.md"),
-            PathBuf::from("/docs/orphan2.md"),
-        ];
-
-        let orphan_files = orphans(&nav, &files);
-
-        assert_eq!(orphan_files.len(), 2);
-    }
-
-    #[test]
-    fn test_orphans_empty_files() {
-        let mut nav = HashSet::new();
-        nav.insert(PathBuf::from("/docs/file.md"));
-
-        let files = vec![];
-
-        let orphan_files = orphans(&nav, &files);
-
-        assert!(orphan_files.is_empty());
-    }
-
-    #[test]
-    fn test_build_link_maps_with_include_and_resolution() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-        let root_docs = root.join("docs");
-        fs::create_dir_all(root_docs.join("dir")).unwrap();
-        fs::write(root_docs.join("a.md"), "# A").unwrap();
-        fs::write(root_docs.join("dir").join("b.md"), "# B").unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let inc_dir = root.join("release-notes");
-        fs::create_dir_all(inc_dir.join("docs")).unwrap();
-        fs::write(inc_dir.join("docs").join("child.md"), "# Child").unwrap();
-
-        let inc_mkdocs = r#"
-nav:
-  - Child: child.md
-"#;
-        fs::write(inc_dir.join("mkdocs.yml"), inc_mkdocs).unwrap();
-
-        let nav = vec![
-            NavItem::Page({
-                let mut m = HashMap::new();
-                m.insert("A".to_string(), "a.md".to_string());
-                m
-            }),
-            NavItem::Page({
-                let mut m = HashMap::new();
-                m.insert("B".to_string(), "dir/b.md".to_string());
-                m
-            }),
-            NavItem::Page({
-                let mut m = HashMap::new();
-                m.insert(
-                    "Include".to_string(),
-                    "!include ./release-notes/mkdocs.yml".to_string(),
-                );
-                m
-            }),
-        ];
-
-        let maps = build_link_maps(&nav, root).unwrap();
-        let keys: Vec<String> = maps.url_to_src.keys().cloned().collect();
-        assert!(
-            keys.contains(&"release-notes/child".to_string()),
-            "keys: {:?}",
-            keys
-        );
-        assert_eq!(
-            maps.url_to_src.get("a").unwrap(),
-            &root.join("docs").join("a.md")
-        );
-        assert_eq!(
-            maps.url_to_src.get("release-notes/child").unwrap(),
-            &inc_dir.join("docs").join("child.md")
-        );
-
-        let from_src = root_docs.join("dir").join("b.md");
-        let target = resolve_link(&from_src, "../a.md", &maps).unwrap();
-        assert_eq!(target, root_docs.join("a.md"));
-
-        let target2 = resolve_link(&from_src, "/release-notes/child.md", &maps).unwrap();
-        assert_eq!(target2, inc_dir.join("docs").join("child.md"));
-    }
-
-    #[test]
-    fn test_ghost_removed_when_linked() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-        let docs = root.join("docs");
-        fs::create_dir_all(&docs).unwrap();
-        fs::write(docs.join("a.md"), "[Link](orphan.md)").unwrap();
-        fs::write(docs.join("orphan.md"), "# Orphan").unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let mkdocs = r#"
-nav:
-  - A: a.md
-"#;
-        fs::write(root.join("mkdocs.yml"), mkdocs).unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(!result.ghost.contains(&docs.join("orphan.md")));
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    #[test]
-    fn test_broken_link_reported() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-        let docs = root.join("docs");
-        fs::create_dir_all(&docs).unwrap();
-        fs::write(docs.join("a.md"), "[Missing](missing)").unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let mkdocs = r#"
-nav:
-  - A: a.md
-"#;
-        fs::write(root.join("mkdocs.yml"), mkdocs).unwrap();
-
-        let extracted = extract_links(&fs::read_to_string(docs.join("a.md")).unwrap());
-        assert_eq!(extracted, vec!["missing"]);
-
-        let files = find_markdown(vec![root]).unwrap();
-        assert_eq!(files.len(), 1);
-
-        let links = normalise_links(extract_links(
-            &fs::read_to_string(docs.join("a.md")).unwrap(),
-        ));
-        assert_eq!(links, vec!["missing.md"]);
-
-        let files_set: HashSet<PathBuf> = files.iter().cloned().collect();
-        let file_contents: Vec<(PathBuf, String)> = files
-            .iter()
-            .map(|p| fs::read_to_string(p).map(|c| (p.clone(), c)))
-            .collect::<io::Result<_>>()
-            .unwrap();
-        let link_maps = build_link_maps(
-            &[NavItem::Page({
-                let mut m = HashMap::new();
-                m.insert("A".to_string(), "a.md".to_string());
-                m
-            })],
-            root,
-        )
-        .unwrap();
-        let (_refd, broken_direct) = analyse_links(
-            &file_contents,
-            &files_set,
-            root,
-            &[],
-            &link_maps,
-            &HashMap::new(),
-            &HashMap::new(),
-            &mut Tracer::new(&[]),
-        )
-        .unwrap();
-        assert_eq!(broken_direct.len(), 1, "{:?}", broken_direct);
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert_eq!(result.broken_links.len(), 1, "{:?}", result.broken_links);
-        assert_eq!(result.broken_links[0].from, docs.join("a.md"));
-        assert_eq!(result.broken_links[0].link, "missing.md");
-    }
-
-    #[test]
-    fn test_relative_parent_link_with_anchor_resolves() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-        let docs = root.join("docs").join("primitive-operators");
-        fs::create_dir_all(&docs).unwrap();
-        fs::write(
-            docs.join("beside.md"),
-            "see [Function Composition](../operator-syntax#function-composition)",
-        )
-        .unwrap();
-        fs::write(
-            root.join("docs").join("operator-syntax.md"),
-            "# Operator Syntax",
-        )
-        .unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let mkdocs = r#"
-nav:
-  - Beside: primitive-operators/beside.md
-"#;
-        fs::write(root.join("mkdocs.yml"), mkdocs).unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    #[test]
-    fn test_non_nav_fs_link_resolves() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-        let docs = root.join("docs").join("primitive-operators");
-        fs::create_dir_all(&docs).unwrap();
-        fs::write(
-            docs.join("beside.md"),
-            "see [Function Composition](../operator-syntax#function-composition)",
-        )
-        .unwrap();
-        // target exists on filesystem but is not in nav
-        fs::write(
-            root.join("docs").join("operator-syntax.md"),
-            "# Operator Syntax",
-        )
-        .unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let mkdocs = r#"
-nav:
-  - Beside: primitive-operators/beside.md
-"#;
-        fs::write(root.join("mkdocs.yml"), mkdocs).unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    #[test]
-    fn test_strip_c_comments_line_comment() {
-        let input = "line1\n// this is a comment\nline2";
-        let result = strip_c_comments(input);
-        assert_eq!(result, "line1\n\nline2");
-    }
-
-    #[test]
-    fn test_strip_c_comments_block_comment() {
-        let input = "line1\n/* block comment */line2";
-        let result = strip_c_comments(input);
-        assert_eq!(result, "line1\nline2");
-    }
-
-    #[test]
-    fn test_expand_url_simple_string() {
-        let macros = HashMap::new();
-        let result = expand_url("path/to/file", &macros);
-        assert_eq!(result, "path/to/file");
-    }
-
-    #[test]
-    fn test_expand_url_with_macro() {
-        let mut macros = HashMap::new();
-        macros.insert(
-            "SY".to_string(),
-            "language-reference-guide/symbols".to_string(),
-        );
-        let result = expand_url("SY\"/comma\"", &macros);
-        assert_eq!(result, "language-reference-guide/symbols/comma");
-    }
-
-    #[test]
-    fn test_inject_docs() {
-        let result = inject_docs("language-reference-guide/symbols/comma");
-        assert_eq!(result, "language-reference-guide/docs/symbols/comma");
-    }
-
-    #[test]
-    fn test_inject_docs_single_component() {
-        let result = inject_docs("file");
-        assert_eq!(result, "file/docs");
-    }
-
-    #[test]
-    fn test_extract_help_urls_ignores_comments() {
-        use std::io::Write;
-        use tempfile::NamedTempFile;
-
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(
-            temp_file,
-            r#"
-// This is a comment with HELP_URL("test", "fake/path")
-#define SY "language-reference-guide/symbols"
-HELP_URL(",", SY"/comma")
-"#
-        )
-        .unwrap();
-
-        let temp_dir = tempfile::tempdir().unwrap();
-        let doc_root = temp_dir.path();
-
-        let result = extract_help_urls(temp_file.path(), doc_root);
-
-        // Should have 1 URL (the comma one), not 2 (comment should be ignored)
-        assert_eq!(result.len(), 1);
-        assert!(
-            result[0]
-                .to_string_lossy()
-                .contains("language-reference-guide/docs/symbols/comma.md")
-        );
-    }
-
-    #[test]
-    fn test_extract_help_urls_expands_macros() {
-        use std::io::Write;
-        use tempfile::NamedTempFile;
-
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(
-            temp_file,
-            r#"
-#define SY "language-reference-guide/symbols"
-HELP_URL(",", SY"/comma")
-"#
-        )
-        .unwrap();
-
-        let temp_dir = tempfile::tempdir().unwrap();
-        let doc_root = temp_dir.path();
-
-        let result = extract_help_urls(temp_file.path(), doc_root);
-
-        assert_eq!(result.len(), 1);
-        let path_str = result[0].to_string_lossy();
-        assert!(path_str.contains("language-reference-guide/docs/symbols/comma.md"));
-    }
-
-    #[test]
-    fn test_extract_help_urls_injects_docs() {
-        use std::io::Write;
-        use tempfile::NamedTempFile;
-
-        let mut temp_file = NamedTempFile::new().unwrap();
-        writeln!(
-            temp_file,
-            r#"
-HELP_URL(":if", "programming-reference-guide/defined-functions-and-operators/traditional-functions-and-operators/control-structures/if")
-"#
-        )
-        .unwrap();
-
-        let temp_dir = tempfile::tempdir().unwrap();
-        let doc_root = temp_dir.path();
-
-        let result = extract_help_urls(temp_file.path(), doc_root);
-
-        assert_eq!(result.len(), 1);
-        let path_str = result[0].to_string_lossy();
-        assert!(path_str.contains("/docs/"));
-        assert!(
-            path_str.contains("programming-reference-guide/docs/defined-functions-and-operators")
-        );
-    }
-
-    #[test]
-    fn test_adjacent_nav_pages_resolve_parent_link() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-        let docs = root.join("docs").join("primitive-operators");
-        fs::create_dir_all(&docs).unwrap();
-        fs::write(
-            docs.join("beside.md"),
-            "see [Operator Syntax](../operator-syntax.md)",
-        )
-        .unwrap();
-        fs::write(
-            root.join("docs").join("operator-syntax.md"),
-            "# Operator Syntax",
-        )
-        .unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let mkdocs = r#"
-nav:
-  - Operator Syntax: operator-syntax.md
-  - Beside: primitive-operators/beside.md
-"#;
-        fs::write(root.join("mkdocs.yml"), mkdocs).unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    #[test]
-    fn test_cross_subsite_link_resolves() {
-        // Simulates monorepo structure:
-        //   root/
-        //     release-notes/docs/new-enhanced.md  (links to programming-reference-guide)
-        //     programming-reference-guide/docs/introduction/arrays/array-notation.md
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-
-        // Create release-notes subsite.
-        // NB: cross-subsite links must be written *without* a `.md` extension —
-        // MkDocs/monorepo only rewrites `.md` links that stay within a subsite, so a
-        // cross-subsite `.md` href 404s on the live site (see issue #876). The valid
-        // form is the directory-style URL below.
-        let rn_docs = root.join("release-notes").join("docs");
-        fs::create_dir_all(&rn_docs).unwrap();
-        fs::write(
-            rn_docs.join("new-enhanced.md"),
-            "see [Array Notation](../../programming-reference-guide/introduction/arrays/array-notation)",
-        )
-        .unwrap();
-        let rn_mkdocs = r#"
-nav:
-  - New: new-enhanced.md
-"#;
-        fs::write(root.join("release-notes").join("mkdocs.yml"), rn_mkdocs).unwrap();
-
-        // Create programming-reference-guide subsite
-        let prg_docs = root
-            .join("programming-reference-guide")
-            .join("docs")
-            .join("introduction")
-            .join("arrays");
-        fs::create_dir_all(&prg_docs).unwrap();
-        fs::write(prg_docs.join("array-notation.md"), "# Array Notation").unwrap();
-        let prg_mkdocs = r#"
-nav:
-  - Introduction:
-    - Arrays:
-      - Array Notation: introduction/arrays/array-notation.md
-"#;
-        fs::write(
-            root.join("programming-reference-guide").join("mkdocs.yml"),
-            prg_mkdocs,
-        )
-        .unwrap();
-
-        // Create root mkdocs.yml that includes both subsites
-        let root_mkdocs = r#"
-nav:
-  - Release Notes: '!include ./release-notes/mkdocs.yml'
-  - Programming Reference: '!include ./programming-reference-guide/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    #[test]
-    fn test_sibling_file_via_parent_link_resolves() {
-        // A *bare* (extensionless) link is served as a directory URL and resolved by the
-        // browser page-as-directory: `../aplan-for-editor` from page `config-params/aplan-for-output`
-        // resolves to `config-params/aplan-for-editor`. This mirrors the real docs, where
-        // the link is written without a `.md` extension. (An explicit `.md` here would be
-        // broken — MkDocs resolves `.md` links source-relative, see issue #876.)
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-
-        let docs = root
-            .join("windows-guide")
-            .join("docs")
-            .join("config-params");
-        fs::create_dir_all(&docs).unwrap();
-
-        fs::write(
-            docs.join("aplan-for-output.md"),
-            "see [Editor](../aplan-for-editor)",
-        )
-        .unwrap();
-        fs::write(docs.join("aplan-for-editor.md"), "# Editor").unwrap();
-
-        let mkdocs = r#"
-nav:
-  - Config:
-    - Output: config-params/aplan-for-output.md
-    - Editor: config-params/aplan-for-editor.md
-"#;
-        fs::write(root.join("windows-guide").join("mkdocs.yml"), mkdocs).unwrap();
-
-        let root_mkdocs = r#"
-nav:
-  - Windows Guide: '!include ./windows-guide/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    #[test]
-    fn test_link_to_directory_with_index_resolves() {
-        // When linking to `foo.md` but `foo/index.md` exists, it should resolve
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-
-        let lrg_docs = root.join("language-reference-guide").join("docs");
-        let intro = lrg_docs.join("introduction").join("arrays");
-        let ravel_dir = lrg_docs.join("primitive-functions").join("ravel");
-        fs::create_dir_all(&intro).unwrap();
-        fs::create_dir_all(&ravel_dir).unwrap();
-
-        // Source file links to ravel.md, but ravel is a directory with index.md.
-        // The link stays within the subsite (source-relative), so the `.md` is valid
-        // and MkDocs rewrites it to the rendered `ravel/` URL.
-        fs::write(
-            intro.join("structuring.md"),
-            "see [Ravel](../../primitive-functions/ravel.md)",
-        )
-        .unwrap();
-        fs::write(ravel_dir.join("index.md"), "# Ravel").unwrap();
-
-        let lrg_mkdocs = r#"
-nav:
-  - Introduction:
-    - Arrays:
-      - Structuring: introduction/arrays/structuring.md
-  - Primitive Functions:
-    - Ravel: primitive-functions/ravel/index.md
-"#;
-        fs::write(
-            root.join("language-reference-guide").join("mkdocs.yml"),
-            lrg_mkdocs,
-        )
-        .unwrap();
-
-        let root_mkdocs = r#"
-nav:
-  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    #[test]
-    fn test_within_subsite_deep_relative_link_resolves() {
-        // Simulates link within same subsite that goes up multiple levels but stays
-        // inside docs/ (so MkDocs rewrites it):
-        //   root/
-        //     language-reference-guide/docs/system-functions/i-beam/shell.md
-        //       links to ../../primitive-operators/i-beam/shell-process-control.md
-        //     language-reference-guide/docs/primitive-operators/i-beam/shell-process-control.md
-        //
-        // This should NOT be treated as cross-subsite (primitive-operators is not a subsite).
-        // The two `../` land back at docs/ root, not above it — contrast with the
-        // over-deep case in test_issue876_overdeep_md_link_is_broken.
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-
-        // Create language-reference-guide subsite with nested structure
-        let lrg_docs = root.join("language-reference-guide").join("docs");
-        let sys_funcs = lrg_docs.join("system-functions").join("i-beam");
-        let prim_ops = lrg_docs.join("primitive-operators").join("i-beam");
-        fs::create_dir_all(&sys_funcs).unwrap();
-        fs::create_dir_all(&prim_ops).unwrap();
-
-        fs::write(
-            sys_funcs.join("shell.md"),
-            "see [Shell Process Control](../../primitive-operators/i-beam/shell-process-control.md)",
-        )
-        .unwrap();
-        fs::write(
-            prim_ops.join("shell-process-control.md"),
-            "# Shell Process Control",
-        )
-        .unwrap();
-
-        let lrg_mkdocs = r#"
-nav:
-  - System Functions:
-    - Shell: system-functions/i-beam/shell.md
-  - Primitive Operators:
-    - I-Beam:
-      - Shell Process Control: primitive-operators/i-beam/shell-process-control.md
-"#;
-        fs::write(
-            root.join("language-reference-guide").join("mkdocs.yml"),
-            lrg_mkdocs,
-        )
-        .unwrap();
-
-        // Create root mkdocs.yml
-        let root_mkdocs = r#"
-nav:
-  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    // ---- Regression tests for issue #876 (links ghost previously failed to flag) ----
-
-    /// An over-deep `.md` link with one too many `../` escapes the subsite docs/ root,
-    /// so MkDocs leaves the literal `.md` href and it 404s. Mirrors the reported
-    /// `system-functions-by-category.md -> ../../primitive-operators/spawn.md`.
-    #[test]
-    fn test_issue876_overdeep_md_link_is_broken() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-        let docs = root.join("language-reference-guide").join("docs");
-        fs::create_dir_all(docs.join("system-functions")).unwrap();
-        fs::create_dir_all(docs.join("primitive-operators")).unwrap();
-        // One `../` too many: ../../ from system-functions/ escapes docs/.
-        fs::write(
-            docs.join("system-functions").join("by-category.md"),
-            "threads via [Spawn](../../primitive-operators/spawn.md)",
-        )
-        .unwrap();
-        fs::write(docs.join("primitive-operators").join("spawn.md"), "# Spawn").unwrap();
-
-        let lrg_mkdocs = r#"
-nav:
-  - By Category: system-functions/by-category.md
-  - Spawn: primitive-operators/spawn.md
-"#;
-        fs::write(
-            root.join("language-reference-guide").join("mkdocs.yml"),
-            lrg_mkdocs,
-        )
-        .unwrap();
-        let root_mkdocs = r#"
-nav:
-  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert_eq!(result.broken_links.len(), 1, "{:?}", result.broken_links);
-        assert_eq!(
-            result.broken_links[0].link,
-            "../../primitive-operators/spawn.md"
-        );
-    }
-
-    /// A cross-subsite link carrying a `.md` extension is not rewritten by the monorepo
-    /// plugin, so the `.md` href 404s even though the target page exists. Mirrors the
-    /// reported `system-functions-by-category.md -> .../interface-guide/dde/shared-variable-principles.md`.
-    #[test]
-    fn test_issue876_cross_subsite_md_link_is_broken() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-
-        let lrg_docs = root.join("language-reference-guide").join("docs");
-        fs::create_dir_all(lrg_docs.join("system-functions")).unwrap();
-        fs::write(
-            lrg_docs.join("system-functions").join("by-category.md"),
-            "the [shared variable](../../../interface-guide/dde/shared-variable-principles.md) interface",
-        )
-        .unwrap();
-        fs::write(
-            root.join("language-reference-guide").join("mkdocs.yml"),
-            "nav:\n  - By Category: system-functions/by-category.md\n",
-        )
-        .unwrap();
-
-        let ig_docs = root.join("interface-guide").join("docs").join("dde");
-        fs::create_dir_all(&ig_docs).unwrap();
-        // The target page genuinely exists — only the `.md` extension makes it broken.
-        fs::write(
-            ig_docs.join("shared-variable-principles.md"),
-            "# Shared Variables",
-        )
-        .unwrap();
-        fs::write(
-            root.join("interface-guide").join("mkdocs.yml"),
-            "nav:\n  - Principles: dde/shared-variable-principles.md\n",
-        )
-        .unwrap();
-
-        let root_mkdocs = r#"
-nav:
-  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
-  - Interfaces: '!include ./interface-guide/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(
-            result
-                .broken_links
-                .iter()
-                .any(|bl| bl.link == "../../../interface-guide/dde/shared-variable-principles.md"),
-            "{:?}",
-            result.broken_links
-        );
-    }
-
-    /// The same cross-subsite target written *without* a `.md` extension (directory-style
-    /// URL) is valid — MkDocs serves it. This must keep resolving so we don't regress the
-    /// hundreds of legitimate bare cross-subsite links.
-    #[test]
-    fn test_bare_cross_subsite_link_resolves() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-
-        let lrg_docs = root.join("language-reference-guide").join("docs");
-        fs::create_dir_all(lrg_docs.join("system-functions")).unwrap();
-        fs::write(
-            lrg_docs.join("system-functions").join("by-category.md"),
-            "the [shared variable](../../../interface-guide/dde/shared-variable-principles) interface",
-        )
-        .unwrap();
-        fs::write(
-            root.join("language-reference-guide").join("mkdocs.yml"),
-            "nav:\n  - By Category: system-functions/by-category.md\n",
-        )
-        .unwrap();
-
-        let ig_docs = root.join("interface-guide").join("docs").join("dde");
-        fs::create_dir_all(&ig_docs).unwrap();
-        fs::write(
-            ig_docs.join("shared-variable-principles.md"),
-            "# Shared Variables",
-        )
-        .unwrap();
-        fs::write(
-            root.join("interface-guide").join("mkdocs.yml"),
-            "nav:\n  - Principles: dde/shared-variable-principles.md\n",
-        )
-        .unwrap();
-
-        let root_mkdocs = r#"
-nav:
-  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
-  - Interfaces: '!include ./interface-guide/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    /// Filenames are mandated lower-case, so a link with mixed case is flagged even
-    /// though it resolves on a case-insensitive (macOS) filesystem. Mirrors the reported
-    /// `thorn.md -> ../primitive-functions/format-by-Specification.md`.
-    #[test]
-    fn test_issue876_mixed_case_link_is_broken() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-        let docs = root.join("language-reference-guide").join("docs");
-        fs::create_dir_all(docs.join("symbols")).unwrap();
-        fs::create_dir_all(docs.join("primitive-functions")).unwrap();
-        fs::write(
-            docs.join("symbols").join("thorn.md"),
-            "[Format By Specification](../primitive-functions/format-by-Specification.md)",
-        )
-        .unwrap();
-        // The real (lower-case) file exists; the link's capital S is the defect.
-        fs::write(
-            docs.join("primitive-functions")
-                .join("format-by-specification.md"),
-            "# Fmt",
-        )
-        .unwrap();
-
-        let lrg_mkdocs = r#"
-nav:
-  - Thorn: symbols/thorn.md
-  - Format: primitive-functions/format-by-specification.md
-"#;
-        fs::write(
-            root.join("language-reference-guide").join("mkdocs.yml"),
-            lrg_mkdocs,
-        )
-        .unwrap();
-        let root_mkdocs = r#"
-nav:
-  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert_eq!(result.broken_links.len(), 1, "{:?}", result.broken_links);
-        assert_eq!(
-            result.broken_links[0].link,
-            "../primitive-functions/format-by-Specification.md"
-        );
-    }
-
-    /// The monorepo plugin mounts a subsite at slug(site_name), which may differ from the
-    /// directory name. A cross-subsite link using the slug must resolve to the directory
-    /// on disk (regression for the net-interface-guide false positives).
-    #[test]
-    fn test_cross_subsite_link_uses_site_name_slug() {
-        let temp = tempfile::tempdir().unwrap();
-        let root = temp.path();
-
-        let lrg = root.join("language-reference-guide").join("docs");
-        fs::create_dir_all(&lrg).unwrap();
-        fs::write(
-            lrg.join("glyphs.md"),
-            "see [Adv](../../net-interface-guide/dotnet-classes/advanced-techniques/)",
-        )
-        .unwrap();
-        fs::write(
-            root.join("language-reference-guide").join("mkdocs.yml"),
-            "site_name: Language Reference Guide\nnav:\n  - Glyphs: glyphs.md\n",
-        )
-        .unwrap();
-
-        // Directory is dotnet-interface-guide, but site_name slugifies to net-interface-guide.
-        let dni = root
-            .join("dotnet-interface-guide")
-            .join("docs")
-            .join("dotnet-classes");
-        fs::create_dir_all(&dni).unwrap();
-        fs::write(dni.join("advanced-techniques.md"), "# Adv").unwrap();
-        fs::write(
-            root.join("dotnet-interface-guide").join("mkdocs.yml"),
-            "site_name: .NET Interface Guide\nnav:\n  - Adv: dotnet-classes/advanced-techniques.md\n",
-        )
-        .unwrap();
-
-        fs::write(
-            root.join("mkdocs.yml"),
-            "nav:\n  - LRG: '!include ./language-reference-guide/mkdocs.yml'\n  - DNI: '!include ./dotnet-interface-guide/mkdocs.yml'\n",
-        )
-        .unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    /// A page pulled in by several HELP_URL entries must be analysed once (not once per
-    /// entry), and a broken link on it should cite every help_urls.h line that references
-    /// it. Regression for the "40 reports from 2 links" duplication.
-    #[test]
-    fn test_help_url_broken_link_deduped_and_cites_lines() {
-        let temp = tempfile::tempdir().unwrap();
-        let root = temp.path();
-        let docs = root.join("guide").join("docs");
-        fs::create_dir_all(&docs).unwrap();
-        fs::write(docs.join("page.md"), "[bad](nonexistent)").unwrap();
-        fs::write(
-            root.join("guide").join("mkdocs.yml"),
-            "site_name: Guide\nnav:\n  - P: page.md\n",
-        )
-        .unwrap();
-        fs::write(
-            root.join("mkdocs.yml"),
-            "nav:\n  - G: '!include ./guide/mkdocs.yml'\n",
-        )
-        .unwrap();
-        // page.md is referenced by two HELP_URL entries (lines 2 and 3).
-        fs::write(
-            root.join("help_urls.h"),
-            "#define P \"guide/page\"\nHELP_URL(\"a\", P)\nHELP_URL(\"b\", P)\n",
-        )
-        .unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        let bad: Vec<&BrokenLink> = result
-            .broken_links
-            .iter()
-            .filter(|b| b.link == "nonexistent.md")
-            .collect();
-        assert_eq!(
-            bad.len(),
-            1,
-            "expected one report, got {:?}",
-            result.broken_links
-        );
-        let lines: Vec<usize> = bad[0].help_refs.iter().map(|r| r.line).collect();
-        assert_eq!(lines, vec![2, 3]);
-        assert_eq!(bad[0].help_refs[0].text, r#"HELP_URL("a", P)"#);
-        assert_eq!(bad[0].help_refs[1].text, r#"HELP_URL("b", P)"#);
-    }
-
-    /// `audit_traced` produces a per-file blow-by-blow for the requested targets:
-    /// discovery/role, per-link verdicts, and "NOT REACHED" for unreachable files.
-    #[test]
-    fn test_audit_traced_reports_processing() {
-        let temp = tempfile::tempdir().unwrap();
-        let root = temp.path();
-        let docs = root.join("guide").join("docs");
-        fs::create_dir_all(&docs).unwrap();
-        fs::write(docs.join("page.md"), "[bad](nonexistent) and [ok](other)").unwrap();
-        fs::write(docs.join("other.md"), "# Other").unwrap();
-        fs::write(docs.join("orphan.md"), "not linked from anywhere").unwrap();
-        fs::write(
-            root.join("guide").join("mkdocs.yml"),
-            "site_name: Guide\nnav:\n  - Page: page.md\n  - Other: other.md\n",
-        )
-        .unwrap();
-        fs::write(
-            root.join("mkdocs.yml"),
-            "nav:\n  - G: '!include ./guide/mkdocs.yml'\n",
-        )
-        .unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let opts = TraceOptions {
-            targets: vec!["guide/docs/page.md".to_string(), "orphan.md".to_string()],
-        };
-        let (_res, trace) =
-            audit_traced(&root.join("mkdocs.yml"), &root.join("help_urls.h"), &opts).unwrap();
-        let t = trace.text;
-
-        // Reached, analysed page with one broken and one good link.
-        assert!(t.contains("FILE guide/docs/page.md"), "{t}");
-        assert!(t.contains("reached by     : nav"), "{t}");
-        assert!(t.contains("analysed       : true"), "{t}");
-        assert!(t.contains("LINK  nonexistent.md"), "{t}");
-        assert!(t.contains("BROKEN"), "{t}");
-        assert!(t.contains("resolved via"), "{t}"); // the [ok](other) link
-
-        // Orphan file: on disk but never reached, so its links are never checked.
-        assert!(t.contains("FILE guide/docs/orphan.md"), "{t}");
-        assert!(t.contains("NOT REACHED"), "{t}");
-
-        // No targets ⇒ no trace.
-        let (_r2, empty) = audit_traced(
-            &root.join("mkdocs.yml"),
-            &root.join("help_urls.h"),
-            &TraceOptions::default(),
-        )
-        .unwrap();
-        assert!(empty.text.is_empty());
-    }
-
-    #[test]
-    fn test_absolute_link_resolves() {
-        // Absolute links (starting with /) resolve from site root
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-
-        let guide_docs = root.join("guide").join("docs");
-        let nested = guide_docs.join("nested").join("deep");
-        fs::create_dir_all(&nested).unwrap();
-
-        // Deep nested file links to root-level page with absolute path
-        fs::write(nested.join("page.md"), "see [Home](/guide/index.md)").unwrap();
-        fs::write(guide_docs.join("index.md"), "# Home").unwrap();
-
-        let mkdocs = r#"
-nav:
-  - Home: index.md
-  - Nested:
-    - Deep:
-      - Page: nested/deep/page.md
-"#;
-        fs::write(root.join("guide").join("mkdocs.yml"), mkdocs).unwrap();
-
-        let root_mkdocs = r#"
-nav:
-  - Guide: '!include ./guide/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    #[test]
-    fn test_link_without_extension_resolves() {
-        // Links without .md extension should resolve (MkDocs supports this)
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-
-        let docs = root.join("guide").join("docs");
-        fs::create_dir_all(&docs).unwrap();
-
-        fs::write(
-            docs.join("source.md"),
-            "see [Target](target)", // no .md extension
-        )
-        .unwrap();
-        fs::write(docs.join("target.md"), "# Target").unwrap();
-
-        let mkdocs = r#"
-nav:
-  - Source: source.md
-  - Target: target.md
-"#;
-        fs::write(root.join("guide").join("mkdocs.yml"), mkdocs).unwrap();
-
-        let root_mkdocs = r#"
-nav:
-  - Guide: '!include ./guide/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    #[test]
-    fn test_link_with_trailing_slash_resolves() {
-        // Links with trailing slash (directory-style) should resolve
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-
-        let docs = root.join("guide").join("docs");
-        fs::create_dir_all(&docs).unwrap();
-
-        fs::write(
-            docs.join("source.md"),
-            "see [Target](target/)", // trailing slash
-        )
-        .unwrap();
-        fs::write(docs.join("target.md"), "# Target").unwrap();
-
-        let mkdocs = r#"
-nav:
-  - Source: source.md
-  - Target: target.md
-"#;
-        fs::write(root.join("guide").join("mkdocs.yml"), mkdocs).unwrap();
-
-        let root_mkdocs = r#"
-nav:
-  - Guide: '!include ./guide/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    #[test]
-    fn test_normalise_links_filters_correctly() {
-        // Unit test for link normalisation logic
-        let links = vec![
-            "page.md".to_string(),                 // already has .md
-            "page".to_string(),                    // needs .md added
-            "dir/page".to_string(),                // needs .md added
-            "page#anchor".to_string(),             // anchor should be stripped
-            "page.md#anchor".to_string(),          // anchor should be stripped
-            "https://example.com".to_string(),     // external, should be dropped
-            "mailto:test@example.com".to_string(), // mailto, should be dropped
-            "path/to/dir/".to_string(),            // trailing slash
-            "image.png".to_string(),               // non-md extension, should be dropped
-            "#just-anchor".to_string(),            // just anchor, should be dropped
-        ];
-
-        let normalised = normalise_links(links);
-
-        assert!(normalised.contains(&"page.md".to_string()));
-        assert!(normalised.contains(&"page.md".to_string()));
-        assert!(normalised.contains(&"dir/page.md".to_string()));
-        assert!(normalised.contains(&"path/to/dir.md".to_string()));
-        assert!(!normalised.iter().any(|l| l.contains('#')));
-        assert!(!normalised.iter().any(|l| l.starts_with("http")));
-        assert!(!normalised.iter().any(|l| l.starts_with("mailto")));
-        assert!(!normalised.iter().any(|l| l.ends_with(".png")));
-    }
-
-    #[test]
-    fn test_extract_links_from_markdown_and_html() {
-        // Verify both markdown links and HTML links are extracted
-        let content = r#"
-# Test Page
-
-Here is a [markdown link](markdown-target.md).
-
-And here is <a href="html-target.md">an HTML link</a>.
-
-And an inline <a href="inline.md">inline link</a> in text.
-"#;
-
-        let links = extract_links(content);
-
-        assert!(links.contains(&"markdown-target.md".to_string()));
-        assert!(links.contains(&"html-target.md".to_string()));
-        assert!(links.contains(&"inline.md".to_string()));
-    }
-
-    #[test]
-    fn test_extract_links_with_bold_text() {
-        // Links with bold text inside should still be extracted
-        let content = r#"[**Applies To**](../propertyapplies/accelerator.md)"#;
-        let links = extract_links(content);
-        eprintln!("Links found: {:?}", links);
-        assert!(links.contains(&"../propertyapplies/accelerator.md".to_string()));
-    }
-
-    #[test]
-    fn test_extract_links_from_markdown_table() {
-        // Links inside markdown tables should be extracted
-        let content = r#"
-|----------------------------------------------|----------------------------------------|
-|[ActiveXControl](../objects/activexcontrol.md)|[Bitmap](../objects/bitmap.md)          |
-|[ButtonEdit](../objects/buttonedit.md)        |[Calendar](../objects/calendar.md)      |
-"#;
-        let links = extract_links(content);
-        eprintln!("Table links found: {:?}", links);
-        assert!(links.contains(&"../objects/activexcontrol.md".to_string()));
-        assert!(links.contains(&"../objects/bitmap.md".to_string()));
-        assert!(links.contains(&"../objects/buttonedit.md".to_string()));
-        assert!(links.contains(&"../objects/calendar.md".to_string()));
-    }
-
-    #[test]
-    fn test_linked_file_not_orphan() {
-        // A file linked from a nav page should not be reported as orphan
-        let temp_dir = tempfile::tempdir().unwrap();
-        let root = temp_dir.path();
-
-        let docs = root.join("object-ref").join("docs");
-        let properties = docs.join("properties");
-        let propertyapplies = docs.join("propertyapplies");
-        fs::create_dir_all(&properties).unwrap();
-        fs::create_dir_all(&propertyapplies).unwrap();
-
-        // Nav page links to non-nav page
-        fs::write(
-            properties.join("accelerator.md"),
-            r#"[**Applies To**](../propertyapplies/accelerator.md)"#,
-        )
-        .unwrap();
-        // Non-nav page (should not be orphan because it's linked)
-        fs::write(propertyapplies.join("accelerator.md"), "# Applies").unwrap();
-
-        let mkdocs = r#"
-nav:
-  - Properties:
-    - Accelerator: properties/accelerator.md
-"#;
-        fs::write(root.join("object-ref").join("mkdocs.yml"), mkdocs).unwrap();
-
-        let root_mkdocs = r#"
-nav:
-  - Object Reference: '!include ./object-ref/mkdocs.yml'
-"#;
-        fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
-        fs::write(root.join("help_urls.h"), "").unwrap();
-
-        let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
-
-        eprintln!("Ghost files: {:?}", result.ghost);
-        eprintln!("Broken links: {:?}", result.broken_links);
-
-        // propertyapplies/accelerator.md should NOT be an orphan
-        assert!(
-            !result
-                .ghost
-                .iter()
-                .any(|p| p.to_string_lossy().contains("propertyapplies")),
-            "propertyapplies/accelerator.md should not be an orphan"
-        );
-        assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
-    }
-
-    #[test]
-    fn test_extract_image_after_html_heading() {
-        // Reproduces bug: pulldown_cmark may swallow markdown image syntax
-        // that follows raw HTML blocks.
-        let md = "<h2 class=\"example\">Example</h2>\n\
-```apl\n\
-\n\
-      \u{2395}USING\u{2190}'System'\n\
-```\n\
-\n\
-![](img/status-window.png)\n";
-        let refs = extract_image_refs(md);
-        assert_eq!(
-            refs,
-            vec!["img/status-window.png"],
-            "image ref after HTML heading should be extracted"
-        );
-    }
-}
+mod lib_tests;
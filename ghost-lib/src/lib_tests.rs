@@ -0,0 +1,2264 @@
+//! Unit tests for the library, including its private helpers. Tests that only need
+//! the public API and a docs tree on disk live in `tests/lib_integration.rs`.
+
+use super::*;
+
+fn section(title: &str, children: Vec<NavItem>) -> NavItem {
+    NavItem::Section(HashMap::from([(title.to_string(), children)]))
+}
+
+fn page(title: &str, path: &str) -> NavItem {
+    NavItem::Page(HashMap::from([(title.to_string(), path.to_string())]))
+}
+
+#[test]
+fn test_collect_pages_with_page() {
+    let nav = vec![NavItem::Page({
+        let mut map = HashMap::new();
+        map.insert("Title".to_string(), "path/to/file.md".to_string());
+        map
+    })];
+
+    let mut pages = HashSet::new();
+    let prefix = Path::new("/tmp/docs");
+
+    collect_pages(&nav, &mut pages, prefix).unwrap();
+
+    assert_eq!(pages.len(), 1);
+    assert!(pages.contains(&PathBuf::from("/tmp/docs/docs/path/to/file.md")));
+}
+
+#[test]
+fn test_collect_pages_with_plain_path() {
+    let nav = vec![NavItem::PlainPath("example.md".to_string())];
+
+    let mut pages = HashSet::new();
+    let prefix = Path::new("/tmp/docs");
+
+    collect_pages(&nav, &mut pages, prefix).unwrap();
+
+    assert_eq!(pages.len(), 1);
+    assert!(pages.contains(&PathBuf::from("/tmp/docs/docs/example.md")));
+}
+
+#[test]
+fn test_collect_pages_with_section() {
+    let nav = vec![NavItem::Section({
+        let mut map = HashMap::new();
+        map.insert(
+            "Section".to_string(),
+            vec![NavItem::Page({
+                let mut inner = HashMap::new();
+                inner.insert("Page1".to_string(), "page1.md".to_string());
+                inner
+            })],
+        );
+        map
+    })];
+
+    let mut pages = HashSet::new();
+    let prefix = Path::new("/tmp/docs");
+
+    collect_pages(&nav, &mut pages, prefix).unwrap();
+
+    assert_eq!(pages.len(), 1);
+    assert!(pages.contains(&PathBuf::from("/tmp/docs/docs/page1.md")));
+}
+
+#[test]
+fn test_collect_pages_nested_sections() {
+    let nav = vec![NavItem::Section({
+        let mut map = HashMap::new();
+        map.insert(
+            "Outer".to_string(),
+            vec![NavItem::Section({
+                let mut inner_map = HashMap::new();
+                inner_map.insert(
+                    "Inner".to_string(),
+                    vec![NavItem::PlainPath("nested/file.md".to_string())],
+                );
+                inner_map
+            })],
+        );
+        map
+    })];
+
+    let mut pages = HashSet::new();
+    let prefix = Path::new("/base");
+
+    collect_pages(&nav, &mut pages, prefix).unwrap();
+
+    assert_eq!(pages.len(), 1);
+    assert!(pages.contains(&PathBuf::from("/base/docs/nested/file.md")));
+}
+
+#[test]
+fn test_collect_pages_multiple_items() {
+    let nav = vec![
+        NavItem::PlainPath("first.md".to_string()),
+        NavItem::Page({
+            let mut map = HashMap::new();
+            map.insert("Second".to_string(), "second.md".to_string());
+            map
+        }),
+        NavItem::Section({
+            let mut map = HashMap::new();
+            map.insert(
+                "Section".to_string(),
+                vec![NavItem::PlainPath("third.md".to_string())],
+            );
+            map
+        }),
+    ];
+
+    let mut pages = HashSet::new();
+    let prefix = Path::new("/root");
+
+    collect_pages(&nav, &mut pages, prefix).unwrap();
+
+    assert_eq!(pages.len(), 3);
+    assert!(pages.contains(&PathBuf::from("/root/docs/first.md")));
+    assert!(pages.contains(&PathBuf::from("/root/docs/second.md")));
+    assert!(pages.contains(&PathBuf::from("/root/docs/third.md")));
+}
+
+#[test]
+fn test_collect_pages_empty_nav() {
+    let nav = vec![];
+    let mut pages = HashSet::new();
+    let prefix = Path::new("/tmp");
+
+    collect_pages(&nav, &mut pages, prefix).unwrap();
+
+    assert_eq!(pages.len(), 0);
+}
+
+#[test]
+fn test_missing_files_all_exist() {
+    let mut pages = HashSet::new();
+    pages.insert(PathBuf::from("src/lib.rs"));
+
+    let missing = missing_files(&pages);
+
+    assert!(missing.is_empty());
+}
+
+#[test]
+fn test_missing_files_detects_missing() {
+    let mut pages = HashSet::new();
+    pages.insert(PathBuf::from("/nonexistent/file.md"));
+
+    let missing = missing_files(&pages);
+
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0], PathBuf::from("/nonexistent/file.md"));
+}
+
+#[test]
+fn test_orphans_no_orphans() {
+    let mut nav = HashSet::new();
+    nav.insert(PathBuf::from("/docs/file1.md"));
+    nav.insert(PathBuf::from("/docs/file2.md"));
+
+    let files = vec![
+        PathBuf::from("/docs/file1.md"),
+        PathBuf::from("/docs/file2.md"),
+    ];
+
+    let orphan_files = orphans(&nav, &files);
+
+    assert_eq!(orphan_files.len(), 0);
+}
+
+#[test]
+fn test_orphans_some_orphans() {
+    let mut nav = HashSet::new();
+    nav.insert(PathBuf::from("/docs/included.md"));
+
+    let files = vec![
+        PathBuf::from("/docs/included.md"),
+        PathBuf::from("/docs/orphan1.md"),
+        PathBuf::from("/docs/orphan2.md"),
+    ];
+
+    let orphan_files = orphans(&nav, &files);
+
+    assert_eq!(orphan_files.len(), 2);
+    assert!(orphan_files.contains(&PathBuf::from("/docs/orphan1.md")));
+    assert!(orphan_files.contains(&PathBuf::from("/docs/orphan2.md")));
+}
+
+#[test]
+fn test_orphans_all_orphans() {
+    let nav = HashSet::new();
+
+    let files = vec![
+        PathBuf::from("/docs/orphan1.md"),
+        PathBuf::from("/docs/orphan2.md"),
+    ];
+
+    let orphan_files = orphans(&nav, &files);
+
+    assert_eq!(orphan_files.len(), 2);
+}
+
+#[test]
+fn test_orphans_empty_files() {
+    let mut nav = HashSet::new();
+    nav.insert(PathBuf::from("/docs/file.md"));
+
+    let files = vec![];
+
+    let orphan_files = orphans(&nav, &files);
+
+    assert!(orphan_files.is_empty());
+}
+
+#[test]
+fn test_broken_link_reported() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("a.md"), "[Missing](missing)").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let mkdocs = r#"
+nav:
+  - A: a.md
+"#;
+    fs::write(root.join("mkdocs.yml"), mkdocs).unwrap();
+
+    let extracted = extract_links(&fs::read_to_string(docs.join("a.md")).unwrap());
+    assert_eq!(
+        extracted,
+        vec![Link {
+            url: "missing".to_string(),
+            anchor: None
+        }]
+    );
+
+    let files = find_markdown(vec![root], true).unwrap();
+    assert_eq!(files.len(), 1);
+
+    let links = normalise_links(extract_hrefs(
+        &fs::read_to_string(docs.join("a.md")).unwrap(),
+    ));
+    assert_eq!(links, vec!["missing.md"]);
+
+    let files_set: HashSet<PathBuf> = files.iter().cloned().collect();
+    let scanned: Vec<ScannedFile> = files
+        .iter()
+        .map(|p| ScannedFile::read(p, None).unwrap())
+        .collect();
+    let link_maps = build_link_maps(
+        &[NavItem::Page({
+            let mut m = HashMap::new();
+            m.insert("A".to_string(), "a.md".to_string());
+            m
+        })],
+        root,
+    )
+    .unwrap();
+    let (_refd, broken_direct, _anchored, _page_links) = analyse_links(
+        &scanned,
+        &files_set,
+        root,
+        &[],
+        &link_maps,
+        &HashMap::new(),
+        &SiteLayout::default(),
+        None,
+        &GhostConfig::default(),
+        &mut None,
+        &|| {},
+        &mut Tracer::new(&[]),
+    )
+    .unwrap();
+    assert_eq!(broken_direct.len(), 1, "{:?}", broken_direct);
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(result.broken_links.len(), 1, "{:?}", result.broken_links);
+    assert_eq!(result.broken_links[0].from, docs.join("a.md"));
+    assert_eq!(result.broken_links[0].link, "missing.md");
+}
+
+#[test]
+fn test_strip_c_comments_line_comment() {
+    let input = "line1\n// this is a comment\nline2";
+    let result = strip_c_comments(input);
+    assert_eq!(result, "line1\n\nline2");
+}
+
+#[test]
+fn test_strip_c_comments_block_comment() {
+    let input = "line1\n/* block comment */line2";
+    let result = strip_c_comments(input);
+    assert_eq!(result, "line1\nline2");
+}
+
+#[test]
+fn test_expand_url_simple_string() {
+    let macros = HashMap::new();
+    let result = expand_url("path/to/file", &macros);
+    assert_eq!(result, "path/to/file");
+}
+
+#[test]
+fn test_expand_url_with_macro() {
+    let mut macros = HashMap::new();
+    macros.insert(
+        "SY".to_string(),
+        "language-reference-guide/symbols".to_string(),
+    );
+    let result = expand_url("SY\"/comma\"", &macros);
+    assert_eq!(result, "language-reference-guide/symbols/comma");
+}
+
+#[test]
+fn test_inject_docs() {
+    let result = inject_docs("language-reference-guide/symbols/comma");
+    assert_eq!(result, "language-reference-guide/docs/symbols/comma");
+}
+
+#[test]
+fn test_inject_docs_single_component() {
+    let result = inject_docs("file");
+    assert_eq!(result, "file/docs");
+}
+
+#[test]
+fn test_slugify() {
+    assert_eq!(slugify("Getting Started"), "getting-started");
+    assert_eq!(slugify("C++ API"), "c-api");
+    assert_eq!(slugify("  --Release notes (2024)--"), "release-notes-2024");
+}
+
+#[test]
+fn test_normalise_url() {
+    assert_eq!(
+        normalise_url(Path::new("guide/./topics/../intro")),
+        "guide/intro"
+    );
+    assert_eq!(normalise_url(Path::new("../intro")), "intro");
+    assert_eq!(normalise_url(Path::new("")), "");
+}
+
+#[test]
+fn test_normalize_path() {
+    assert_eq!(
+        normalize_path(Path::new("/repo/guide/docs/../docs/./img/a.png")),
+        PathBuf::from("/repo/guide/docs/img/a.png")
+    );
+    // A leading `..` has nothing to cancel and is kept.
+    assert_eq!(
+        normalize_path(Path::new("../shared/a.md")),
+        PathBuf::from("../shared/a.md")
+    );
+}
+
+#[test]
+fn test_normalise_links_filters_correctly() {
+    // Unit test for link normalisation logic
+    let links = vec![
+        "page.md".to_string(),                 // already has .md
+        "page".to_string(),                    // needs .md added
+        "dir/page".to_string(),                // needs .md added
+        "page#anchor".to_string(),             // anchor should be stripped
+        "page.md#anchor".to_string(),          // anchor should be stripped
+        "https://example.com".to_string(),     // external, should be dropped
+        "mailto:test@example.com".to_string(), // mailto, should be dropped
+        "path/to/dir/".to_string(),            // trailing slash
+        "image.png".to_string(),               // non-md extension, should be dropped
+        "#just-anchor".to_string(),            // just anchor, should be dropped
+    ];
+
+    let normalised = normalise_links(links);
+
+    assert!(normalised.contains(&"page.md".to_string()));
+    assert!(normalised.contains(&"page.md".to_string()));
+    assert!(normalised.contains(&"dir/page.md".to_string()));
+    assert!(normalised.contains(&"path/to/dir.md".to_string()));
+    assert!(!normalised.iter().any(|l| l.contains('#')));
+    assert!(!normalised.iter().any(|l| l.starts_with("http")));
+    assert!(!normalised.iter().any(|l| l.starts_with("mailto")));
+    assert!(!normalised.iter().any(|l| l.ends_with(".png")));
+}
+
+#[test]
+fn test_extract_links_from_markdown_and_html() {
+    // Verify both markdown links and HTML links are extracted
+    let content = r#"
+# Test Page
+
+Here is a [markdown link](markdown-target.md).
+
+And here is <a href="html-target.md">an HTML link</a>.
+
+And an inline <a href="inline.md">inline link</a> in text.
+"#;
+
+    let links: Vec<String> = extract_links(content).into_iter().map(|l| l.url).collect();
+
+    assert!(links.contains(&"markdown-target.md".to_string()));
+    assert!(links.contains(&"html-target.md".to_string()));
+    assert!(links.contains(&"inline.md".to_string()));
+}
+
+#[test]
+fn test_extract_links_splits_off_anchors() {
+    let content = "[A](page.md#setup) [B](other.md#) <a href=\"#top\">Top</a>";
+    assert_eq!(
+        extract_links(content),
+        vec![
+            Link {
+                url: "page.md".to_string(),
+                anchor: Some("setup".to_string())
+            },
+            Link {
+                url: "other.md".to_string(),
+                anchor: None
+            },
+            Link {
+                url: String::new(),
+                anchor: Some("top".to_string())
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_extract_links_with_bold_text() {
+    // Links with bold text inside should still be extracted
+    let content = r#"[**Applies To**](../propertyapplies/accelerator.md)"#;
+    let links: Vec<String> = extract_links(content).into_iter().map(|l| l.url).collect();
+    eprintln!("Links found: {:?}", links);
+    assert!(links.contains(&"../propertyapplies/accelerator.md".to_string()));
+}
+
+#[test]
+fn test_extract_links_from_markdown_table() {
+    // Links inside markdown tables should be extracted
+    let content = r#"
+|----------------------------------------------|----------------------------------------|
+|[ActiveXControl](../objects/activexcontrol.md)|[Bitmap](../objects/bitmap.md)          |
+|[ButtonEdit](../objects/buttonedit.md)        |[Calendar](../objects/calendar.md)      |
+"#;
+    let links: Vec<String> = extract_links(content).into_iter().map(|l| l.url).collect();
+    eprintln!("Table links found: {:?}", links);
+    assert!(links.contains(&"../objects/activexcontrol.md".to_string()));
+    assert!(links.contains(&"../objects/bitmap.md".to_string()));
+    assert!(links.contains(&"../objects/buttonedit.md".to_string()));
+    assert!(links.contains(&"../objects/calendar.md".to_string()));
+}
+
+#[test]
+fn test_extract_image_after_html_heading() {
+    // Reproduces bug: pulldown_cmark may swallow markdown image syntax
+    // that follows raw HTML blocks.
+    let md = "<h2 class=\"example\">Example</h2>\n\
+```apl\n\
+\n\
+  \u{2395}USING\u{2190}'System'\n\
+```\n\
+\n\
+![](img/status-window.png)\n";
+    let refs = extract_image_refs(md);
+    assert_eq!(
+        refs,
+        vec!["img/status-window.png"],
+        "image ref after HTML heading should be extracted"
+    );
+}
+
+#[test]
+fn test_find_absolute_internal_links() {
+    let md = "[a](https://docs.example.com/guide/page.md) \
+              [b](https://docs.example.com.evil/x.md) \
+              [c](https://other.com/page.md) [d](../relative.md)";
+    let found = find_absolute_internal_links(md, "https://docs.example.com/");
+    assert_eq!(found, vec!["https://docs.example.com/guide/page.md"]);
+    assert_eq!(
+        find_absolute_internal_links(md, "https://docs.example.com"),
+        found
+    );
+}
+
+#[test]
+fn test_title_case_violations() {
+    let nav = vec![
+        section(
+            "User Guide",
+            vec![
+                section("Getting started", vec![page("Intro", "intro.md")]),
+                section("Working with the API", vec![page("Calls", "calls.md")]),
+            ],
+        ),
+        section("Release notes", vec![page("Latest", "latest.md")]),
+    ];
+
+    let title = find_title_case_violations(&nav, TitleCaseStyle::AllTitle);
+    assert_eq!(
+        title,
+        vec![
+            (
+                vec!["User Guide".to_string()],
+                "Getting started".to_string()
+            ),
+            (vec![], "Release notes".to_string()),
+        ]
+    );
+
+    // "API" is an acronym, so "Working with the API" is sentence case.
+    let sentence = find_title_case_violations(&nav, TitleCaseStyle::AllSentence);
+    assert_eq!(sentence, vec![(vec![], "User Guide".to_string())]);
+
+    assert!(find_title_case_violations(&nav, TitleCaseStyle::Mixed).is_empty());
+}
+
+#[test]
+fn test_title_case_style_from_str() {
+    assert_eq!(
+        "title".parse::<TitleCaseStyle>(),
+        Ok(TitleCaseStyle::AllTitle)
+    );
+    assert_eq!(
+        "Sentence".parse::<TitleCaseStyle>(),
+        Ok(TitleCaseStyle::AllSentence)
+    );
+    assert!("shouty".parse::<TitleCaseStyle>().is_err());
+}
+
+#[test]
+fn test_find_links_with_query_strings_internal() {
+    let md = "[a](page.md?version=2) [b](../guide/?tab=1#top) [c](plain.md)";
+    assert_eq!(
+        find_links_with_query_strings(md),
+        vec!["page.md?version=2", "../guide/?tab=1#top"]
+    );
+}
+
+#[test]
+fn test_find_links_with_query_strings_ignores_external_and_fragment() {
+    let md = "[a](https://example.com/search?q=ghost) \
+              [b](mailto:docs@example.com?subject=Hi) \
+              [c](page.md#what-is-this?)";
+    assert!(find_links_with_query_strings(md).is_empty());
+}
+
+#[test]
+fn test_edit_distance() {
+    assert_eq!(edit_distance("guide", "guide"), 0);
+    assert_eq!(edit_distance("guide", "glide"), 1);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("", "abc"), 3);
+}
+
+#[test]
+fn test_find_stale_absolute_prefix_links() {
+    let mut maps = LinkMaps::default();
+    for url in [
+        "user-guide/install",
+        "user-guide/start",
+        "reference/api",
+        "about",
+    ] {
+        maps.url_to_src
+            .insert(url.to_string(), PathBuf::from(format!("{url}.md")));
+    }
+    let broken = |link: &str| BrokenLink {
+        from: PathBuf::from("docs/index.md"),
+        link: link.to_string(),
+        help_refs: vec![],
+        suggestions: vec![],
+    };
+    let broken_links = vec![
+        broken("/guide/install.md"),
+        broken("/refrence/api.md"),
+        broken("/changelog/2024.md"),
+        broken("/user-guide/gone.md"),
+        broken("../guide/install.md"),
+        broken("/missing.md"),
+    ];
+
+    let stale = find_stale_absolute_prefix_links(&broken_links, &maps);
+
+    assert_eq!(
+        stale,
+        vec![
+            (
+                PathBuf::from("docs/index.md"),
+                "/guide/install.md".to_string(),
+                vec!["user-guide".to_string()]
+            ),
+            (
+                PathBuf::from("docs/index.md"),
+                "/refrence/api.md".to_string(),
+                vec!["reference".to_string()]
+            ),
+            (
+                PathBuf::from("docs/index.md"),
+                "/changelog/2024.md".to_string(),
+                vec![]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_extract_image_refs_with_alt() {
+    let md = "![Ravel *example*](img/ravel.png)\n\n\
+              <img src=\"img/a.png\" alt=\"Session\">\n\n\
+              <img src=\"img/b.png\">\n\n\
+              ![Ravel *example*](img/ravel.png)\n";
+    let refs = extract_image_refs_with_alt(md);
+    assert_eq!(
+        refs,
+        vec![
+            ImageRef {
+                src: "img/ravel.png".to_string(),
+                alt: Some("Ravel example".to_string()),
+                syntax: ImageSyntax::Markdown,
+            },
+            ImageRef {
+                src: "img/a.png".to_string(),
+                alt: Some("Session".to_string()),
+                syntax: ImageSyntax::Html,
+            },
+            ImageRef {
+                src: "img/b.png".to_string(),
+                alt: None,
+                syntax: ImageSyntax::Html,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_find_poor_alt_text_builtin_list() {
+    let md = "![Screenshot](img/a.png)\n\n\
+              ![](img/b.png)\n\n\
+              ![The Session toolbar](img/c.png)\n\n\
+              <img src=\"img/d.png\" alt=\" logo \">\n\n\
+              <img src=\"img/e.png\">\n";
+    assert_eq!(
+        find_poor_alt_text(md),
+        vec![
+            ("img/a.png".to_string(), "Screenshot".to_string()),
+            ("img/b.png".to_string(), String::new()),
+            ("img/d.png".to_string(), " logo ".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_find_poor_alt_text_with_configured_additions() {
+    let md = "![Dyalog](img/a.png)\n\n![Screenshot](img/b.png)\n\n![Ravel](img/c.png)\n";
+    assert_eq!(
+        find_poor_alt_text_with(md, &["dyalog".to_string()]),
+        vec![
+            ("img/a.png".to_string(), "Dyalog".to_string()),
+            ("img/b.png".to_string(), "Screenshot".to_string()),
+        ]
+    );
+    assert_eq!(find_poor_alt_text(md).len(), 1);
+}
+
+#[test]
+fn test_find_localhost_links() {
+    let md = "[a](http://localhost:8000/guide/) \
+              [b](https://127.0.0.1/page.md) \
+              [c](http://0.0.0.0:3000) \
+              [d](http://LOCALHOST?x=1) \
+              [e](http://localhost.example.com/) \
+              [f](https://docs.example.com/localhost) \
+              [g](../localhost.md) \
+              [h](http://127.0.0.10/)";
+    assert_eq!(
+        find_localhost_links(md),
+        vec![
+            "http://localhost:8000/guide/",
+            "https://127.0.0.1/page.md",
+            "http://0.0.0.0:3000",
+            "http://LOCALHOST?x=1",
+        ]
+    );
+}
+
+#[test]
+fn test_severity_from_str() {
+    assert_eq!("Warning".parse::<Severity>(), Ok(Severity::Warning));
+    assert_eq!("error".parse::<Severity>(), Ok(Severity::Error));
+    assert!("fatal".parse::<Severity>().is_err());
+    assert_eq!(
+        GhostConfig::default().localhost_links_severity,
+        Severity::Error
+    );
+}
+
+#[test]
+fn test_find_unexpected_binaries() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let docs = temp_dir.path().join("docs");
+    fs::create_dir_all(docs.join("img")).unwrap();
+    fs::create_dir_all(docs.join("tools")).unwrap();
+    fs::write(docs.join("index.md"), "# Home").unwrap();
+    fs::write(docs.join("img").join("logo.PNG"), "").unwrap();
+    fs::write(docs.join("CNAME"), "docs.example.com").unwrap();
+    fs::write(docs.join("tools").join("setup.exe"), [0x4d, 0x5a]).unwrap();
+    fs::write(docs.join("tools").join("bundle.zip"), "").unwrap();
+
+    assert_eq!(
+        find_unexpected_binaries(&docs, DOCS_EXTENSIONS, true),
+        vec![
+            normalize_path(&docs.join("tools").join("bundle.zip")),
+            normalize_path(&docs.join("tools").join("setup.exe")),
+        ]
+    );
+
+    let mut allowed = DOCS_EXTENSIONS.to_vec();
+    allowed.push("zip");
+    assert_eq!(
+        find_unexpected_binaries(&docs, &allowed, true),
+        vec![normalize_path(&docs.join("tools").join("setup.exe"))]
+    );
+}
+
+#[test]
+fn test_validate_frontmatter() {
+    let md = "---\ntitle: Home\ndescription:\ntags: []\n---\n# Home\n";
+    let frontmatter = extract_frontmatter(md).unwrap();
+    assert_eq!(
+        validate_frontmatter(&frontmatter, &["title", "description", "tags", "author"]),
+        vec!["description", "tags", "author"]
+    );
+    assert_eq!(extract_frontmatter("# No front matter\n"), None);
+    assert_eq!(extract_frontmatter("---\n: [unclosed\n---\n"), None);
+}
+
+#[test]
+fn test_extract_frontmatter_image_refs() {
+    let md = "---\ntitle: Home\nhero_image: img/hero.png\nog_image:\n  - img/a.png\n  - img/b.png\n---\n\n# Home\n";
+    assert_eq!(
+        frontmatter_text(md),
+        Some("title: Home\nhero_image: img/hero.png\nog_image:\n  - img/a.png\n  - img/b.png\n")
+    );
+    let fields = GhostConfig::default().frontmatter_image_fields;
+    assert_eq!(
+        extract_frontmatter_image_refs(md, &fields),
+        vec!["img/hero.png", "img/a.png", "img/b.png"]
+    );
+    assert!(extract_frontmatter_image_refs("# No front matter\n", &fields).is_empty());
+}
+
+#[test]
+fn test_find_url_encoded_internal_links() {
+    let md = "[a](my%20page.md) [b](guide/caf%C3%A9/) [c](page.md#caf%C3%A9) \
+              [d](https://example.com/a%20b) [e](100%.md)";
+    assert_eq!(
+        find_url_encoded_internal_links(md),
+        vec!["my%20page.md", "guide/caf%C3%A9/"]
+    );
+    assert_eq!(
+        normalise_links(vec!["my%20page.md#x".to_string()]),
+        vec!["my page.md"]
+    );
+}
+
+#[test]
+fn test_decode_link_path() {
+    assert_eq!(decode_link_path("img/my%20pic.png"), "img/my pic.png");
+    assert_eq!(decode_link_path("guide%2Fpage.md"), "guide/page.md");
+    // At the edge of a segment, %2F would make an empty component or an absolute path.
+    assert_eq!(decode_link_path("%2Fetc/passwd.md"), "%2Fetc/passwd.md");
+    assert_eq!(decode_link_path("guide%2F/page.md"), "guide%2F/page.md");
+    assert_eq!(decode_link_path("100%.md"), "100%.md");
+}
+
+#[test]
+fn test_find_single_child_sections() {
+    let nav = vec![
+        // Top level: never flagged, even with a single child.
+        section(
+            "Guide",
+            vec![
+                section("Empty", vec![]),
+                section("Lonely", vec![page("Only page", "only.md")]),
+                section(
+                    "Pair",
+                    vec![
+                        page("One", "one.md"),
+                        section("Deep", vec![NavItem::PlainPath("deep.md".into())]),
+                    ],
+                ),
+                section(
+                    "Trio",
+                    vec![
+                        page("One", "a.md"),
+                        page("Two", "b.md"),
+                        section(
+                            "Wrapper",
+                            vec![section("Inner", vec![page("X", "x.md"), page("Y", "y.md")])],
+                        ),
+                    ],
+                ),
+            ],
+        ),
+        section("Reference", vec![page("API", "api.md")]),
+    ];
+
+    let path = |titles: &[&str]| titles.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        find_single_child_sections(&nav),
+        vec![
+            (path(&["Guide", "Lonely"]), "Only page".to_string()),
+            (path(&["Guide", "Pair", "Deep"]), "deep.md".to_string()),
+            (path(&["Guide", "Trio", "Wrapper"]), "Inner".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_find_unknown_fence_languages() {
+    let known: HashSet<String> = KNOWN_CODE_LANGUAGES.iter().map(|l| l.to_string()).collect();
+    let md = "# Code\n\n```Python title=\"x.py\"\nprint(1)\n```\n\n```dyalog\n⍳5\n```\n\n\
+              ```\nplain\n```\n\n    indented\n\n```{.klingon}\nQapla'\n```\n";
+    assert_eq!(
+        find_unknown_fence_languages(md, &known),
+        vec![(7, "dyalog".to_string()), (17, "klingon".to_string())]
+    );
+
+    let mut extended = known.clone();
+    extended.insert("dyalog".to_string());
+    assert_eq!(
+        find_unknown_fence_languages(md, &extended),
+        vec![(17, "klingon".to_string())]
+    );
+}
+
+#[test]
+fn test_extract_code_blocks() {
+    let md = "# Code\n\n```python title=\"x.py\"\nprint(1)\n```\n\n```\nplain\n```\n\n    indented\n\n- item\n\n  ```{.yaml}\n  key: value\n  ```\n";
+    assert_eq!(
+        extract_code_blocks(md),
+        vec![
+            CodeBlock {
+                lang: Some("python".to_string()),
+                content: "print(1)\n".to_string(),
+                start_line: 3,
+            },
+            CodeBlock {
+                lang: None,
+                content: "plain\n".to_string(),
+                start_line: 7,
+            },
+            CodeBlock {
+                lang: None,
+                content: "indented\n".to_string(),
+                start_line: 11,
+            },
+            CodeBlock {
+                lang: Some("yaml".to_string()),
+                content: "key: value\n".to_string(),
+                start_line: 15,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_has_headings_ignores_frontmatter() {
+    assert!(has_headings("# Title\n\nBody."));
+    assert!(has_headings("Title\n=====\n"));
+    assert!(!has_headings(""));
+    assert!(!has_headings("---\ntitle: Stub\n---\n"));
+    assert!(!has_headings("Just a paragraph."));
+    assert!(has_headings("---\ntitle: Real\n---\n\n## Section\n"));
+}
+
+#[test]
+fn test_extract_nav_descriptions() {
+    let nav: Vec<NavItem> = serde_yaml::from_str(
+        "- Intro:\n    - Start: start.md\n      description: See [setup](setup.md) first.\n- plain.md\n- Other: other.md\n",
+    )
+    .unwrap();
+    assert_eq!(
+        extract_nav_descriptions(&nav),
+        vec!["See [setup](setup.md) first.".to_string()]
+    );
+}
+
+#[test]
+fn test_normalise_links_drops_non_document_schemes() {
+    for link in [
+        "tel:+1234567890",
+        "sms:+1234",
+        "ftp://ftp.example.com/pub/file",
+        "ftps://ftp.example.com/pub",
+        "irc://irc.libera.chat/apl",
+        "xmpp:someone@example.com",
+        "TEL:+1234567890",
+    ] {
+        assert!(normalise_one(link).is_none(), "{link}");
+        assert!(normalise_links([link.to_string()]).is_empty(), "{link}");
+    }
+    // A page whose name merely starts like a scheme is still a page.
+    assert_eq!(
+        normalise_links(["telemetry".to_string()]),
+        vec!["telemetry.md"]
+    );
+}
+
+#[test]
+fn test_extract_headings_all_levels() {
+    let md = "---\ntitle: Levels\n---\n# One\n\n## Two\n\n### Three `code`\n\n#### Four\n\n##### Five\n\n###### Six\n\nSetext\n------\n";
+    let headings = extract_headings(md);
+    let levels: Vec<usize> = headings.iter().map(|h| h.level).collect();
+    assert_eq!(levels, vec![1, 2, 3, 4, 5, 6, 2]);
+    assert_eq!(headings[0].line, 4);
+    assert_eq!(headings[0].text, "One");
+    assert_eq!(headings[2].text, "Three code");
+    assert_eq!(headings[5].line, 14);
+    assert_eq!(headings[6].text, "Setext");
+}
+
+#[test]
+fn test_extract_headings_anchors() {
+    let md = "# Using **bold** and `code`\n\n## See [the guide](guide.md)\n\n\
+              <!--\n## Hidden\n-->\n\n## Examples\n\n## Examples\n\n## Custom {#my-id}\n";
+    let headings = extract_headings(md);
+    let found: Vec<(&str, &str)> = headings
+        .iter()
+        .map(|h| (h.text.as_str(), h.anchor.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("Using bold and code", "using-bold-and-code"),
+            ("See the guide", "see-the-guide"),
+            ("Examples", "examples"),
+            ("Examples", "examples_1"),
+            ("Custom {#my-id}", "my-id"),
+        ]
+    );
+}
+
+#[test]
+fn test_heading_slug_follows_python_markdown() {
+    assert_eq!(heading_slug("What's new"), "whats-new");
+    assert_eq!(heading_slug("v1.2"), "v12");
+    assert_eq!(heading_slug("Über"), "uber");
+    assert_eq!(heading_slug("snake_case name"), "snake_case-name");
+    assert_eq!(heading_slug("  Set-up -- & Install "), "set-up-install");
+    assert_eq!(
+        extract_headings(
+            "## What's new in v1.2
+"
+        )[0]
+        .anchor,
+        "whats-new-in-v12"
+    );
+}
+
+#[test]
+fn test_find_deep_headings_threshold() {
+    let md = "# One\n## Two\n### Three\n#### Four\n##### Five\n###### Six\n";
+    for max_level in 0..=6 {
+        let deep = find_deep_headings(md, max_level);
+        assert_eq!(deep.len(), 6 - max_level, "max_level {max_level}");
+        assert!(deep.iter().all(|(_, level, _)| *level > max_level));
+    }
+    assert_eq!(
+        find_deep_headings(md, 3),
+        vec![
+            (4, 4, "Four".to_string()),
+            (5, 5, "Five".to_string()),
+            (6, 6, "Six".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_suggest_link_fixes() {
+    let pages: Vec<PathBuf> = [
+        "docs/arrays.md",
+        "docs/array.md",
+        "docs/arrays/index.md",
+        "docs/x.md",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .collect();
+    assert_eq!(
+        suggest_link_fixes("../guide/arays.md", &pages),
+        vec![
+            PathBuf::from("docs/arrays/index.md"),
+            PathBuf::from("docs/arrays.md"),
+            PathBuf::from("docs/array.md"),
+        ]
+    );
+    assert!(suggest_link_fixes("functions/", &pages).is_empty());
+}
+
+#[test]
+fn test_title_similarity() {
+    assert_eq!(title_similarity("Ravel", "The Ravel Function (,)"), 1.0);
+    assert_eq!(title_similarity("ravel", "RAVEL"), 1.0);
+    assert!(title_similarity("Ravel", "Reshape") < 0.5);
+    assert!(title_similarity("Installing", "Installation") >= 0.5);
+}
+
+#[test]
+fn test_check_heading_hierarchy() {
+    let md = "## Intro\n#### Detail\n##### Finer\n# Part two\n### Skipped\n## Fine\n";
+    assert_eq!(
+        check_heading_hierarchy(md),
+        vec![
+            HeadingError {
+                line: 2,
+                level: 4,
+                max_expected: 3,
+            },
+            HeadingError {
+                line: 5,
+                level: 3,
+                max_expected: 2,
+            },
+        ]
+    );
+    assert!(check_heading_hierarchy("# A\n## B\n### C\n# D\n").is_empty());
+}
+
+#[test]
+fn test_extract_links_with_text() {
+    let md = "Intro\n\nSee [the `⍳` guide](guide.md) and\n[two\nlines](two.md).";
+    assert_eq!(
+        extract_links_with_text(md),
+        vec![
+            (3, "the `⍳` guide".to_string(), "guide.md".to_string()),
+            (4, "two lines".to_string(), "two.md".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_find_repeated_words_in_link_text() {
+    let md = "[see the the guide](guide.md)\n\n\
+              [The the start](start.md)\n\n\
+              [wrapped across\nacross lines](wrap.md)\n\n\
+              [fine text](fine.md)";
+    assert_eq!(
+        find_repeated_words_in_link_text(md),
+        vec![
+            (1, "see the the guide".to_string()),
+            (3, "The the start".to_string()),
+            (5, "wrapped across across lines".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_repeated_words_false_positives() {
+    // Intentional repetition inside code, numbers, and words that merely share a
+    // prefix or are separated by punctuation.
+    let md = "[the `x x` idiom](idiom.md)\n\n\
+              [version 2 2](v2.md)\n\n\
+              [then the guide](guide.md)\n\n\
+              [yes, yes](yes.md)\n\n\
+              [the theory](theory.md)";
+    assert!(
+        find_repeated_words_in_link_text(md).is_empty(),
+        "{:?}",
+        find_repeated_words_in_link_text(md)
+    );
+}
+
+#[test]
+fn test_docs_root_and_subsite_name() {
+    let page = Path::new("/repo/guide/docs/topics/page.md");
+    assert_eq!(docs_root_for(page), Some(PathBuf::from("/repo/guide")));
+    assert_eq!(subsite_name_for(page), Some("guide"));
+
+    // The nearest docs/ wins.
+    let nested = Path::new("/repo/docs/tutorials/docs/page.md");
+    assert_eq!(
+        docs_root_for(nested),
+        Some(PathBuf::from("/repo/docs/tutorials"))
+    );
+    assert_eq!(subsite_name_for(nested), Some("tutorials"));
+
+    assert_eq!(subsite_name_for(Path::new("guide/docs")), Some("guide"));
+    assert_eq!(docs_root_for(Path::new("/repo/guide/page.md")), None);
+    assert_eq!(subsite_name_for(Path::new("/repo/guide/page.md")), None);
+    // A relative docs/ has an empty parent and so no name.
+    assert_eq!(subsite_name_for(Path::new("docs/page.md")), None);
+}
+
+#[test]
+fn test_find_subsites_without_mkdocs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("mkdocs.yml"), "nav: []\n").unwrap();
+    fs::create_dir_all(root.join("guide").join("docs")).unwrap();
+    fs::write(root.join("guide").join("mkdocs.yml"), "nav: []\n").unwrap();
+    fs::create_dir_all(root.join("legacy").join("docs")).unwrap();
+    fs::write(root.join("legacy").join("mkdocs.yaml"), "nav: []\n").unwrap();
+    // Newly added, not yet configured.
+    fs::create_dir_all(root.join("new-subsite").join("docs")).unwrap();
+    // A docs/ folder nested inside another docs/ is content, not a subsite.
+    fs::create_dir_all(root.join("guide").join("docs").join("api").join("docs")).unwrap();
+    fs::create_dir_all(root.join(".cache").join("docs")).unwrap();
+    // Vendored, and ignored.
+    fs::create_dir_all(root.join("node_modules").join("pkg").join("docs")).unwrap();
+    fs::write(root.join(".gitignore"), "node_modules/\n").unwrap();
+
+    let found = find_subsites_without_mkdocs(root, true);
+    assert_eq!(found, vec![normalize_path(&root.join("new-subsite"))]);
+
+    let found = find_subsites_without_mkdocs(root, false);
+    assert_eq!(
+        found,
+        vec![
+            normalize_path(&root.join("new-subsite")),
+            normalize_path(&root.join("node_modules").join("pkg")),
+        ]
+    );
+}
+
+#[test]
+fn test_find_duplicate_section_titles_by_slug() {
+    let nav = vec![
+        section("Examples", vec![page("One", "one.md")]),
+        section(
+            "Reference",
+            vec![
+                section("C++ API", vec![page("A", "a.md")]),
+                section("C API", vec![page("B", "b.md")]),
+                section("Python API", vec![page("C", "c.md")]),
+            ],
+        ),
+        section("examples", vec![page("Two", "two.md")]),
+    ];
+    assert_eq!(
+        find_duplicate_section_titles(&nav),
+        vec![
+            (vec!["Reference".to_string()], "C API".to_string()),
+            (Vec::new(), "examples".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_same_title_in_different_sections_is_fine() {
+    let nav = vec![
+        section("Guide", vec![section("Examples", vec![page("A", "a.md")])]),
+        section(
+            "Reference",
+            vec![section("Examples", vec![page("B", "b.md")])],
+        ),
+    ];
+    assert!(find_duplicate_section_titles(&nav).is_empty());
+}
+
+#[test]
+fn test_is_relay_page() {
+    let pure =
+        "---\ntitle: Topics\n---\n# Topics\n\n- [Arrays](arrays.md)\n- [Functions](functions.md)\n";
+    assert!(is_relay_page(pure));
+
+    let mixed = "# Topics\n\nStart with arrays, then move on.\n\n- [Arrays](arrays.md)\n";
+    assert!(!is_relay_page(mixed));
+
+    // No links at all: a stub, not a relay.
+    assert!(!is_relay_page("# Empty\n"));
+    assert!(!is_relay_page(""));
+}
+
+#[test]
+fn test_is_relay_page_with_threshold() {
+    let see_also = "# More\n\nSee also: [Arrays](arrays.md), [Functions](functions.md).\n";
+    assert!(!is_relay_page(see_also));
+    assert!(is_relay_page_with(see_also, 3));
+    assert!(!is_relay_page_with(see_also, 2));
+}
+
+#[test]
+fn test_word_count_ignores_markup() {
+    assert_eq!(
+        word_count("# A Long Title\n<!-- a comment of many words -->\n"),
+        0
+    );
+    assert_eq!(
+        word_count("---\ntitle: Guide\n---\n# Guide\n\n```apl\n1 2 3 + 4\n```\n"),
+        0
+    );
+    assert_eq!(
+        word_count("# Guide\n\nSee [the arrays page](arrays.md) and `⍴`, <b>bold</b>.\n"),
+        7
+    );
+}
+
+#[test]
+fn test_page_stats() {
+    let md = "---\nlinks: \"[x](y.md)\"\n---\n# Guide\n\nSee [arrays](arrays.md) and \
+              <a href=\"b.md\">b</a>.\n\n![Logo](logo.png)\n<img src=\"x.png\">\n\n\
+              ## Example\n\n```apl\n1 2 3\n```\n\n    indented code\n";
+    assert_eq!(
+        page_stats(md),
+        PageStats {
+            word_count: 5,
+            link_count: 2,
+            image_count: 2,
+            code_block_count: 2,
+            heading_count: 2,
+        }
+    );
+}
+
+#[test]
+fn test_find_non_md_nav_items() {
+    let nav = vec![
+        page("Home", "index.md"),
+        page("Shouting", "LOUD.MD"),
+        page("Readme", "README.rst"),
+        section(
+            "Guide",
+            vec![
+                page("Old", "old/page.html"),
+                NavItem::PlainPath("notes.txt".to_string()),
+                NavItem::PlainPath("plain.md".to_string()),
+                page("No extension", "intro"),
+            ],
+        ),
+        page("Sub", "!include ./sub/mkdocs.yml"),
+        page("Issues", "https://github.com/example/issues"),
+    ];
+    assert_eq!(
+        find_non_md_nav_items(&nav),
+        vec!["README.rst", "old/page.html", "notes.txt", "intro"]
+    );
+}
+
+#[test]
+fn test_h1_checks() {
+    let one = "# Title\n\n## Section\n";
+    assert!(!has_multiple_h1(one));
+    assert!(!has_h1_not_first(one));
+
+    let none = "## Section\n\n### Sub\n";
+    assert!(!has_multiple_h1(none));
+    assert!(!has_h1_not_first(none));
+
+    let two = "# Title\n\n## Section\n\n# Another title\n";
+    assert!(has_multiple_h1(two));
+    assert!(!has_h1_not_first(two));
+
+    let shell = "# Title\n\n```sh\n# install it\nmake install\n```\n";
+    assert!(!has_multiple_h1(shell));
+
+    let late = "## Preamble\n\n# Title\n";
+    assert!(!has_multiple_h1(late));
+    assert!(has_h1_not_first(late));
+
+    // Front matter's closing line isn't a setext heading.
+    let frontmatter = "---\ntitle: T\n---\n# Title\n";
+    assert!(!has_h1_not_first(frontmatter));
+}
+
+#[test]
+fn test_extract_css_class_selectors() {
+    let css = "/* .commented { } */\n\
+               .note > .md-nav__item--active, p.lead { margin: 0.5em; background: url(img/bg.png); }\n\
+               @media (min-width: 76.25em) {\n  .note .wide { width: 1.5rem; }\n}\n";
+    assert_eq!(
+        extract_css_class_selectors(css),
+        vec!["note", "md-nav__item--active", "lead", "wide"]
+    );
+}
+
+#[test]
+fn test_extract_used_classes() {
+    let md = "<div class=\"note wide\">Hi</div>\n\n\
+              [Start](start.md){ .md-button .md-button--primary }\n\n\
+              ## Title {: #title .lead }\n";
+    assert_eq!(
+        extract_used_classes(md),
+        vec!["note", "wide", "md-button", "md-button--primary", "lead"]
+    );
+}
+
+#[test]
+fn test_find_potentially_dead_css_selectors() {
+    let css = ".note { color: red; }\n.wy-nav-content { max-width: none; }\n\
+               .md-header { background: navy; }\n";
+    let used: HashSet<String> = ["note".to_string()].into_iter().collect();
+    assert_eq!(
+        find_potentially_dead_css_selectors(css, &used),
+        vec!["wy-nav-content"]
+    );
+}
+
+#[test]
+fn test_find_absolute_fs_image_refs_unix() {
+    let md = "![logo](/home/user/docs/images/logo.png)\n\
+              ![mac](/Users/jane/Desktop/shot.png)\n\
+              <img src=\"/tmp/export/chart.svg\" alt=\"Chart\">\n\
+              ![site](/img/logo.png)\n\
+              ![relative](img/logo.png)\n\
+              ![file](file:///home/user/diagram.png)\n";
+    assert_eq!(
+        find_absolute_fs_image_refs(md),
+        vec![
+            "/home/user/docs/images/logo.png",
+            "/Users/jane/Desktop/shot.png",
+            "/tmp/export/chart.svg",
+            "file:///home/user/diagram.png",
+        ]
+    );
+}
+
+#[test]
+fn test_find_absolute_fs_image_refs_windows() {
+    let md = "![a](C:\\Users\\jane\\logo.png)\n\
+              ![b](d:/docs/img/b.png)\n\
+              <img src=\"\\\\fileserver\\share\\c.png\" alt=\"C\">\n\
+              ![not a drive](cd:/x.png)\n";
+    assert_eq!(
+        find_absolute_fs_image_refs(md),
+        vec![
+            "C:\\Users\\jane\\logo.png",
+            "d:/docs/img/b.png",
+            "\\\\fileserver\\share\\c.png",
+        ]
+    );
+}
+
+#[test]
+fn test_help_url_for() {
+    let root = Path::new("/repo");
+    assert_eq!(
+        help_url_for(Path::new("/repo/guide/docs/topics/page.md"), root),
+        Some("guide/topics/page".to_string())
+    );
+    assert_eq!(help_url_for(Path::new("/repo/guide/page.md"), root), None);
+    assert_eq!(
+        help_url_for(Path::new("/elsewhere/guide/docs/a.md"), root),
+        None
+    );
+}
+
+#[test]
+fn test_find_private_ip_links_10() {
+    let md = "[a](http://10.0.0.5/wiki) [b](https://10.255.255.255:8443) [c](http://10.1.2/)";
+    assert_eq!(
+        find_private_ip_links(md),
+        vec!["http://10.0.0.5/wiki", "https://10.255.255.255:8443"]
+    );
+}
+
+#[test]
+fn test_find_private_ip_links_172_16() {
+    let md = "[a](http://172.16.1.1/) [b](http://172.31.0.9?x=1) \
+              [c](http://172.15.0.1/) [d](http://172.32.0.1/)";
+    assert_eq!(
+        find_private_ip_links(md),
+        vec!["http://172.16.1.1/", "http://172.31.0.9?x=1"]
+    );
+}
+
+#[test]
+fn test_find_private_ip_links_192_168() {
+    let md = "[a](https://192.168.1.20/admin) [b](http://192.169.1.20/)";
+    assert_eq!(
+        find_private_ip_links(md),
+        vec!["https://192.168.1.20/admin"]
+    );
+}
+
+#[test]
+fn test_find_private_ip_links_ignores_public_ips() {
+    let md = "[a](http://8.8.8.8/) [b](https://110.0.0.1/) [c](http://10.0.0.256/) \
+              [d](http://10.0.0.1.example.com/) [e](https://example.com/10.0.0.1) \
+              [f](http://127.0.0.1/)";
+    assert!(find_private_ip_links(md).is_empty());
+    assert_eq!(
+        GhostConfig::default().private_ip_links_severity,
+        Severity::Warning
+    );
+}
+
+#[test]
+fn test_find_case_sensitivity_issues() {
+    let pages: HashSet<PathBuf> = [
+        "docs/intro.md",
+        "docs/Intro.md",
+        "docs/INTRO.md",
+        "docs/guide.md",
+        "docs/Guide/setup.md",
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .collect();
+    assert_eq!(
+        find_case_sensitivity_issues(&pages),
+        vec![
+            (
+                PathBuf::from("docs/INTRO.md"),
+                PathBuf::from("docs/Intro.md")
+            ),
+            (
+                PathBuf::from("docs/INTRO.md"),
+                PathBuf::from("docs/intro.md")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_find_case_collisions() {
+    let pages: HashSet<PathBuf> = ["docs/foo.md", "docs/FOO.md", "docs/bar.md"]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+    assert_eq!(
+        find_case_collisions(&pages),
+        vec![(PathBuf::from("docs/FOO.md"), PathBuf::from("docs/foo.md"))]
+    );
+}
+
+#[test]
+fn test_mixed_image_ref_styles_consistent_pages() {
+    assert!(!has_mixed_image_ref_styles(
+        "![A](img/a.png)\n\n![B](../img/b.png)\n"
+    ));
+    assert!(!has_mixed_image_ref_styles(
+        "![A](/img/a.png)\n\n![B](/img/b.png)\n"
+    ));
+    assert!(!has_mixed_image_ref_styles(
+        "<img src=\"img/a.png\">\n\n<img src=\"img/b.png\">\n"
+    ));
+    assert!(!has_mixed_image_ref_styles("No images here."));
+}
+
+#[test]
+fn test_mixed_image_ref_styles_absolute_and_relative() {
+    assert!(has_mixed_image_ref_styles(
+        "![A](/img/a.png)\n\n![B](img/b.png)\n"
+    ));
+    assert!(has_mixed_image_ref_styles(
+        "<img src=\"/img/a.png\">\n\n<img src=\"../img/b.png\">\n"
+    ));
+}
+
+#[test]
+fn test_mixed_image_ref_styles_external_urls_are_neither() {
+    assert!(!has_mixed_image_ref_styles(
+        "![A](/img/a.png)\n\n![B](https://example.com/b.png)\n"
+    ));
+    assert!(!has_mixed_image_ref_styles(
+        "![A](img/a.png)\n\n![B](data:image/png;base64,iVBORw0KGgo=)\n"
+    ));
+}
+
+#[test]
+fn test_mixed_image_ref_styles_markdown_and_html() {
+    assert!(has_mixed_image_ref_styles(
+        "![A](img/a.png)\n\n<img src=\"img/b.png\" width=\"200\">\n"
+    ));
+    assert!(has_mixed_image_ref_styles(
+        "![A](https://example.com/a.png)\n\n<img src=\"https://example.com/b.png\">\n"
+    ));
+}
+
+#[test]
+fn test_find_scheme_relative_links() {
+    let md = "[a](//example.com/page.md) [b](/guide/page.md) [c](https://example.com/) \
+              [d](//guide/intro.md#setup) [e](page.md)";
+    assert_eq!(
+        find_scheme_relative_links(md),
+        vec!["//example.com/page.md", "//guide/intro.md#setup"]
+    );
+    assert_eq!(
+        GhostConfig::default().scheme_relative_links_severity,
+        Severity::Warning
+    );
+}
+
+#[test]
+fn test_normalise_links_skips_scheme_relative_links() {
+    let links = vec![
+        "//example.com/page.md".to_string(),
+        "/guide/page.md".to_string(),
+    ];
+    assert_eq!(normalise_links(links), vec!["/guide/page.md"]);
+}
+
+#[test]
+fn test_extract_frontmatter_todos_list() {
+    let md = "---\ntitle: Arrays\ntodo: [\"Add examples\", \"Fix broken link\"]\n---\n# Arrays\n";
+    assert_eq!(
+        extract_frontmatter_todos(md),
+        vec!["Add examples", "Fix broken link"]
+    );
+    let block = "---\ntodo:\n  - Add examples\n  - Fix broken link\n---\n";
+    assert_eq!(
+        extract_frontmatter_todos(block),
+        vec!["Add examples", "Fix broken link"]
+    );
+}
+
+#[test]
+fn test_extract_frontmatter_todos_scalar() {
+    let md = "---\ntodo: Rewrite the introduction\n---\n# Intro\n";
+    assert_eq!(
+        extract_frontmatter_todos(md),
+        vec!["Rewrite the introduction"]
+    );
+}
+
+#[test]
+fn test_extract_frontmatter_todos_none() {
+    assert!(extract_frontmatter_todos("---\ntitle: Done\n---\n# Done\n").is_empty());
+    assert!(extract_frontmatter_todos("---\ntodo: []\n---\n").is_empty());
+    // Only front matter counts, not a todo: line in the body.
+    assert!(extract_frontmatter_todos("# Page\n\ntodo: later\n").is_empty());
+}
+
+#[test]
+fn test_find_consecutive_blank_lines_boundaries() {
+    let md = "# Title\n\nOne.\n\n\nTwo.\n\n\n\nThree.\n";
+    // Runs of 1 (line 2), 2 (lines 4-5) and 3 (lines 7-9).
+    assert_eq!(find_consecutive_blank_lines(md, 0), vec![2, 4, 7]);
+    assert_eq!(find_consecutive_blank_lines(md, 1), vec![4, 7]);
+    assert_eq!(find_consecutive_blank_lines(md, 2), vec![7]);
+    assert!(find_consecutive_blank_lines(md, 3).is_empty());
+}
+
+#[test]
+fn test_find_consecutive_blank_lines_at_end_and_whitespace_only() {
+    // Lines holding only whitespace are blank; a run at the end of the file counts.
+    let md = "Text.\n  \n\t\nMore.\n\n\n";
+    assert_eq!(find_consecutive_blank_lines(md, 1), vec![2, 5]);
+}
+
+#[test]
+fn test_find_consecutive_blank_lines_skips_code_blocks() {
+    let md = "Intro.\n\n```python\nx = 1\n\n\n\ny = 2\n```\n\nOutro.\n";
+    assert!(find_consecutive_blank_lines(md, 1).is_empty());
+}
+
+#[test]
+fn test_find_include_only_sections() {
+    let nav = vec![
+        page("Home", "index.md"),
+        section(
+            "Products",
+            vec![
+                page("Dyalog", "!include ./dyalog/mkdocs.yml"),
+                page("RIDE", "!include ./ride/mkdocs.yml"),
+            ],
+        ),
+        section(
+            "Guides",
+            vec![
+                page("Overview", "guides.md"),
+                page("Install", "!include ./install/mkdocs.yml"),
+            ],
+        ),
+        section(
+            "Archive",
+            vec![section(
+                "2023",
+                vec![page("Notes", "!include ./notes-2023/mkdocs.yml")],
+            )],
+        ),
+        section("Empty", vec![]),
+    ];
+    assert_eq!(
+        find_include_only_sections(&nav),
+        vec![
+            vec!["Products".to_string()],
+            vec!["Archive".to_string(), "2023".to_string()],
+        ]
+    );
+}
+
+#[test]
+fn test_extract_scss_imports() {
+    let scss = r#"
+@use "sass:math";
+@use "variables" as vars;
+@forward 'mixins';
+@import "base/reset", "base/type";
+@import "theme.css";
+@import url("https://fonts.example.com/inter.css");
+@use "typography";
+// @use "disabled";
+/* @use "also-disabled"; */
+"#;
+    assert_eq!(
+        extract_scss_imports(scss),
+        vec![
+            "variables",
+            "mixins",
+            "base/reset",
+            "base/type",
+            "typography"
+        ]
+    );
+}
+
+#[test]
+fn test_theme_features_valid() {
+    let config: MkDocsConfig = serde_yaml::from_str(
+        "theme:\n  name: material\n  features:\n    - navigation.tabs\n    - content.code.copy\nnav: []\n",
+    )
+    .unwrap();
+    let theme = config.theme.unwrap();
+    assert_eq!(theme.features, vec!["navigation.tabs", "content.code.copy"]);
+    assert!(find_unknown_theme_features(&theme).is_empty());
+}
+
+#[test]
+fn test_theme_features_invalid() {
+    let theme = ThemeConfig {
+        name: Some("material".to_string()),
+        features: vec!["navigation.tab".to_string(), "toc.follow".to_string()],
+    };
+    assert_eq!(find_unknown_theme_features(&theme), vec!["navigation.tab"]);
+    // Other themes' features aren't Material's to judge.
+    let other = ThemeConfig {
+        name: Some("readthedocs".to_string()),
+        ..theme
+    };
+    assert!(find_unknown_theme_features(&other).is_empty());
+}
+
+#[test]
+fn test_theme_features_absent() {
+    let config: MkDocsConfig = serde_yaml::from_str("theme:\n  name: material\nnav: []\n").unwrap();
+    assert!(find_unknown_theme_features(&config.theme.unwrap()).is_empty());
+
+    let config: MkDocsConfig = serde_yaml::from_str("theme: readthedocs\nnav: []\n").unwrap();
+    assert_eq!(
+        config.theme,
+        Some(ThemeConfig {
+            name: Some("readthedocs".to_string()),
+            features: Vec::new(),
+        })
+    );
+
+    let config: MkDocsConfig = serde_yaml::from_str("nav: []\n").unwrap();
+    assert_eq!(config.theme, None);
+}
+
+#[test]
+fn test_detect_nav_reordering_within_section() {
+    let baseline = vec![
+        page("Home", "index.md"),
+        section(
+            "Guide",
+            vec![
+                page("Install", "guide/install.md"),
+                page("Usage", "guide/usage.md"),
+                page("FAQ", "guide/faq.md"),
+            ],
+        ),
+    ];
+    let current = vec![
+        page("Home", "index.md"),
+        section(
+            "Guide",
+            vec![
+                page("Usage", "guide/usage.md"),
+                page("New", "guide/new.md"),
+                page("Install", "guide/install.md"),
+                page("FAQ", "guide/faq.md"),
+            ],
+        ),
+    ];
+
+    let changes = detect_nav_reordering(&current, &baseline);
+
+    assert_eq!(
+        changes,
+        vec![NavOrderChange {
+            section: vec!["Guide".to_string()],
+            old_order: vec![
+                "Install".to_string(),
+                "Usage".to_string(),
+                "FAQ".to_string()
+            ],
+            new_order: vec![
+                "Usage".to_string(),
+                "Install".to_string(),
+                "FAQ".to_string()
+            ],
+        }]
+    );
+}
+
+#[test]
+fn test_detect_nav_reordering_ignores_additions_and_removals() {
+    let baseline = vec![page("Home", "index.md"), page("Old", "old.md")];
+    let current = vec![
+        page("New", "new.md"),
+        page("Home", "index.md"),
+        NavItem::PlainPath("extra.md".to_string()),
+    ];
+
+    assert!(detect_nav_reordering(&current, &baseline).is_empty());
+}
+
+#[test]
+fn test_find_empty_sections() {
+    let nav: Vec<NavItem> = serde_yaml::from_str(
+        "- Home: index.md\n- Empty: []\n- Guide:\n  - Inner: []\n  - A: a.md\n",
+    )
+    .unwrap();
+    assert!(matches!(&nav[1], NavItem::Section(map) if map["Empty"].is_empty()));
+
+    assert_eq!(
+        find_empty_sections(&nav),
+        vec!["Empty".to_string(), "Inner".to_string()]
+    );
+}
+
+#[test]
+fn test_nav_items_deserialize_leniently() {
+    let yaml = "- &home\n  Home: index.md\n- <<: *home\n- ~\n- 42\n- Guide: !include ./guide/mkdocs.yml\n- Section:\n  - a.md\n  - [b.md]\n";
+    let nav: Vec<NavItem> = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(nav.len(), 6);
+
+    assert!(matches!(&nav[1], NavItem::Page(map) if map["Home"] == "index.md"));
+    assert!(matches!(&nav[2], NavItem::Unrecognised(entry) if entry == "null"));
+    assert!(matches!(&nav[3], NavItem::PlainPath(path) if path == "42"));
+    assert!(matches!(&nav[4], NavItem::Page(map) if map["Guide"] == "!include ./guide/mkdocs.yml"));
+    let NavItem::Section(map) = &nav[5] else {
+        panic!("expected a section, got {:?}", nav[5]);
+    };
+    assert!(matches!(&map["Section"][0], NavItem::PlainPath(path) if path == "a.md"));
+    assert!(matches!(&map["Section"][1], NavItem::Unrecognised(entry) if entry == "- b.md"));
+
+    assert_eq!(
+        validate_nav_structure(&nav),
+        vec![
+            NavError::Unrecognised {
+                entry: "null".to_string()
+            },
+            NavError::Unrecognised {
+                entry: "- b.md".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_validate_nav_structure() {
+    let nav: Vec<NavItem> = serde_yaml::from_str(
+        "- Home: index.md\n- {B: b.md, A: a.md}\n- Guide:\n  - {D: d.md, C: c.md}\n  - E: e.md\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        validate_nav_structure(&nav),
+        vec![
+            NavError::MultipleKeysInPage {
+                keys: vec!["A".to_string(), "B".to_string()]
+            },
+            NavError::MultipleKeysInPage {
+                keys: vec!["C".to_string(), "D".to_string()]
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_find_duplicate_anchors() {
+    let md = "# Guide\n\n## Setup\n\n## Set-up\n\n## Usage {#setup}\n\n## Setup!\n";
+    assert_eq!(
+        find_duplicate_anchors(md),
+        vec![("setup".to_string(), 3, 7), ("setup".to_string(), 3, 9)]
+    );
+    assert!(find_duplicate_anchors("## Notes\n\n## Notes 2\n").is_empty());
+}
+
+#[test]
+fn test_heading_anchors() {
+    let md = "# Getting Started\n\n## Set-up & Install\n\n## Notes\n\n## Notes\n\n## Custom {#my-id}\n\n<a name=\"legacy\"></a>\n";
+    let anchors = heading_anchors(md);
+    for anchor in [
+        "getting-started",
+        "set-up-install",
+        "notes",
+        "notes_1",
+        "my-id",
+        "legacy",
+    ] {
+        assert!(
+            anchors.contains(anchor),
+            "{anchor} missing from {anchors:?}"
+        );
+    }
+    assert_eq!(anchors.len(), 6);
+}
+
+#[test]
+fn test_load_allowlist_reads_toml() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("allowlist.toml");
+    fs::write(
+        &path,
+        "# known\n\"docs/a.md\" = [\"x.md\", 'y.md',\n  \"z.md\"]\n'docs/b.md' = []\n",
+    )
+    .unwrap();
+
+    let allowlist = load_allowlist(&path).unwrap();
+    assert_eq!(allowlist[Path::new("docs/a.md")], ["x.md", "y.md", "z.md"]);
+    assert!(allowlist[Path::new("docs/b.md")].is_empty());
+}
+
+#[test]
+fn test_load_allowlist_rejects_a_page_without_a_list() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("allowlist.toml");
+    fs::write(
+        &path,
+        "\"docs/a.md\" = [\"x.md\"]\n\"docs/b.md\" = \"y.md\"\n",
+    )
+    .unwrap();
+
+    assert!(matches!(
+        load_allowlist(&path),
+        Err(GhostError::Allowlist(_))
+    ));
+}
+
+#[test]
+fn test_apply_allowlist_drops_only_listed_links() {
+    let link = |from: &str, link: &str| BrokenLink {
+        from: PathBuf::from(from),
+        link: link.to_string(),
+        help_refs: vec![],
+        suggestions: vec![],
+    };
+    let mut result = AuditResult {
+        broken_links: vec![
+            link("/site/docs/a.md", "x.md"),
+            link("/site/docs/a.md", "y.md"),
+            link("/site/docs/b.md", "x.md"),
+        ],
+        ..Default::default()
+    };
+    let allowlist = HashMap::from([(PathBuf::from("docs/a.md"), vec!["x.md".to_string()])]);
+
+    apply_allowlist(&mut result, &allowlist);
+    assert_eq!(
+        result.broken_links,
+        vec![
+            link("/site/docs/a.md", "y.md"),
+            link("/site/docs/b.md", "x.md")
+        ]
+    );
+}
+
+#[test]
+fn test_diff_audit_results_reports_new_and_fixed() {
+    let link = |from: &str, link: &str, help: &str| BrokenLink {
+        from: PathBuf::from(from),
+        link: link.to_string(),
+        help_refs: if help.is_empty() {
+            vec![]
+        } else {
+            vec![HelpRef {
+                line: 1,
+                text: help.to_string(),
+            }]
+        },
+        suggestions: vec![],
+    };
+    let baseline = AuditResult {
+        ghost: vec![PathBuf::from("docs/old.md"), PathBuf::from("docs/kept.md")],
+        broken_links: vec![link("docs/a.md", "x.md", ""), link("docs/a.md", "y.md", "")],
+        ..Default::default()
+    };
+    let current = AuditResult {
+        ghost: vec![PathBuf::from("docs/kept.md"), PathBuf::from("docs/new.md")],
+        broken_links: vec![
+            link("docs/a.md", "y.md", "HELP_URL(a)"),
+            link("docs/b.md", "x.md", ""),
+        ],
+        ..Default::default()
+    };
+
+    let diff = diff_audit_results(&baseline, &current);
+    assert_eq!(diff.new.ghost, vec![PathBuf::from("docs/new.md")]);
+    assert_eq!(diff.fixed.ghost, vec![PathBuf::from("docs/old.md")]);
+    assert_eq!(diff.new.broken_links, vec![link("docs/b.md", "x.md", "")]);
+    assert_eq!(diff.fixed.broken_links, vec![link("docs/a.md", "x.md", "")]);
+    assert!(
+        diff_audit_results(&current, &current)
+            .new
+            .broken_links
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_external_links_drops_fragments_and_internal_links() {
+    let md = "[a](https://example.com/page#part) [b](page.md) [c](http://example.org)\n\
+              <a href=\"https://example.net/x\">d</a> [e](mailto:me@example.com)";
+    assert_eq!(
+        external_links(md),
+        vec![
+            "https://example.com/page",
+            "http://example.org",
+            "https://example.net/x"
+        ]
+    );
+}
+
+#[test]
+fn test_external_link_cache_answers_within_ttl() {
+    let mut cache = ExternalLinkCache::default();
+    let hour = Duration::from_secs(60 * 60);
+    cache.urls.insert(
+        "https://fresh.example".to_string(),
+        (SystemTime::now(), Some("404 Not Found".to_string())),
+    );
+    cache.urls.insert(
+        "https://stale.example".to_string(),
+        (SystemTime::now() - 2 * hour, None),
+    );
+
+    assert_eq!(
+        cache.answer("https://fresh.example", hour),
+        Some(&Some("404 Not Found".to_string()))
+    );
+    assert_eq!(cache.answer("https://stale.example", hour), None);
+    assert_eq!(cache.answer("https://stale.example", 3 * hour), Some(&None));
+}
+
+#[test]
+fn test_broken_external_links_come_from_cache_without_requests() {
+    let page = PathBuf::from("/site/docs/a.md");
+    let pages = vec![(
+        page.clone(),
+        "[gone](https://gone.example/) [fine](https://fine.example/)".to_string(),
+    )];
+    let mut cache = ExternalLinkCache::default();
+    for (url, error) in [
+        ("https://gone.example/", Some("404 Not Found".to_string())),
+        ("https://fine.example/", None),
+    ] {
+        cache
+            .urls
+            .insert(url.to_string(), (SystemTime::now(), error));
+    }
+
+    assert_eq!(
+        find_broken_external_links(&pages, &AuditOptions::default(), &mut cache),
+        vec![ExternalBrokenLink {
+            from: page,
+            url: "https://gone.example/".to_string(),
+            error: "404 Not Found".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_to_dot_colours_nodes() {
+    let root = Path::new("/site");
+    let page = |name: &str| root.join("docs").join(name);
+    let graph = HashMap::from([
+        (
+            page("index.md"),
+            vec![page("linked.md"), page("say \"hi\".md")],
+        ),
+        (page("linked.md"), vec![]),
+    ]);
+    let nav_pages = HashSet::from([page("index.md")]);
+
+    assert_eq!(
+        to_dot(&graph, Some(root), &nav_pages, &[page("ghost.md")]),
+        r#"digraph pages {
+    node [shape=box];
+    "docs/ghost.md" [color=red, fontcolor=red];
+    "docs/index.md" [color=blue, fontcolor=blue];
+    "docs/linked.md" [color=grey, fontcolor=grey];
+    "docs/say \"hi\".md" [color=grey, fontcolor=grey];
+    "docs/index.md" -> "docs/linked.md";
+    "docs/index.md" -> "docs/say \"hi\".md";
+}
+"#
+    );
+}
+
+#[test]
+fn test_merge_concatenates_and_dedups() {
+    let a = AuditResult {
+        ghost: vec![PathBuf::from("one/docs/a.md"), PathBuf::from("shared.md")],
+        pages_with_todos: vec![(PathBuf::from("one/docs/a.md"), vec!["fix".to_string()])],
+        ..Default::default()
+    };
+    let b = AuditResult {
+        ghost: vec![PathBuf::from("shared.md"), PathBuf::from("two/docs/b.md")],
+        nav_missing: vec![PathBuf::from("two/docs/c.md")],
+        ..Default::default()
+    };
+
+    let merged = a.clone().merge(b);
+    assert_eq!(
+        merged.ghost,
+        vec![
+            PathBuf::from("one/docs/a.md"),
+            PathBuf::from("shared.md"),
+            PathBuf::from("two/docs/b.md"),
+        ]
+    );
+    assert_eq!(merged.nav_missing, vec![PathBuf::from("two/docs/c.md")]);
+    assert_eq!(merged.pages_with_todos, a.pages_with_todos);
+    assert_eq!(a.clone().merge(a.clone()), a);
+}
+
+#[test]
+fn test_total_issues_skips_informational_lists() {
+    let mut result = AuditResult {
+        pages_with_footnotes: vec![PathBuf::from("a.md")],
+        pages_with_images: vec![PathBuf::from("a.md")],
+        pages_with_links: vec![PathBuf::from("a.md")],
+        page_links: vec![(PathBuf::from("a.md"), vec![PathBuf::from("b.md")])],
+        ..Default::default()
+    };
+    let config = GhostConfig::default();
+    assert_eq!(result.total_issues(), 0);
+    assert!(result.is_clean());
+    assert_eq!(result.total_issues_with_config(&config), 0);
+
+    result.ghost.push(PathBuf::from("c.md"));
+    result.stub_pages.push(PathBuf::from("a.md"));
+    result
+        .unknown_theme_features
+        .push("navigation.teleport".to_string());
+    assert_eq!(result.total_issues(), 3);
+    assert_eq!(result.total_issues_with_config(&config), 3);
+    assert!(!result.is_clean());
+}
+
+#[test]
+fn test_total_issues_counts_every_report() {
+    let page = || PathBuf::from("a.md");
+    let result = AuditResult {
+        relay_pages: vec![page()],
+        case_collisions: vec![(PathBuf::from("A.md"), page())],
+        stub_pages: vec![page()],
+        missing_frontmatter_fields: vec![(page(), vec!["title".to_string()])],
+        pages_with_unlabelled_code: vec![page()],
+        pages_with_todos: vec![(page(), vec!["TODO".to_string()])],
+        ..Default::default()
+    };
+    assert_eq!(result.total_issues(), 6);
+    assert!(!result.is_clean());
+    // Frontmatter todos are a warning by default.
+    assert_eq!(result.total_issues_with_config(&GhostConfig::default()), 5);
+
+    // Every serialised field is either counted or one of the lists left out on purpose.
+    let informational = [
+        "pages_with_footnotes",
+        "pages_with_images",
+        "pages_with_links",
+        "page_links",
+        "help_url_fragments",
+        "nav_missing_link_sources",
+        "stale_prefix_links",
+    ];
+    let serde_json::Value::Object(fields) = serde_json::to_value(AuditResult::default()).unwrap()
+    else {
+        panic!("AuditResult serialises to an object");
+    };
+    let mut uncounted: Vec<&str> = fields
+        .keys()
+        .map(String::as_str)
+        .filter(|field| !informational.contains(field))
+        .collect();
+    let mut counted: Vec<&str> = result
+        .issue_counts()
+        .into_iter()
+        .map(|(field, _)| field)
+        .collect();
+    uncounted.sort();
+    counted.sort();
+    assert_eq!(counted, uncounted);
+}
+
+#[test]
+fn test_total_issues_leaves_out_warnings() {
+    let link = (PathBuf::from("a.md"), "http://10.0.0.1/".to_string());
+    let result = AuditResult {
+        localhost_links: vec![(PathBuf::from("a.md"), "http://localhost/".to_string())],
+        private_ip_links: vec![link.clone()],
+        scheme_relative_links: vec![link],
+        pages_with_todos: vec![(PathBuf::from("a.md"), vec!["TODO".to_string()])],
+        ..Default::default()
+    };
+    let config = GhostConfig::default();
+    assert_eq!(result.total_issues_with_config(&config), 1);
+    assert_eq!(result.total_warnings(&config), 3);
+
+    let strict = GhostConfig {
+        private_ip_links_severity: Severity::Error,
+        pages_with_todos_severity: Severity::Error,
+        ..GhostConfig::default()
+    };
+    assert_eq!(result.total_issues_with_config(&strict), 3);
+    assert_eq!(result.total_warnings(&strict), 1);
+}
+
+#[test]
+fn test_into_flat_issues() {
+    let result = AuditResult {
+        ghost: vec![PathBuf::from("docs/stray.md")],
+        nav_missing: vec![PathBuf::from("docs/gone.md")],
+        broken_links: vec![BrokenLink {
+            from: PathBuf::from("docs/a.md"),
+            link: "b.md".to_string(),
+            help_refs: vec![],
+            suggestions: vec![],
+        }],
+        missing_images: vec![BrokenImage {
+            from: PathBuf::from("docs/a.md"),
+            image: "x.png".to_string(),
+        }],
+        stub_pages: vec![PathBuf::from("docs/a.md")],
+        ..Default::default()
+    };
+
+    let issues: Vec<Issue> = result.into_flat_issues().collect();
+    assert_eq!(
+        issues,
+        vec![
+            Issue::NavMissing(PathBuf::from("docs/gone.md")),
+            Issue::Ghost(PathBuf::from("docs/stray.md")),
+            Issue::BrokenLink {
+                from: PathBuf::from("docs/a.md"),
+                link: "b.md".to_string(),
+                from_help_url: false,
+            },
+            Issue::MissingImage {
+                from: PathBuf::from("docs/a.md"),
+                image: "x.png".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_absolute_links_use_site_url_path() {
+    let config = |site_url: &str| MkDocsConfig {
+        nav: vec![],
+        site_name: None,
+        site_dir: None,
+        site_description: None,
+        site_author: None,
+        theme: None,
+        docs_dir: None,
+        site_url: Some(site_url.to_string()),
+    };
+    assert_eq!(
+        config("https://docs.example.com/en/").site_path(),
+        Some("/en/")
+    );
+    assert_eq!(config("https://docs.example.com").site_path(), Some("/"));
+
+    let root = Path::new("/base");
+    let src = root.join("guide").join("docs").join("page.md");
+    let subsites = SiteLayout {
+        mounts: HashMap::from([("reference".to_string(), root.join("reference"))]),
+        ..SiteLayout::default()
+    };
+    let expected = vec![root.join("reference").join("docs").join("intro.md")];
+    let resolve = |link: &str, site_path: Option<&str>| {
+        resolve_link_via_url_space(&src, link, root, site_path.map(Path::new), &subsites)
+    };
+    assert_eq!(resolve("/reference/intro", None), expected);
+    assert_eq!(resolve("/en/reference/intro", Some("/en/")), expected);
+    assert!(resolve("/reference/intro", Some("/en/")).is_empty());
+}
+
+#[test]
+fn test_filter_by_subsite_matches_globs() {
+    let root = Path::new("/base");
+    let page = |subsite: &str| root.join(subsite).join("docs").join("index.md");
+    let result = AuditResult {
+        ghost: vec![
+            page("guide"),
+            page("guide-legacy"),
+            page("archived-2019"),
+            page("archive"),
+        ],
+        ..Default::default()
+    };
+
+    let result = result.filter_by_subsite(&["*-legacy", "archived-*"], root);
+    assert_eq!(result.ghost, vec![page("guide"), page("archive")]);
+}
+
+#[test]
+fn test_extract_ghost_directives() {
+    let md = "See [A](a.md) and <!-- ghost: ignore-next-link --> [B](future.md).\n\n\
+              <!-- ghost: ignore-next-link -->\n\n[C](c.md)\n";
+    let directives = extract_ghost_directives(md);
+    assert!(!directives.ignore_all_links);
+    assert_eq!(directives.ignored_links, vec!["future.md".to_string()]);
+
+    let md = "---\ntitle: Next release\n---\n<!-- ghost: ignore-all-links -->\n\n[A](a.md)\n";
+    assert!(extract_ghost_directives(md).ignore_all_links);
+    let md = "# Title\n\n<!-- ghost: ignore-all-links -->\n";
+    assert!(!extract_ghost_directives(md).ignore_all_links);
+}
+
+#[test]
+fn test_without_ignored_links() {
+    let links = vec!["a.md".to_string(), "b.md".to_string(), "b.md".to_string()];
+    let directives = GhostDirectives {
+        ignore_all_links: false,
+        ignored_links: vec!["b.md".to_string()],
+    };
+    assert_eq!(
+        without_ignored_links(links.clone(), directives),
+        vec!["a.md".to_string(), "b.md".to_string()]
+    );
+    let all = GhostDirectives {
+        ignore_all_links: true,
+        ignored_links: vec![],
+    };
+    assert!(without_ignored_links(links, all).is_empty());
+}
+
+#[test]
+fn test_other_report_items_lists_every_report_beyond_the_main_ones() {
+    let root = Path::new("/site");
+    let result = AuditResult {
+        ghost: vec![root.join("guide/docs/stray.md")],
+        stub_pages: vec![root.join("guide/docs/empty.md")],
+        case_collisions: vec![(root.join("guide/docs/A.md"), root.join("guide/docs/a.md"))],
+        pages_with_footnotes: vec![root.join("guide/docs/notes.md")],
+        ..Default::default()
+    };
+    let items = result.other_report_items(Some(root));
+    assert_eq!(
+        items,
+        BTreeMap::from([
+            (
+                "case_collisions".to_string(),
+                vec![serde_json::json!(["guide/docs/A.md", "guide/docs/a.md"])]
+            ),
+            (
+                "stub_pages".to_string(),
+                vec![serde_json::json!("guide/docs/empty.md")]
+            ),
+        ])
+    );
+    let listed: usize = items.values().map(Vec::len).sum();
+    assert_eq!(listed + result.ghost.len(), result.total_issues());
+}
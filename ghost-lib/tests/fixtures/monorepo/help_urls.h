@@ -0,0 +1,7 @@
+#define GD "guide"
+#define REF "reference/topics"
+
+HELP_URL("home", GD"/index")
+HELP_URL("start", GD"/topics/page-01")
+HELP_URL("lookup", REF"/page-05")
+HELP_URL("tutorial", "tutorials/topics/page-10")
@@ -0,0 +1,178 @@
+//! End-to-end audits of the monorepo fixture in `tests/fixtures/monorepo`: three
+//! subsites (guide, reference, tutorials) of 20 pages each, with cross-subsite links,
+//! images, CSS and a help_urls.h. The fixture is clean; each scenario copies it to a
+//! temp dir and breaks one thing.
+
+use ghost_lib::{AuditOptions, AuditResult, GhostConfig, audit, audit_with_options};
+use rstest::{fixture, rstest};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("monorepo")
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), &target).unwrap();
+        }
+    }
+}
+
+fn audit_fixture(root: &Path) -> AuditResult {
+    audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap()
+}
+
+#[fixture]
+fn clean_result() -> AuditResult {
+    audit_fixture(&fixture_root())
+}
+
+#[rstest]
+fn test_clean_fixture_has_no_issues(clean_result: AuditResult) {
+    assert!(
+        clean_result.nav_missing.is_empty(),
+        "{:?}",
+        clean_result.nav_missing
+    );
+    assert!(clean_result.ghost.is_empty(), "{:?}", clean_result.ghost);
+    assert!(
+        clean_result.help_missing.is_empty(),
+        "{:?}",
+        clean_result.help_missing
+    );
+    assert!(
+        clean_result.broken_links.is_empty(),
+        "{:?}",
+        clean_result.broken_links
+    );
+    assert!(
+        clean_result.missing_images.is_empty(),
+        "{:?}",
+        clean_result.missing_images
+    );
+    assert!(
+        clean_result.orphan_images.is_empty(),
+        "{:?}",
+        clean_result.orphan_images
+    );
+    assert_eq!(
+        clean_result.total_issues_with_config(&GhostConfig::default()),
+        0
+    );
+}
+
+#[rstest]
+fn test_clean_fixture_scans_every_page(clean_result: AuditResult) {
+    assert_eq!(clean_result.pages_with_links.len(), 60);
+    assert_eq!(clean_result.pages_with_images.len(), 3 * 4);
+}
+
+#[test]
+fn test_clean_fixture_has_no_duplicate_images() {
+    let root = fixture_root();
+    let options = AuditOptions::builder().check_duplicate_images(true).build();
+    let result = audit_with_options(
+        &root.join("mkdocs.yml"),
+        &root.join("help_urls.h"),
+        &options,
+    )
+    .unwrap();
+    assert!(
+        result.duplicate_image_files.is_empty(),
+        "{:?}",
+        result.duplicate_image_files
+    );
+    assert!(result.is_clean());
+}
+
+/// One way of breaking the fixture.
+#[derive(Debug)]
+enum Breakage {
+    /// Append a link to a page that doesn't exist.
+    BrokenLink,
+    /// Add an image no page or stylesheet refers to.
+    OrphanImage,
+    /// Add a link with an upper-case letter, which the production server won't serve.
+    MixedCaseLink,
+}
+
+#[rstest]
+#[case::broken_link(Breakage::BrokenLink)]
+#[case::orphan_image(Breakage::OrphanImage)]
+#[case::mixed_case_link(Breakage::MixedCaseLink)]
+fn test_breakage_is_reported(#[case] breakage: Breakage) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    copy_dir(&fixture_root(), root);
+
+    let page = root
+        .join("reference")
+        .join("docs")
+        .join("topics")
+        .join("page-07.md");
+    let append = |text: &str| {
+        let mut content = fs::read_to_string(&page).unwrap();
+        content.push_str(text);
+        fs::write(&page, content).unwrap();
+    };
+    match breakage {
+        Breakage::BrokenLink => append("\nSee [the missing page](page-99.md).\n"),
+        Breakage::OrphanImage => fs::write(
+            root.join("tutorials")
+                .join("docs")
+                .join("img")
+                .join("unused.png"),
+            "",
+        )
+        .unwrap(),
+        Breakage::MixedCaseLink => append("\nSee [the next page](Page-08.md).\n"),
+    }
+
+    let result = audit_fixture(root);
+
+    match breakage {
+        Breakage::BrokenLink | Breakage::MixedCaseLink => {
+            assert_eq!(result.broken_links.len(), 1, "{:?}", result.broken_links);
+            assert!(
+                result.broken_links[0]
+                    .from
+                    .ends_with("reference/docs/topics/page-07.md")
+            );
+            assert!(
+                result.orphan_images.is_empty(),
+                "{:?}",
+                result.orphan_images
+            );
+        }
+        Breakage::OrphanImage => {
+            assert_eq!(result.orphan_images.len(), 1, "{:?}", result.orphan_images);
+            assert!(result.orphan_images[0].ends_with("tutorials/docs/img/unused.png"));
+            assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+        }
+    }
+}
+
+#[rstest]
+fn test_include_cycle_is_detected() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    copy_dir(&fixture_root(), root);
+
+    let tutorials = root.join("tutorials").join("mkdocs.yml");
+    let mut config = fs::read_to_string(&tutorials).unwrap();
+    config.push_str("  - Back to the start: '!include ../mkdocs.yml'\n");
+    fs::write(&tutorials, config).unwrap();
+
+    let err = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap_err();
+    assert!(err.to_string().contains("circular !include"), "{err}");
+}
@@ -0,0 +1,2989 @@
+//! Audits of small docs trees built in temp dirs, one scenario per test, through the
+//! public API.
+
+use ghost_lib::{
+    AuditOptions, AuditResult, BrokenAnchor, BrokenImage, BrokenLink, GHOST_TOML, GHOSTIGNORE,
+    GhostConfig, HeadingError, HelpRef, HelpUrlEntry, NavItem, NavNode, Progress, ProjectConfig,
+    TitleCaseStyle, TraceOptions, audit, audit_multiple, audit_traced, audit_with_config,
+    audit_with_options, build_link_maps, collect_pages, detect_nav_reordering, extract_help_urls,
+    find_cross_file_help_url_duplicates, find_duplicate_images, find_duplicate_nav_entries,
+    find_orphan_mkdocs_files, find_oversized_svgs, find_scss_import_cycles,
+    find_svgs_with_embedded_rasters, load_audit_result, load_config, load_mkdocs_nav,
+    load_nav_baseline, nav_tree, page_dependency_graph, resolve_link, save_audit_result,
+    save_nav_baseline, to_sarif,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[test]
+fn test_collect_pages_with_include() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let prefix = temp_dir.path();
+
+    let include_dir = prefix.join("release-notes");
+    fs::create_dir_all(include_dir.join("docs")).unwrap();
+    let include_yaml = r#"
+nav:
+  - Child Page: child.md
+"#;
+    fs::write(include_dir.join("mkdocs.yml"), include_yaml).unwrap();
+
+    let nav = vec![NavItem::Page({
+        let mut map = HashMap::new();
+        map.insert(
+            "Release Notes".to_string(),
+            "!include ./release-notes/mkdocs.yml".to_string(),
+        );
+        map
+    })];
+
+    let mut pages = HashSet::new();
+
+    collect_pages(&nav, &mut pages, prefix).unwrap();
+
+    assert_eq!(pages.len(), 1);
+    assert!(pages.contains(&include_dir.join("docs").join("child.md")));
+}
+
+#[test]
+fn test_build_link_maps_with_include_and_resolution() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let root_docs = root.join("docs");
+    fs::create_dir_all(root_docs.join("dir")).unwrap();
+    fs::write(root_docs.join("a.md"), "# A").unwrap();
+    fs::write(root_docs.join("dir").join("b.md"), "# B").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let inc_dir = root.join("release-notes");
+    fs::create_dir_all(inc_dir.join("docs")).unwrap();
+    fs::write(inc_dir.join("docs").join("child.md"), "# Child").unwrap();
+
+    let inc_mkdocs = r#"
+nav:
+  - Child: child.md
+"#;
+    fs::write(inc_dir.join("mkdocs.yml"), inc_mkdocs).unwrap();
+
+    let nav = vec![
+        NavItem::Page({
+            let mut m = HashMap::new();
+            m.insert("A".to_string(), "a.md".to_string());
+            m
+        }),
+        NavItem::Page({
+            let mut m = HashMap::new();
+            m.insert("B".to_string(), "dir/b.md".to_string());
+            m
+        }),
+        NavItem::Page({
+            let mut m = HashMap::new();
+            m.insert(
+                "Include".to_string(),
+                "!include ./release-notes/mkdocs.yml".to_string(),
+            );
+            m
+        }),
+    ];
+
+    let maps = build_link_maps(&nav, root).unwrap();
+    let keys: Vec<String> = maps.url_to_src.keys().cloned().collect();
+    assert!(
+        keys.contains(&"release-notes/child".to_string()),
+        "keys: {:?}",
+        keys
+    );
+    assert_eq!(
+        maps.url_to_src.get("a").unwrap(),
+        &root.join("docs").join("a.md")
+    );
+    assert_eq!(
+        maps.url_to_src.get("release-notes/child").unwrap(),
+        &inc_dir.join("docs").join("child.md")
+    );
+
+    let from_src = root_docs.join("dir").join("b.md");
+    let target = resolve_link(&from_src, "../a.md", &maps).unwrap();
+    assert_eq!(target, root_docs.join("a.md"));
+
+    let target2 = resolve_link(&from_src, "/release-notes/child.md", &maps).unwrap();
+    assert_eq!(target2, inc_dir.join("docs").join("child.md"));
+}
+
+#[test]
+fn test_ghost_removed_when_linked() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("a.md"), "[Link](orphan.md)").unwrap();
+    fs::write(docs.join("orphan.md"), "# Orphan").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let mkdocs = r#"
+nav:
+  - A: a.md
+"#;
+    fs::write(root.join("mkdocs.yml"), mkdocs).unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(!result.ghost.contains(&docs.join("orphan.md")));
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_relative_parent_link_with_anchor_resolves() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs").join("primitive-operators");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(
+        docs.join("beside.md"),
+        "see [Function Composition](../operator-syntax#function-composition)",
+    )
+    .unwrap();
+    fs::write(
+        root.join("docs").join("operator-syntax.md"),
+        "# Operator Syntax",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let mkdocs = r#"
+nav:
+  - Beside: primitive-operators/beside.md
+"#;
+    fs::write(root.join("mkdocs.yml"), mkdocs).unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_non_nav_fs_link_resolves() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs").join("primitive-operators");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(
+        docs.join("beside.md"),
+        "see [Function Composition](../operator-syntax#function-composition)",
+    )
+    .unwrap();
+    // target exists on filesystem but is not in nav
+    fs::write(
+        root.join("docs").join("operator-syntax.md"),
+        "# Operator Syntax",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let mkdocs = r#"
+nav:
+  - Beside: primitive-operators/beside.md
+"#;
+    fs::write(root.join("mkdocs.yml"), mkdocs).unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_extract_help_urls_ignores_comments() {
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        r#"
+// This is a comment with HELP_URL("test", "fake/path")
+#define SY "language-reference-guide/symbols"
+HELP_URL(",", SY"/comma")
+"#
+    )
+    .unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let doc_root = temp_dir.path();
+
+    let result = extract_help_urls(temp_file.path(), doc_root).unwrap();
+
+    // Should have 1 URL (the comma one), not 2 (comment should be ignored)
+    assert_eq!(result.len(), 1);
+    assert!(
+        result[0]
+            .path
+            .to_string_lossy()
+            .contains("language-reference-guide/docs/symbols/comma.md")
+    );
+}
+
+#[test]
+fn test_extract_help_urls_expands_macros() {
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        r#"
+#define SY "language-reference-guide/symbols"
+HELP_URL(",", SY"/comma")
+"#
+    )
+    .unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let doc_root = temp_dir.path();
+
+    let result = extract_help_urls(temp_file.path(), doc_root).unwrap();
+
+    assert_eq!(result.len(), 1);
+    let path_str = result[0].path.to_string_lossy();
+    assert!(path_str.contains("language-reference-guide/docs/symbols/comma.md"));
+}
+
+#[test]
+fn test_extract_help_urls_injects_docs() {
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        r#"
+HELP_URL(":if", "programming-reference-guide/defined-functions-and-operators/traditional-functions-and-operators/control-structures/if")
+"#
+    )
+    .unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let doc_root = temp_dir.path();
+
+    let result = extract_help_urls(temp_file.path(), doc_root).unwrap();
+
+    assert_eq!(result.len(), 1);
+    let path_str = result[0].path.to_string_lossy();
+    assert!(path_str.contains("/docs/"));
+    assert!(path_str.contains("programming-reference-guide/docs/defined-functions-and-operators"));
+}
+
+#[test]
+fn test_adjacent_nav_pages_resolve_parent_link() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs").join("primitive-operators");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(
+        docs.join("beside.md"),
+        "see [Operator Syntax](../operator-syntax.md)",
+    )
+    .unwrap();
+    fs::write(
+        root.join("docs").join("operator-syntax.md"),
+        "# Operator Syntax",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let mkdocs = r#"
+nav:
+  - Operator Syntax: operator-syntax.md
+  - Beside: primitive-operators/beside.md
+"#;
+    fs::write(root.join("mkdocs.yml"), mkdocs).unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_cross_subsite_link_resolves() {
+    // Simulates monorepo structure:
+    //   root/
+    //     release-notes/docs/new-enhanced.md  (links to programming-reference-guide)
+    //     programming-reference-guide/docs/introduction/arrays/array-notation.md
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+
+    // Create release-notes subsite.
+    // NB: cross-subsite links must be written *without* a `.md` extension —
+    // MkDocs/monorepo only rewrites `.md` links that stay within a subsite, so a
+    // cross-subsite `.md` href 404s on the live site (see issue #876). The valid
+    // form is the directory-style URL below.
+    let rn_docs = root.join("release-notes").join("docs");
+    fs::create_dir_all(&rn_docs).unwrap();
+    fs::write(
+        rn_docs.join("new-enhanced.md"),
+        "see [Array Notation](../../programming-reference-guide/introduction/arrays/array-notation)",
+    )
+    .unwrap();
+    let rn_mkdocs = r#"
+nav:
+  - New: new-enhanced.md
+"#;
+    fs::write(root.join("release-notes").join("mkdocs.yml"), rn_mkdocs).unwrap();
+
+    // Create programming-reference-guide subsite
+    let prg_docs = root
+        .join("programming-reference-guide")
+        .join("docs")
+        .join("introduction")
+        .join("arrays");
+    fs::create_dir_all(&prg_docs).unwrap();
+    fs::write(prg_docs.join("array-notation.md"), "# Array Notation").unwrap();
+    let prg_mkdocs = r#"
+nav:
+  - Introduction:
+    - Arrays:
+      - Array Notation: introduction/arrays/array-notation.md
+"#;
+    fs::write(
+        root.join("programming-reference-guide").join("mkdocs.yml"),
+        prg_mkdocs,
+    )
+    .unwrap();
+
+    // Create root mkdocs.yml that includes both subsites
+    let root_mkdocs = r#"
+nav:
+  - Release Notes: '!include ./release-notes/mkdocs.yml'
+  - Programming Reference: '!include ./programming-reference-guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_sibling_file_via_parent_link_resolves() {
+    // A *bare* (extensionless) link is served as a directory URL and resolved by the
+    // browser page-as-directory: `../aplan-for-editor` from page `config-params/aplan-for-output`
+    // resolves to `config-params/aplan-for-editor`. This mirrors the real docs, where
+    // the link is written without a `.md` extension. (An explicit `.md` here would be
+    // broken — MkDocs resolves `.md` links source-relative, see issue #876.)
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+
+    let docs = root
+        .join("windows-guide")
+        .join("docs")
+        .join("config-params");
+    fs::create_dir_all(&docs).unwrap();
+
+    fs::write(
+        docs.join("aplan-for-output.md"),
+        "see [Editor](../aplan-for-editor)",
+    )
+    .unwrap();
+    fs::write(docs.join("aplan-for-editor.md"), "# Editor").unwrap();
+
+    let mkdocs = r#"
+nav:
+  - Config:
+    - Output: config-params/aplan-for-output.md
+    - Editor: config-params/aplan-for-editor.md
+"#;
+    fs::write(root.join("windows-guide").join("mkdocs.yml"), mkdocs).unwrap();
+
+    let root_mkdocs = r#"
+nav:
+  - Windows Guide: '!include ./windows-guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_link_to_directory_with_index_resolves() {
+    // When linking to `foo.md` but `foo/index.md` exists, it should resolve
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+
+    let lrg_docs = root.join("language-reference-guide").join("docs");
+    let intro = lrg_docs.join("introduction").join("arrays");
+    let ravel_dir = lrg_docs.join("primitive-functions").join("ravel");
+    fs::create_dir_all(&intro).unwrap();
+    fs::create_dir_all(&ravel_dir).unwrap();
+
+    // Source file links to ravel.md, but ravel is a directory with index.md.
+    // The link stays within the subsite (source-relative), so the `.md` is valid
+    // and MkDocs rewrites it to the rendered `ravel/` URL.
+    fs::write(
+        intro.join("structuring.md"),
+        "see [Ravel](../../primitive-functions/ravel.md)",
+    )
+    .unwrap();
+    fs::write(ravel_dir.join("index.md"), "# Ravel").unwrap();
+
+    let lrg_mkdocs = r#"
+nav:
+  - Introduction:
+    - Arrays:
+      - Structuring: introduction/arrays/structuring.md
+  - Primitive Functions:
+    - Ravel: primitive-functions/ravel/index.md
+"#;
+    fs::write(
+        root.join("language-reference-guide").join("mkdocs.yml"),
+        lrg_mkdocs,
+    )
+    .unwrap();
+
+    let root_mkdocs = r#"
+nav:
+  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_within_subsite_deep_relative_link_resolves() {
+    // Simulates link within same subsite that goes up multiple levels but stays
+    // inside docs/ (so MkDocs rewrites it):
+    //   root/
+    //     language-reference-guide/docs/system-functions/i-beam/shell.md
+    //       links to ../../primitive-operators/i-beam/shell-process-control.md
+    //     language-reference-guide/docs/primitive-operators/i-beam/shell-process-control.md
+    //
+    // This should NOT be treated as cross-subsite (primitive-operators is not a subsite).
+    // The two `../` land back at docs/ root, not above it — contrast with the
+    // over-deep case in test_issue876_overdeep_md_link_is_broken.
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+
+    // Create language-reference-guide subsite with nested structure
+    let lrg_docs = root.join("language-reference-guide").join("docs");
+    let sys_funcs = lrg_docs.join("system-functions").join("i-beam");
+    let prim_ops = lrg_docs.join("primitive-operators").join("i-beam");
+    fs::create_dir_all(&sys_funcs).unwrap();
+    fs::create_dir_all(&prim_ops).unwrap();
+
+    fs::write(
+        sys_funcs.join("shell.md"),
+        "see [Shell Process Control](../../primitive-operators/i-beam/shell-process-control.md)",
+    )
+    .unwrap();
+    fs::write(
+        prim_ops.join("shell-process-control.md"),
+        "# Shell Process Control",
+    )
+    .unwrap();
+
+    let lrg_mkdocs = r#"
+nav:
+  - System Functions:
+    - Shell: system-functions/i-beam/shell.md
+  - Primitive Operators:
+    - I-Beam:
+      - Shell Process Control: primitive-operators/i-beam/shell-process-control.md
+"#;
+    fs::write(
+        root.join("language-reference-guide").join("mkdocs.yml"),
+        lrg_mkdocs,
+    )
+    .unwrap();
+
+    // Create root mkdocs.yml
+    let root_mkdocs = r#"
+nav:
+  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+// ---- Regression tests for issue #876 (links ghost previously failed to flag) ----
+
+/// An over-deep `.md` link with one too many `../` escapes the subsite docs/ root,
+/// so MkDocs leaves the literal `.md` href and it 404s. Mirrors the reported
+/// `system-functions-by-category.md -> ../../primitive-operators/spawn.md`.
+#[test]
+fn test_issue876_overdeep_md_link_is_broken() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("language-reference-guide").join("docs");
+    fs::create_dir_all(docs.join("system-functions")).unwrap();
+    fs::create_dir_all(docs.join("primitive-operators")).unwrap();
+    // One `../` too many: ../../ from system-functions/ escapes docs/.
+    fs::write(
+        docs.join("system-functions").join("by-category.md"),
+        "threads via [Spawn](../../primitive-operators/spawn.md)",
+    )
+    .unwrap();
+    fs::write(docs.join("primitive-operators").join("spawn.md"), "# Spawn").unwrap();
+
+    let lrg_mkdocs = r#"
+nav:
+  - By Category: system-functions/by-category.md
+  - Spawn: primitive-operators/spawn.md
+"#;
+    fs::write(
+        root.join("language-reference-guide").join("mkdocs.yml"),
+        lrg_mkdocs,
+    )
+    .unwrap();
+    let root_mkdocs = r#"
+nav:
+  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(result.broken_links.len(), 1, "{:?}", result.broken_links);
+    assert_eq!(
+        result.broken_links[0].link,
+        "../../primitive-operators/spawn.md"
+    );
+}
+
+/// A cross-subsite link carrying a `.md` extension is not rewritten by the monorepo
+/// plugin, so the `.md` href 404s even though the target page exists. Mirrors the
+/// reported `system-functions-by-category.md -> .../interface-guide/dde/shared-variable-principles.md`.
+#[test]
+fn test_issue876_cross_subsite_md_link_is_broken() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+
+    let lrg_docs = root.join("language-reference-guide").join("docs");
+    fs::create_dir_all(lrg_docs.join("system-functions")).unwrap();
+    fs::write(
+        lrg_docs.join("system-functions").join("by-category.md"),
+        "the [shared variable](../../../interface-guide/dde/shared-variable-principles.md) interface",
+    )
+    .unwrap();
+    fs::write(
+        root.join("language-reference-guide").join("mkdocs.yml"),
+        "nav:\n  - By Category: system-functions/by-category.md\n",
+    )
+    .unwrap();
+
+    let ig_docs = root.join("interface-guide").join("docs").join("dde");
+    fs::create_dir_all(&ig_docs).unwrap();
+    // The target page genuinely exists — only the `.md` extension makes it broken.
+    fs::write(
+        ig_docs.join("shared-variable-principles.md"),
+        "# Shared Variables",
+    )
+    .unwrap();
+    fs::write(
+        root.join("interface-guide").join("mkdocs.yml"),
+        "nav:\n  - Principles: dde/shared-variable-principles.md\n",
+    )
+    .unwrap();
+
+    let root_mkdocs = r#"
+nav:
+  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
+  - Interfaces: '!include ./interface-guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(
+        result
+            .broken_links
+            .iter()
+            .any(|bl| bl.link == "../../../interface-guide/dde/shared-variable-principles.md"),
+        "{:?}",
+        result.broken_links
+    );
+}
+
+/// The same cross-subsite target written *without* a `.md` extension (directory-style
+/// URL) is valid — MkDocs serves it. This must keep resolving so we don't regress the
+/// hundreds of legitimate bare cross-subsite links.
+#[test]
+fn test_bare_cross_subsite_link_resolves() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+
+    let lrg_docs = root.join("language-reference-guide").join("docs");
+    fs::create_dir_all(lrg_docs.join("system-functions")).unwrap();
+    fs::write(
+        lrg_docs.join("system-functions").join("by-category.md"),
+        "the [shared variable](../../../interface-guide/dde/shared-variable-principles) interface",
+    )
+    .unwrap();
+    fs::write(
+        root.join("language-reference-guide").join("mkdocs.yml"),
+        "nav:\n  - By Category: system-functions/by-category.md\n",
+    )
+    .unwrap();
+
+    let ig_docs = root.join("interface-guide").join("docs").join("dde");
+    fs::create_dir_all(&ig_docs).unwrap();
+    fs::write(
+        ig_docs.join("shared-variable-principles.md"),
+        "# Shared Variables",
+    )
+    .unwrap();
+    fs::write(
+        root.join("interface-guide").join("mkdocs.yml"),
+        "nav:\n  - Principles: dde/shared-variable-principles.md\n",
+    )
+    .unwrap();
+
+    let root_mkdocs = r#"
+nav:
+  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
+  - Interfaces: '!include ./interface-guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+/// Filenames are mandated lower-case, so a link with mixed case is flagged even
+/// though it resolves on a case-insensitive (macOS) filesystem. Mirrors the reported
+/// `thorn.md -> ../primitive-functions/format-by-Specification.md`.
+#[test]
+fn test_issue876_mixed_case_link_is_broken() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("language-reference-guide").join("docs");
+    fs::create_dir_all(docs.join("symbols")).unwrap();
+    fs::create_dir_all(docs.join("primitive-functions")).unwrap();
+    fs::write(
+        docs.join("symbols").join("thorn.md"),
+        "[Format By Specification](../primitive-functions/format-by-Specification.md)",
+    )
+    .unwrap();
+    // The real (lower-case) file exists; the link's capital S is the defect.
+    fs::write(
+        docs.join("primitive-functions")
+            .join("format-by-specification.md"),
+        "# Fmt",
+    )
+    .unwrap();
+
+    let lrg_mkdocs = r#"
+nav:
+  - Thorn: symbols/thorn.md
+  - Format: primitive-functions/format-by-specification.md
+"#;
+    fs::write(
+        root.join("language-reference-guide").join("mkdocs.yml"),
+        lrg_mkdocs,
+    )
+    .unwrap();
+    let root_mkdocs = r#"
+nav:
+  - Language Reference: '!include ./language-reference-guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(result.broken_links.len(), 1, "{:?}", result.broken_links);
+    assert_eq!(
+        result.broken_links[0].link,
+        "../primitive-functions/format-by-Specification.md"
+    );
+}
+
+/// The monorepo plugin mounts a subsite at slug(site_name), which may differ from the
+/// directory name. A cross-subsite link using the slug must resolve to the directory
+/// on disk (regression for the net-interface-guide false positives).
+#[test]
+fn test_cross_subsite_link_uses_site_name_slug() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+
+    let lrg = root.join("language-reference-guide").join("docs");
+    fs::create_dir_all(&lrg).unwrap();
+    fs::write(
+        lrg.join("glyphs.md"),
+        "see [Adv](../../net-interface-guide/dotnet-classes/advanced-techniques/)",
+    )
+    .unwrap();
+    fs::write(
+        root.join("language-reference-guide").join("mkdocs.yml"),
+        "site_name: Language Reference Guide\nnav:\n  - Glyphs: glyphs.md\n",
+    )
+    .unwrap();
+
+    // Directory is dotnet-interface-guide, but site_name slugifies to net-interface-guide.
+    let dni = root
+        .join("dotnet-interface-guide")
+        .join("docs")
+        .join("dotnet-classes");
+    fs::create_dir_all(&dni).unwrap();
+    fs::write(dni.join("advanced-techniques.md"), "# Adv").unwrap();
+    fs::write(
+        root.join("dotnet-interface-guide").join("mkdocs.yml"),
+        "site_name: .NET Interface Guide\nnav:\n  - Adv: dotnet-classes/advanced-techniques.md\n",
+    )
+    .unwrap();
+
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - LRG: '!include ./language-reference-guide/mkdocs.yml'\n  - DNI: '!include ./dotnet-interface-guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+/// A page pulled in by several HELP_URL entries must be analysed once (not once per
+/// entry), and a broken link on it should cite every help_urls.h line that references
+/// it. Regression for the "40 reports from 2 links" duplication.
+#[test]
+fn test_help_url_broken_link_deduped_and_cites_lines() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("page.md"), "[bad](nonexistent)").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "site_name: Guide\nnav:\n  - P: page.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - G: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    // page.md is referenced by two HELP_URL entries (lines 2 and 3).
+    fs::write(
+        root.join("help_urls.h"),
+        "#define P \"guide/page\"\nHELP_URL(\"a\", P)\nHELP_URL(\"b\", P)\n",
+    )
+    .unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    let bad: Vec<&BrokenLink> = result
+        .broken_links
+        .iter()
+        .filter(|b| b.link == "nonexistent.md")
+        .collect();
+    assert_eq!(
+        bad.len(),
+        1,
+        "expected one report, got {:?}",
+        result.broken_links
+    );
+    let lines: Vec<usize> = bad[0].help_refs.iter().map(|r| r.line).collect();
+    assert_eq!(lines, vec![2, 3]);
+    assert_eq!(bad[0].help_refs[0].text, r#"HELP_URL("a", P)"#);
+    assert_eq!(bad[0].help_refs[1].text, r#"HELP_URL("b", P)"#);
+}
+
+/// `audit_traced` produces a per-file blow-by-blow for the requested targets:
+/// discovery/role, per-link verdicts, and "NOT REACHED" for unreachable files.
+#[test]
+fn test_audit_traced_reports_processing() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = temp.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("page.md"), "[bad](nonexistent) and [ok](other)").unwrap();
+    fs::write(docs.join("other.md"), "# Other").unwrap();
+    fs::write(docs.join("orphan.md"), "not linked from anywhere").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "site_name: Guide\nnav:\n  - Page: page.md\n  - Other: other.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - G: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let opts = TraceOptions {
+        targets: vec!["guide/docs/page.md".to_string(), "orphan.md".to_string()],
+    };
+    let (_res, trace) =
+        audit_traced(&root.join("mkdocs.yml"), &root.join("help_urls.h"), &opts).unwrap();
+    let t = trace.text;
+
+    // Reached, analysed page with one broken and one good link.
+    assert!(t.contains("FILE guide/docs/page.md"), "{t}");
+    assert!(t.contains("reached by     : nav"), "{t}");
+    assert!(t.contains("analysed       : true"), "{t}");
+    assert!(t.contains("LINK  nonexistent.md"), "{t}");
+    assert!(t.contains("BROKEN"), "{t}");
+    assert!(t.contains("resolved via"), "{t}"); // the [ok](other) link
+
+    // Orphan file: on disk but never reached, so its links are never checked.
+    assert!(t.contains("FILE guide/docs/orphan.md"), "{t}");
+    assert!(t.contains("NOT REACHED"), "{t}");
+
+    // No targets ⇒ no trace.
+    let (_r2, empty) = audit_traced(
+        &root.join("mkdocs.yml"),
+        &root.join("help_urls.h"),
+        &TraceOptions::default(),
+    )
+    .unwrap();
+    assert!(empty.text.is_empty());
+}
+
+#[test]
+fn test_absolute_link_resolves() {
+    // Absolute links (starting with /) resolve from site root
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+
+    let guide_docs = root.join("guide").join("docs");
+    let nested = guide_docs.join("nested").join("deep");
+    fs::create_dir_all(&nested).unwrap();
+
+    // Deep nested file links to root-level page with absolute path
+    fs::write(nested.join("page.md"), "see [Home](/guide/index.md)").unwrap();
+    fs::write(guide_docs.join("index.md"), "# Home").unwrap();
+
+    let mkdocs = r#"
+nav:
+  - Home: index.md
+  - Nested:
+    - Deep:
+      - Page: nested/deep/page.md
+"#;
+    fs::write(root.join("guide").join("mkdocs.yml"), mkdocs).unwrap();
+
+    let root_mkdocs = r#"
+nav:
+  - Guide: '!include ./guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_link_without_extension_resolves() {
+    // Links without .md extension should resolve (MkDocs supports this)
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+
+    fs::write(
+        docs.join("source.md"),
+        "see [Target](target)", // no .md extension
+    )
+    .unwrap();
+    fs::write(docs.join("target.md"), "# Target").unwrap();
+
+    let mkdocs = r#"
+nav:
+  - Source: source.md
+  - Target: target.md
+"#;
+    fs::write(root.join("guide").join("mkdocs.yml"), mkdocs).unwrap();
+
+    let root_mkdocs = r#"
+nav:
+  - Guide: '!include ./guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_link_with_trailing_slash_resolves() {
+    // Links with trailing slash (directory-style) should resolve
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+
+    fs::write(
+        docs.join("source.md"),
+        "see [Target](target/)", // trailing slash
+    )
+    .unwrap();
+    fs::write(docs.join("target.md"), "# Target").unwrap();
+
+    let mkdocs = r#"
+nav:
+  - Source: source.md
+  - Target: target.md
+"#;
+    fs::write(root.join("guide").join("mkdocs.yml"), mkdocs).unwrap();
+
+    let root_mkdocs = r#"
+nav:
+  - Guide: '!include ./guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_linked_file_not_orphan() {
+    // A file linked from a nav page should not be reported as orphan
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+
+    let docs = root.join("object-ref").join("docs");
+    let properties = docs.join("properties");
+    let propertyapplies = docs.join("propertyapplies");
+    fs::create_dir_all(&properties).unwrap();
+    fs::create_dir_all(&propertyapplies).unwrap();
+
+    // Nav page links to non-nav page
+    fs::write(
+        properties.join("accelerator.md"),
+        r#"[**Applies To**](../propertyapplies/accelerator.md)"#,
+    )
+    .unwrap();
+    // Non-nav page (should not be orphan because it's linked)
+    fs::write(propertyapplies.join("accelerator.md"), "# Applies").unwrap();
+
+    let mkdocs = r#"
+nav:
+  - Properties:
+    - Accelerator: properties/accelerator.md
+"#;
+    fs::write(root.join("object-ref").join("mkdocs.yml"), mkdocs).unwrap();
+
+    let root_mkdocs = r#"
+nav:
+  - Object Reference: '!include ./object-ref/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+
+    eprintln!("Ghost files: {:?}", result.ghost);
+    eprintln!("Broken links: {:?}", result.broken_links);
+
+    // propertyapplies/accelerator.md should NOT be an orphan
+    assert!(
+        !result
+            .ghost
+            .iter()
+            .any(|p| p.to_string_lossy().contains("propertyapplies")),
+        "propertyapplies/accelerator.md should not be an orphan"
+    );
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_absolute_internal_links_validated_as_site_absolute() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(
+        docs.join("page.md"),
+        "[ok](https://docs.example.com/guide/target.md) \
+         [bad](https://docs.example.com/guide/missing.md)",
+    )
+    .unwrap();
+    fs::write(docs.join("target.md"), "# Target").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Page: page.md\n  - Target: target.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let config = GhostConfig {
+        site_base_url: Some("https://docs.example.com/".to_string()),
+        ..Default::default()
+    };
+    let (result, _) = audit_with_config(
+        &root.join("mkdocs.yml"),
+        &root.join("help_urls.h"),
+        &config,
+        &TraceOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(result.absolute_internal_links.len(), 2);
+    assert_eq!(result.broken_links.len(), 1, "{:?}", result.broken_links);
+    assert_eq!(result.broken_links[0].link, "/guide/missing.md");
+
+    // Without a base URL the links are external and nothing is checked.
+    let plain = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(plain.absolute_internal_links.is_empty());
+    assert!(plain.broken_links.is_empty(), "{:?}", plain.broken_links);
+}
+
+#[test]
+fn test_title_case_checked_inside_includes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("page.md"), "# Page").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Advanced topics:\n    - Page: page.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let config = GhostConfig {
+        title_case_style: TitleCaseStyle::AllTitle,
+        ..Default::default()
+    };
+    let (result, _) = audit_with_config(
+        &root.join("mkdocs.yml"),
+        &root.join("help_urls.h"),
+        &config,
+        &TraceOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        result.title_case_violations,
+        vec![(vec!["Guide".to_string()], "Advanced topics".to_string())]
+    );
+}
+
+#[test]
+fn test_audit_result_save_load_round_trip() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("result.json");
+    let result = AuditResult {
+        nav_missing: vec![PathBuf::from("guide/docs/missing.md")],
+        broken_links: vec![BrokenLink {
+            from: PathBuf::from("guide/docs/index.md"),
+            link: "nowhere.md".to_string(),
+            help_refs: vec![HelpRef {
+                line: 3,
+                text: "HELP_URL(\"x\", \"guide/index\")".to_string(),
+            }],
+            suggestions: vec![PathBuf::from("guide/docs/somewhere.md")],
+        }],
+        missing_images: vec![BrokenImage {
+            from: PathBuf::from("guide/docs/index.md"),
+            image: "img/gone.png".to_string(),
+        }],
+        title_case_violations: vec![(vec!["Guide".to_string()], "bad Title".to_string())],
+        ..Default::default()
+    };
+
+    save_audit_result(&result, &path).unwrap();
+    let loaded = load_audit_result(&path).unwrap();
+
+    assert_eq!(loaded, result);
+}
+
+#[test]
+fn test_load_audit_result_defaults_missing_fields() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("old.json");
+    fs::write(&path, r#"{"ghost": ["guide/docs/orphan.md"]}"#).unwrap();
+
+    let loaded = load_audit_result(&path).unwrap();
+
+    assert_eq!(loaded.ghost, vec![PathBuf::from("guide/docs/orphan.md")]);
+    assert!(loaded.broken_links.is_empty());
+}
+
+#[test]
+fn test_frontmatter_image_checked() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(docs.join("img")).unwrap();
+    fs::write(
+        docs.join("page.md"),
+        "---\nhero_image: missing.png\nthumbnail: img/thumb.png\n---\n\n# Page\n",
+    )
+    .unwrap();
+    fs::write(docs.join("img").join("thumb.png"), "").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Page: page.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+
+    assert_eq!(
+        result.missing_images.len(),
+        1,
+        "{:?}",
+        result.missing_images
+    );
+    assert_eq!(result.missing_images[0].image, "missing.png");
+    assert!(result.missing_images[0].from.ends_with("docs/page.md"));
+    assert!(
+        result.orphan_images.is_empty(),
+        "{:?}",
+        result.orphan_images
+    );
+}
+
+#[test]
+fn test_percent_encoded_link_resolves() {
+    // MkDocs decodes `my%20page.md` to the file `my page.md`
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+
+    fs::write(
+        docs.join("source.md"),
+        "see [Target](my%20page.md) and [Gone](no%20page.md)",
+    )
+    .unwrap();
+    fs::write(docs.join("my page.md"), "# My page").unwrap();
+
+    let mkdocs = r#"
+nav:
+  - Source: source.md
+"#;
+    fs::write(root.join("guide").join("mkdocs.yml"), mkdocs).unwrap();
+
+    let root_mkdocs = r#"
+nav:
+  - Guide: '!include ./guide/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(result.broken_links.len(), 1, "{:?}", result.broken_links);
+    assert_eq!(result.broken_links[0].link, "no page.md");
+    assert!(result.ghost.is_empty(), "{:?}", result.ghost);
+    assert_eq!(result.url_encoded_internal_links.len(), 2);
+}
+
+#[test]
+fn test_files_inside_site_dir_reported() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::create_dir_all(root.join("build")).unwrap();
+    fs::write(docs.join("page.md"), "# Page").unwrap();
+    fs::write(root.join("build").join("notes.md"), "# Overwritten").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Page: page.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "site_dir: build\nnav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(
+        result.files_inside_site_dir.len(),
+        1,
+        "{:?}",
+        result.files_inside_site_dir
+    );
+    assert!(result.files_inside_site_dir[0].ends_with("build/notes.md"));
+
+    // Without site_dir nothing is checked.
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.files_inside_site_dir.is_empty());
+}
+
+#[test]
+fn test_help_url_page_not_in_nav_reported() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("page.md"), "# Page").unwrap();
+    fs::write(docs.join("hidden.md"), "# Only reachable from help").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Page: page.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("help_urls.h"),
+        "HELP_URL(\"page\", \"guide/page\")\n\
+         HELP_URL(\"hidden\", \"guide/hidden\")\n\
+         HELP_URL(\"gone\", \"guide/gone\")\n",
+    )
+    .unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(
+        result.help_url_pages_not_in_nav.len(),
+        1,
+        "{:?}",
+        result.help_url_pages_not_in_nav
+    );
+    assert!(result.help_url_pages_not_in_nav[0].ends_with("guide/docs/hidden.md"));
+    // It isn't a ghost: help_urls.h references it.
+    assert!(result.ghost.is_empty(), "{:?}", result.ghost);
+    assert_eq!(result.help_missing.len(), 1);
+}
+
+#[test]
+fn test_nav_pages_without_content_only_nav_pages() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(
+        docs.join("page.md"),
+        "# Page\n\n[stub](stub.md) [ghost](ghost.md)",
+    )
+    .unwrap();
+    fs::write(docs.join("stub.md"), "---\ntitle: Stub\n---\n").unwrap();
+    fs::write(docs.join("empty.md"), "").unwrap();
+    // Linked but not in the nav: a stub, but not one readers browse to.
+    fs::write(docs.join("ghost.md"), "").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Page: page.md\n  - Stub: stub.md\n  - Empty: empty.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    let stubs = &result.nav_pages_without_content;
+    assert_eq!(stubs.len(), 2, "{stubs:?}");
+    assert!(stubs[0].ends_with("guide/docs/empty.md"));
+    assert!(stubs[1].ends_with("guide/docs/stub.md"));
+}
+
+#[test]
+fn test_broken_link_in_nav_description_reported() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("page.md"), "# Page").unwrap();
+    fs::write(docs.join("setup.md"), "# Setup").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Page: page.md\n    description: |\n      Read [setup](setup.md) and [the FAQ](faq.md) first.\n  - Setup: setup.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    let broken = &result.broken_links_in_nav_descriptions;
+    assert_eq!(broken.len(), 1, "{broken:?}");
+    assert_eq!(broken[0].link, "faq.md");
+    assert!(broken[0].from.ends_with("guide/mkdocs.yml"));
+    // The description itself isn't mistaken for a nav page.
+    assert!(result.nav_missing.is_empty(), "{:?}", result.nav_missing);
+}
+
+#[test]
+fn test_deep_headings_only_checked_when_configured() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("page.md"), "# Page\n\n#### Too deep\n").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Page: page.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.pages_with_deep_headings.is_empty());
+
+    let config = GhostConfig {
+        max_heading_level: Some(3),
+        ..GhostConfig::default()
+    };
+    let (result, _) = audit_with_config(
+        &root.join("mkdocs.yml"),
+        &root.join("help_urls.h"),
+        &config,
+        &TraceOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(result.pages_with_deep_headings.len(), 1);
+    assert_eq!(
+        result.pages_with_deep_headings[0].1,
+        vec![(3, 4, "Too deep".to_string())]
+    );
+}
+
+#[test]
+fn test_include_depth_limit() {
+    // root -> level1 -> level2 -> level3 -> level4 -> level5
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let mut dir = root.to_path_buf();
+    let mut yaml = root.join("mkdocs.yml");
+    for level in 1..=5 {
+        let name = format!("level{level}");
+        fs::write(
+            &yaml,
+            format!("nav:\n  - Page: page.md\n  - Next: '!include ./{name}/mkdocs.yml'\n"),
+        )
+        .unwrap();
+        dir = dir.join(&name);
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(dir.join("docs").join("page.md"), format!("# Level {level}")).unwrap();
+        yaml = dir.join("mkdocs.yml");
+    }
+    fs::write(&yaml, "nav:\n  - Page: page.md\n").unwrap();
+    fs::create_dir_all(root.join("docs")).unwrap();
+    fs::write(root.join("docs").join("page.md"), "# Root").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let config = GhostConfig {
+        max_include_depth: 3,
+        ..GhostConfig::default()
+    };
+    let (result, _) = audit_with_config(
+        &root.join("mkdocs.yml"),
+        &root.join("help_urls.h"),
+        &config,
+        &TraceOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(result.include_depth_violations.len(), 1);
+    let (file, depth) = &result.include_depth_violations[0];
+    assert!(
+        file.ends_with("level1/level2/level3/level4/mkdocs.yml"),
+        "{file:?}"
+    );
+    assert_eq!(*depth, 4);
+    // Levels 1 to 3 were still audited.
+    assert!(result.nav_missing.is_empty(), "{:?}", result.nav_missing);
+
+    let unlimited = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(unlimited.include_depth_violations.is_empty());
+}
+
+#[test]
+fn test_missing_help_url_suggests_typo_fix() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(docs.join("topics")).unwrap();
+    fs::write(docs.join("topics").join("arrays.md"), "# Arrays").unwrap();
+    fs::write(docs.join("topics").join("functions.md"), "# Functions").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Arrays: topics/arrays.md\n  - Functions: topics/functions.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("help_urls.h"),
+        "HELP_URL(\"arrays\", \"guide/topics/arrys\")\n\
+         HELP_URL(\"nothing\", \"guide/completely/unrelated/page\")\n",
+    )
+    .unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(result.help_missing.len(), 2, "{:?}", result.help_missing);
+    assert!(
+        result.help_missing[0]
+            .path
+            .ends_with("guide/docs/topics/arrys.md")
+    );
+    assert_eq!(
+        result.help_missing[0].suggested_help_url_fix.as_deref(),
+        Some("guide/topics/arrays")
+    );
+    assert_eq!(result.help_missing[1].suggested_help_url_fix, None);
+}
+
+#[test]
+fn test_broken_links_to_nav_missing_pages_are_flagged() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    // `gone.md` is in the nav but was never written, so both the nav entry and the link
+    // to it are reported; `typo.md` is an unrelated broken link.
+    fs::write(docs.join("a.md"), "[Gone](gone.md#intro) [Typo](typo.md)").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - A: a.md\n  - Gone: gone.md\n",
+    )
+    .unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(result.nav_missing, vec![docs.join("gone.md")]);
+    assert_eq!(result.broken_links.len(), 2, "{:?}", result.broken_links);
+    assert_eq!(
+        result.nav_missing_link_sources,
+        vec![(docs.join("a.md"), "gone.md".to_string())]
+    );
+}
+
+#[test]
+fn test_find_oversized_svgs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let small = temp_dir.path().join("small.svg");
+    let large = temp_dir.path().join("large.svg");
+    fs::write(&small, "<svg></svg>").unwrap();
+    fs::write(&large, format!("<svg>{}</svg>", " ".repeat(2000))).unwrap();
+
+    let svgs = vec![small, large.clone(), temp_dir.path().join("missing.svg")];
+    assert_eq!(find_oversized_svgs(&svgs, 1024), vec![(large, 2011)]);
+    assert!(find_oversized_svgs(&svgs, 4096).is_empty());
+}
+
+#[test]
+fn test_find_svgs_with_embedded_rasters() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    let raster = dir.join("raster.svg");
+    let vector = dir.join("vector.svg");
+    let nested = dir.join("nested.svg");
+    fs::write(
+        &raster,
+        r#"<svg><image xlink:href="DATA:image/png;base64,iVBORw0KGgo="/></svg>"#,
+    )
+    .unwrap();
+    fs::write(&vector, r#"<svg><path d="M0 0L10 10"/></svg>"#).unwrap();
+    fs::write(
+        &nested,
+        r#"<svg><image href="data:image/svg+xml;base64,PHN2Zz4="/></svg>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        find_svgs_with_embedded_rasters(&[raster.clone(), vector, nested]),
+        vec![raster]
+    );
+}
+
+#[test]
+fn test_audit_checks_svgs_in_docs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let guide = root.join("guide");
+    let docs = guide.join("docs");
+    fs::create_dir_all(docs.join("img")).unwrap();
+    fs::write(docs.join("index.md"), "![Chart](img/chart.svg)").unwrap();
+    fs::write(
+        docs.join("img").join("chart.svg"),
+        format!(
+            r#"<svg><image href="data:image/png;base64,{}"/></svg>"#,
+            "A".repeat(200 * 1024)
+        ),
+    )
+    .unwrap();
+    fs::write(guide.join("mkdocs.yml"), "nav:\n  - Home: index.md\n").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    let chart = docs.join("img").join("chart.svg");
+    assert_eq!(result.oversized_svgs.len(), 1);
+    assert_eq!(result.oversized_svgs[0].0, chart);
+    assert_eq!(result.svgs_with_embedded_rasters, vec![chart]);
+}
+
+#[test]
+fn test_links_to_a_differently_cased_target_are_reported_once() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let guide = root.join("guide");
+    let docs = guide.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("index.md"), "[Intro](Intro.md) [Also](intro.md)").unwrap();
+    fs::write(docs.join("intro.md"), "# Intro").unwrap();
+    fs::write(
+        guide.join("mkdocs.yml"),
+        "nav:\n  - Home: index.md\n  - Intro: intro.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    // The mixed-case link is broken, so it isn't a case violation as well.
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(
+        result
+            .broken_links
+            .iter()
+            .map(|bl| (bl.from.clone(), bl.link.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(docs.join("index.md"), "Intro.md")]
+    );
+    assert!(
+        result.case_sensitivity_violations.is_empty(),
+        "{:?}",
+        result.case_sensitivity_violations
+    );
+
+    let options = AuditOptions::builder().skip_broken_links(true).build();
+    let result = audit_with_options(
+        &root.join("mkdocs.yml"),
+        &root.join("help_urls.h"),
+        &options,
+    )
+    .unwrap();
+    assert!(result.broken_links.is_empty());
+    assert_eq!(
+        result.case_sensitivity_violations,
+        vec![(docs.join("Intro.md"), docs.join("intro.md"))]
+    );
+}
+
+#[test]
+fn test_extract_help_urls_splits_off_fragments() {
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        r#"
+#define SY "language-reference-guide/symbols"
+HELP_URL("⍳", SY"/iota#dyadic-form")
+HELP_URL("⍴", "language-reference-guide/symbols/rho#")
+HELP_URL(",", SY"/comma")
+"#
+    )
+    .unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let doc_root = temp_dir.path();
+    let symbols = doc_root.join("language-reference-guide/docs/symbols");
+
+    let result = extract_help_urls(temp_file.path(), doc_root).unwrap();
+
+    assert_eq!(
+        result,
+        vec![
+            HelpUrlEntry {
+                path: symbols.join("iota.md"),
+                fragment: Some("dyadic-form".to_string()),
+                docs_in_path: false,
+            },
+            HelpUrlEntry {
+                path: symbols.join("rho.md"),
+                fragment: None,
+                docs_in_path: false,
+            },
+            HelpUrlEntry {
+                path: symbols.join("comma.md"),
+                fragment: None,
+                docs_in_path: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_help_url_fragments_in_audit_result() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("page.md"), "# Page\n\n## Details\n").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Page: page.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("help_urls.h"),
+        "HELP_URL(\"a\", \"guide/page#details\")\nHELP_URL(\"b\", \"guide/page\")\n",
+    )
+    .unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.help_missing.is_empty(), "{:?}", result.help_missing);
+    assert_eq!(result.help_url_fragments.len(), 1);
+    assert!(
+        result.help_url_fragments[0]
+            .path
+            .ends_with("guide/docs/page.md")
+    );
+    assert_eq!(
+        result.help_url_fragments[0].fragment.as_deref(),
+        Some("details")
+    );
+}
+
+#[test]
+fn test_find_orphan_mkdocs_files() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    for subsite in ["guide", "nested", "abandoned", "legacy"] {
+        fs::create_dir_all(root.join(subsite).join("docs")).unwrap();
+    }
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    // Reached through guide's own !include, so not an orphan.
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Nested: '!include ../nested/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("nested").join("mkdocs.yml"), "nav: []\n").unwrap();
+    fs::write(root.join("abandoned").join("mkdocs.yml"), "nav: []\n").unwrap();
+    fs::write(root.join("legacy").join("mkdocs.yaml"), "nav: []\n").unwrap();
+    // Inside docs/: page content, not a subsite config.
+    fs::write(root.join("guide").join("docs").join("mkdocs.yml"), "").unwrap();
+
+    assert_eq!(
+        find_orphan_mkdocs_files(root, &root.join("mkdocs.yml"), true),
+        vec![
+            root.join("abandoned").join("mkdocs.yml"),
+            root.join("legacy").join("mkdocs.yaml"),
+        ]
+    );
+}
+
+#[test]
+fn test_broken_links_in_site_description() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("index.md"), "# Home").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "site_name: Docs\n\
+         site_author: Docs Team\n\
+         site_description: 'See [Home](index.md), [Start](getting-started.md) \
+         and [Elsewhere](https://example.com/).'\n\
+         nav:\n  - Home: index.md\n",
+    )
+    .unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(result.description_broken_links, vec!["getting-started.md"]);
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_help_urls_shared_across_header_files() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("shared.md"), "# Shared").unwrap();
+    fs::write(docs.join("own.md"), "# Own").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Shared: shared.md\n  - Own: own.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    let main_header = root.join("help_urls.h");
+    let extra_header = root.join("product_help_urls.h");
+    fs::write(
+        &main_header,
+        "HELP_URL(\"a\", \"guide/shared\")\nHELP_URL(\"b\", \"guide/own\")\n",
+    )
+    .unwrap();
+    fs::write(
+        &extra_header,
+        "HELP_URL(\"x\", \"guide/shared\")\nHELP_URL(\"y\", \"guide/shared\")\n",
+    )
+    .unwrap();
+
+    let config = GhostConfig {
+        extra_help_urls: vec![extra_header.clone()],
+        ..GhostConfig::default()
+    };
+    let (result, _) = audit_with_config(
+        &root.join("mkdocs.yml"),
+        &main_header,
+        &config,
+        &TraceOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        result.cross_file_help_url_duplicates,
+        vec![(
+            docs.join("shared.md"),
+            vec![main_header.clone(), extra_header]
+        )]
+    );
+    assert!(result.help_missing.is_empty(), "{:?}", result.help_missing);
+
+    let gone = root.join("gone_help_urls.h");
+    let config = GhostConfig {
+        extra_help_urls: vec![gone.clone()],
+        ..GhostConfig::default()
+    };
+    let err = audit_with_config(
+        &root.join("mkdocs.yml"),
+        &main_header,
+        &config,
+        &TraceOptions::default(),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains(&gone.display().to_string()),
+        "{err}"
+    );
+
+    // A header that opens but isn't UTF-8 fails the same way instead of panicking.
+    let latin1 = root.join("latin1_help_urls.h");
+    fs::write(&latin1, b"HELP_URL(\"caf\xe9\", \"guide/own\")\n").unwrap();
+    let config = GhostConfig {
+        extra_help_urls: vec![latin1.clone()],
+        ..GhostConfig::default()
+    };
+    let err = audit_with_config(
+        &root.join("mkdocs.yml"),
+        &main_header,
+        &config,
+        &TraceOptions::default(),
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains(&latin1.display().to_string()),
+        "{err}"
+    );
+    let err =
+        find_cross_file_help_url_duplicates(&[main_header, latin1.clone()], root).unwrap_err();
+    assert!(
+        err.to_string().contains(&latin1.display().to_string()),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_find_duplicate_images() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    let logo_a = dir.join("a").join("logo.png");
+    let logo_b = dir.join("b").join("logo.png");
+    let other = dir.join("b").join("other.png");
+    let same_size = dir.join("b").join("same-size.png");
+    fs::create_dir_all(dir.join("a")).unwrap();
+    fs::create_dir_all(dir.join("b")).unwrap();
+    fs::write(&logo_a, b"\x89PNG logo bytes").unwrap();
+    fs::write(&logo_b, b"\x89PNG logo bytes").unwrap();
+    fs::write(&other, b"\x89PNG different").unwrap();
+    // Same length as the logo, different bytes.
+    fs::write(&same_size, b"\x89PNG logo BYTES").unwrap();
+
+    let images = vec![
+        logo_a.clone(),
+        other,
+        logo_b.clone(),
+        same_size,
+        dir.join("missing.png"),
+    ];
+    assert_eq!(find_duplicate_images(&images), vec![vec![logo_a, logo_b]]);
+}
+
+#[test]
+fn test_find_scss_import_cycles() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    fs::write(dir.join("a.scss"), "@use \"b\";\n.a { color: red; }\n").unwrap();
+    // A partial, loaded without its underscore.
+    fs::write(dir.join("_b.scss"), "@use './a';\n").unwrap();
+    fs::write(dir.join("c.scss"), "@use \"a\";\n").unwrap();
+
+    let files = vec![dir.join("a.scss"), dir.join("_b.scss"), dir.join("c.scss")];
+    assert_eq!(
+        find_scss_import_cycles(&files),
+        vec![vec![dir.join("_b.scss"), dir.join("a.scss")]]
+    );
+}
+
+#[test]
+fn test_find_scss_import_cycles_none() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    fs::write(dir.join("a.scss"), "@use \"b\";\n@use \"c\";\n").unwrap();
+    fs::write(dir.join("b.scss"), "@use \"c\";\n").unwrap();
+    fs::write(dir.join("c.scss"), ".c { color: red; }\n").unwrap();
+
+    let files = vec![dir.join("a.scss"), dir.join("b.scss"), dir.join("c.scss")];
+    assert!(find_scss_import_cycles(&files).is_empty());
+}
+
+#[test]
+fn test_nav_entries_outside_docs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("docs").join("guide")).unwrap();
+    fs::create_dir_all(root.join("shared")).unwrap();
+    fs::create_dir_all(root.join("sub").join("docs")).unwrap();
+    fs::write(root.join("docs").join("index.md"), "# Home").unwrap();
+    fs::write(root.join("docs").join("guide").join("a.md"), "# A").unwrap();
+    fs::write(root.join("shared").join("page.md"), "# Shared").unwrap();
+    fs::write(root.join("sub").join("docs").join("b.md"), "# B").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    // Within the subsite's own docs/, `../docs/b.md` stays inside; `../../docs/index.md`
+    // escapes it even though it lands in the root site's docs/.
+    fs::write(
+        root.join("sub").join("mkdocs.yml"),
+        "nav:\n  - B: ../docs/b.md\n  - Escape: ../../docs/index.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Home: index.md\n  - A: guide/../guide/a.md\n  - Shared: ../shared/page.md\n  - Sub: '!include ./sub/mkdocs.yml'\n",
+    )
+    .unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(
+        result.nav_entries_outside_docs,
+        vec!["../shared/page.md", "../../docs/index.md"]
+    );
+}
+
+#[test]
+fn test_nav_baseline_round_trip() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mkdocs = temp_dir.path().join("mkdocs.yml");
+    fs::write(
+        &mkdocs,
+        "site_name: Test\nnav:\n  - Home: index.md\n  - Guide:\n    - A: a.md\n    - B: b.md\n",
+    )
+    .unwrap();
+    let path = temp_dir.path().join("nav.json");
+
+    let nav = load_mkdocs_nav(&mkdocs).unwrap();
+    save_nav_baseline(&nav, &path).unwrap();
+    let baseline = load_nav_baseline(&path).unwrap();
+    assert!(detect_nav_reordering(&nav, &baseline).is_empty());
+
+    fs::write(
+        &mkdocs,
+        "site_name: Test\nnav:\n  - Home: index.md\n  - Guide:\n    - B: b.md\n    - A: a.md\n",
+    )
+    .unwrap();
+    let nav = load_mkdocs_nav(&mkdocs).unwrap();
+    let changes = detect_nav_reordering(&nav, &baseline);
+
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].section, vec!["Guide".to_string()]);
+    assert_eq!(changes[0].new_order, vec!["B".to_string(), "A".to_string()]);
+}
+
+#[test]
+fn test_extract_help_urls_keeps_existing_docs_component() {
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    let mut temp_file = NamedTempFile::new().unwrap();
+    writeln!(
+        temp_file,
+        r#"
+HELP_URL("x", "guide/docs/page")
+HELP_URL("y", "guide/page")
+"#
+    )
+    .unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let doc_root = temp_dir.path();
+
+    let result = extract_help_urls(temp_file.path(), doc_root).unwrap();
+
+    assert_eq!(
+        result,
+        vec![
+            HelpUrlEntry {
+                path: doc_root.join("guide/docs/page.md"),
+                fragment: None,
+                docs_in_path: true,
+            },
+            HelpUrlEntry {
+                path: doc_root.join("guide/docs/page.md"),
+                fragment: None,
+                docs_in_path: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_to_sarif_locates_results() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let page = root.join("guide/docs/index.md");
+    fs::create_dir_all(page.parent().unwrap()).unwrap();
+    fs::write(&page, "# Guide\n\nSee [the setup](setup.md).\n").unwrap();
+    fs::write(
+        root.join("guide/mkdocs.yml"),
+        "nav:\n  - Home: index.md\n  - Setup: setup.md\n",
+    )
+    .unwrap();
+    let result = AuditResult {
+        nav_missing: vec![root.join("guide/docs/setup.md")],
+        ghost: vec![root.join("guide/docs/orphan.md")],
+        broken_links: vec![BrokenLink {
+            from: page.clone(),
+            link: "setup.md".to_string(),
+            help_refs: vec![],
+            suggestions: vec![],
+        }],
+        ..Default::default()
+    };
+
+    let sarif: serde_json::Value = serde_json::from_str(&to_sarif(&result, root)).unwrap();
+
+    let run = &sarif["runs"][0];
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 4);
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["ruleId"], "nav-missing");
+    assert_eq!(results[1]["ruleId"], "ghost-file");
+    assert_eq!(results[2]["ruleId"], "broken-link");
+    // The missing page is located at its entry in the nav that lists it.
+    let location = &results[0]["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "guide/mkdocs.yml");
+    assert_eq!(location["region"]["startLine"], 3);
+    let location = &results[2]["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "guide/docs/index.md");
+    assert_eq!(location["region"]["startLine"], 3);
+}
+
+#[test]
+fn test_audit_with_options_limits_scan_and_skips_checks() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let guide = root.join("guide");
+    let docs = guide.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("a.md"), "[B](b.md)").unwrap();
+    fs::write(docs.join("b.md"), "[Gone](gone.md)").unwrap();
+    fs::write(docs.join("orphan.md"), "# Orphan").unwrap();
+    fs::write(guide.join("mkdocs.yml"), "nav:\n  - A: a.md\n").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("draft/docs")).unwrap();
+    fs::create_dir_all(root.join("old")).unwrap();
+    fs::write(root.join("old/mkdocs.yml"), "nav: []\n").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    let mkdocs = root.join("mkdocs.yml");
+    let help_urls = root.join("help_urls.h");
+
+    let full = audit_with_options(&mkdocs, &help_urls, &AuditOptions::default()).unwrap();
+    assert_eq!(full.broken_links.len(), 1);
+    assert_eq!(full.ghost, vec![docs.join("orphan.md")]);
+    assert_eq!(full.subsites_without_mkdocs.len(), 1);
+    assert_eq!(full.orphan_mkdocs_files.len(), 1);
+
+    // b.md is a link away from the nav, so depth 0 never reads it.
+    let shallow = AuditOptions::builder().max_scan_depth(0).build();
+    let result = audit_with_options(&mkdocs, &help_urls, &shallow).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+    assert!(!result.ghost.contains(&docs.join("b.md")));
+
+    let skipping = AuditOptions::builder()
+        .skip_broken_links(true)
+        .skip_missing_images(true)
+        .skip_orphan_images(true)
+        .build();
+    let result = audit_with_options(&mkdocs, &help_urls, &skipping).unwrap();
+    assert!(result.broken_links.is_empty());
+    assert_eq!(result.ghost, vec![docs.join("orphan.md")]);
+    assert_eq!(result.subsites_without_mkdocs.len(), 1);
+
+    let skipping_walks = AuditOptions::builder()
+        .skip_subsites_without_mkdocs(true)
+        .skip_orphan_mkdocs_files(true)
+        .build();
+    let result = audit_with_options(&mkdocs, &help_urls, &skipping_walks).unwrap();
+    assert!(result.subsites_without_mkdocs.is_empty());
+    assert!(result.orphan_mkdocs_files.is_empty());
+
+    let excluding = AuditOptions::builder().exclude_subsite("guide").build();
+    let result = audit_with_options(&mkdocs, &help_urls, &excluding).unwrap();
+    assert!(result.ghost.is_empty(), "{:?}", result.ghost);
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_excluded_subsite_left_out_of_every_report() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    for subsite in ["guide", "legacy"] {
+        let docs = root.join(subsite).join("docs");
+        fs::create_dir_all(docs.join("img")).unwrap();
+        fs::write(docs.join("a.md"), "![A](img/a.png) ![B](img/b.png)").unwrap();
+        fs::write(docs.join("img/a.png"), subsite).unwrap();
+        fs::write(docs.join("img/b.png"), subsite).unwrap();
+        fs::write(root.join(subsite).join("mkdocs.yml"), "nav:\n  - A: a.md\n").unwrap();
+    }
+    fs::create_dir_all(root.join("draft/docs")).unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n  - Legacy: '!include ./legacy/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    let mkdocs = root.join("mkdocs.yml");
+    let help_urls = root.join("help_urls.h");
+
+    let options = AuditOptions::builder().check_duplicate_images(true);
+    let full = audit_with_options(&mkdocs, &help_urls, &options.clone().build()).unwrap();
+    assert_eq!(full.duplicate_image_files.len(), 2);
+    assert_eq!(full.subsites_without_mkdocs.len(), 1);
+
+    let excluding = options
+        .exclude_subsite("legacy")
+        .exclude_subsite("draft")
+        .build();
+    let result = audit_with_options(&mkdocs, &help_urls, &excluding).unwrap();
+    assert_eq!(result.duplicate_image_files.len(), 1);
+    assert!(result.duplicate_image_files[0][0].starts_with(root.join("guide")));
+    assert!(
+        result.subsites_without_mkdocs.is_empty(),
+        "{:?}",
+        result.subsites_without_mkdocs
+    );
+}
+
+#[test]
+fn test_broken_anchor_reported() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(
+        docs.join("a.md"),
+        "[Real](b.md#real-heading) [Custom](b.md#custom) [Gone](b.md#no-such-section)",
+    )
+    .unwrap();
+    fs::write(docs.join("b.md"), "# Real heading\n\n## Other {#custom}\n").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(root.join("mkdocs.yml"), "nav:\n  - A: a.md\n  - B: b.md\n").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+    assert_eq!(
+        result.broken_anchors,
+        vec![BrokenAnchor {
+            from: docs.join("a.md"),
+            link: "b.md".to_string(),
+            anchor: "no-such-section".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_find_duplicate_nav_entries_across_include() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let include_dir = root.join("guide");
+    fs::create_dir_all(include_dir.join("docs")).unwrap();
+    fs::write(
+        include_dir.join("mkdocs.yml"),
+        "nav:\n  - Intro: intro.md\n  - Other: other.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n  - Shortcut: ../guide/docs/intro.md\n",
+    )
+    .unwrap();
+
+    let nav = load_mkdocs_nav(&root.join("mkdocs.yml")).unwrap();
+    let duplicates = find_duplicate_nav_entries(&nav, root).unwrap();
+
+    assert_eq!(duplicates, vec![include_dir.join("docs").join("intro.md")]);
+}
+
+#[test]
+fn test_three_file_include_cycle_is_an_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    for (subsite, next) in [("a", "b"), ("b", "c"), ("c", "a")] {
+        fs::create_dir_all(root.join(subsite).join("docs")).unwrap();
+        fs::write(
+            root.join(subsite).join("mkdocs.yml"),
+            format!("nav:\n  - Next: '!include ../{next}/mkdocs.yml'\n"),
+        )
+        .unwrap();
+    }
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - A: '!include ./a/mkdocs.yml'\n",
+    )
+    .unwrap();
+
+    let err = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap_err();
+
+    let message = err.to_string();
+    assert!(message.starts_with("circular !include: "), "{message}");
+    let chain: Vec<&str> = message
+        .trim_start_matches("circular !include: ")
+        .split(" → ")
+        .collect();
+    assert_eq!(chain.len(), 4, "{message}");
+    assert_eq!(chain[0], chain[3]);
+    assert!(chain[0].ends_with("mkdocs.yml"));
+}
+
+#[test]
+fn test_link_cache_reuses_links_of_unmodified_pages() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("a.md"), "[B](b.md)").unwrap();
+    fs::write(docs.join("b.md"), "# B").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(root.join("mkdocs.yml"), "nav:\n  - A: a.md\n  - B: b.md\n").unwrap();
+    let mkdocs = root.join("mkdocs.yml");
+    let help_urls = root.join("help_urls.h");
+    let cache = root.join("links.json");
+    let options = AuditOptions::builder().cache_path(&cache).build();
+
+    let result = audit_with_options(&mkdocs, &help_urls, &options).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+    assert!(cache.is_file());
+
+    // Same modification time, so the cached links are used and the new one isn't seen.
+    let page = docs.join("a.md");
+    let modified = fs::metadata(&page).unwrap().modified().unwrap();
+    fs::write(&page, "[B](b.md) [Gone](gone.md)").unwrap();
+    fs::File::options()
+        .write(true)
+        .open(&page)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    let result = audit_with_options(&mkdocs, &help_urls, &options).unwrap();
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+
+    // A cache from another version is thrown away, so the page is parsed afresh.
+    fs::write(&cache, r#"{"version": 0, "files": {}}"#).unwrap();
+    let result = audit_with_options(&mkdocs, &help_urls, &options).unwrap();
+    assert_eq!(result.broken_links.len(), 1, "{:?}", result.broken_links);
+    assert_eq!(result.broken_links[0].link, "gone.md");
+}
+
+#[test]
+fn test_stub_pages_reported_below_min_word_count() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(
+        docs.join("a.md"),
+        "# A\n\nA page with enough words to pass, and a [link](b.md) onward.\n",
+    )
+    .unwrap();
+    // Not in the nav, but reached by a link, so still checked.
+    fs::write(docs.join("b.md"), "# B\n\n<!-- TODO: write this page -->\n").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(root.join("mkdocs.yml"), "nav:\n  - A: a.md\n").unwrap();
+    let mkdocs = root.join("mkdocs.yml");
+    let help_urls = root.join("help_urls.h");
+
+    let result = audit(&mkdocs, &help_urls).unwrap();
+    assert!(result.stub_pages.is_empty());
+
+    let config = GhostConfig {
+        min_word_count: 5,
+        ..GhostConfig::default()
+    };
+    let (result, _) =
+        audit_with_config(&mkdocs, &help_urls, &config, &TraceOptions::default()).unwrap();
+    assert_eq!(result.stub_pages, vec![docs.join("b.md")]);
+}
+
+#[test]
+fn test_heading_errors_only_when_requested() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("a.md"), "# A\n\n### Too deep\n").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(root.join("mkdocs.yml"), "nav:\n  - A: a.md\n").unwrap();
+    let mkdocs = root.join("mkdocs.yml");
+    let help_urls = root.join("help_urls.h");
+
+    let result = audit(&mkdocs, &help_urls).unwrap();
+    assert!(result.heading_errors.is_empty());
+
+    let options = AuditOptions::builder().check_headings(true).build();
+    let result = audit_with_options(&mkdocs, &help_urls, &options).unwrap();
+    assert_eq!(
+        result.heading_errors,
+        vec![(
+            docs.join("a.md"),
+            vec![HeadingError {
+                line: 3,
+                level: 3,
+                max_expected: 2,
+            }]
+        )]
+    );
+}
+
+#[test]
+fn test_multiple_h1_only_when_requested() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("a.md"), "# A\n\nText.\n\n# Pasted title\n").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(root.join("mkdocs.yml"), "nav:\n  - A: a.md\n").unwrap();
+    let mkdocs = root.join("mkdocs.yml");
+    let help_urls = root.join("help_urls.h");
+
+    let result = audit(&mkdocs, &help_urls).unwrap();
+    assert!(result.pages_with_multiple_h1.is_empty());
+
+    let options = AuditOptions::builder().check_multiple_h1(true).build();
+    let result = audit_with_options(&mkdocs, &help_urls, &options).unwrap();
+    assert_eq!(result.pages_with_multiple_h1, vec![docs.join("a.md")]);
+}
+
+#[test]
+fn test_missing_frontmatter_fields_reported() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(
+        docs.join("a.md"),
+        "---\ntitle: A\ndescription: About A\n---\n# A\n\n[B](b.md)\n",
+    )
+    .unwrap();
+    fs::write(docs.join("b.md"), "---\ntitle: B\n---\n# B\n").unwrap();
+    fs::write(docs.join("c.md"), "# C\n").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(root.join("mkdocs.yml"), "nav:\n  - A: a.md\n  - C: c.md\n").unwrap();
+
+    let options = AuditOptions::builder()
+        .require_frontmatter_field("title")
+        .require_frontmatter_field("description")
+        .build();
+    let mut result = audit_with_options(
+        &root.join("mkdocs.yml"),
+        &root.join("help_urls.h"),
+        &options,
+    )
+    .unwrap();
+    result.missing_frontmatter_fields.sort();
+    assert_eq!(
+        result.missing_frontmatter_fields,
+        vec![
+            (docs.join("b.md"), vec!["description".to_string()]),
+            (
+                docs.join("c.md"),
+                vec!["title".to_string(), "description".to_string()]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_title_mismatches_across_includes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    let sub_docs = root.join("sub").join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::create_dir_all(&sub_docs).unwrap();
+    fs::write(docs.join("ravel.md"), "# The Ravel Function (,)\n").unwrap();
+    fs::write(sub_docs.join("ravel.md"), "# Reshape\n").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Ravel: ravel.md\n  - Sub: '!include sub/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("sub").join("mkdocs.yml"),
+        "nav:\n  - Ravel: ravel.md\n",
+    )
+    .unwrap();
+
+    let options = AuditOptions::builder()
+        .check_title_consistency(true)
+        .build();
+    let result = audit_with_options(
+        &root.join("mkdocs.yml"),
+        &root.join("help_urls.h"),
+        &options,
+    )
+    .unwrap();
+    assert_eq!(
+        result.title_mismatches,
+        vec![(
+            sub_docs.join("ravel.md"),
+            "Ravel".to_string(),
+            "Reshape".to_string()
+        )]
+    );
+}
+
+#[test]
+fn test_broken_link_suggestions_only_when_requested() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let guide = root.join("guide");
+    let docs = guide.join("docs");
+    fs::create_dir_all(docs.join("installation")).unwrap();
+    fs::write(docs.join("a.md"), "[Install](instalation.md)").unwrap();
+    fs::write(docs.join("installation").join("index.md"), "# Installation").unwrap();
+    fs::write(docs.join("unrelated.md"), "# Unrelated").unwrap();
+    fs::write(guide.join("mkdocs.yml"), "nav:\n  - A: a.md\n").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    let mkdocs = root.join("mkdocs.yml");
+    let help_urls = root.join("help_urls.h");
+
+    let result = audit(&mkdocs, &help_urls).unwrap();
+    assert!(result.broken_links[0].suggestions.is_empty());
+
+    let options = AuditOptions::builder().suggest_fixes(true).build();
+    let result = audit_with_options(&mkdocs, &help_urls, &options).unwrap();
+    assert_eq!(result.broken_links.len(), 1);
+    assert_eq!(
+        result.broken_links[0].suggestions,
+        vec![docs.join("installation").join("index.md")]
+    );
+}
+
+#[test]
+fn test_percent_encoded_image_resolves() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("guide").join("docs");
+    fs::create_dir_all(docs.join("img")).unwrap();
+    fs::write(
+        docs.join("page.md"),
+        "# Page\n\n![Screen](img/my%20pic.png)\n",
+    )
+    .unwrap();
+    fs::write(docs.join("img").join("my pic.png"), "").unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Page: page.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(
+        result.missing_images.is_empty(),
+        "{:?}",
+        result.missing_images
+    );
+    assert!(
+        result.orphan_images.is_empty(),
+        "{:?}",
+        result.orphan_images
+    );
+}
+
+#[test]
+fn test_nav_entries_differing_only_in_case_collide() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("intro.md"), "# Intro").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Intro: intro.md\n  - Again: Intro.md\n",
+    )
+    .unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(
+        result.case_collisions,
+        vec![(docs.join("Intro.md"), docs.join("intro.md"))]
+    );
+
+    let options = AuditOptions::builder().skip_case_sensitivity(true).build();
+    let result = audit_with_options(
+        &root.join("mkdocs.yml"),
+        &root.join("help_urls.h"),
+        &options,
+    )
+    .unwrap();
+    assert!(result.case_collisions.is_empty());
+}
+
+#[test]
+fn test_case_collisions_on_disk_are_only_case_sensitivity_violations() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let guide = root.join("guide");
+    let docs = guide.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("intro.md"), "# Intro").unwrap();
+    fs::write(docs.join("Intro.md"), "# Intro").unwrap();
+    fs::write(
+        guide.join("mkdocs.yml"),
+        "nav:\n  - Intro: intro.md\n  - Again: Intro.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(
+        result.case_sensitivity_violations,
+        vec![(docs.join("Intro.md"), docs.join("intro.md"))]
+    );
+    assert!(result.case_collisions.is_empty());
+}
+
+#[test]
+fn test_ghostignore_drops_matching_paths_from_reports() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let guide = root.join("guide");
+    let docs = guide.join("docs");
+    fs::create_dir_all(docs.join("legacy")).unwrap();
+    fs::write(docs.join("index.md"), "# Home").unwrap();
+    fs::write(docs.join("draft.md"), "# Draft").unwrap();
+    fs::write(docs.join("keep.md"), "# Keep").unwrap();
+    fs::write(docs.join("legacy").join("old.md"), "[gone](missing.md)").unwrap();
+    fs::write(guide.join("mkdocs.yml"), "nav:\n  - Home: index.md\n").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(
+        root.join(GHOSTIGNORE),
+        "# work in progress\nguide/docs/legacy/\n*.md\n!keep.md\n!index.md\n",
+    )
+    .unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(result.ghost, vec![docs.join("keep.md")]);
+    assert!(result.broken_links.is_empty(), "{:?}", result.broken_links);
+}
+
+#[test]
+fn test_audit_reports_progress() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let guide = root.join("guide");
+    let docs = guide.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("index.md"), "[Next](next.md)").unwrap();
+    fs::write(docs.join("next.md"), "# Next").unwrap();
+    fs::write(guide.join("mkdocs.yml"), "nav:\n  - Home: index.md\n").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let options = AuditOptions::builder()
+        .on_progress({
+            let seen = Arc::clone(&seen);
+            move |p| seen.lock().unwrap().push(p)
+        })
+        .build();
+    audit_with_options(
+        &root.join("mkdocs.yml"),
+        &root.join("help_urls.h"),
+        &options,
+    )
+    .unwrap();
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen[0], Progress::Scanning { files_found: 2 });
+    assert_eq!(
+        seen.last(),
+        Some(&Progress::Analysing { done: 2, total: 2 })
+    );
+}
+
+#[test]
+fn test_audit_multiple_keeps_sites_apart() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut sites = Vec::new();
+    for (name, extra) in [("one", "stray.md"), ("two", "")] {
+        let root = temp_dir.path().join(name);
+        let docs = root.join("guide").join("docs");
+        fs::create_dir_all(&docs).unwrap();
+        fs::write(docs.join("index.md"), "# Home").unwrap();
+        if !extra.is_empty() {
+            fs::write(docs.join(extra), "# Stray").unwrap();
+        }
+        fs::write(
+            root.join("guide").join("mkdocs.yml"),
+            "nav:\n  - Home: index.md\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("mkdocs.yml"),
+            "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+        )
+        .unwrap();
+        fs::write(root.join("help_urls.h"), "").unwrap();
+        sites.push(root);
+    }
+    let mkdocs: Vec<PathBuf> = sites.iter().map(|r| r.join("mkdocs.yml")).collect();
+    let help: Vec<PathBuf> = sites.iter().map(|r| r.join("help_urls.h")).collect();
+    let projects: Vec<(&Path, &Path)> = mkdocs
+        .iter()
+        .zip(&help)
+        .map(|(m, h)| (m.as_path(), h.as_path()))
+        .collect();
+
+    let results = audit_multiple(&projects, &AuditOptions::default()).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, mkdocs[0]);
+    assert_eq!(
+        results[0].1.ghost,
+        vec![sites[0].join("guide").join("docs").join("stray.md")]
+    );
+    assert!(results[1].1.ghost.is_empty(), "{:?}", results[1].1.ghost);
+}
+
+#[test]
+fn test_nav_tree_expands_includes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("docs")).unwrap();
+    fs::create_dir_all(root.join("guide").join("docs")).unwrap();
+    fs::write(
+        root.join("guide").join("mkdocs.yml"),
+        "nav:\n  - Page: page.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - index.md\n  - Start:\n    - Intro: intro.md\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+
+    let nav = load_mkdocs_nav(&root.join("mkdocs.yml")).unwrap();
+    let tree = nav_tree(&nav, root).unwrap();
+    let leaf = |title: Option<&str>, path: PathBuf, url: &str| NavNode {
+        title: title.map(str::to_string),
+        path: Some(path),
+        url: url.to_string(),
+        children: vec![],
+    };
+    let branch = |title: &str, children: Vec<NavNode>| NavNode {
+        title: Some(title.to_string()),
+        path: None,
+        url: String::new(),
+        children,
+    };
+    assert_eq!(
+        tree,
+        vec![
+            leaf(None, root.join("docs").join("index.md"), "index"),
+            branch(
+                "Start",
+                vec![leaf(
+                    Some("Intro"),
+                    root.join("docs").join("intro.md"),
+                    "start/intro"
+                )]
+            ),
+            branch(
+                "Guide",
+                vec![leaf(
+                    Some("Page"),
+                    root.join("guide").join("docs").join("page.md"),
+                    "guide/page"
+                )]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_page_dependency_graph() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("index.md"), "[A](a.md) [B](b.md) [gone](gone.md)").unwrap();
+    fs::write(docs.join("a.md"), "[B](b.md)").unwrap();
+    fs::write(docs.join("b.md"), "# B").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(root.join("mkdocs.yml"), "nav:\n  - Home: index.md\n").unwrap();
+
+    // Not in the nav and not linked to, so the audit doesn't scan it.
+    fs::write(docs.join("stray.md"), "[B](b.md) [gone](gone.md)").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    let scanned_contents: HashMap<PathBuf, String> = ["index.md", "a.md", "b.md", "stray.md"]
+        .into_iter()
+        .map(|name| {
+            let page = docs.join(name);
+            let content = fs::read_to_string(&page).unwrap();
+            (page, content)
+        })
+        .collect();
+    let graph = page_dependency_graph(&result, &scanned_contents);
+    assert_eq!(graph.len(), 4);
+    assert_eq!(
+        graph[&docs.join("index.md")],
+        vec![docs.join("a.md"), docs.join("b.md")]
+    );
+    assert_eq!(graph[&docs.join("a.md")], vec![docs.join("b.md")]);
+    assert!(graph[&docs.join("b.md")].is_empty());
+    assert_eq!(graph[&docs.join("stray.md")], vec![docs.join("b.md")]);
+}
+
+#[test]
+fn test_filter_by_subsite_drops_excluded_subsites() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    for name in ["one", "two"] {
+        let docs = root.join(name).join("docs");
+        fs::create_dir_all(&docs).unwrap();
+        fs::write(docs.join("index.md"), "[Gone](missing.md)").unwrap();
+        fs::write(docs.join("stray.md"), "# Stray").unwrap();
+        fs::write(
+            root.join(name).join("mkdocs.yml"),
+            "nav:\n  - Home: index.md\n",
+        )
+        .unwrap();
+    }
+    let root_mkdocs = r#"
+nav:
+  - One: '!include ./one/mkdocs.yml'
+  - Two: '!include ./two/mkdocs.yml'
+"#;
+    fs::write(root.join("mkdocs.yml"), root_mkdocs).unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(result.ghost.len(), 2, "{:?}", result.ghost);
+    assert_eq!(result.broken_links.len(), 2, "{:?}", result.broken_links);
+
+    let result = result.filter_by_subsite(&["one"], root);
+    let two = root.join("two").join("docs");
+    assert_eq!(result.ghost, vec![two.join("stray.md")]);
+    assert_eq!(result.broken_links.len(), 1);
+    assert_eq!(result.broken_links[0].from, two.join("index.md"));
+}
+
+#[test]
+fn test_docs_dir_setting_moves_pages() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let guide = root.join("guide");
+    let source = guide.join("source");
+    fs::create_dir_all(source.join("img")).unwrap();
+    fs::write(
+        source.join("a.md"),
+        "[B](b.md) [Gone](gone.md) ![Logo](img/logo.png) ![Top](/img/logo.png) \
+         ![Lost](img/lost.png)",
+    )
+    .unwrap();
+    fs::write(source.join("b.md"), "# B").unwrap();
+    fs::write(source.join("img").join("logo.png"), "").unwrap();
+    // Where the pages would be without docs_dir; none of these may satisfy a link.
+    let decoys = guide.join("docs");
+    fs::create_dir_all(decoys.join("img")).unwrap();
+    fs::write(decoys.join("gone.md"), "# Gone").unwrap();
+    fs::write(decoys.join("img").join("lost.png"), "").unwrap();
+    fs::write(
+        guide.join("mkdocs.yml"),
+        "docs_dir: source\nnav:\n  - A: a.md\n  - B: b.md\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert!(result.nav_missing.is_empty(), "{:?}", result.nav_missing);
+    let broken: Vec<&str> = result
+        .broken_links
+        .iter()
+        .map(|b| b.link.as_str())
+        .collect();
+    assert_eq!(broken, vec!["gone.md"]);
+    let missing: Vec<&str> = result
+        .missing_images
+        .iter()
+        .map(|m| m.image.as_str())
+        .collect();
+    assert_eq!(missing, vec!["img/lost.png"]);
+    assert!(
+        !result
+            .orphan_images
+            .contains(&source.join("img").join("logo.png"))
+    );
+
+    // An included subsite's pages live under its own docs_dir.
+    let sub = root.join("sub");
+    fs::create_dir_all(sub.join("source")).unwrap();
+    fs::write(
+        sub.join("mkdocs.yml"),
+        "docs_dir: source\nnav:\n  - Child: child.md\n",
+    )
+    .unwrap();
+    let nav = vec![NavItem::Page(HashMap::from([(
+        "Sub".to_string(),
+        "!include ./sub/mkdocs.yml".to_string(),
+    )]))];
+    let mut pages = HashSet::new();
+    collect_pages(&nav, &mut pages, root).unwrap();
+    assert_eq!(pages, HashSet::from([sub.join("source").join("child.md")]));
+}
+
+#[test]
+fn test_load_config() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    assert_eq!(load_config(root, None).unwrap(), ProjectConfig::default());
+    assert!(load_config(root, Some(&root.join("ghost.toml"))).is_err());
+
+    fs::write(
+        root.join(GHOST_TOML),
+        "help_urls_path = \"include/help_urls.h\"\n\
+         check_headings = true\n\
+         exclude_subsites = [\"*-legacy\"]\n\
+         external_link_timeout = 5\n",
+    )
+    .unwrap();
+    let config = load_config(root, None).unwrap();
+    assert_eq!(
+        config.help_urls_path,
+        Some(root.join("include/help_urls.h"))
+    );
+    assert!(config.check_headings);
+    assert_eq!(config.exclude_subsites, vec!["*-legacy".to_string()]);
+    let options = config.audit_options().build();
+    assert!(options.check_headings);
+    assert_eq!(options.external_link_timeout, Some(Duration::from_secs(5)));
+    assert!(options.respect_gitignore);
+
+    let ci = root.join("ci");
+    fs::create_dir_all(&ci).unwrap();
+    fs::write(
+        ci.join("ghost.toml"),
+        "help_urls_path = \"../help_urls.h\"\n\
+         cache_path = \"links.json\"\n\
+         respect_gitignore = false\n",
+    )
+    .unwrap();
+    let config = load_config(root, Some(&ci.join("ghost.toml"))).unwrap();
+    assert_eq!(config.help_urls_path, Some(ci.join("../help_urls.h")));
+    assert_eq!(config.cache_path, Some(ci.join("links.json")));
+    assert!(!config.check_headings);
+    assert!(!config.audit_options().build().respect_gitignore);
+
+    fs::write(root.join(GHOST_TOML), "check_heading = true\n").unwrap();
+    assert!(load_config(root, None).is_err());
+}
+
+#[test]
+fn test_missing_include_is_reported() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("docs")).unwrap();
+    fs::write(root.join("docs").join("index.md"), "# Home").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Home: index.md\n  - Gone: '!include ./gone/mkdocs.yml'\n",
+    )
+    .unwrap();
+
+    let result = audit(&root.join("mkdocs.yml"), &root.join("help_urls.h")).unwrap();
+    assert_eq!(result.missing_includes.len(), 1);
+    assert!(result.missing_includes[0].ends_with("gone/mkdocs.yml"));
+    assert!(result.nav_missing.is_empty());
+
+    let nav = load_mkdocs_nav(&root.join("mkdocs.yml")).unwrap();
+    let err = build_link_maps(&nav, root).unwrap_err();
+    let message = err.to_string();
+    assert!(message.starts_with("!include of "), "{message}");
+    assert!(message.contains("gone/mkdocs.yml"), "{message}");
+}
+
+#[test]
+fn test_ghost_search_respects_gitignore() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let guide = root.join("guide");
+    fs::create_dir_all(guide.join("docs")).unwrap();
+    fs::write(guide.join("docs").join("index.md"), "# Guide").unwrap();
+    fs::write(guide.join("mkdocs.yml"), "nav:\n  - Home: index.md\n").unwrap();
+    let vendored = guide.join("node_modules").join("some-lib");
+    fs::create_dir_all(&vendored).unwrap();
+    fs::write(vendored.join("README.md"), "# some-lib").unwrap();
+    fs::create_dir_all(guide.join(".cache")).unwrap();
+    fs::write(guide.join(".cache").join("notes.md"), "# Notes").unwrap();
+    fs::write(guide.join(".gitignore"), "node_modules/\n").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(
+        root.join("mkdocs.yml"),
+        "nav:\n  - Guide: '!include ./guide/mkdocs.yml'\n",
+    )
+    .unwrap();
+    let mkdocs = root.join("mkdocs.yml");
+    let help_urls = root.join("help_urls.h");
+
+    let result = audit_with_options(&mkdocs, &help_urls, &AuditOptions::default()).unwrap();
+    assert!(result.ghost.is_empty(), "{:?}", result.ghost);
+
+    let options = AuditOptions::builder().respect_gitignore(false).build();
+    let mut ghost = audit_with_options(&mkdocs, &help_urls, &options)
+        .unwrap()
+        .ghost;
+    ghost.sort();
+    assert_eq!(
+        ghost,
+        vec![
+            guide.join(".cache").join("notes.md"),
+            vendored.join("README.md"),
+        ]
+    );
+}
+
+#[test]
+fn test_unlabelled_code_only_when_requested() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    let docs = root.join("docs");
+    fs::create_dir_all(&docs).unwrap();
+    fs::write(docs.join("a.md"), "# A\n\n```python\nprint(1)\n```\n").unwrap();
+    fs::write(docs.join("b.md"), "# B\n\n```\nls -l\n```\n").unwrap();
+    fs::write(root.join("help_urls.h"), "").unwrap();
+    fs::write(root.join("mkdocs.yml"), "nav:\n  - A: a.md\n  - B: b.md\n").unwrap();
+    let mkdocs = root.join("mkdocs.yml");
+    let help_urls = root.join("help_urls.h");
+
+    let result = audit(&mkdocs, &help_urls).unwrap();
+    assert!(result.pages_with_unlabelled_code.is_empty());
+
+    let options = AuditOptions::builder()
+        .check_unlabelled_code_blocks(true)
+        .build();
+    let result = audit_with_options(&mkdocs, &help_urls, &options).unwrap();
+    assert_eq!(result.pages_with_unlabelled_code, vec![docs.join("b.md")]);
+}